@@ -0,0 +1,556 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Credentials Provider for a local credentials endpoint
+//!
+//! This credentials provider loads credentials from an endpoint on the local machine, such as a
+//! secrets agent that stores long-lived machine credentials. Unlike `credential_process`, no process
+//! is spawned to refresh credentials.
+//!
+//! ## Endpoints
+//! The endpoint MUST be one of:
+//! 1. An `http` URL whose host is a loopback address or `localhost`, e.g.
+//!    `http://127.0.0.1:8125/credentials`. Credentials are requested with `GET` at the path of the URL.
+//! 2. A Unix domain socket, given as `unix://` followed by the absolute path of the socket, e.g.
+//!    `unix:///run/secrets-agent/credentials.sock`. Credentials are requested with an HTTP/1.1
+//!    `GET /` request over the socket.
+//!    _Note: Unix domain sockets are only supported on Unix platforms with the
+//!    `credential-endpoint-unix` feature._
+//!
+//! If an authorization token is configured, it is sent in the `Authorization` header.
+//!
+//! ## Credentials Format
+//! Credentials MUST be returned with a `2xx` status in the same JSON format as the ECS credentials
+//! provider:
+//! ```json
+//! {
+//!    "AccessKeyId" : "MUA...",
+//!    "SecretAccessKey" : "/7PC5om....",
+//!    "Token" : "AQoDY....=",
+//!    "Expiration" : "2016-02-25T06:03:31Z",
+//!    "AccountId" : "123456789012"
+//! }
+//! ```
+//!
+//! `AccountId` is optional. Keys are case insensitive.
+//!
+//! Credentials errors MAY be returned with a `Code` and `Message` field:
+//! ```json
+//! {
+//!   "Code": "ErrorCode",
+//!   "Message": "Helpful error message."
+//! }
+//! ```
+//!
+//! ## Profile Configuration
+//! This credentials provider is included in the profile credentials provider, and can be
+//! configured using the `credential_endpoint` attribute. For example:
+//!
+//! ```ini
+//! [profile example]
+//! credential_endpoint = unix:///run/secrets-agent/credentials.sock
+//! ```
+
+use crate::http_credential_provider::HttpCredentialProvider;
+use crate::provider_config::ProviderConfig;
+use aws_credential_types::provider::{self, error::CredentialsError, future, ProvideCredentials};
+use aws_smithy_runtime_api::client::http::HttpConnectorSettings;
+use http::uri::{PathAndQuery, Scheme};
+use http::{HeaderValue, Uri};
+use std::error::Error;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const PROVIDER_NAME: &str = "CredentialEndpoint";
+const UNIX_SCHEME_PREFIX: &str = "unix://";
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Credentials provider that loads credentials from a local credentials endpoint
+///
+/// See the [module documentation](crate::credential_endpoint) for the supported endpoints and the
+/// format of the response.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::credential_endpoint::CredentialEndpointProvider;
+///
+/// let provider = CredentialEndpointProvider::builder()
+///     .endpoint("unix:///run/secrets-agent/credentials.sock")
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct CredentialEndpointProvider {
+    transport: Transport,
+    authorization: Option<Result<HeaderValue, String>>,
+}
+
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+enum Transport {
+    Http(HttpCredentialProvider),
+    #[cfg(all(unix, feature = "credential-endpoint-unix"))]
+    Unix(unix::UnixSocketTransport),
+    InvalidConfiguration(InvalidEndpointError),
+}
+
+impl CredentialEndpointProvider {
+    /// Builder for [`CredentialEndpointProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn credentials(&self) -> provider::Result {
+        let auth = match &self.authorization {
+            Some(Ok(auth)) => Some(auth.clone()),
+            Some(Err(message)) => {
+                return Err(CredentialsError::invalid_configuration(message.clone()))
+            }
+            None => None,
+        };
+        match &self.transport {
+            Transport::Http(provider) => provider.credentials(auth).await,
+            #[cfg(all(unix, feature = "credential-endpoint-unix"))]
+            Transport::Unix(transport) => transport.credentials(auth).await,
+            Transport::InvalidConfiguration(err) => {
+                Err(CredentialsError::invalid_configuration(err.clone()))
+            }
+        }
+    }
+}
+
+impl ProvideCredentials for CredentialEndpointProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+/// Builder for [`CredentialEndpointProvider`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    provider_config: Option<ProviderConfig>,
+    endpoint: Option<String>,
+    authorization_token: Option<String>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Override the configuration used for this provider
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Set the endpoint that credentials are loaded from
+    ///
+    /// This must be a loopback `http` URL or a `unix://` socket path. An invalid or missing
+    /// endpoint causes the provider to return `CredentialsError::InvalidConfiguration`.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Send `token` in the `Authorization` header of credentials requests
+    pub fn authorization_token(mut self, token: impl Into<String>) -> Self {
+        self.authorization_token = Some(token.into());
+        self
+    }
+
+    /// Override the connect timeout for the credentials endpoint
+    ///
+    /// Defaults to 2 seconds.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Override the read timeout for the credentials endpoint
+    ///
+    /// Defaults to 5 seconds.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Create a [`CredentialEndpointProvider`] from this builder
+    pub fn build(self) -> CredentialEndpointProvider {
+        let provider_config = self.provider_config.unwrap_or_default();
+        let connect_timeout = self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+        let read_timeout = self.read_timeout.unwrap_or(DEFAULT_READ_TIMEOUT);
+        let endpoint = match self.endpoint {
+            Some(endpoint) => parse_endpoint(&endpoint),
+            None => Err(InvalidEndpointError::new(
+                "",
+                "no endpoint was configured for the credential endpoint provider",
+            )),
+        };
+        let transport = match endpoint {
+            Ok(Endpoint::Http { endpoint, path }) => Transport::Http(
+                HttpCredentialProvider::builder()
+                    .configure(&provider_config)
+                    .http_connector_settings(
+                        HttpConnectorSettings::builder()
+                            .connect_timeout(connect_timeout)
+                            .read_timeout(read_timeout)
+                            .build(),
+                    )
+                    .build(PROVIDER_NAME, &endpoint, path),
+            ),
+            #[cfg(all(unix, feature = "credential-endpoint-unix"))]
+            Ok(Endpoint::Unix(socket)) => Transport::Unix(unix::UnixSocketTransport::new(
+                socket,
+                provider_config.sleep_impl(),
+                connect_timeout,
+                read_timeout,
+            )),
+            #[cfg(not(all(unix, feature = "credential-endpoint-unix")))]
+            Ok(Endpoint::Unix(socket)) => {
+                Transport::InvalidConfiguration(InvalidEndpointError::new(
+                    &format!("{UNIX_SCHEME_PREFIX}{}", socket.display()),
+                    "Unix domain socket endpoints are only supported on Unix platforms with the \
+                `credential-endpoint-unix` feature",
+                ))
+            }
+            Err(err) => Transport::InvalidConfiguration(err),
+        };
+        let authorization = self.authorization_token.map(|token| {
+            HeaderValue::from_str(&token).map_err(|err| {
+                format!("the authorization token is not a valid header value: {err}")
+            })
+        });
+        CredentialEndpointProvider {
+            transport,
+            authorization,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Endpoint {
+    Http { endpoint: String, path: String },
+    Unix(PathBuf),
+}
+
+fn parse_endpoint(endpoint: &str) -> Result<Endpoint, InvalidEndpointError> {
+    if let Some(socket) = endpoint.strip_prefix(UNIX_SCHEME_PREFIX) {
+        let socket = PathBuf::from(socket);
+        if !socket.is_absolute() {
+            return Err(InvalidEndpointError::new(
+                endpoint,
+                "the path of a Unix domain socket must be absolute",
+            ));
+        }
+        return Ok(Endpoint::Unix(socket));
+    }
+    let uri = endpoint
+        .parse::<Uri>()
+        .map_err(|err| InvalidEndpointError::new(endpoint, format!("invalid URI: {err}")))?;
+    if uri.scheme() != Some(&Scheme::HTTP) {
+        return Err(InvalidEndpointError::new(
+            endpoint,
+            "the endpoint must be an `http` URL or a `unix://` socket path",
+        ));
+    }
+    let is_loopback = match uri.host() {
+        Some(host) if host.eq_ignore_ascii_case("localhost") => true,
+        Some(host) => host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false),
+        None => false,
+    };
+    if !is_loopback {
+        return Err(InvalidEndpointError::new(
+            endpoint,
+            "the host of the endpoint must be a loopback address or `localhost`",
+        ));
+    }
+    let path = uri
+        .path_and_query()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    let mut parts = uri.into_parts();
+    parts.path_and_query = Some(PathAndQuery::from_static("/"));
+    let endpoint = Uri::from_parts(parts)
+        .expect("parts will be valid")
+        .to_string();
+    Ok(Endpoint::Http { endpoint, path })
+}
+
+/// The configured endpoint cannot be used to load credentials
+#[derive(Clone, Debug)]
+struct InvalidEndpointError {
+    endpoint: String,
+    message: String,
+}
+
+impl InvalidEndpointError {
+    fn new(endpoint: &str, message: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for InvalidEndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid credential endpoint `{}`: {}",
+            self.endpoint, self.message
+        )
+    }
+}
+
+impl Error for InvalidEndpointError {}
+
+#[cfg(all(unix, feature = "credential-endpoint-unix"))]
+mod unix {
+    use super::PROVIDER_NAME;
+    use crate::http_credential_provider::parse_credentials;
+    use aws_credential_types::provider::{self, error::CredentialsError};
+    use aws_smithy_async::future::timeout::Timeout;
+    use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use aws_smithy_runtime_api::http::StatusCode;
+    use http::header::{ACCEPT, AUTHORIZATION, HOST};
+    use http::HeaderValue;
+    use hyper::body::HttpBody;
+    use hyper::client::conn::{self, Connection};
+    use hyper::{Body, Request};
+    use std::future::{poll_fn, Future};
+    use std::path::PathBuf;
+    use std::pin::{pin, Pin};
+    use std::task::Poll;
+    use std::time::Duration;
+    use tokio::net::UnixStream;
+
+    /// Response bodies larger than this are rejected
+    const MAX_RESPONSE_SIZE: usize = 64 * 1024;
+
+    /// Sends HTTP/1.1 credentials requests over a Unix domain socket
+    #[derive(Debug)]
+    pub(super) struct UnixSocketTransport {
+        socket: PathBuf,
+        sleep_impl: Option<SharedAsyncSleep>,
+        connect_timeout: Duration,
+        read_timeout: Duration,
+    }
+
+    impl UnixSocketTransport {
+        pub(super) fn new(
+            socket: PathBuf,
+            sleep_impl: Option<SharedAsyncSleep>,
+            connect_timeout: Duration,
+            read_timeout: Duration,
+        ) -> Self {
+            Self {
+                socket,
+                sleep_impl,
+                connect_timeout,
+                read_timeout,
+            }
+        }
+
+        pub(super) async fn credentials(&self, auth: Option<HeaderValue>) -> provider::Result {
+            tracing::debug!(socket = %self.socket.display(), "loading credentials from Unix domain socket");
+            let stream = self
+                .with_timeout(self.connect_timeout, UnixStream::connect(&self.socket))
+                .await?
+                .map_err(|err| {
+                    CredentialsError::provider_error(format!(
+                        "could not connect to the credential endpoint at {}: {err}",
+                        self.socket.display()
+                    ))
+                })?;
+            let (status, body) = self
+                .with_timeout(self.read_timeout, request(stream, auth))
+                .await?
+                .map_err(CredentialsError::provider_error)?;
+            parse_credentials(PROVIDER_NAME, status, &body)
+        }
+
+        async fn with_timeout<T>(
+            &self,
+            timeout: Duration,
+            future: impl Future<Output = T>,
+        ) -> Result<T, CredentialsError> {
+            match &self.sleep_impl {
+                Some(sleep_impl) => Timeout::new(future, sleep_impl.sleep(timeout))
+                    .await
+                    .map_err(|_| CredentialsError::provider_timed_out(timeout)),
+                None => Ok(future.await),
+            }
+        }
+    }
+
+    /// Send a credentials request over `stream`, returning the status and body of the response
+    async fn request(
+        stream: UnixStream,
+        auth: Option<HeaderValue>,
+    ) -> Result<(StatusCode, Vec<u8>), BoxError> {
+        let (mut sender, connection) = conn::Builder::new().handshake(stream).await?;
+        let mut request = Request::get("/")
+            .header(HOST, "localhost")
+            .header(ACCEPT, "application/json");
+        if let Some(auth) = auth {
+            request = request.header(AUTHORIZATION, auth);
+        }
+        let request = request.body(Body::empty())?;
+        with_connection(connection, async move {
+            let response = sender.send_request(request).await?;
+            let status = StatusCode::try_from(response.status().as_u16())?;
+            let mut body = response.into_body();
+            let mut data = Vec::new();
+            while let Some(chunk) = body.data().await {
+                let chunk = chunk?;
+                if data.len() + chunk.len() > MAX_RESPONSE_SIZE {
+                    return Err("the response from the credential endpoint was too large".into());
+                }
+                data.extend_from_slice(&chunk);
+            }
+            Ok((status, data))
+        })
+        .await
+    }
+
+    /// Run `exchange` while driving `connection`, which performs the I/O for it
+    ///
+    /// The connection is polled in place rather than spawned so that it's closed as soon as the
+    /// exchange completes or times out.
+    async fn with_connection<T>(
+        mut connection: Connection<UnixStream, Body>,
+        exchange: impl Future<Output = Result<T, BoxError>>,
+    ) -> Result<T, BoxError> {
+        let mut exchange = pin!(exchange);
+        let mut connection_closed = false;
+        poll_fn(|cx| {
+            if let Poll::Ready(result) = exchange.as_mut().poll(cx) {
+                return Poll::Ready(result);
+            }
+            if !connection_closed {
+                match Pin::new(&mut connection).poll(cx) {
+                    Poll::Ready(Ok(())) => connection_closed = true,
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                    Poll::Pending => {}
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use aws_smithy_types::body::SdkBody;
+
+    fn http(endpoint: &str, path: &str) -> Endpoint {
+        Endpoint::Http {
+            endpoint: endpoint.into(),
+            path: path.into(),
+        }
+    }
+
+    #[test]
+    fn parse_valid_endpoints() {
+        assert_eq!(
+            http("http://127.0.0.1:8125/", "/credentials"),
+            parse_endpoint("http://127.0.0.1:8125/credentials").unwrap()
+        );
+        assert_eq!(
+            http("http://localhost/", "/"),
+            parse_endpoint("http://localhost").unwrap()
+        );
+        assert_eq!(
+            http("http://[::1]:8125/", "/v1/credentials?role=machine"),
+            parse_endpoint("http://[::1]:8125/v1/credentials?role=machine").unwrap()
+        );
+        assert_eq!(
+            Endpoint::Unix("/run/secrets-agent/credentials.sock".into()),
+            parse_endpoint("unix:///run/secrets-agent/credentials.sock").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_endpoints() {
+        for endpoint in [
+            "https://127.0.0.1/credentials",
+            "http://169.254.169.254/credentials",
+            "http://example.com/credentials",
+            "unix://relative.sock",
+            "not a uri",
+        ] {
+            parse_endpoint(endpoint).expect_err(endpoint);
+        }
+    }
+
+    #[tokio::test]
+    async fn load_credentials_from_loopback_http() {
+        let http_client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .uri("http://127.0.0.1:8125/credentials")
+                .header("authorization", "Bearer agent-token")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .body(SdkBody::from(
+                    r#"{
+                        "AccessKeyId" : "AKIDAGENT",
+                        "SecretAccessKey" : "agentsecret",
+                        "Token" : "agenttoken",
+                        "Expiration" : "2016-02-25T06:03:31Z",
+                        "AccountId" : "123456789012"
+                    }"#,
+                ))
+                .unwrap(),
+        )]);
+        let provider = CredentialEndpointProvider::builder()
+            .configure(&ProviderConfig::no_configuration().with_http_client(http_client.clone()))
+            .endpoint("http://127.0.0.1:8125/credentials")
+            .authorization_token("Bearer agent-token")
+            .build();
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!("AKIDAGENT", creds.access_key_id());
+        assert_eq!(
+            Some("123456789012"),
+            creds.account_id().map(|id| id.as_str())
+        );
+        http_client.assert_requests_match(&[]);
+    }
+
+    #[tokio::test]
+    async fn invalid_configuration() {
+        for provider in [
+            CredentialEndpointProvider::builder().build(),
+            CredentialEndpointProvider::builder()
+                .endpoint("http://example.com")
+                .build(),
+            CredentialEndpointProvider::builder()
+                .endpoint("http://127.0.0.1/credentials")
+                .authorization_token("invalid\r\ntoken")
+                .build(),
+        ] {
+            let err = provider.provide_credentials().await.expect_err("invalid");
+            assert!(
+                matches!(err, CredentialsError::InvalidConfiguration(_)),
+                "{err:?}"
+            );
+        }
+    }
+}
@@ -142,6 +142,7 @@ pub(crate) fn parse_credential_process_json_credentials(
     let mut secret_access_key = None;
     let mut session_token = None;
     let mut expiration = None;
+    let mut account_id = None;
     json_parse_loop(credentials_response.as_bytes(), |key, value| {
         match (key, value) {
             /*
@@ -149,7 +150,8 @@ pub(crate) fn parse_credential_process_json_credentials(
              "AccessKeyId": "ASIARTESTID",
              "SecretAccessKey": "TESTSECRETKEY",
              "SessionToken": "TESTSESSIONTOKEN",
-             "Expiration": "2022-05-02T18:36:00+00:00"
+             "Expiration": "2022-05-02T18:36:00+00:00",
+             "AccountId": "123456789012"
             */
             (key, Token::ValueNumber { value, .. }) if key.eq_ignore_ascii_case("Version") => {
                 version = Some(i32::try_from(*value).map_err(|err| {
@@ -173,6 +175,9 @@ pub(crate) fn parse_credential_process_json_credentials(
             (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Expiration") => {
                 expiration = Some(value.to_unescaped()?)
             }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("AccountId") => {
+                account_id = Some(value.to_unescaped()?)
+            }
 
             _ => {}
         };
@@ -197,13 +202,14 @@ pub(crate) fn parse_credential_process_json_credentials(
     if expiration.is_none() {
         tracing::debug!("no expiration provided for credentials provider credentials. these credentials will never be refreshed.")
     }
-    Ok(Credentials::new(
-        access_key_id,
-        secret_access_key,
-        session_token.map(|tok| tok.to_string()),
-        expiration,
-        "CredentialProcess",
-    ))
+    let mut builder = Credentials::builder()
+        .access_key_id(access_key_id)
+        .secret_access_key(secret_access_key)
+        .provider_name("CredentialProcess");
+    builder.set_session_token(session_token.map(|tok| tok.to_string()));
+    builder.set_expiry(expiration);
+    builder.set_account_id(account_id.map(Into::into));
+    Ok(builder.build())
 }
 
 fn parse_expiration(expiration: impl AsRef<str>) -> Result<SystemTime, InvalidJsonCredentials> {
@@ -229,12 +235,16 @@ mod test {
     #[cfg_attr(windows, ignore)]
     async fn test_credential_process() {
         let provider = CredentialProcessProvider::new(String::from(
-            r#"echo '{ "Version": 1, "AccessKeyId": "ASIARTESTID", "SecretAccessKey": "TESTSECRETKEY", "SessionToken": "TESTSESSIONTOKEN", "Expiration": "2022-05-02T18:36:00+00:00" }'"#,
+            r#"echo '{ "Version": 1, "AccessKeyId": "ASIARTESTID", "SecretAccessKey": "TESTSECRETKEY", "SessionToken": "TESTSESSIONTOKEN", "Expiration": "2022-05-02T18:36:00+00:00", "AccountId": "123456789012" }'"#,
         ));
         let creds = provider.provide_credentials().await.expect("valid creds");
         assert_eq!(creds.access_key_id(), "ASIARTESTID");
         assert_eq!(creds.secret_access_key(), "TESTSECRETKEY");
         assert_eq!(creds.session_token(), Some("TESTSESSIONTOKEN"));
+        assert_eq!(
+            creds.account_id().map(|account_id| account_id.as_str()),
+            Some("123456789012")
+        );
         assert_eq!(
             creds.expiry(),
             Some(
@@ -259,6 +269,7 @@ mod test {
         assert_eq!(creds.secret_access_key(), "TESTSECRETKEY");
         assert_eq!(creds.session_token(), None);
         assert_eq!(creds.expiry(), None);
+        assert_eq!(creds.account_id(), None);
     }
 
     #[tokio::test]
@@ -9,6 +9,7 @@ use aws_credential_types::provider::{self, future, ProvideCredentials};
 use aws_credential_types::Credentials;
 use tracing::Instrument;
 
+use crate::diagnostics::ProviderReport;
 use crate::environment::credentials::EnvironmentVariableCredentialsProvider;
 use crate::meta::credentials::CredentialsProviderChain;
 use crate::meta::region::ProvideRegion;
@@ -75,6 +76,14 @@ impl DefaultCredentialsChain {
             .instrument(tracing::debug_span!("provide_credentials", provider = %"default_chain"))
             .await
     }
+
+    /// Load credentials along with the outcome of each provider in this chain
+    pub(crate) async fn credentials_with_report(&self) -> (provider::Result, Vec<ProviderReport>) {
+        self.provider_chain
+            .credentials_with_report()
+            .instrument(tracing::debug_span!("provide_credentials", provider = %"default_chain"))
+            .await
+    }
 }
 
 impl ProvideCredentials for DefaultCredentialsChain {
@@ -143,6 +152,14 @@ impl Builder {
         self
     }
 
+    /// Set the provider of MFA token codes for profiles that assume a role with `mfa_serial`
+    ///
+    /// See [`mfa_token_code_provider`](crate::profile::credentials::Builder::mfa_token_code_provider)
+    pub fn mfa_token_code_provider(mut self, provider: crate::sts::MfaTokenCodeProvider) -> Self {
+        self.profile_file_builder = self.profile_file_builder.mfa_token_code_provider(provider);
+        self
+    }
+
     /// Override the profile name used by this provider
     ///
     /// When unset, the value of the `AWS_PROFILE` environment variable will be used.
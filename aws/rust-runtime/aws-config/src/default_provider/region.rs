@@ -5,6 +5,7 @@
 
 use aws_types::region::Region;
 
+use crate::diagnostics::ProviderReport;
 use crate::environment::region::EnvironmentVariableRegionProvider;
 use crate::meta::region::{ProvideRegion, RegionProviderChain};
 use crate::provider_config::ProviderConfig;
@@ -30,6 +31,11 @@ impl DefaultRegionChain {
         self.0.region().await
     }
 
+    /// Load a region along with the outcome of each provider in this chain
+    pub(crate) async fn region_with_report(&self) -> (Option<Region>, Vec<ProviderReport>) {
+        self.0.region_with_report().await
+    }
+
     /// Builder for [`DefaultRegionChain`]
     pub fn builder() -> Builder {
         Builder::default()
@@ -64,9 +70,9 @@ impl Builder {
     /// Build a [DefaultRegionChain]
     pub fn build(self) -> DefaultRegionChain {
         DefaultRegionChain(
-            RegionProviderChain::first_try(self.env_provider)
-                .or_else(self.profile_file.build())
-                .or_else(self.imds.build()),
+            RegionProviderChain::first_try_named("Environment", self.env_provider)
+                .or_else_named("Profile", self.profile_file.build())
+                .or_else_named("Ec2InstanceMetadata", self.imds.build()),
         )
     }
 }
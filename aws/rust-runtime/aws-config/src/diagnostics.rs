@@ -0,0 +1,555 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Reports that explain how region and credentials were resolved
+//!
+//! A [`ResolutionReport`] lists each provider of the region and credentials provider chains, whether
+//! it was skipped, not configured, failed, or succeeded, and where the values it used came from.
+//! Use [`ConfigLoader::load_with_report`](crate::ConfigLoader::load_with_report) to get one.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn example() {
+//! let (sdk_config, report) = aws_config::from_env().load_with_report().await;
+//! // Prints the profile, the region, and the outcome of each provider along with its sources
+//! println!("{report}");
+//! # }
+//! ```
+
+use crate::profile::credentials::repr::{
+    credential_endpoint, credential_process, role, roles_anywhere, sso, static_credentials,
+    web_identity_token,
+};
+use crate::profile::{Profile, ProfileSet, PropertySource};
+use aws_credential_types::provider::{self, future, ProvideCredentials, SharedCredentialsProvider};
+use aws_credential_types::Credentials;
+use aws_runtime::env_config::file::EnvConfigFileKind;
+use aws_smithy_async::time::SharedTimeSource;
+use aws_types::os_shim_internal::Env;
+use aws_types::region::Region;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Profile keys that configure the credentials loaded from a profile
+const PROFILE_CREDENTIALS_KEYS: &[&str] = &[
+    static_credentials::AWS_ACCESS_KEY_ID,
+    static_credentials::AWS_SECRET_ACCESS_KEY,
+    static_credentials::AWS_SESSION_TOKEN,
+    static_credentials::AWS_ACCOUNT_ID,
+    role::ROLE_ARN,
+    role::SOURCE_PROFILE,
+    role::CREDENTIAL_SOURCE,
+    role::EXTERNAL_ID,
+    role::SESSION_NAME,
+    role::MFA_SERIAL,
+    role::DURATION_SECONDS,
+    web_identity_token::TOKEN_FILE,
+    credential_process::CREDENTIAL_PROCESS,
+    sso::SESSION_NAME,
+    sso::START_URL,
+    sso::REGION,
+    sso::ACCOUNT_ID,
+    sso::ROLE_NAME,
+    roles_anywhere::TRUST_ANCHOR_ARN,
+    roles_anywhere::PROFILE_ARN,
+    roles_anywhere::CERTIFICATE,
+    roles_anywhere::PRIVATE_KEY,
+    roles_anywhere::CERTIFICATE_CHAIN,
+    credential_endpoint::CREDENTIAL_ENDPOINT,
+];
+
+/// Report of how a [`ConfigLoader`](crate::ConfigLoader) resolved region and credentials
+#[derive(Clone, Debug, Default)]
+pub struct ResolutionReport {
+    pub(crate) profile_name: String,
+    pub(crate) region: Option<Region>,
+    pub(crate) region_providers: Vec<ProviderReport>,
+    pub(crate) credentials_providers: Vec<ProviderReport>,
+}
+
+impl ResolutionReport {
+    /// The name of the selected profile
+    pub fn profile_name(&self) -> &str {
+        &self.profile_name
+    }
+
+    /// The resolved region
+    pub fn region(&self) -> Option<&Region> {
+        self.region.as_ref()
+    }
+
+    /// The outcome of each provider in the region provider chain, in the order they are checked
+    pub fn region_providers(&self) -> &[ProviderReport] {
+        &self.region_providers
+    }
+
+    /// The outcome of each provider in the credentials provider chain, in the order they are checked
+    ///
+    /// This is empty if credentials were explicitly disabled with
+    /// [`no_credentials`](crate::ConfigLoader::no_credentials).
+    pub fn credentials_providers(&self) -> &[ProviderReport] {
+        &self.credentials_providers
+    }
+
+    /// Fill in the sources of the providers from the default chains that were tried
+    pub(crate) fn annotate_sources(&mut self, env: &Env, profiles: Option<&ProfileSet>) {
+        for provider in self.region_providers.iter_mut() {
+            if provider.was_tried() && provider.sources.is_empty() {
+                provider.sources = region_sources(&provider.name, env, profiles);
+            }
+        }
+        for provider in self.credentials_providers.iter_mut() {
+            if provider.was_tried() && provider.sources.is_empty() {
+                provider.sources = credentials_sources(&provider.name, env, profiles);
+            }
+        }
+    }
+}
+
+impl fmt::Display for ResolutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "profile: {}", self.profile_name)?;
+        match &self.region {
+            Some(region) => writeln!(f, "region: {region}")?,
+            None => writeln!(f, "region: <not set>")?,
+        }
+        for (title, providers) in [
+            ("region providers", &self.region_providers),
+            ("credentials providers", &self.credentials_providers),
+        ] {
+            writeln!(f, "{title}:")?;
+            for provider in providers {
+                write!(f, "{provider}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of a single provider in a provider chain, and where its values came from
+#[derive(Clone, Debug)]
+pub struct ProviderReport {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) outcome: ProviderOutcome,
+    pub(crate) sources: Vec<ValueSource>,
+}
+
+impl ProviderReport {
+    pub(crate) fn new(name: impl Into<Cow<'static, str>>, outcome: ProviderOutcome) -> Self {
+        Self {
+            name: name.into(),
+            outcome,
+            sources: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_sources(mut self, sources: Vec<ValueSource>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// The name of the provider within its chain
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the provider was skipped, not configured, failed, or succeeded
+    pub fn outcome(&self) -> &ProviderOutcome {
+        &self.outcome
+    }
+
+    /// Where the values used by the provider came from
+    ///
+    /// This is only filled in for providers that were tried.
+    pub fn sources(&self) -> &[ValueSource] {
+        &self.sources
+    }
+
+    fn was_tried(&self) -> bool {
+        self.outcome != ProviderOutcome::Skipped
+    }
+}
+
+impl fmt::Display for ProviderReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  {}: {}", self.name, self.outcome)?;
+        for source in &self.sources {
+            writeln!(f, "    {source}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of a single provider in a provider chain
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ProviderOutcome {
+    /// The provider was not checked because an earlier provider in the chain succeeded or failed
+    Skipped,
+    /// The provider was not configured, so the chain moved on to the next provider
+    NotConfigured {
+        /// Why the provider did not provide a value
+        reason: String,
+    },
+    /// The provider failed, which ended the chain with an error
+    Failed {
+        /// The error returned by the provider
+        reason: String,
+    },
+    /// The provider supplied the value
+    Succeeded,
+}
+
+impl fmt::Display for ProviderOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderOutcome::Skipped => write!(f, "skipped"),
+            ProviderOutcome::NotConfigured { reason } => write!(f, "not configured ({reason})"),
+            ProviderOutcome::Failed { reason } => write!(f, "failed ({reason})"),
+            ProviderOutcome::Succeeded => write!(f, "succeeded"),
+        }
+    }
+}
+
+/// Where a value used by a provider came from
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ValueSource {
+    /// An environment variable
+    EnvironmentVariable {
+        /// The name of the environment variable
+        name: Cow<'static, str>,
+    },
+    /// A key within a profile
+    Profile {
+        /// The name of the profile
+        profile: String,
+        /// The key within the profile
+        key: String,
+        /// The file the key was loaded from
+        file: Option<PropertySource>,
+    },
+    /// The EC2 instance metadata service
+    InstanceMetadata,
+    /// A provider set on the [`ConfigLoader`](crate::ConfigLoader)
+    Programmatic,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::EnvironmentVariable { name } => write!(f, "environment variable {name}"),
+            ValueSource::Profile { profile, key, file } => {
+                write!(f, "`{key}` in profile `{profile}`")?;
+                match file {
+                    Some(file) => {
+                        let kind = match file.kind() {
+                            EnvConfigFileKind::Config => "config file",
+                            EnvConfigFileKind::Credentials => "credentials file",
+                        };
+                        match file.path() {
+                            Some(path) => write!(f, " of the {kind} {path}"),
+                            None => write!(f, " of the {kind}"),
+                        }
+                    }
+                    None => Ok(()),
+                }
+            }
+            ValueSource::InstanceMetadata => write!(f, "EC2 instance metadata"),
+            ValueSource::Programmatic => write!(f, "set programmatically"),
+        }
+    }
+}
+
+/// Returns the credentials that were loaded for a report the first time credentials are asked for,
+/// so that they aren't loaded twice, and defers to the provider that loaded them after that
+#[derive(Debug)]
+struct ReportedCredentials {
+    credentials: Mutex<Option<Credentials>>,
+    provider: SharedCredentialsProvider,
+    time_source: SharedTimeSource,
+}
+
+impl ProvideCredentials for ReportedCredentials {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        let credentials = self.credentials.lock().unwrap().take();
+        // Credentials that expired since they were loaded are loaded again
+        let expired = |credentials: &Credentials| matches!(credentials.expiry(), Some(expiry) if expiry <= self.time_source.now());
+        match credentials {
+            Some(credentials) if !expired(&credentials) => {
+                future::ProvideCredentials::ready(Ok(credentials))
+            }
+            _ => self.provider.provide_credentials(),
+        }
+    }
+
+    fn fallback_on_interrupt(&self) -> Option<Credentials> {
+        self.provider.fallback_on_interrupt()
+    }
+}
+
+/// Wraps `provider` so that the credentials it loaded for a report are reused for the first request
+pub(crate) fn reuse_reported_credentials(
+    provider: SharedCredentialsProvider,
+    loaded: provider::Result,
+    time_source: SharedTimeSource,
+) -> SharedCredentialsProvider {
+    match loaded {
+        Ok(credentials) => SharedCredentialsProvider::new(ReportedCredentials {
+            credentials: Mutex::new(Some(credentials)),
+            provider,
+            time_source,
+        }),
+        // Failures aren't cached so that loading is retried
+        Err(_) => provider,
+    }
+}
+
+/// Formats an error along with its sources, e.g. `outer error: inner error`
+pub(crate) fn error_reason(err: &(dyn Error + 'static)) -> String {
+    let mut reason = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        reason.push_str(": ");
+        reason.push_str(&err.to_string());
+        source = err.source();
+    }
+    reason
+}
+
+fn env_sources(env: &Env, names: &[&'static str]) -> Vec<ValueSource> {
+    names
+        .iter()
+        .filter(|name| matches!(env.get(name), Ok(value) if !value.trim().is_empty()))
+        .map(|name| ValueSource::EnvironmentVariable {
+            name: Cow::Borrowed(*name),
+        })
+        .collect()
+}
+
+fn profile_source(profile: &Profile, key: &str) -> Option<ValueSource> {
+    profile.property(key).map(|property| ValueSource::Profile {
+        profile: profile.name().to_string(),
+        key: property.key().to_string(),
+        file: property.source().cloned(),
+    })
+}
+
+/// Walks from the selected profile through its `source_profile`s, stopping at loops
+fn source_profiles(profiles: &ProfileSet) -> Vec<&Profile> {
+    let mut chain: Vec<&Profile> = Vec::new();
+    let mut name = profiles.selected_profile();
+    while let Some(profile) = profiles.get_profile(name) {
+        if chain.iter().any(|visited| visited.name() == name) {
+            break;
+        }
+        chain.push(profile);
+        // Static credentials end the chain after the first profile
+        if chain.len() > 1 && profile.get("aws_access_key_id").is_some() {
+            break;
+        }
+        match profile.get("source_profile") {
+            Some(source_profile) => name = source_profile,
+            None => break,
+        }
+    }
+    chain
+}
+
+fn region_sources(provider: &str, env: &Env, profiles: Option<&ProfileSet>) -> Vec<ValueSource> {
+    match provider {
+        "Environment" => {
+            let mut sources = env_sources(env, &["AWS_REGION", "AWS_DEFAULT_REGION"]);
+            sources.truncate(1);
+            sources
+        }
+        "Profile" => profiles
+            .into_iter()
+            .flat_map(source_profiles)
+            .find_map(|profile| profile_source(profile, "region"))
+            .into_iter()
+            .collect(),
+        "Ec2InstanceMetadata" => vec![ValueSource::InstanceMetadata],
+        _ => Vec::new(),
+    }
+}
+
+fn credentials_sources(
+    provider: &str,
+    env: &Env,
+    profiles: Option<&ProfileSet>,
+) -> Vec<ValueSource> {
+    match provider {
+        "Environment" => env_sources(
+            env,
+            &[
+                "AWS_ACCESS_KEY_ID",
+                "AWS_SECRET_ACCESS_KEY",
+                "AWS_SESSION_TOKEN",
+                "AWS_ACCOUNT_ID",
+            ],
+        ),
+        "Profile" => profiles
+            .into_iter()
+            .flat_map(source_profiles)
+            .flat_map(|profile| {
+                PROFILE_CREDENTIALS_KEYS
+                    .iter()
+                    .filter_map(|key| profile_source(profile, key))
+            })
+            .collect(),
+        "WebIdentityToken" => env_sources(
+            env,
+            &[
+                "AWS_WEB_IDENTITY_TOKEN_FILE",
+                "AWS_ROLE_ARN",
+                "AWS_ROLE_SESSION_NAME",
+            ],
+        ),
+        "EcsContainer" => env_sources(
+            env,
+            &[
+                "AWS_CONTAINER_CREDENTIALS_RELATIVE_URI",
+                "AWS_CONTAINER_CREDENTIALS_FULL_URI",
+                "AWS_CONTAINER_AUTHORIZATION_TOKEN",
+                "AWS_CONTAINER_AUTHORIZATION_TOKEN_FILE",
+            ],
+        ),
+        "Ec2InstanceMetadata" => vec![ValueSource::InstanceMetadata],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[allow(deprecated)]
+    use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
+    use crate::provider_config::ProviderConfig;
+    use aws_types::os_shim_internal::Fs;
+
+    async fn profile_set(config: &str, credentials: &str, profile: &str) -> ProfileSet {
+        let fs = Fs::from_slice(&[
+            ("/home/.aws/config", config),
+            ("/home/.aws/credentials", credentials),
+        ]);
+        let env = Env::from_slice(&[("HOME", "/home")]);
+        #[allow(deprecated)]
+        let profile_files = ProfileFiles::builder()
+            .with_file(ProfileFileKind::Config, "/home/.aws/config")
+            .with_file(ProfileFileKind::Credentials, "/home/.aws/credentials")
+            .build();
+        let provider_config = ProviderConfig::empty()
+            .with_fs(fs)
+            .with_env(env)
+            .with_profile_config(Some(profile_files), Some(profile.to_string()));
+        provider_config
+            .profile()
+            .await
+            .expect("valid profiles")
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn profile_sources_follow_source_profile() {
+        let profiles = profile_set(
+            "[profile admin]\nrole_arn = arn:aws:iam::123456789012:role/Admin\nsource_profile = base\n\
+             [profile base]\nregion = us-west-2",
+            "[base]\naws_access_key_id = AKID\naws_secret_access_key = SECRET",
+            "admin",
+        )
+        .await;
+
+        let sources = credentials_sources("Profile", &Env::from_slice(&[]), Some(&profiles));
+        let keys: Vec<_> = sources
+            .iter()
+            .map(|source| match source {
+                ValueSource::Profile { profile, key, .. } => format!("{profile}.{key}"),
+                other => panic!("unexpected source: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                "admin.role_arn",
+                "admin.source_profile",
+                "base.aws_access_key_id",
+                "base.aws_secret_access_key",
+            ],
+            keys
+        );
+        assert_eq!(
+            "`aws_access_key_id` in profile `base` of the credentials file /home/.aws/credentials",
+            sources[2].to_string()
+        );
+
+        let sources = region_sources("Profile", &Env::from_slice(&[]), Some(&profiles));
+        assert_eq!(
+            vec!["`region` in profile `base` of the config file /home/.aws/config".to_string()],
+            sources.iter().map(|s| s.to_string()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn reported_credentials_keep_the_fallback_on_interrupt() {
+        #[derive(Debug)]
+        struct WithFallback;
+
+        impl ProvideCredentials for WithFallback {
+            fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+            where
+                Self: 'a,
+            {
+                future::ProvideCredentials::ready(Ok(Credentials::for_tests()))
+            }
+
+            fn fallback_on_interrupt(&self) -> Option<Credentials> {
+                Some(Credentials::for_tests_with_session_token())
+            }
+        }
+
+        let provider = reuse_reported_credentials(
+            SharedCredentialsProvider::new(WithFallback),
+            Ok(Credentials::for_tests()),
+            SharedTimeSource::default(),
+        );
+        assert_eq!(
+            Some(Credentials::for_tests_with_session_token()),
+            provider.fallback_on_interrupt()
+        );
+    }
+
+    #[test]
+    fn environment_sources_skip_blank_variables() {
+        let env = Env::from_slice(&[
+            ("AWS_ACCESS_KEY_ID", "AKID"),
+            ("AWS_SECRET_ACCESS_KEY", "SECRET"),
+            ("AWS_SESSION_TOKEN", " "),
+            ("AWS_DEFAULT_REGION", "us-east-2"),
+        ]);
+        assert_eq!(
+            vec![
+                ValueSource::EnvironmentVariable {
+                    name: "AWS_ACCESS_KEY_ID".into()
+                },
+                ValueSource::EnvironmentVariable {
+                    name: "AWS_SECRET_ACCESS_KEY".into()
+                },
+            ],
+            credentials_sources("Environment", &env, None)
+        );
+        assert_eq!(
+            vec![ValueSource::EnvironmentVariable {
+                name: "AWS_DEFAULT_REGION".into()
+            }],
+            region_sources("Environment", &env, None)
+        );
+    }
+}
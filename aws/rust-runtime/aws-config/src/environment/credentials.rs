@@ -15,6 +15,7 @@ use aws_types::os_shim_internal::Env;
 /// - `AWS_ACCESS_KEY_ID`
 /// - `AWS_SECRET_ACCESS_KEY` with fallback to `SECRET_ACCESS_KEY`
 /// - `AWS_SESSION_TOKEN`
+/// - `AWS_ACCOUNT_ID`
 #[derive(Debug, Clone)]
 pub struct EnvironmentVariableCredentialsProvider {
     env: Env,
@@ -42,13 +43,14 @@ impl EnvironmentVariableCredentialsProvider {
                     "" => None,
                     s => Some(s.to_string()),
                 });
-        Ok(Credentials::new(
-            access_key,
-            secret_key,
-            session_token,
-            None,
-            ENV_PROVIDER,
-        ))
+        let account_id = self.env.get("AWS_ACCOUNT_ID").and_then(err_if_blank).ok();
+        let mut builder = Credentials::builder()
+            .access_key_id(access_key)
+            .secret_access_key(secret_key)
+            .provider_name(ENV_PROVIDER);
+        builder.set_session_token(session_token);
+        builder.set_account_id(account_id.map(Into::into));
+        Ok(builder.build())
     }
 }
 
@@ -146,6 +148,37 @@ mod test {
         assert_eq!(creds.secret_access_key(), "secret");
     }
 
+    #[test]
+    fn valid_with_account_id() {
+        let provider = make_provider(&[
+            ("AWS_ACCESS_KEY_ID", "access"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_ACCOUNT_ID", "123456789012"),
+        ]);
+
+        let creds = provider
+            .provide_credentials()
+            .now_or_never()
+            .unwrap()
+            .expect("valid credentials");
+        assert_eq!(
+            creds.account_id().map(|account_id| account_id.as_str()),
+            Some("123456789012")
+        );
+
+        let provider = make_provider(&[
+            ("AWS_ACCESS_KEY_ID", "access"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_ACCOUNT_ID", ""),
+        ]);
+        let creds = provider
+            .provide_credentials()
+            .now_or_never()
+            .unwrap()
+            .expect("valid credentials");
+        assert_eq!(creds.account_id(), None);
+    }
+
     #[test]
     fn empty_token_env_var() {
         for token_value in &["", " "] {
@@ -25,6 +25,7 @@ use aws_smithy_runtime_api::client::result::SdkError;
 use aws_smithy_runtime_api::client::retries::classifiers::ClassifyRetry;
 use aws_smithy_runtime_api::client::retries::classifiers::RetryAction;
 use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+use aws_smithy_runtime_api::http::StatusCode;
 use aws_smithy_types::body::SdkBody;
 use aws_smithy_types::config_bag::Layer;
 use aws_smithy_types::retry::RetryConfig;
@@ -151,38 +152,46 @@ fn parse_response(
     provider_name: &'static str,
     response: &HttpResponse,
 ) -> Result<Credentials, OrchestratorError<CredentialsError>> {
-    if !response.status().is_success() {
-        return Err(OrchestratorError::operation(
-            CredentialsError::provider_error(format!(
-                "Non-success status from HTTP credential provider: {:?}",
-                response.status()
-            )),
-        ));
-    }
     let resp_bytes = response.body().bytes().expect("non-streaming deserializer");
-    let str_resp = std::str::from_utf8(resp_bytes)
-        .map_err(|err| OrchestratorError::operation(CredentialsError::unhandled(err)))?;
-    let json_creds = parse_json_credentials(str_resp)
-        .map_err(|err| OrchestratorError::operation(CredentialsError::unhandled(err)))?;
+    parse_credentials(provider_name, response.status(), resp_bytes)
+        .map_err(OrchestratorError::operation)
+}
+
+/// Parse the response of an HTTP credential provider from its status code and body
+pub(crate) fn parse_credentials(
+    provider_name: &'static str,
+    status: StatusCode,
+    body: &[u8],
+) -> Result<Credentials, CredentialsError> {
+    if !status.is_success() {
+        return Err(CredentialsError::provider_error(format!(
+            "Non-success status from HTTP credential provider: {:?}",
+            status
+        )));
+    }
+    let str_resp = std::str::from_utf8(body).map_err(CredentialsError::unhandled)?;
+    let json_creds = parse_json_credentials(str_resp).map_err(CredentialsError::unhandled)?;
     match json_creds {
         JsonCredentials::RefreshableCredentials(RefreshableCredentials {
             access_key_id,
             secret_access_key,
             session_token,
             expiration,
-        }) => Ok(Credentials::new(
-            access_key_id,
-            secret_access_key,
-            Some(session_token.to_string()),
-            Some(expiration),
-            provider_name,
-        )),
-        JsonCredentials::Error { code, message } => Err(OrchestratorError::operation(
-            CredentialsError::provider_error(format!(
-                "failed to load credentials [{}]: {}",
-                code, message
-            )),
-        )),
+            account_id,
+        }) => {
+            let mut builder = Credentials::builder()
+                .access_key_id(access_key_id)
+                .secret_access_key(secret_access_key)
+                .session_token(session_token)
+                .expiry(expiration)
+                .provider_name(provider_name);
+            builder.set_account_id(account_id.map(Into::into));
+            Ok(builder.build())
+        }
+        JsonCredentials::Error { code, message } => Err(CredentialsError::provider_error(format!(
+            "failed to load credentials [{}]: {}",
+            code, message
+        ))),
     }
 }
 
@@ -38,6 +38,7 @@ use aws_smithy_types::timeout::TimeoutConfig;
 use aws_types::os_shim_internal::Env;
 use http::Uri;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::error::Error as _;
 use std::fmt;
 use std::str::FromStr;
@@ -53,6 +54,8 @@ const DEFAULT_ATTEMPTS: u32 = 4;
 const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
 const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(1);
 
+const INSTANCE_TAGS_PATH: &str = "/latest/meta-data/tags/instance";
+
 fn user_agent() -> AwsUserAgent {
     AwsUserAgent::new_from_environment(Env::real(), ApiMetadata::new("imds", PKG_VERSION))
 }
@@ -192,6 +195,51 @@ impl Client {
                 _ => ImdsError::unexpected(err),
             })
     }
+
+    /// Retrieve information from IMDS, returning `None` if the path doesn't exist
+    pub(crate) async fn get_if_present(
+        &self,
+        path: impl Into<String>,
+    ) -> Result<Option<SensitiveString>, ImdsError> {
+        match self.get(path).await {
+            Ok(value) => Ok(Some(value)),
+            Err(ImdsError::ErrorResponse(context))
+                if context.response().status().as_u16() == 404 =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Retrieve the tags of the instance
+    ///
+    /// Tags are only available from IMDS when access to tags in instance metadata has been enabled
+    /// for the instance. Otherwise, IMDS responds with a 404 which is returned as an
+    /// [`ImdsError::ErrorResponse`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aws_config::imds::client::Client;
+    /// # async fn docs() {
+    /// let client = Client::builder().build();
+    /// let tags = client
+    ///   .instance_tags()
+    ///   .await
+    ///   .expect("failure communicating with IMDS");
+    /// println!("this instance is named {:?}", tags.get("Name"));
+    /// # }
+    /// ```
+    pub async fn instance_tags(&self) -> Result<BTreeMap<String, String>, ImdsError> {
+        let keys = self.get(INSTANCE_TAGS_PATH).await?;
+        let mut tags = BTreeMap::new();
+        for key in keys.as_ref().lines().filter(|key| !key.is_empty()) {
+            let value = self.get(format!("{INSTANCE_TAGS_PATH}/{key}")).await?;
+            tags.insert(key.to_string(), String::from(value));
+        }
+        Ok(tags)
+    }
 }
 
 /// New-type around `String` that doesn't emit the string value in the `Debug` impl.
@@ -89,7 +89,7 @@ impl ImdsError {
         })
     }
 
-    pub(super) fn unexpected(source: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
+    pub(crate) fn unexpected(source: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
         Self::Unexpected(Unexpected {
             source: source.into(),
         })
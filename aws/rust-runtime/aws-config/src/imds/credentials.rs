@@ -11,6 +11,7 @@
 use super::client::error::ImdsError;
 use crate::imds::{self, Client};
 use crate::json_credentials::{parse_json_credentials, JsonCredentials, RefreshableCredentials};
+use crate::meta::credentials::maybe_extend_expiration;
 use crate::provider_config::ProviderConfig;
 use aws_credential_types::provider::{self, error::CredentialsError, future, ProvideCredentials};
 use aws_credential_types::Credentials;
@@ -20,12 +21,13 @@ use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::fmt;
 use std::sync::{Arc, RwLock};
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
 
+// The refresh offset should be less than 15 minutes, the smallest amount of time credentials are
+// valid for. Setting it to something longer than that may have the risk of the credentials
+// expiring before the next refresh.
 const CREDENTIAL_EXPIRATION_INTERVAL: Duration = Duration::from_secs(10 * 60);
-const WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY: &str =
-    "Attempting credential expiration extension due to a credential service availability issue. \
-    A refresh of these credentials will be attempted again within the next";
+const CREDENTIAL_EXPIRATION_JITTER: Duration = Duration::from_secs(5 * 60);
 
 #[derive(Debug)]
 struct ImdsCommunicationError {
@@ -176,36 +178,6 @@ impl ImdsCredentialsProvider {
         }
     }
 
-    // Extend the cached expiration time if necessary
-    //
-    // This allows continued use of the credentials even when IMDS returns expired ones.
-    fn maybe_extend_expiration(&self, expiration: SystemTime) -> SystemTime {
-        let now = self.time_source.now();
-        // If credentials from IMDS are not stale, use them as they are.
-        if now < expiration {
-            return expiration;
-        }
-
-        let mut rng = fastrand::Rng::with_seed(
-            now.duration_since(SystemTime::UNIX_EPOCH)
-                .expect("now should be after UNIX EPOCH")
-                .as_secs(),
-        );
-        // Calculate credentials' refresh offset with jitter, which should be less than 15 minutes
-        // the smallest amount of time credentials are valid for.
-        // Setting it to something longer than that may have the risk of the credentials expiring
-        // before the next refresh.
-        let refresh_offset = CREDENTIAL_EXPIRATION_INTERVAL + Duration::from_secs(rng.u64(0..=300));
-        let new_expiry = now + refresh_offset;
-
-        tracing::warn!(
-            "{WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY} {:.2} minutes.",
-            refresh_offset.as_secs_f64() / 60.0,
-        );
-
-        new_expiry
-    }
-
     async fn retrieve_credentials(&self) -> provider::Result {
         if self.imds_disabled() {
             tracing::debug!(
@@ -235,16 +207,23 @@ impl ImdsCredentialsProvider {
                 secret_access_key,
                 session_token,
                 expiration,
-                ..
+                account_id,
             })) => {
-                let expiration = self.maybe_extend_expiration(expiration);
-                let creds = Credentials::new(
-                    access_key_id,
-                    secret_access_key,
-                    Some(session_token.to_string()),
-                    expiration.into(),
-                    "IMDSv2",
+                // This allows continued use of the credentials even when IMDS returns expired ones.
+                let expiration = maybe_extend_expiration(
+                    expiration,
+                    self.time_source.now(),
+                    CREDENTIAL_EXPIRATION_INTERVAL,
+                    CREDENTIAL_EXPIRATION_JITTER,
                 );
+                let mut builder = Credentials::builder()
+                    .access_key_id(access_key_id)
+                    .secret_access_key(secret_access_key)
+                    .session_token(session_token)
+                    .expiry(expiration)
+                    .provider_name("IMDSv2");
+                builder.set_account_id(account_id.map(Into::into));
+                let creds = builder.build();
                 *self.last_retrieved_credentials.write().unwrap() = Some(creds.clone());
                 Ok(creds)
             }
@@ -286,6 +265,7 @@ mod test {
     use crate::imds::client::test::{
         imds_request, imds_response, make_imds_client, token_request, token_response,
     };
+    use crate::meta::credentials::WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY;
     use crate::provider_config::ProviderConfig;
     use aws_credential_types::provider::ProvideCredentials;
     use aws_smithy_async::test_util::instant_time_and_sleep;
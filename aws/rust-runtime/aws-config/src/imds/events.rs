@@ -0,0 +1,639 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Spot interruption notices, rebalance recommendations and scheduled events
+//!
+//! EC2 publishes upcoming interruptions of an instance to IMDS. They can be checked once with
+//! [`Client::spot_instance_action`], [`Client::rebalance_recommendation`] and
+//! [`Client::scheduled_events`], or watched continuously with an [`InterruptionWatcher`].
+
+use crate::imds::client::error::ImdsError;
+use crate::imds::json;
+use crate::imds::Client;
+use crate::provider_config::ProviderConfig;
+use aws_smithy_async::future::pagination_stream::fn_stream::FnStream;
+use aws_smithy_async::future::pagination_stream::PaginationStream;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_json::deserialize::error::DeserializeError;
+use aws_smithy_json::deserialize::token::skip_value;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+
+const SPOT_INSTANCE_ACTION_PATH: &str = "/latest/meta-data/spot/instance-action";
+const REBALANCE_RECOMMENDATION_PATH: &str = "/latest/meta-data/events/recommendations/rebalance";
+const SCHEDULED_EVENTS_PATH: &str = "/latest/meta-data/events/maintenance/scheduled";
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The action EC2 will take when interrupting a Spot Instance
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SpotAction {
+    /// The instance will be terminated
+    Terminate,
+    /// The instance will be stopped
+    Stop,
+    /// The instance will be hibernated
+    Hibernate,
+    /// An action not known to this version of the SDK
+    Unknown(String),
+}
+
+impl SpotAction {
+    fn from_str(action: &str) -> Self {
+        match action {
+            "terminate" => Self::Terminate,
+            "stop" => Self::Stop,
+            "hibernate" => Self::Hibernate,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+
+    /// The string representation of the action used by IMDS
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Terminate => "terminate",
+            Self::Stop => "stop",
+            Self::Hibernate => "hibernate",
+            Self::Unknown(other) => other,
+        }
+    }
+}
+
+impl fmt::Display for SpotAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A Spot Instance interruption notice
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpotInstanceAction {
+    action: SpotAction,
+    time: SystemTime,
+}
+
+impl SpotInstanceAction {
+    fn parse(input: &str) -> Result<Self, DeserializeError> {
+        let (mut action, mut time) = (None, None);
+        let mut tokens = json::tokens(input);
+        json::read_object(&mut tokens, |key, tokens| {
+            match key {
+                "action" => action = json::string_or_null(tokens)?,
+                "time" => time = json::string_or_null(tokens)?,
+                _ => skip_value(tokens)?,
+            }
+            Ok(())
+        })?;
+        json::expect_end(&mut tokens)?;
+        Ok(Self {
+            action: SpotAction::from_str(&json::required(action, "action")?),
+            time: json::date_time(&json::required(time, "time")?)?,
+        })
+    }
+
+    /// The action that will be taken
+    pub fn action(&self) -> &SpotAction {
+        &self.action
+    }
+
+    /// The time at which the action will be taken
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+}
+
+/// A recommendation to rebalance a Spot Instance because it is at elevated risk of interruption
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RebalanceRecommendation {
+    notice_time: SystemTime,
+}
+
+impl RebalanceRecommendation {
+    fn parse(input: &str) -> Result<Self, DeserializeError> {
+        let mut notice_time = None;
+        let mut tokens = json::tokens(input);
+        json::read_object(&mut tokens, |key, tokens| {
+            match key {
+                "noticeTime" => notice_time = json::string_or_null(tokens)?,
+                _ => skip_value(tokens)?,
+            }
+            Ok(())
+        })?;
+        json::expect_end(&mut tokens)?;
+        Ok(Self {
+            notice_time: json::date_time(&json::required(notice_time, "noticeTime")?)?,
+        })
+    }
+
+    /// The time at which the recommendation was emitted
+    pub fn notice_time(&self) -> SystemTime {
+        self.notice_time
+    }
+}
+
+/// A scheduled event such as a reboot, stop or retirement of the instance
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScheduledEvent {
+    code: String,
+    description: String,
+    event_id: String,
+    state: String,
+    not_before: SystemTime,
+    not_after: Option<SystemTime>,
+    not_before_deadline: Option<SystemTime>,
+}
+
+impl ScheduledEvent {
+    fn parse_list(input: &str) -> Result<Vec<Self>, DeserializeError> {
+        let mut events = Vec::new();
+        let mut tokens = json::tokens(input);
+        json::read_array(&mut tokens, |tokens| {
+            let mut code = None;
+            let mut description = None;
+            let mut event_id = None;
+            let mut state = None;
+            let mut not_before = None;
+            let mut not_after = None;
+            let mut not_before_deadline = None;
+            json::read_object(tokens, |key, tokens| {
+                match key {
+                    "Code" => code = json::string_or_null(tokens)?,
+                    "Description" => description = json::string_or_null(tokens)?,
+                    "EventId" => event_id = json::string_or_null(tokens)?,
+                    "State" => state = json::string_or_null(tokens)?,
+                    "NotBefore" => not_before = event_time(tokens)?,
+                    "NotAfter" => not_after = event_time(tokens)?,
+                    "NotBeforeDeadline" => not_before_deadline = event_time(tokens)?,
+                    _ => skip_value(tokens)?,
+                }
+                Ok(())
+            })?;
+            events.push(Self {
+                code: json::required(code, "Code")?,
+                description: description.unwrap_or_default(),
+                event_id: json::required(event_id, "EventId")?,
+                state: json::required(state, "State")?,
+                not_before: json::required(not_before, "NotBefore")?,
+                not_after,
+                not_before_deadline,
+            });
+            Ok(())
+        })?;
+        json::expect_end(&mut tokens)?;
+        Ok(events)
+    }
+
+    /// The event code, e.g. `system-reboot` or `instance-retirement`
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// A description of the event
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The ID of the event
+    pub fn event_id(&self) -> &str {
+        &self.event_id
+    }
+
+    /// The state of the event, e.g. `active`, `completed` or `canceled`
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// The earliest time at which the event can start
+    pub fn not_before(&self) -> SystemTime {
+        self.not_before
+    }
+
+    /// The latest time at which the event can start, if known
+    pub fn not_after(&self) -> Option<SystemTime> {
+        self.not_after
+    }
+
+    /// The latest time to which the event can be rescheduled, if it can be rescheduled
+    pub fn not_before_deadline(&self) -> Option<SystemTime> {
+        self.not_before_deadline
+    }
+}
+
+/// Parses a scheduled event time like `21 Jan 2019 09:00:43 GMT`
+fn event_time(tokens: &mut json::Tokens<'_>) -> Result<Option<SystemTime>, DeserializeError> {
+    let value = match json::string_or_null(tokens)? {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let format = time::format_description::parse(
+        "[day padding:none] [month repr:short] [year] [hour]:[minute]:[second] GMT",
+    )
+    .expect("valid format description");
+    time::PrimitiveDateTime::parse(&value, &format)
+        .map(|time| Some(time.assume_utc().into()))
+        .map_err(|err| {
+            DeserializeError::custom_source(format!("`{value}` is not a valid event time"), err)
+        })
+}
+
+impl Client {
+    /// Retrieve the pending Spot Instance interruption, if any
+    ///
+    /// IMDS only reports an interruption during the two minutes before the instance is interrupted.
+    pub async fn spot_instance_action(&self) -> Result<Option<SpotInstanceAction>, ImdsError> {
+        match self.get_if_present(SPOT_INSTANCE_ACTION_PATH).await? {
+            Some(action) => SpotInstanceAction::parse(action.as_ref())
+                .map(Some)
+                .map_err(ImdsError::unexpected),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieve the rebalance recommendation of the instance, if any
+    pub async fn rebalance_recommendation(
+        &self,
+    ) -> Result<Option<RebalanceRecommendation>, ImdsError> {
+        match self.get_if_present(REBALANCE_RECOMMENDATION_PATH).await? {
+            Some(recommendation) => RebalanceRecommendation::parse(recommendation.as_ref())
+                .map(Some)
+                .map_err(ImdsError::unexpected),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieve the events scheduled for the instance
+    ///
+    /// The list includes completed and canceled events.
+    pub async fn scheduled_events(&self) -> Result<Vec<ScheduledEvent>, ImdsError> {
+        match self.get_if_present(SCHEDULED_EVENTS_PATH).await? {
+            Some(events) => {
+                ScheduledEvent::parse_list(events.as_ref()).map_err(ImdsError::unexpected)
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// An interruption reported by an [`InterruptionWatcher`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InterruptionEvent {
+    /// The Spot Instance will be interrupted
+    SpotInterruption(SpotInstanceAction),
+    /// The Spot Instance is at elevated risk of interruption
+    RebalanceRecommendation(RebalanceRecommendation),
+    /// An event was scheduled for the instance, or the state of a scheduled event changed
+    ScheduledEvent(ScheduledEvent),
+}
+
+/// A handle to stop an [`InterruptionWatcher`]
+///
+/// Cancelling ends every stream returned by [`InterruptionWatcher::watch`], including streams
+/// started after cancellation.
+#[derive(Clone, Debug)]
+pub struct CancelHandle {
+    sender: Arc<watch::Sender<bool>>,
+    receiver: watch::Receiver<bool>,
+}
+
+impl CancelHandle {
+    fn new() -> Self {
+        let (sender, receiver) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            receiver,
+        }
+    }
+
+    /// Stops the watcher
+    pub fn cancel(&self) {
+        // The handle holds a receiver, so sending can't fail
+        let _ = self.sender.send(true);
+    }
+
+    /// Returns `true` if the watcher has been stopped
+    pub fn is_cancelled(&self) -> bool {
+        *self.receiver.borrow()
+    }
+}
+
+/// Watches IMDS for Spot interruptions, rebalance recommendations and scheduled events
+///
+/// The watcher polls IMDS at a fixed interval and emits each notice once. Scheduled events are
+/// emitted again when their state changes, e.g. when an event is completed or canceled. Errors
+/// communicating with IMDS are emitted to the stream and polling continues.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::imds::events::{InterruptionEvent, InterruptionWatcher};
+/// use aws_config::provider_config::ProviderConfig;
+/// # async fn docs() {
+/// let watcher = InterruptionWatcher::builder()
+///     .configure(&ProviderConfig::default())
+///     .build();
+/// let mut events = watcher.watch();
+/// while let Some(event) = events.next().await {
+///     match event {
+///         Ok(InterruptionEvent::SpotInterruption(action)) => {
+///             println!("shutting down: the instance will {}", action.action());
+///             break;
+///         }
+///         Ok(other) => println!("{other:?}"),
+///         Err(err) => eprintln!("failed to poll IMDS: {err}"),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct InterruptionWatcher {
+    client: Client,
+    sleep_impl: SharedAsyncSleep,
+    poll_interval: Duration,
+    cancel_handle: CancelHandle,
+}
+
+impl InterruptionWatcher {
+    /// Builder for [`InterruptionWatcher`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns a handle that stops the watcher
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// Starts polling IMDS, returning a stream of interruptions
+    ///
+    /// The stream ends when the watcher is cancelled through its [`CancelHandle`].
+    pub fn watch(&self) -> PaginationStream<Result<InterruptionEvent, ImdsError>> {
+        let client = self.client.clone();
+        let sleep_impl = self.sleep_impl.clone();
+        let poll_interval = self.poll_interval;
+        let cancel_sender = self.cancel_handle.sender.clone();
+        let mut cancelled = self.cancel_handle.receiver.clone();
+        PaginationStream::new(FnStream::new(move |tx| {
+            Box::pin(async move {
+                // Keep the sender alive so that `changed` only completes on cancellation, even if
+                // the watcher is dropped while the stream is in use
+                let _cancel_sender = cancel_sender;
+                let mut seen = SeenEvents::default();
+                loop {
+                    if *cancelled.borrow() {
+                        return;
+                    }
+                    for event in seen.poll(&client).await {
+                        if tx.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    // Completes with `Ok` if the watcher was cancelled before the interval elapsed
+                    if Timeout::new(cancelled.changed(), sleep_impl.sleep(poll_interval))
+                        .await
+                        .is_ok()
+                    {
+                        return;
+                    }
+                }
+            })
+        }))
+    }
+}
+
+/// The notices that an [`InterruptionWatcher`] has already emitted
+#[derive(Default)]
+struct SeenEvents {
+    spot_instance_action: Option<SpotInstanceAction>,
+    rebalance_recommendation: Option<RebalanceRecommendation>,
+    scheduled_events: HashSet<(String, String)>,
+}
+
+impl SeenEvents {
+    async fn poll(&mut self, client: &Client) -> Vec<Result<InterruptionEvent, ImdsError>> {
+        let mut events = Vec::new();
+        match client.spot_instance_action().await {
+            Ok(Some(action)) if self.spot_instance_action.as_ref() != Some(&action) => {
+                self.spot_instance_action = Some(action.clone());
+                events.push(Ok(InterruptionEvent::SpotInterruption(action)));
+            }
+            Ok(_) => {}
+            Err(err) => events.push(Err(err)),
+        }
+        match client.rebalance_recommendation().await {
+            Ok(Some(recommendation))
+                if self.rebalance_recommendation.as_ref() != Some(&recommendation) =>
+            {
+                self.rebalance_recommendation = Some(recommendation.clone());
+                events.push(Ok(InterruptionEvent::RebalanceRecommendation(
+                    recommendation,
+                )));
+            }
+            Ok(_) => {}
+            Err(err) => events.push(Err(err)),
+        }
+        match client.scheduled_events().await {
+            Ok(scheduled_events) => {
+                for event in scheduled_events {
+                    let key = (event.event_id.clone(), event.state.clone());
+                    if self.scheduled_events.insert(key) {
+                        events.push(Ok(InterruptionEvent::ScheduledEvent(event)));
+                    }
+                }
+            }
+            Err(err) => events.push(Err(err)),
+        }
+        events
+    }
+}
+
+/// Builder for [`InterruptionWatcher`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    client: Option<Client>,
+    sleep_impl: Option<SharedAsyncSleep>,
+    poll_interval: Option<Duration>,
+    provider_config: Option<ProviderConfig>,
+}
+
+impl Builder {
+    /// Configure generic options of the [`InterruptionWatcher`]
+    ///
+    /// The sleep implementation and the settings of the default IMDS client are taken from the
+    /// provider config.
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Override the IMDS client used to poll for interruptions
+    pub fn imds_client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Override the interval between polls
+    ///
+    /// Defaults to 5 seconds. Spot interruption notices are issued two minutes before the
+    /// interruption.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = Some(poll_interval);
+        self
+    }
+
+    /// Override the sleep implementation used to wait between polls
+    pub fn sleep_impl(mut self, sleep_impl: impl AsyncSleep + 'static) -> Self {
+        self.sleep_impl = Some(SharedAsyncSleep::new(sleep_impl));
+        self
+    }
+
+    /// Create an [`InterruptionWatcher`] from this builder
+    ///
+    /// # Panics
+    ///
+    /// Panics if no sleep implementation was set, either directly or through the provider config.
+    pub fn build(self) -> InterruptionWatcher {
+        let provider_config = self.provider_config.unwrap_or_default();
+        let client = self
+            .client
+            .unwrap_or_else(|| Client::builder().configure(&provider_config).build());
+        let sleep_impl = self
+            .sleep_impl
+            .or_else(|| provider_config.sleep_impl())
+            .expect("an InterruptionWatcher requires a sleep implementation");
+        InterruptionWatcher {
+            client,
+            sleep_impl,
+            poll_interval: self.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL),
+            cancel_handle: CancelHandle::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::imds::client::test::{
+        imds_request, imds_response, make_imds_client, token_request, token_response,
+    };
+    use crate::imds::events::{InterruptionEvent, InterruptionWatcher, SpotAction};
+    use aws_smithy_async::test_util::InstantSleep;
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use aws_smithy_types::body::SdkBody;
+
+    const TOKEN: &str = "token";
+    const SCHEDULED_EVENTS: &str = r#"[
+        {
+            "NotBefore" : "21 Jan 2019 09:00:43 GMT",
+            "Code" : "system-reboot",
+            "Description" : "scheduled reboot",
+            "EventId" : "instance-event-0d59937288b749b32",
+            "NotAfter" : "21 Jan 2019 09:17:23 GMT",
+            "State" : "active"
+        }
+    ]"#;
+
+    fn not_found(path: &'static str) -> ReplayEvent {
+        ReplayEvent::new(
+            imds_request(path, TOKEN),
+            http::Response::builder()
+                .status(404)
+                .body(SdkBody::empty())
+                .unwrap(),
+        )
+    }
+
+    fn found(path: &'static str, body: &'static str) -> ReplayEvent {
+        ReplayEvent::new(imds_request(path, TOKEN), imds_response(body))
+    }
+
+    #[tokio::test]
+    async fn watcher_emits_each_notice_once_until_cancelled() {
+        let http_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(
+                token_request("http://169.254.169.254", 21600),
+                token_response(21600, TOKEN),
+            ),
+            not_found("http://169.254.169.254/latest/meta-data/spot/instance-action"),
+            not_found("http://169.254.169.254/latest/meta-data/events/recommendations/rebalance"),
+            found(
+                "http://169.254.169.254/latest/meta-data/events/maintenance/scheduled",
+                SCHEDULED_EVENTS,
+            ),
+            found(
+                "http://169.254.169.254/latest/meta-data/spot/instance-action",
+                r#"{"action": "stop", "time": "2017-09-18T08:22:00Z"}"#,
+            ),
+            not_found("http://169.254.169.254/latest/meta-data/events/recommendations/rebalance"),
+            found(
+                "http://169.254.169.254/latest/meta-data/events/maintenance/scheduled",
+                SCHEDULED_EVENTS,
+            ),
+        ]);
+        let watcher = InterruptionWatcher::builder()
+            .imds_client(make_imds_client(&http_client))
+            .sleep_impl(InstantSleep::unlogged())
+            .build();
+        let cancel_handle = watcher.cancel_handle();
+        let mut events = watcher.watch();
+
+        match events.next().await.expect("an event").expect("success") {
+            InterruptionEvent::ScheduledEvent(event) => {
+                assert_eq!("system-reboot", event.code());
+                assert_eq!("instance-event-0d59937288b749b32", event.event_id());
+            }
+            other => panic!("expected a scheduled event, got {other:?}"),
+        }
+        // The scheduled event is returned again by the second poll but isn't emitted twice
+        match events.next().await.expect("an event").expect("success") {
+            InterruptionEvent::SpotInterruption(action) => {
+                assert_eq!(&SpotAction::Stop, action.action())
+            }
+            other => panic!("expected a spot interruption, got {other:?}"),
+        }
+
+        cancel_handle.cancel();
+        assert!(cancel_handle.is_cancelled());
+        assert!(events.next().await.is_none());
+        http_client.assert_requests_match(&[]);
+    }
+
+    #[tokio::test]
+    async fn watcher_reports_errors_and_keeps_polling() {
+        let http_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(
+                token_request("http://169.254.169.254", 21600),
+                token_response(21600, TOKEN),
+            ),
+            found(
+                "http://169.254.169.254/latest/meta-data/spot/instance-action",
+                "not json",
+            ),
+            not_found("http://169.254.169.254/latest/meta-data/events/recommendations/rebalance"),
+            not_found("http://169.254.169.254/latest/meta-data/events/maintenance/scheduled"),
+            found(
+                "http://169.254.169.254/latest/meta-data/spot/instance-action",
+                r#"{"action": "terminate", "time": "2017-09-18T08:22:00Z"}"#,
+            ),
+        ]);
+        let watcher = InterruptionWatcher::builder()
+            .imds_client(make_imds_client(&http_client))
+            .sleep_impl(InstantSleep::unlogged())
+            .build();
+        let mut events = watcher.watch();
+
+        assert!(events.next().await.expect("an event").is_err());
+        match events.next().await.expect("an event").expect("success") {
+            InterruptionEvent::SpotInterruption(action) => {
+                assert_eq!(&SpotAction::Terminate, action.action())
+            }
+            other => panic!("expected a spot interruption, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,395 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! The instance identity document
+//!
+//! The [instance identity document](https://docs.aws.amazon.com/AWSEC2/latest/UserGuide/instance-identity-documents.html)
+//! describes an EC2 instance: its ID, account, region, AMI and so on. IMDS also serves a PKCS#7
+//! signature of the document that can be verified against the public AWS certificate for the
+//! region to prove that the document was produced by EC2. Signature verification requires the
+//! `imds-verification` feature.
+
+use crate::imds::client::error::ImdsError;
+use crate::imds::json;
+use crate::imds::Client;
+use aws_smithy_json::deserialize::error::DeserializeError;
+use aws_smithy_json::deserialize::token::skip_value;
+use std::time::SystemTime;
+
+const DOCUMENT_PATH: &str = "/latest/dynamic/instance-identity/document";
+
+/// The instance identity document of an EC2 instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstanceIdentityDocument {
+    account_id: String,
+    architecture: String,
+    availability_zone: String,
+    billing_products: Vec<String>,
+    image_id: String,
+    instance_id: String,
+    instance_type: String,
+    kernel_id: Option<String>,
+    marketplace_product_codes: Vec<String>,
+    pending_time: SystemTime,
+    private_ip: String,
+    ramdisk_id: Option<String>,
+    region: String,
+    version: String,
+}
+
+impl InstanceIdentityDocument {
+    fn parse(document: &str) -> Result<Self, DeserializeError> {
+        let mut account_id = None;
+        let mut architecture = None;
+        let mut availability_zone = None;
+        let mut billing_products = Vec::new();
+        let mut image_id = None;
+        let mut instance_id = None;
+        let mut instance_type = None;
+        let mut kernel_id = None;
+        let mut marketplace_product_codes = Vec::new();
+        let mut pending_time = None;
+        let mut private_ip = None;
+        let mut ramdisk_id = None;
+        let mut region = None;
+        let mut version = None;
+
+        let mut tokens = json::tokens(document);
+        json::read_object(&mut tokens, |key, tokens| {
+            match key {
+                "accountId" => account_id = json::string_or_null(tokens)?,
+                "architecture" => architecture = json::string_or_null(tokens)?,
+                "availabilityZone" => availability_zone = json::string_or_null(tokens)?,
+                "billingProducts" => billing_products = json::string_list(tokens)?,
+                "imageId" => image_id = json::string_or_null(tokens)?,
+                "instanceId" => instance_id = json::string_or_null(tokens)?,
+                "instanceType" => instance_type = json::string_or_null(tokens)?,
+                "kernelId" => kernel_id = json::string_or_null(tokens)?,
+                "marketplaceProductCodes" => marketplace_product_codes = json::string_list(tokens)?,
+                "pendingTime" => {
+                    pending_time = json::string_or_null(tokens)?
+                        .map(|time| json::date_time(&time))
+                        .transpose()?
+                }
+                "privateIp" => private_ip = json::string_or_null(tokens)?,
+                "ramdiskId" => ramdisk_id = json::string_or_null(tokens)?,
+                "region" => region = json::string_or_null(tokens)?,
+                "version" => version = json::string_or_null(tokens)?,
+                _ => skip_value(tokens)?,
+            }
+            Ok(())
+        })?;
+        json::expect_end(&mut tokens)?;
+
+        Ok(Self {
+            account_id: json::required(account_id, "accountId")?,
+            architecture: json::required(architecture, "architecture")?,
+            availability_zone: json::required(availability_zone, "availabilityZone")?,
+            billing_products,
+            image_id: json::required(image_id, "imageId")?,
+            instance_id: json::required(instance_id, "instanceId")?,
+            instance_type: json::required(instance_type, "instanceType")?,
+            kernel_id,
+            marketplace_product_codes,
+            pending_time: json::required(pending_time, "pendingTime")?,
+            private_ip: json::required(private_ip, "privateIp")?,
+            ramdisk_id,
+            region: json::required(region, "region")?,
+            version: json::required(version, "version")?,
+        })
+    }
+
+    /// The ID of the AWS account that launched the instance
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// The architecture of the AMI used to launch the instance, e.g. `x86_64` or `arm64`
+    pub fn architecture(&self) -> &str {
+        &self.architecture
+    }
+
+    /// The Availability Zone in which the instance is running
+    pub fn availability_zone(&self) -> &str {
+        &self.availability_zone
+    }
+
+    /// The billing products of the instance
+    pub fn billing_products(&self) -> &[String] {
+        &self.billing_products
+    }
+
+    /// The ID of the AMI used to launch the instance
+    pub fn image_id(&self) -> &str {
+        &self.image_id
+    }
+
+    /// The ID of the instance
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// The instance type of the instance, e.g. `t3.micro`
+    pub fn instance_type(&self) -> &str {
+        &self.instance_type
+    }
+
+    /// The ID of the kernel associated with the instance, if any
+    pub fn kernel_id(&self) -> Option<&str> {
+        self.kernel_id.as_deref()
+    }
+
+    /// The AWS Marketplace product codes of the AMI used to launch the instance
+    pub fn marketplace_product_codes(&self) -> &[String] {
+        &self.marketplace_product_codes
+    }
+
+    /// The time at which the instance was launched
+    pub fn pending_time(&self) -> SystemTime {
+        self.pending_time
+    }
+
+    /// The private IPv4 address of the instance
+    pub fn private_ip(&self) -> &str {
+        &self.private_ip
+    }
+
+    /// The ID of the RAM disk associated with the instance, if any
+    pub fn ramdisk_id(&self) -> Option<&str> {
+        self.ramdisk_id.as_deref()
+    }
+
+    /// The region in which the instance is running
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// The version of the instance identity document format
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+impl Client {
+    /// Retrieve and parse the instance identity document
+    ///
+    /// The document is not verified. Use `verified_instance_identity_document` (requires the
+    /// `imds-verification` feature) when the document must be proven to originate from EC2.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aws_config::imds::client::Client;
+    /// # async fn docs() {
+    /// let client = Client::builder().build();
+    /// let document = client
+    ///   .instance_identity_document()
+    ///   .await
+    ///   .expect("failure communicating with IMDS");
+    /// println!("running {} in {}", document.instance_id(), document.region());
+    /// # }
+    /// ```
+    pub async fn instance_identity_document(&self) -> Result<InstanceIdentityDocument, ImdsError> {
+        let document = self.get(DOCUMENT_PATH).await?;
+        InstanceIdentityDocument::parse(document.as_ref()).map_err(ImdsError::unexpected)
+    }
+}
+
+#[cfg(feature = "imds-verification")]
+pub use verification::{IdentityCertificate, IdentityVerificationError};
+
+#[cfg(feature = "imds-verification")]
+mod verification {
+    use super::InstanceIdentityDocument;
+    use crate::imds::client::error::ImdsError;
+    use crate::imds::pkcs7;
+    use crate::imds::Client;
+    use aws_sigv4::sign::x509::asn1;
+    use aws_smithy_types::base64;
+    use std::error::Error;
+    use std::fmt;
+
+    const SIGNATURE_PATH: &str = "/latest/dynamic/instance-identity/rsa2048";
+
+    /// The AWS public certificate used to verify instance identity documents
+    ///
+    /// The certificate differs between regions. The certificates for each region are listed under
+    /// [Verify the instance identity document](https://docs.aws.amazon.com/AWSEC2/latest/UserGuide/verify-rsa2048.html)
+    /// in the EC2 user guide. Use the RSA-2048 certificate for the region the instance runs in.
+    #[derive(Clone)]
+    pub struct IdentityCertificate {
+        public_key: Vec<u8>,
+    }
+
+    impl fmt::Debug for IdentityCertificate {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("IdentityCertificate")
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl IdentityCertificate {
+        /// Loads a PEM-encoded X.509 certificate
+        pub fn from_pem(pem: &str) -> Result<Self, IdentityVerificationError> {
+            let (_, certificate) = asn1::pem_blocks(pem.as_bytes())
+                .ok()
+                .and_then(|blocks| blocks.into_iter().find(|(label, _)| label == "CERTIFICATE"))
+                .ok_or_else(|| {
+                    IdentityVerificationError::invalid_certificate(
+                        "the certificate is not valid PEM",
+                    )
+                })?;
+            Self::from_der(&certificate)
+        }
+
+        /// Loads a DER-encoded X.509 certificate
+        pub fn from_der(der: &[u8]) -> Result<Self, IdentityVerificationError> {
+            let public_key = pkcs7::rsa_public_key(der)
+                .map_err(IdentityVerificationError::invalid_certificate)?;
+            Ok(Self { public_key })
+        }
+    }
+
+    impl InstanceIdentityDocument {
+        /// Verifies a PKCS#7 signature of an instance identity document, returning the signed document
+        ///
+        /// `signature` is the base64-encoded signature returned by IMDS at
+        /// `/latest/dynamic/instance-identity/rsa2048`. The signature embeds the document that was
+        /// signed so the document returned here can be trusted, unlike one retrieved separately.
+        pub fn verify(
+            signature: &str,
+            certificate: &IdentityCertificate,
+        ) -> Result<Self, IdentityVerificationError> {
+            let signature = decode_base64(signature).ok_or_else(|| {
+                IdentityVerificationError::invalid_signature("the signature is not valid base64")
+            })?;
+            let document = pkcs7::verify_signed_data(&signature, &certificate.public_key)
+                .map_err(IdentityVerificationError::invalid_signature)?;
+            let document = std::str::from_utf8(&document).map_err(|_| {
+                IdentityVerificationError::invalid_document("the document is not valid UTF-8")
+            })?;
+            Self::parse(document).map_err(IdentityVerificationError::invalid_document)
+        }
+    }
+
+    impl Client {
+        /// Retrieve the instance identity document and verify its signature
+        ///
+        /// The document is taken from the signature served by IMDS so that only signed content is
+        /// returned.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use aws_config::imds::client::Client;
+        /// use aws_config::imds::identity::IdentityCertificate;
+        /// # async fn docs(certificate_pem: &str) {
+        /// let certificate = IdentityCertificate::from_pem(certificate_pem).expect("valid certificate");
+        /// let client = Client::builder().build();
+        /// let document = client
+        ///   .verified_instance_identity_document(&certificate)
+        ///   .await
+        ///   .expect("the document could not be verified");
+        /// # }
+        /// ```
+        pub async fn verified_instance_identity_document(
+            &self,
+            certificate: &IdentityCertificate,
+        ) -> Result<InstanceIdentityDocument, IdentityVerificationError> {
+            let signature = self
+                .get(SIGNATURE_PATH)
+                .await
+                .map_err(IdentityVerificationError::imds)?;
+            InstanceIdentityDocument::verify(signature.as_ref(), certificate)
+        }
+    }
+
+    /// Decodes base64 content, ignoring whitespace and PEM armor lines
+    fn decode_base64(input: &str) -> Option<Vec<u8>> {
+        let encoded: String = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        base64::decode(encoded).ok()
+    }
+
+    #[derive(Debug)]
+    enum VerificationErrorKind {
+        Imds(ImdsError),
+        InvalidCertificate(&'static str),
+        InvalidSignature(&'static str),
+        InvalidDocument(Box<dyn Error + Send + Sync>),
+    }
+
+    /// An error verifying the instance identity document
+    #[derive(Debug)]
+    pub struct IdentityVerificationError {
+        kind: VerificationErrorKind,
+    }
+
+    impl IdentityVerificationError {
+        fn imds(source: ImdsError) -> Self {
+            Self {
+                kind: VerificationErrorKind::Imds(source),
+            }
+        }
+
+        fn invalid_certificate(message: &'static str) -> Self {
+            Self {
+                kind: VerificationErrorKind::InvalidCertificate(message),
+            }
+        }
+
+        fn invalid_signature(message: &'static str) -> Self {
+            Self {
+                kind: VerificationErrorKind::InvalidSignature(message),
+            }
+        }
+
+        fn invalid_document(source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+            Self {
+                kind: VerificationErrorKind::InvalidDocument(source.into()),
+            }
+        }
+
+        /// Returns `true` if the signature did not match the certificate or the signed content
+        ///
+        /// This indicates that the document was not produced by EC2 or was signed for a different
+        /// region than the certificate.
+        pub fn is_invalid_signature(&self) -> bool {
+            matches!(self.kind, VerificationErrorKind::InvalidSignature(_))
+        }
+    }
+
+    impl fmt::Display for IdentityVerificationError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.kind {
+                VerificationErrorKind::Imds(_) => {
+                    write!(f, "failed to load the instance identity signature")
+                }
+                VerificationErrorKind::InvalidCertificate(message) => {
+                    write!(f, "invalid identity certificate: {message}")
+                }
+                VerificationErrorKind::InvalidSignature(message) => {
+                    write!(f, "invalid instance identity signature: {message}")
+                }
+                VerificationErrorKind::InvalidDocument(_) => {
+                    write!(f, "the signed instance identity document is invalid")
+                }
+            }
+        }
+    }
+
+    impl Error for IdentityVerificationError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match &self.kind {
+                VerificationErrorKind::Imds(source) => Some(source),
+                VerificationErrorKind::InvalidDocument(source) => Some(source.as_ref()),
+                _ => None,
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Helpers for parsing the JSON documents returned by IMDS
+
+use aws_smithy_json::deserialize::error::DeserializeError;
+use aws_smithy_json::deserialize::token::{
+    expect_start_array, expect_start_object, expect_string_or_null,
+};
+use aws_smithy_json::deserialize::{json_token_iter, JsonTokenIterator, Token};
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::DateTime;
+use std::iter::Peekable;
+use std::time::SystemTime;
+
+pub(super) type Tokens<'a> = Peekable<JsonTokenIterator<'a>>;
+
+pub(super) fn tokens(input: &str) -> Tokens<'_> {
+    json_token_iter(input.as_bytes()).peekable()
+}
+
+/// Calls `f` with each key of the object at the front of `tokens`. `f` must consume the key's value.
+pub(super) fn read_object<'a>(
+    tokens: &mut Tokens<'a>,
+    mut f: impl FnMut(&str, &mut Tokens<'a>) -> Result<(), DeserializeError>,
+) -> Result<(), DeserializeError> {
+    expect_start_object(tokens.next())?;
+    loop {
+        match tokens.next().transpose()? {
+            Some(Token::EndObject { .. }) => return Ok(()),
+            Some(Token::ObjectKey { key, .. }) => f(&key.to_unescaped()?, tokens)?,
+            _ => return Err(DeserializeError::custom("expected an object key")),
+        }
+    }
+}
+
+/// Calls `f` for each element of the array at the front of `tokens`. `f` must consume the element.
+///
+/// `null` is treated as an empty array.
+pub(super) fn read_array<'a>(
+    tokens: &mut Tokens<'a>,
+    mut f: impl FnMut(&mut Tokens<'a>) -> Result<(), DeserializeError>,
+) -> Result<(), DeserializeError> {
+    if let Some(Ok(Token::ValueNull { .. })) = tokens.peek() {
+        tokens.next();
+        return Ok(());
+    }
+    expect_start_array(tokens.next())?;
+    loop {
+        if let Some(Ok(Token::EndArray { .. })) = tokens.peek() {
+            tokens.next();
+            return Ok(());
+        }
+        f(tokens)?;
+    }
+}
+
+pub(super) fn string_or_null(tokens: &mut Tokens<'_>) -> Result<Option<String>, DeserializeError> {
+    Ok(match expect_string_or_null(tokens.next())? {
+        Some(value) => Some(value.to_unescaped()?.into_owned()),
+        None => None,
+    })
+}
+
+pub(super) fn string_list(tokens: &mut Tokens<'_>) -> Result<Vec<String>, DeserializeError> {
+    let mut values = Vec::new();
+    read_array(tokens, |tokens| {
+        values.extend(string_or_null(tokens)?);
+        Ok(())
+    })?;
+    Ok(values)
+}
+
+pub(super) fn expect_end(tokens: &mut Tokens<'_>) -> Result<(), DeserializeError> {
+    match tokens.next() {
+        None => Ok(()),
+        Some(_) => Err(DeserializeError::custom(
+            "found more JSON tokens after completing parsing",
+        )),
+    }
+}
+
+pub(super) fn required<T>(value: Option<T>, field: &'static str) -> Result<T, DeserializeError> {
+    value.ok_or_else(|| DeserializeError::custom(format!("missing field `{field}`")))
+}
+
+/// Parses an RFC-3339 timestamp like `2024-11-19T16:32:11Z`
+pub(super) fn date_time(value: &str) -> Result<SystemTime, DeserializeError> {
+    DateTime::from_str(value, Format::DateTime)
+        .ok()
+        .and_then(|date_time| SystemTime::try_from(date_time).ok())
+        .ok_or_else(|| DeserializeError::custom(format!("`{value}` is not a valid timestamp")))
+}
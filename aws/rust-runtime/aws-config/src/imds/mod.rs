@@ -3,12 +3,17 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! IMDSv2 Client, credential, and region provider
+//! IMDSv2 Client, credential and region providers, and typed instance metadata
 //!
 //! See [`client`] for more information.
 pub mod client;
 
 pub mod credentials;
+pub mod events;
+pub mod identity;
+mod json;
+#[cfg(feature = "imds-verification")]
+mod pkcs7;
 pub mod region;
 
 mod env {
@@ -17,3 +22,123 @@ mod env {
 
 #[doc(inline)]
 pub use client::Client;
+
+#[cfg(test)]
+mod test {
+    use crate::imds::client::error::ImdsError;
+    use crate::imds::identity::InstanceIdentityDocument;
+    use crate::imds::Client;
+    use crate::provider_config::ProviderConfig;
+    use aws_smithy_types::date_time::Format;
+    use aws_smithy_types::DateTime;
+    use serde_json::{json, Value};
+    use std::time::SystemTime;
+
+    /// Runs the test case in `test-data/imds/$name`, converting the output with `$run`
+    macro_rules! make_test {
+        ($name:ident, $run:expr $(, #[$m:meta])*) => {
+            $(#[$m])*
+            #[tokio::test]
+            async fn $name() {
+                let _ = crate::test_case::TestEnvironment::from_dir(
+                    concat!("./test-data/imds/", stringify!($name)),
+                    crate::test_case::test_json_provider(|config: ProviderConfig| async move {
+                        $run(Client::builder().configure(&config).build()).await
+                    }),
+                )
+                .await
+                .unwrap()
+                .execute()
+                .await;
+            }
+        };
+    }
+
+    fn timestamp(time: SystemTime) -> Value {
+        json!(DateTime::from(time).fmt(Format::DateTime).unwrap())
+    }
+
+    fn document_json(document: InstanceIdentityDocument) -> Value {
+        json!({
+            "account_id": document.account_id(),
+            "architecture": document.architecture(),
+            "availability_zone": document.availability_zone(),
+            "billing_products": document.billing_products(),
+            "image_id": document.image_id(),
+            "instance_id": document.instance_id(),
+            "instance_type": document.instance_type(),
+            "kernel_id": document.kernel_id(),
+            "marketplace_product_codes": document.marketplace_product_codes(),
+            "pending_time": timestamp(document.pending_time()),
+            "private_ip": document.private_ip(),
+            "ramdisk_id": document.ramdisk_id(),
+            "region": document.region(),
+            "version": document.version(),
+        })
+    }
+
+    async fn load_interruption_notices(client: Client) -> Result<Value, ImdsError> {
+        let spot_instance_action = client.spot_instance_action().await?.map(|action| {
+            json!({ "action": action.action().as_str(), "time": timestamp(action.time()) })
+        });
+        let rebalance_recommendation = client.rebalance_recommendation().await?.map(
+            |recommendation| json!({ "notice_time": timestamp(recommendation.notice_time()) }),
+        );
+        let scheduled_events: Vec<Value> = client
+            .scheduled_events()
+            .await?
+            .iter()
+            .map(|event| {
+                json!({
+                    "code": event.code(),
+                    "description": event.description(),
+                    "event_id": event.event_id(),
+                    "state": event.state(),
+                    "not_before": timestamp(event.not_before()),
+                    "not_after": event.not_after().map(timestamp),
+                    "not_before_deadline": event.not_before_deadline().map(timestamp),
+                })
+            })
+            .collect();
+        Ok(json!({
+            "spot_instance_action": spot_instance_action,
+            "rebalance_recommendation": rebalance_recommendation,
+            "scheduled_events": scheduled_events,
+        }))
+    }
+
+    #[cfg(feature = "imds-verification")]
+    async fn load_verified_identity_document(
+        client: Client,
+    ) -> Result<Value, crate::imds::identity::IdentityVerificationError> {
+        let certificate = crate::imds::identity::IdentityCertificate::from_pem(
+            &std::fs::read_to_string("./test-data/imds/certificate.pem").unwrap(),
+        )?;
+        client
+            .verified_instance_identity_document(&certificate)
+            .await
+            .map(document_json)
+    }
+
+    make_test!(identity_document, |client: Client| async move {
+        client.instance_identity_document().await.map(document_json)
+    });
+    make_test!(
+        verified_identity_document,
+        load_verified_identity_document,
+        #[cfg(feature = "imds-verification")]
+    );
+    make_test!(
+        verified_identity_document_wrong_certificate,
+        load_verified_identity_document,
+        #[cfg(feature = "imds-verification")]
+    );
+    make_test!(instance_tags, |client: Client| async move {
+        client.instance_tags().await.map(|tags| json!(tags))
+    });
+    make_test!(instance_tags_disabled, |client: Client| async move {
+        client.instance_tags().await.map(|tags| json!(tags))
+    });
+    make_test!(interruption_notices, load_interruption_notices);
+    make_test!(no_interruption_notices, load_interruption_notices);
+}
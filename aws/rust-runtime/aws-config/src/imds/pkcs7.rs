@@ -0,0 +1,189 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Just enough PKCS#7 and X.509 parsing to verify the signature of the instance identity document
+//!
+//! IMDS returns the signature as a PKCS#7 `SignedData` structure that embeds the signed document. The
+//! structure may use BER indefinite-length encoding, so both BER and DER are accepted.
+
+use aws_sigv4::sign::x509::asn1::{
+    Element, Elements, BIT_STRING, CONSTRUCTED_OCTET_STRING, CONTEXT_0, CONTEXT_1, INTEGER,
+    MALFORMED, MAX_DEPTH, OCTET_STRING, OID, SEQUENCE, SET,
+};
+use ring::digest::{digest, SHA256};
+use ring::signature::{UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256};
+
+// 1.2.840.113549.1.7.2
+const OID_SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+// 1.2.840.113549.1.7.1
+const OID_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x01];
+// 2.16.840.1.101.3.4.2.1
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+// 1.2.840.113549.1.1.1
+const OID_RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+// 1.2.840.113549.1.1.11
+const OID_SHA256_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+// 1.2.840.113549.1.9.4
+const OID_MESSAGE_DIGEST: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x04];
+
+/// Returns the RSA public key (a DER `RSAPublicKey`) of a DER-encoded X.509 certificate
+pub(super) fn rsa_public_key(certificate: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut certificate = Elements::new(Elements::new(certificate).expect(SEQUENCE)?.contents);
+    let mut tbs_certificate = Elements::new(certificate.expect(SEQUENCE)?.contents);
+    tbs_certificate.optional(CONTEXT_0)?; // version
+    tbs_certificate.expect(INTEGER)?; // serial number
+    tbs_certificate.expect(SEQUENCE)?; // signature algorithm
+    tbs_certificate.expect(SEQUENCE)?; // issuer
+    tbs_certificate.expect(SEQUENCE)?; // validity
+    tbs_certificate.expect(SEQUENCE)?; // subject
+    let mut public_key_info = Elements::new(tbs_certificate.expect(SEQUENCE)?.contents);
+    let mut algorithm = Elements::new(public_key_info.expect(SEQUENCE)?.contents);
+    if algorithm.expect(OID)?.contents != OID_RSA_ENCRYPTION {
+        return Err("the certificate does not contain an RSA public key");
+    }
+    match public_key_info.expect(BIT_STRING)?.contents.split_first() {
+        Some((0, public_key)) => Ok(public_key.to_vec()),
+        _ => Err(MALFORMED),
+    }
+}
+
+/// Verifies the signature of a PKCS#7 `SignedData` structure, returning the content that was signed
+pub(super) fn verify_signed_data(
+    signed_data: &[u8],
+    public_key: &[u8],
+) -> Result<Vec<u8>, &'static str> {
+    let mut content_info = Elements::new(Elements::new(signed_data).expect(SEQUENCE)?.contents);
+    if content_info.expect(OID)?.contents != OID_SIGNED_DATA {
+        return Err("the signature is not PKCS#7 signed data");
+    }
+    let mut explicit = Elements::new(content_info.expect(CONTEXT_0)?.contents);
+    let mut signed_data = Elements::new(explicit.expect(SEQUENCE)?.contents);
+    signed_data.expect(INTEGER)?; // version
+    signed_data.expect(SET)?; // digest algorithms
+
+    let mut encapsulated_content = Elements::new(signed_data.expect(SEQUENCE)?.contents);
+    if encapsulated_content.expect(OID)?.contents != OID_DATA {
+        return Err("the signed content is not data");
+    }
+    let mut explicit = Elements::new(encapsulated_content.expect(CONTEXT_0)?.contents);
+    let content = octet_string(explicit.next_element()?, 0)?;
+
+    signed_data.optional(CONTEXT_0)?; // certificates
+    signed_data.optional(CONTEXT_1)?; // certificate revocation lists
+    let mut signer_infos = Elements::new(signed_data.expect(SET)?.contents);
+    let mut signer_info = Elements::new(signer_infos.expect(SEQUENCE)?.contents);
+    signer_info.expect(INTEGER)?; // version
+    signer_info.next_element()?; // signer identifier
+    let mut digest_algorithm = Elements::new(signer_info.expect(SEQUENCE)?.contents);
+    if digest_algorithm.expect(OID)?.contents != OID_SHA256 {
+        return Err("unsupported digest algorithm; only SHA-256 is supported");
+    }
+    let signed_attributes = signer_info.optional(CONTEXT_0)?;
+    let mut signature_algorithm = Elements::new(signer_info.expect(SEQUENCE)?.contents);
+    let algorithm = signature_algorithm.expect(OID)?.contents;
+    if algorithm != OID_RSA_ENCRYPTION && algorithm != OID_SHA256_WITH_RSA {
+        return Err("unsupported signature algorithm; only RSA is supported");
+    }
+    let signature = signer_info.expect(OCTET_STRING)?.contents;
+
+    let message = match signed_attributes {
+        Some(attributes) => {
+            if message_digest(attributes.contents)? != digest(&SHA256, &content).as_ref() {
+                return Err("the message digest does not match the signed content");
+            }
+            // The signature covers the attributes encoded as a SET rather than as the implicitly tagged field
+            let mut message = attributes.encoded.to_vec();
+            message[0] = SET;
+            message
+        }
+        None => content.clone(),
+    };
+    UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, public_key)
+        .verify(&message, signature)
+        .map_err(|_| "the signature was not created by the certificate's private key")?;
+    Ok(content)
+}
+
+/// Finds the value of the `messageDigest` attribute among signed attributes
+fn message_digest(attributes: &[u8]) -> Result<&[u8], &'static str> {
+    let mut attributes = Elements::new(attributes);
+    while let Some(attribute) = attributes.next_optional()? {
+        let mut attribute = Elements::new(attribute.contents);
+        if attribute.expect(OID)?.contents == OID_MESSAGE_DIGEST {
+            let mut values = Elements::new(attribute.expect(SET)?.contents);
+            return Ok(values.expect(OCTET_STRING)?.contents);
+        }
+    }
+    Err("the signed attributes do not include a message digest")
+}
+
+/// Returns the contents of an octet string, joining the segments of a constructed octet string
+///
+/// Constructed octet strings may be nested up to [`MAX_DEPTH`] deep.
+fn octet_string(element: Element<'_>, depth: usize) -> Result<Vec<u8>, &'static str> {
+    if depth > MAX_DEPTH {
+        return Err(MALFORMED);
+    }
+    match element.tag {
+        OCTET_STRING => Ok(element.contents.to_vec()),
+        CONSTRUCTED_OCTET_STRING => {
+            let mut contents = Vec::new();
+            let mut segments = Elements::new(element.contents);
+            while let Some(segment) = segments.next_optional()? {
+                contents.extend(octet_string(segment, depth + 1)?);
+            }
+            Ok(contents)
+        }
+        _ => Err(MALFORMED),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{octet_string, verify_signed_data, MALFORMED, MAX_DEPTH};
+    use aws_sigv4::sign::x509::asn1::read_element;
+
+    /// Encodes the tag and four-byte definite length of an element
+    fn header(tag: u8, length: usize) -> Vec<u8> {
+        let mut header = vec![tag, 0x84];
+        header.extend_from_slice(&(length as u32).to_be_bytes());
+        header
+    }
+
+    #[test]
+    fn deeply_nested_signed_data_is_rejected() {
+        let nested = |depth: usize| {
+            let mut input = [0x30, 0x80].repeat(depth);
+            input.extend(vec![0; depth * 2]);
+            input
+        };
+        // Deep enough to overflow the stack without a limit
+        let signed_data = nested(1_000_000);
+        assert_eq!(Err(MALFORMED), verify_signed_data(&signed_data, &[]));
+    }
+
+    #[test]
+    fn deeply_nested_octet_strings_are_rejected() {
+        // `depth` constructed octet strings wrapped around a primitive one
+        let nested = |depth: usize| {
+            let mut content = header(0x04, 7);
+            content.extend_from_slice(b"content");
+            let mut input = Vec::new();
+            for level in (0..depth).rev() {
+                input.extend(header(0x24, level * 6 + content.len()));
+            }
+            input.extend(content);
+            input
+        };
+        let octet_string_at = |depth: usize| {
+            let input = nested(depth);
+            let (element, _) = read_element(&input).unwrap();
+            octet_string(element, 0)
+        };
+        assert_eq!(Ok(b"content".to_vec()), octet_string_at(MAX_DEPTH));
+        assert_eq!(Err(MALFORMED), octet_string_at(MAX_DEPTH + 1));
+        assert_eq!(Err(MALFORMED), octet_string_at(100_000));
+    }
+}
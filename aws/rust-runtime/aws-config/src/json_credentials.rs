@@ -68,6 +68,7 @@ pub(crate) struct RefreshableCredentials<'a> {
     pub(crate) secret_access_key: Cow<'a, str>,
     pub(crate) session_token: Cow<'a, str>,
     pub(crate) expiration: SystemTime,
+    pub(crate) account_id: Option<Cow<'a, str>>,
 }
 
 impl<'a> fmt::Debug for RefreshableCredentials<'a> {
@@ -77,6 +78,7 @@ impl<'a> fmt::Debug for RefreshableCredentials<'a> {
             .field("secret_access_key", &"** redacted **")
             .field("session_token", &"** redacted **")
             .field("expiration", &self.expiration)
+            .field("account_id", &self.account_id)
             .finish()
     }
 }
@@ -126,6 +128,7 @@ pub(crate) fn parse_json_credentials(
     let mut secret_access_key = None;
     let mut session_token = None;
     let mut expiration = None;
+    let mut account_id = None;
     let mut message = None;
     json_parse_loop(credentials_response.as_bytes(), |key, value| {
         match (key, value) {
@@ -136,7 +139,8 @@ pub(crate) fn parse_json_credentials(
              "SecretAccessKey" : "secret",
              "Token" : "token",
              "Expiration" : "....",
-             "LastUpdated" : "2009-11-23T00:00:00Z"
+             "LastUpdated" : "2009-11-23T00:00:00Z",
+             "AccountId" : "123456789012"
             */
             (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Code") => {
                 code = Some(value.to_unescaped()?);
@@ -155,6 +159,9 @@ pub(crate) fn parse_json_credentials(
             (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Expiration") => {
                 expiration = Some(value.to_unescaped()?);
             }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("AccountId") => {
+                account_id = Some(value.to_unescaped()?);
+            }
 
             // Error case handling: message will be set
             (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Message") => {
@@ -195,6 +202,7 @@ pub(crate) fn parse_json_credentials(
                     secret_access_key,
                     session_token,
                     expiration,
+                    account_id,
                 },
             ))
         }
@@ -267,6 +275,31 @@ mod test {
                 secret_access_key: "xjtest".into(),
                 session_token: "IQote///test".into(),
                 expiration: UNIX_EPOCH + Duration::from_secs(1631935916),
+                account_id: None,
+            })
+        )
+    }
+
+    #[test]
+    fn json_credentials_with_account_id() {
+        let response = r#"
+        {
+          "Code" : "Success",
+          "AccessKeyId" : "ASIARTEST",
+          "SecretAccessKey" : "xjtest",
+          "Token" : "IQote///test",
+          "Expiration" : "2021-09-18T03:31:56Z",
+          "AccountId" : "123456789012"
+        }"#;
+        let parsed = parse_json_credentials(response).expect("valid JSON");
+        assert_eq!(
+            parsed,
+            JsonCredentials::RefreshableCredentials(RefreshableCredentials {
+                access_key_id: "ASIARTEST".into(),
+                secret_access_key: "xjtest".into(),
+                session_token: "IQote///test".into(),
+                expiration: UNIX_EPOCH + Duration::from_secs(1631935916),
+                account_id: Some("123456789012".into()),
             })
         )
     }
@@ -307,6 +340,7 @@ mod test {
                 secret_access_key: "xjtest".into(),
                 session_token: "IQote///test".into(),
                 expiration: UNIX_EPOCH + Duration::from_secs(1631935916),
+                account_id: None,
             })
         )
     }
@@ -380,7 +414,8 @@ mod test {
                     access_key_id: Cow::Borrowed("ASIARTEST"),
                     secret_access_key: Cow::Borrowed("SECRETTEST"),
                     session_token,
-                    expiration
+                    expiration,
+                    account_id: None,
                 }) if session_token.starts_with("token") && *expiration == UNIX_EPOCH + Duration::from_secs(1234567890)
             ),
             "{:?}",
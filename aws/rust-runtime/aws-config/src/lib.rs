@@ -123,8 +123,10 @@ mod json_credentials;
 #[cfg(test)]
 mod test_case;
 
+pub mod credential_endpoint;
 pub mod credential_process;
 pub mod default_provider;
+pub mod diagnostics;
 pub mod ecs;
 mod env_service_config;
 pub mod environment;
@@ -133,11 +135,15 @@ pub mod meta;
 pub mod profile;
 pub mod provider_config;
 pub mod retry;
+#[cfg(feature = "roles-anywhere")]
+pub mod roles_anywhere;
 mod sensitive_command;
 #[cfg(feature = "sso")]
 pub mod sso;
 pub mod stalled_stream_protection;
 pub mod sts;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 pub mod timeout;
 pub mod web_identity_token;
 
@@ -216,6 +222,7 @@ pub async fn load_defaults(version: BehaviorVersion) -> SdkConfig {
 mod loader {
     use crate::env_service_config::EnvServiceConfig;
     use aws_credential_types::provider::{
+        error::CredentialsError,
         token::{ProvideToken, SharedTokenProvider},
         ProvideCredentials, SharedCredentialsProvider,
     };
@@ -242,6 +249,10 @@ mod loader {
         ignore_configured_endpoint_urls as ignore_ep, region, request_min_compression_size_bytes,
         retry_config, timeout_config, use_dual_stack, use_fips,
     };
+    use crate::diagnostics::{
+        error_reason, reuse_reported_credentials, ProviderOutcome, ProviderReport,
+        ResolutionReport, ValueSource,
+    };
     use crate::meta::region::ProvideRegion;
     #[allow(deprecated)]
     use crate::profile::profile_file::ProfileFiles;
@@ -727,6 +738,31 @@ mod loader {
         /// This means that if you provide a region provider that does not return a region, no region will
         /// be set in the resulting [`SdkConfig`].
         pub async fn load(self) -> SdkConfig {
+            self.load_inner(None).await
+        }
+
+        /// Load the default configuration chain along with a report of how region and credentials were resolved
+        ///
+        /// This behaves like [`load`](Self::load), except that credentials are also loaded eagerly so that the
+        /// outcome of each credentials provider can be reported. The credentials that are loaded are reused for the
+        /// first request rather than loaded again. The report lists each provider that was checked,
+        /// whether it was skipped, not configured, failed, or succeeded, and the environment variables and
+        /// profile keys that supplied its values.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn example() {
+        /// let (sdk_config, report) = aws_config::from_env().load_with_report().await;
+        /// println!("{report}");
+        /// # }
+        /// ```
+        pub async fn load_with_report(self) -> (SdkConfig, ResolutionReport) {
+            let mut report = ResolutionReport::default();
+            let sdk_config = self.load_inner(Some(&mut report)).await;
+            (sdk_config, report)
+        }
+
+        async fn load_inner(self, mut report: Option<&mut ResolutionReport>) -> SdkConfig {
             let time_source = self.time_source.unwrap_or_default();
 
             let sleep_impl = if self.sleep.is_some() {
@@ -774,13 +810,26 @@ mod loader {
                 .with_use_dual_stack(use_dual_stack);
 
             let region = if let Some(provider) = self.region {
-                provider.region().await
+                let region = provider.region().await;
+                if let Some(report) = report.as_deref_mut() {
+                    report.region_providers = vec![programmatic_report(match region {
+                        Some(_) => ProviderOutcome::Succeeded,
+                        None => ProviderOutcome::NotConfigured {
+                            reason: "the provider did not return a region".into(),
+                        },
+                    })];
+                }
+                region
             } else {
-                region::Builder::default()
-                    .configure(&conf)
-                    .build()
-                    .region()
-                    .await
+                let chain = region::Builder::default().configure(&conf).build();
+                match report.as_deref_mut() {
+                    Some(report) => {
+                        let (region, providers) = chain.region_with_report().await;
+                        report.region_providers = providers;
+                        region
+                    }
+                    None => chain.region().await,
+                }
             };
 
             let retry_config = if let Some(retry_config) = self.retry_config {
@@ -826,13 +875,48 @@ mod loader {
                 .unwrap_or_else(|| TimeoutConfig::builder().build());
             timeout_config.take_defaults_from(&base_config);
 
+            // Credentials that are loaded for the report are reused rather than loaded again
             let credentials_provider = match self.credentials_provider {
-                TriStateOption::Set(provider) => Some(provider),
+                TriStateOption::Set(provider) => match report.as_deref_mut() {
+                    Some(report) => {
+                        let loaded = provider.provide_credentials().await;
+                        let outcome = match &loaded {
+                            Ok(_) => ProviderOutcome::Succeeded,
+                            Err(err @ CredentialsError::CredentialsNotLoaded(_)) => {
+                                ProviderOutcome::NotConfigured {
+                                    reason: error_reason(err),
+                                }
+                            }
+                            Err(err) => ProviderOutcome::Failed {
+                                reason: error_reason(err),
+                            },
+                        };
+                        report.credentials_providers = vec![programmatic_report(outcome)];
+                        Some(reuse_reported_credentials(
+                            provider,
+                            loaded,
+                            time_source.clone(),
+                        ))
+                    }
+                    None => Some(provider),
+                },
                 TriStateOption::NotSet => {
                     let mut builder =
                         credentials::DefaultCredentialsChain::builder().configure(conf.clone());
                     builder.set_region(region.clone());
-                    Some(SharedCredentialsProvider::new(builder.build().await))
+                    let chain = builder.build().await;
+                    match report.as_deref_mut() {
+                        Some(report) => {
+                            let (loaded, providers) = chain.credentials_with_report().await;
+                            report.credentials_providers = providers;
+                            Some(reuse_reported_credentials(
+                                SharedCredentialsProvider::new(chain),
+                                loaded,
+                                time_source.clone(),
+                            ))
+                        }
+                        None => Some(SharedCredentialsProvider::new(chain)),
+                    }
                 }
                 TriStateOption::ExplicitlyUnset => None,
             };
@@ -856,6 +940,14 @@ mod loader {
             };
 
             let profiles = conf.profile().await;
+            if let Some(report) = report {
+                report.profile_name = profiles
+                    .map(|profiles| profiles.selected_profile())
+                    .unwrap_or("default")
+                    .to_string();
+                report.region = region.clone();
+                report.annotate_sources(&conf.env(), profiles);
+            }
             let service_config = EnvServiceConfig {
                 env: conf.env(),
                 env_config_sections: profiles.cloned().unwrap_or_default(),
@@ -921,6 +1013,15 @@ mod loader {
         }
     }
 
+    /// Reports a provider that was set on the [`ConfigLoader`] rather than loaded from a default chain
+    fn programmatic_report(outcome: ProviderOutcome) -> ProviderReport {
+        let sources = match outcome {
+            ProviderOutcome::Succeeded => vec![ValueSource::Programmatic],
+            _ => Vec::new(),
+        };
+        ProviderReport::new("Programmatic", outcome).with_sources(sources)
+    }
+
     #[cfg(test)]
     impl ConfigLoader {
         pub(crate) fn env(mut self, env: Env) -> Self {
@@ -936,12 +1037,15 @@ mod loader {
 
     #[cfg(test)]
     mod test {
+        use crate::diagnostics::{ProviderOutcome, ProviderReport, ValueSource};
         #[allow(deprecated)]
         use crate::profile::profile_file::{ProfileFileKind, ProfileFiles};
         use crate::test_case::{no_traffic_client, InstantSleep};
         use crate::BehaviorVersion;
         use crate::{defaults, ConfigLoader};
+        use aws_credential_types::credential_fn::provide_credentials_fn;
         use aws_credential_types::provider::ProvideCredentials;
+        use aws_credential_types::Credentials;
         use aws_smithy_async::rt::sleep::TokioSleep;
         use aws_smithy_runtime::client::http::test_util::{infallible_client_fn, NeverClient};
         use aws_smithy_runtime::test_util::capture_test_logs::capture_test_logs;
@@ -1013,6 +1117,117 @@ mod loader {
                 .http_client(no_traffic_client())
         }
 
+        #[tokio::test]
+        async fn load_with_report() {
+            let env = Env::from_slice(&[("HOME", "/home")]);
+            let fs = Fs::from_slice(&[
+                ("/home/.aws/config", "[profile custom]\nregion = us-west-2"),
+                (
+                    "/home/.aws/credentials",
+                    "[custom]\naws_access_key_id = AKID\naws_secret_access_key = SECRET",
+                ),
+            ]);
+            let (config, report) = base_conf()
+                .env(env)
+                .fs(fs)
+                .profile_name("custom")
+                .load_with_report()
+                .await;
+            assert_eq!("us-west-2", config.region().unwrap().as_ref());
+            assert_eq!("custom", report.profile_name());
+            assert_eq!("us-west-2", report.region().unwrap().as_ref());
+
+            let summarize = |providers: &[ProviderReport]| {
+                providers
+                    .iter()
+                    .map(|provider| {
+                        let outcome = match provider.outcome() {
+                            ProviderOutcome::Skipped => "skipped",
+                            ProviderOutcome::NotConfigured { .. } => "not configured",
+                            ProviderOutcome::Failed { .. } => "failed",
+                            ProviderOutcome::Succeeded => "succeeded",
+                        };
+                        let sources: Vec<_> =
+                            provider.sources().iter().map(|s| s.to_string()).collect();
+                        (provider.name().to_string(), outcome, sources)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                vec![
+                    ("Environment".to_string(), "not configured", vec![]),
+                    (
+                        "Profile".to_string(),
+                        "succeeded",
+                        vec![
+                            "`region` in profile `custom` of the config file /home/.aws/config"
+                                .to_string()
+                        ]
+                    ),
+                    ("Ec2InstanceMetadata".to_string(), "skipped", vec![]),
+                ],
+                summarize(report.region_providers())
+            );
+            assert_eq!(
+                vec![
+                    ("Environment".to_string(), "not configured", vec![]),
+                    (
+                        "Profile".to_string(),
+                        "succeeded",
+                        vec![
+                            "`aws_access_key_id` in profile `custom` of the credentials file /home/.aws/credentials".to_string(),
+                            "`aws_secret_access_key` in profile `custom` of the credentials file /home/.aws/credentials".to_string(),
+                        ]
+                    ),
+                    ("WebIdentityToken".to_string(), "skipped", vec![]),
+                    ("EcsContainer".to_string(), "skipped", vec![]),
+                    ("Ec2InstanceMetadata".to_string(), "skipped", vec![]),
+                ],
+                summarize(report.credentials_providers())
+            );
+        }
+
+        #[tokio::test]
+        async fn load_with_report_programmatic_overrides() {
+            let (_, report) = base_conf()
+                .region("us-east-1")
+                .test_credentials()
+                .load_with_report()
+                .await;
+            for providers in [report.region_providers(), report.credentials_providers()] {
+                assert_eq!(1, providers.len());
+                assert_eq!("Programmatic", providers[0].name());
+                assert_eq!(&ProviderOutcome::Succeeded, providers[0].outcome());
+                assert_eq!(&[ValueSource::Programmatic], providers[0].sources());
+            }
+        }
+
+        #[tokio::test]
+        async fn load_with_report_reuses_the_loaded_credentials() {
+            let loads = Arc::new(AtomicUsize::new(0));
+            let provider = {
+                let loads = loads.clone();
+                provide_credentials_fn(move || {
+                    let loads = loads.clone();
+                    async move {
+                        loads.fetch_add(1, Ordering::Relaxed);
+                        Ok(Credentials::for_tests())
+                    }
+                })
+            };
+            let (conf, _) = base_conf()
+                .credentials_provider(provider)
+                .load_with_report()
+                .await;
+            assert_eq!(1, loads.load(Ordering::Relaxed));
+
+            let provider = conf.credentials_provider().unwrap();
+            provider.provide_credentials().await.unwrap();
+            assert_eq!(1, loads.load(Ordering::Relaxed));
+            provider.provide_credentials().await.unwrap();
+            assert_eq!(2, loads.load(Ordering::Relaxed));
+        }
+
         #[tokio::test]
         async fn test_origin_programmatic() {
             let _ = tracing_subscriber::fmt::try_init();
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::diagnostics::{error_reason, ProviderOutcome, ProviderReport};
 use aws_credential_types::{
     provider::{self, error::CredentialsError, future, ProvideCredentials},
     Credentials,
@@ -91,25 +92,45 @@ impl CredentialsProviderChain {
     }
 
     async fn credentials(&self) -> provider::Result {
-        for (name, provider) in &self.providers {
+        self.credentials_with_report().await.0
+    }
+
+    /// Load credentials along with the outcome of each provider in the chain
+    pub(crate) async fn credentials_with_report(&self) -> (provider::Result, Vec<ProviderReport>) {
+        let mut reports: Vec<_> = self
+            .providers
+            .iter()
+            .map(|(name, _)| ProviderReport::new(name.clone(), ProviderOutcome::Skipped))
+            .collect();
+        for (index, (name, provider)) in self.providers.iter().enumerate() {
             let span = tracing::debug_span!("load_credentials", provider = %name);
             match provider.provide_credentials().instrument(span).await {
                 Ok(credentials) => {
                     tracing::debug!(provider = %name, "loaded credentials");
-                    return Ok(credentials);
+                    reports[index].outcome = ProviderOutcome::Succeeded;
+                    return (Ok(credentials), reports);
                 }
                 Err(err @ CredentialsError::CredentialsNotLoaded(_)) => {
                     tracing::debug!(provider = %name, context = %DisplayErrorContext(&err), "provider in chain did not provide credentials");
+                    reports[index].outcome = ProviderOutcome::NotConfigured {
+                        reason: error_reason(&err),
+                    };
                 }
                 Err(err) => {
                     tracing::warn!(provider = %name, error = %DisplayErrorContext(&err), "provider failed to provide credentials");
-                    return Err(err);
+                    reports[index].outcome = ProviderOutcome::Failed {
+                        reason: error_reason(&err),
+                    };
+                    return (Err(err), reports);
                 }
             }
         }
-        Err(CredentialsError::not_loaded(
-            "no providers in chain provided credentials",
-        ))
+        (
+            Err(CredentialsError::not_loaded(
+                "no providers in chain provided credentials",
+            )),
+            reports,
+        )
     }
 }
 
@@ -143,6 +164,7 @@ mod tests {
     };
     use aws_smithy_async::future::timeout::Timeout;
 
+    use crate::diagnostics::ProviderOutcome;
     use crate::meta::credentials::CredentialsProviderChain;
 
     #[derive(Debug)]
@@ -164,6 +186,51 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn report_records_the_outcome_of_each_provider() {
+        let chain = CredentialsProviderChain::first_try(
+            "provider1",
+            provide_credentials_fn(|| async {
+                Err(CredentialsError::not_loaded("provider1 is not configured"))
+            }),
+        )
+        .or_else(
+            "provider2",
+            provide_credentials_fn(|| async {
+                Err(CredentialsError::provider_error("provider2 failed"))
+            }),
+        )
+        .or_else("provider3", Credentials::for_tests());
+
+        let (result, reports) = chain.credentials_with_report().await;
+        assert!(matches!(result, Err(CredentialsError::ProviderError(_))));
+        let outcomes: Vec<_> = reports
+            .iter()
+            .map(|report| (report.name(), report.outcome().clone()))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "provider1",
+                    ProviderOutcome::NotConfigured {
+                        reason:
+                            "the credential provider was not enabled: provider1 is not configured"
+                                .to_string()
+                    }
+                ),
+                (
+                    "provider2",
+                    ProviderOutcome::Failed {
+                        reason: "an error occurred while loading credentials: provider2 failed"
+                            .to_string()
+                    }
+                ),
+                ("provider3", ProviderOutcome::Skipped),
+            ],
+            outcomes
+        );
+    }
+
     #[tokio::test]
     async fn fallback_credentials_should_be_returned_from_provider2_on_timeout_while_provider2_was_providing_credentials(
     ) {
@@ -7,3 +7,11 @@
 
 mod chain;
 pub use chain::CredentialsProviderChain;
+
+mod static_stability;
+pub(crate) use static_stability::maybe_extend_expiration;
+#[cfg(test)]
+pub(crate) use static_stability::WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY;
+pub use static_stability::{
+    StaticStabilityCredentialsProvider, StaticStabilityCredentialsProviderBuilder,
+};
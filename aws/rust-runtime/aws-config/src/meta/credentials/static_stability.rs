@@ -0,0 +1,414 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::provider_config::ProviderConfig;
+use aws_credential_types::provider::error::CredentialsError;
+use aws_credential_types::provider::{self, future, ProvideCredentials, SharedCredentialsProvider};
+use aws_credential_types::Credentials;
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_EXTENSION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_EXTENSION_JITTER: Duration = Duration::from_secs(5 * 60);
+pub(crate) const WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY: &str =
+    "Attempting credential expiration extension due to a credential service availability issue. \
+    A refresh of these credentials will be attempted again within the next";
+
+/// Extends an `expiration` that has passed by `interval` plus a random jitter of up to `jitter`
+///
+/// This allows continued use of stale credentials while fresh ones can't be retrieved. A warning is
+/// logged whenever the expiration is extended.
+pub(crate) fn maybe_extend_expiration(
+    expiration: SystemTime,
+    now: SystemTime,
+    interval: Duration,
+    jitter: Duration,
+) -> SystemTime {
+    // If the credentials are not stale, use them as they are.
+    if now < expiration {
+        return expiration;
+    }
+
+    let mut rng = fastrand::Rng::with_seed(
+        now.duration_since(SystemTime::UNIX_EPOCH)
+            .expect("now should be after UNIX EPOCH")
+            .as_secs(),
+    );
+    let refresh_offset = interval + Duration::from_secs(rng.u64(0..=jitter.as_secs()));
+    let new_expiry = now + refresh_offset;
+
+    tracing::warn!(
+        "{WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY} {:.2} minutes.",
+        refresh_offset.as_secs_f64() / 60.0,
+    );
+
+    new_expiry
+}
+
+/// Credentials provider that keeps serving the last successfully retrieved credentials when the
+/// inner provider is unavailable
+///
+/// Credential endpoints like IMDS and the ECS container credentials endpoint can be briefly
+/// unavailable. When the cached credentials expire during an outage, every request fails. To ride
+/// through the outage, this provider returns the last successfully retrieved credentials whenever
+/// the inner provider fails with a provider error or times out. Credentials that have expired are
+/// returned with their expiration extended by a short, randomized interval, and a warning is logged
+/// each time the expiration is extended.
+///
+/// The extended expiration is also when the inner provider will be retried: until then, this
+/// provider returns the extended credentials without calling the inner provider. Once the inner
+/// provider succeeds again, its credentials replace the extended credentials. Other errors, such as
+/// invalid configuration, are returned as they are, as are the errors of an inner provider that has
+/// never succeeded.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::ecs::EcsCredentialsProvider;
+/// use aws_config::meta::credentials::StaticStabilityCredentialsProvider;
+///
+/// let provider =
+///     StaticStabilityCredentialsProvider::builder(EcsCredentialsProvider::builder().build())
+///         .build();
+/// ```
+#[derive(Debug)]
+pub struct StaticStabilityCredentialsProvider {
+    provider: SharedCredentialsProvider,
+    time_source: SharedTimeSource,
+    extension_interval: Duration,
+    extension_jitter: Duration,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    last_retrieved_credentials: Option<Credentials>,
+    /// When set, the inner provider is failing and will not be called again until this time
+    next_refresh: Option<SystemTime>,
+}
+
+impl StaticStabilityCredentialsProvider {
+    /// Builder for a [`StaticStabilityCredentialsProvider`] that wraps `provider`
+    pub fn builder(
+        provider: impl ProvideCredentials + 'static,
+    ) -> StaticStabilityCredentialsProviderBuilder {
+        StaticStabilityCredentialsProviderBuilder {
+            provider: SharedCredentialsProvider::new(provider),
+            provider_config: None,
+            extension_interval: None,
+            extension_jitter: None,
+        }
+    }
+
+    async fn credentials(&self) -> provider::Result {
+        let now = self.time_source.now();
+        {
+            let state = self.state.lock().unwrap();
+            if let (Some(next_refresh), Some(credentials)) =
+                (state.next_refresh, &state.last_retrieved_credentials)
+            {
+                if now < next_refresh {
+                    return Ok(credentials.clone());
+                }
+            }
+        }
+
+        let result = self.provider.provide_credentials().await;
+        let mut state = self.state.lock().unwrap();
+        match result {
+            Ok(credentials) => {
+                state.last_retrieved_credentials = Some(credentials.clone());
+                state.next_refresh = None;
+                Ok(credentials)
+            }
+            // The credentials service being unavailable MUST NOT impede use of existing credentials.
+            Err(
+                err @ (CredentialsError::ProviderError(_) | CredentialsError::ProviderTimedOut(_)),
+            ) => {
+                let Some(credentials) = &state.last_retrieved_credentials else {
+                    return Err(err);
+                };
+                tracing::warn!(
+                    error = %DisplayErrorContext(&err),
+                    "failed to refresh credentials; using the last successfully retrieved credentials"
+                );
+                let mut credentials = credentials.clone();
+                if let Some(expiry) = credentials.expiry() {
+                    let extended = maybe_extend_expiration(
+                        expiry,
+                        now,
+                        self.extension_interval,
+                        self.extension_jitter,
+                    );
+                    if extended != expiry {
+                        *credentials.expiry_mut() = Some(extended);
+                        state.last_retrieved_credentials = Some(credentials.clone());
+                        state.next_refresh = Some(extended);
+                    }
+                }
+                Ok(credentials)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl ProvideCredentials for StaticStabilityCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+
+    fn fallback_on_interrupt(&self) -> Option<Credentials> {
+        let last_retrieved_credentials = self
+            .state
+            .lock()
+            .unwrap()
+            .last_retrieved_credentials
+            .clone();
+        last_retrieved_credentials.or_else(|| self.provider.fallback_on_interrupt())
+    }
+}
+
+/// Builder for [`StaticStabilityCredentialsProvider`]
+#[derive(Debug)]
+pub struct StaticStabilityCredentialsProviderBuilder {
+    provider: SharedCredentialsProvider,
+    provider_config: Option<ProviderConfig>,
+    extension_interval: Option<Duration>,
+    extension_jitter: Option<Duration>,
+}
+
+impl StaticStabilityCredentialsProviderBuilder {
+    /// Override the configuration used for this provider
+    ///
+    /// Only the time source is used, to determine when credentials are extended and refreshed.
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Override the minimum time that the expiration of credentials is extended by when the inner
+    /// provider fails
+    ///
+    /// Defaults to 5 minutes.
+    pub fn extension_interval(mut self, interval: Duration) -> Self {
+        self.extension_interval = Some(interval);
+        self
+    }
+
+    /// Override the maximum random time added to the extension interval
+    ///
+    /// The jitter spreads out the refreshes of a fleet whose credentials were extended at the same
+    /// time. Defaults to 5 minutes.
+    pub fn extension_jitter(mut self, jitter: Duration) -> Self {
+        self.extension_jitter = Some(jitter);
+        self
+    }
+
+    /// Create a [`StaticStabilityCredentialsProvider`] from this builder
+    pub fn build(self) -> StaticStabilityCredentialsProvider {
+        let provider_config = self.provider_config.unwrap_or_default();
+        StaticStabilityCredentialsProvider {
+            provider: self.provider,
+            time_source: provider_config.time_source(),
+            extension_interval: self
+                .extension_interval
+                .unwrap_or(DEFAULT_EXTENSION_INTERVAL),
+            extension_jitter: self.extension_jitter.unwrap_or(DEFAULT_EXTENSION_JITTER),
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aws_credential_types::credential_fn::provide_credentials_fn;
+    use aws_smithy_async::test_util::{instant_time_and_sleep, ManualTimeSource};
+    use aws_smithy_async::time::TimeSource;
+    use std::collections::VecDeque;
+    use std::time::UNIX_EPOCH;
+    use tracing_test::traced_test;
+
+    const ONE_HOUR: Duration = Duration::from_secs(60 * 60);
+
+    /// A provider that returns `results` in order, and records how many times it was called
+    #[derive(Clone, Default)]
+    struct Responses {
+        results: Arc<Mutex<VecDeque<provider::Result>>>,
+        calls: Arc<Mutex<usize>>,
+    }
+
+    impl Responses {
+        fn push(&self, result: provider::Result) {
+            self.results.lock().unwrap().push_back(result);
+        }
+
+        fn calls(&self) -> usize {
+            *self.calls.lock().unwrap()
+        }
+
+        fn provider(&self, time_source: &ManualTimeSource) -> StaticStabilityCredentialsProvider {
+            let responses = self.clone();
+            let inner = provide_credentials_fn(move || {
+                *responses.calls.lock().unwrap() += 1;
+                let result = responses
+                    .results
+                    .lock()
+                    .unwrap()
+                    .pop_front()
+                    .expect("unexpected call to the inner provider");
+                async move { result }
+            });
+            StaticStabilityCredentialsProvider::builder(inner)
+                .configure(
+                    &ProviderConfig::no_configuration().with_time_source(time_source.clone()),
+                )
+                .build()
+        }
+    }
+
+    fn credentials(access_key_id: &str, expiry: SystemTime) -> Credentials {
+        Credentials::new(access_key_id, "secret", None, Some(expiry), "test")
+    }
+
+    fn outage() -> provider::Result {
+        Err(CredentialsError::provider_error(
+            "the credentials endpoint is unavailable",
+        ))
+    }
+
+    #[tokio::test]
+    async fn credentials_are_returned_unchanged_while_the_provider_succeeds() {
+        let (time_source, _) = instant_time_and_sleep(UNIX_EPOCH);
+        let responses = Responses::default();
+        let provider = responses.provider(&time_source);
+        responses.push(Ok(credentials("AKID1", UNIX_EPOCH + ONE_HOUR)));
+
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(credentials("AKID1", UNIX_EPOCH + ONE_HOUR), creds);
+    }
+
+    #[tokio::test]
+    async fn errors_are_returned_without_previous_credentials() {
+        let (time_source, _) = instant_time_and_sleep(UNIX_EPOCH);
+        let responses = Responses::default();
+        let provider = responses.provider(&time_source);
+        responses.push(outage());
+
+        let err = provider.provide_credentials().await.expect_err("no creds");
+        assert!(matches!(err, CredentialsError::ProviderError(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn expired_credentials_are_extended_during_an_outage() {
+        let (time_source, _) = instant_time_and_sleep(UNIX_EPOCH);
+        let responses = Responses::default();
+        let provider = responses.provider(&time_source);
+        responses.push(Ok(credentials("AKID1", UNIX_EPOCH + ONE_HOUR)));
+        provider.provide_credentials().await.expect("valid creds");
+
+        time_source.advance(ONE_HOUR);
+        responses.push(outage());
+        let now = time_source.now();
+        let extended = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!("AKID1", extended.access_key_id());
+        let expiry = extended.expiry().unwrap();
+        assert!(expiry >= now + DEFAULT_EXTENSION_INTERVAL, "{expiry:?}");
+        assert!(
+            expiry <= now + DEFAULT_EXTENSION_INTERVAL + DEFAULT_EXTENSION_JITTER,
+            "{expiry:?}"
+        );
+        assert!(logs_contain(WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY));
+
+        // Until the extended expiration, the inner provider is not called again.
+        assert_eq!(extended, provider.provide_credentials().await.unwrap());
+        assert_eq!(2, responses.calls());
+
+        // After the extended expiration, the inner provider is retried.
+        time_source.advance(expiry.duration_since(now).unwrap());
+        responses.push(outage());
+        let extended_again = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!("AKID1", extended_again.access_key_id());
+        assert!(extended_again.expiry().unwrap() > expiry);
+        assert_eq!(3, responses.calls());
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn credentials_that_have_not_expired_are_not_extended() {
+        let (time_source, _) = instant_time_and_sleep(UNIX_EPOCH);
+        let responses = Responses::default();
+        let provider = responses.provider(&time_source);
+        responses.push(Ok(credentials("AKID1", UNIX_EPOCH + ONE_HOUR)));
+        provider.provide_credentials().await.expect("valid creds");
+
+        responses.push(Err(CredentialsError::provider_timed_out(
+            Duration::from_secs(5),
+        )));
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(credentials("AKID1", UNIX_EPOCH + ONE_HOUR), creds);
+        assert!(!logs_contain(WARNING_FOR_EXTENDING_CREDENTIALS_EXPIRY));
+
+        // The inner provider is called again on the next request.
+        responses.push(outage());
+        provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(3, responses.calls());
+    }
+
+    #[tokio::test]
+    async fn errors_other_than_outages_are_returned() {
+        let (time_source, _) = instant_time_and_sleep(UNIX_EPOCH);
+        let responses = Responses::default();
+        let provider = responses.provider(&time_source);
+        responses.push(Ok(credentials("AKID1", UNIX_EPOCH + ONE_HOUR)));
+        provider.provide_credentials().await.expect("valid creds");
+
+        time_source.advance(ONE_HOUR);
+        responses.push(Err(CredentialsError::invalid_configuration(
+            "the role can no longer be assumed",
+        )));
+        let err = provider.provide_credentials().await.expect_err("no creds");
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "{err:?}"
+        );
+        responses.push(Err(CredentialsError::not_loaded("no credentials")));
+        let err = provider.provide_credentials().await.expect_err("no creds");
+        assert!(
+            matches!(err, CredentialsError::CredentialsNotLoaded(_)),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn new_credentials_replace_extended_credentials() {
+        let (time_source, _) = instant_time_and_sleep(UNIX_EPOCH);
+        let responses = Responses::default();
+        let provider = StaticStabilityCredentialsProvider {
+            extension_jitter: Duration::ZERO,
+            ..responses.provider(&time_source)
+        };
+        responses.push(Ok(credentials("AKID1", UNIX_EPOCH + ONE_HOUR)));
+        provider.provide_credentials().await.expect("valid creds");
+
+        time_source.advance(ONE_HOUR);
+        responses.push(outage());
+        provider.provide_credentials().await.expect("valid creds");
+
+        time_source.advance(DEFAULT_EXTENSION_INTERVAL);
+        let expiry = time_source.now() + ONE_HOUR;
+        responses.push(Ok(credentials("AKID2", expiry)));
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(credentials("AKID2", expiry), creds);
+        assert_eq!(Some(creds), provider.fallback_on_interrupt());
+    }
+}
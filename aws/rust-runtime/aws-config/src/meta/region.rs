@@ -5,6 +5,7 @@
 
 //! Region providers that augment existing providers with new functionality
 
+use crate::diagnostics::{ProviderOutcome, ProviderReport};
 use aws_types::region::Region;
 use std::borrow::Cow;
 use std::fmt::Debug;
@@ -29,49 +30,86 @@ use tracing::Instrument;
 /// ```
 #[derive(Debug)]
 pub struct RegionProviderChain {
-    providers: Vec<Box<dyn ProvideRegion>>,
+    providers: Vec<(Cow<'static, str>, Box<dyn ProvideRegion>)>,
 }
 
+/// Name of providers added without a name, used in resolution reports
+const UNNAMED_PROVIDER: &str = "Custom";
+const DEFAULT_PROVIDER: &str = "DefaultProviderChain";
+
 impl RegionProviderChain {
     /// Load a region from the provider chain
     ///
     /// The first provider to return a non-optional region will be selected
     pub async fn region(&self) -> Option<Region> {
-        for provider in &self.providers {
+        self.region_with_report().await.0
+    }
+
+    /// Load a region along with the outcome of each provider in the chain
+    pub(crate) async fn region_with_report(&self) -> (Option<Region>, Vec<ProviderReport>) {
+        let mut reports: Vec<_> = self
+            .providers
+            .iter()
+            .map(|(name, _)| ProviderReport::new(name.clone(), ProviderOutcome::Skipped))
+            .collect();
+        for (index, (_, provider)) in self.providers.iter().enumerate() {
             if let Some(region) = provider
                 .region()
                 .instrument(tracing::info_span!("load_region", provider = ?provider))
                 .await
             {
-                return Some(region);
+                reports[index].outcome = ProviderOutcome::Succeeded;
+                return (Some(region), reports);
             }
+            reports[index].outcome = ProviderOutcome::NotConfigured {
+                reason: "the provider did not return a region".into(),
+            };
         }
-        None
+        (None, reports)
     }
 
     /// Create a default provider chain that starts by checking this provider.
     pub fn first_try(provider: impl ProvideRegion + 'static) -> Self {
+        Self::first_try_named(UNNAMED_PROVIDER, provider)
+    }
+
+    /// Add a fallback provider to the region provider chain.
+    pub fn or_else(self, fallback: impl ProvideRegion + 'static) -> Self {
+        self.or_else_named(UNNAMED_PROVIDER, fallback)
+    }
+
+    pub(crate) fn first_try_named(
+        name: impl Into<Cow<'static, str>>,
+        provider: impl ProvideRegion + 'static,
+    ) -> Self {
         RegionProviderChain {
-            providers: vec![Box::new(provider)],
+            providers: vec![(name.into(), Box::new(provider))],
         }
     }
 
-    /// Add a fallback provider to the region provider chain.
-    pub fn or_else(mut self, fallback: impl ProvideRegion + 'static) -> Self {
-        self.providers.push(Box::new(fallback));
+    pub(crate) fn or_else_named(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        fallback: impl ProvideRegion + 'static,
+    ) -> Self {
+        self.providers.push((name.into(), Box::new(fallback)));
         self
     }
 
     /// Create a region provider chain that starts by checking the default provider.
     pub fn default_provider() -> Self {
-        Self::first_try(crate::default_provider::region::default_provider())
+        Self::first_try_named(
+            DEFAULT_PROVIDER,
+            crate::default_provider::region::default_provider(),
+        )
     }
 
     /// Fallback to the default provider
-    pub fn or_default_provider(mut self) -> Self {
-        self.providers
-            .push(Box::new(crate::default_provider::region::default_provider()));
-        self
+    pub fn or_default_provider(self) -> Self {
+        self.or_else_named(
+            DEFAULT_PROVIDER,
+            crate::default_provider::region::default_provider(),
+        )
     }
 }
 
@@ -162,6 +200,7 @@ impl ProvideRegion for &'static str {
 
 #[cfg(test)]
 mod test {
+    use crate::diagnostics::ProviderOutcome;
     use crate::meta::region::RegionProviderChain;
     use aws_types::region::Region;
     use futures_util::FutureExt;
@@ -177,6 +216,32 @@ mod test {
         );
     }
 
+    #[test]
+    fn report_records_the_outcome_of_each_provider() {
+        let chain = RegionProviderChain::first_try_named("first", None)
+            .or_else_named("second", Region::new("us-east-1"))
+            .or_else(Region::new("us-west-2"));
+        let (region, reports) = chain.region_with_report().now_or_never().expect("ready");
+        assert_eq!(Some(Region::new("us-east-1")), region);
+        let outcomes: Vec<_> = reports
+            .iter()
+            .map(|report| (report.name(), report.outcome().clone()))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "first",
+                    ProviderOutcome::NotConfigured {
+                        reason: "the provider did not return a region".into()
+                    }
+                ),
+                ("second", ProviderOutcome::Succeeded),
+                ("Custom", ProviderOutcome::Skipped),
+            ],
+            outcomes
+        );
+    }
+
     #[test]
     fn empty_chain() {
         let chain = RegionProviderChain::first_try(None).or_else(None);
@@ -25,7 +25,7 @@ pub use aws_runtime::env_config::error::EnvConfigFileLoadError as ProfileFileLoa
 #[doc(inline)]
 pub use aws_runtime::env_config::parse::EnvConfigParseError as ProfileParseError;
 #[doc(inline)]
-pub use aws_runtime::env_config::property::Property;
+pub use aws_runtime::env_config::property::{Property, PropertySource};
 #[doc(inline)]
 pub use aws_runtime::env_config::section::{EnvConfigSections as ProfileSet, Profile};
 #[doc(inline)]
@@ -28,6 +28,7 @@ use crate::profile::profile_file::ProfileFiles;
 use crate::profile::Profile;
 use crate::profile::ProfileFileLoadError;
 use crate::provider_config::ProviderConfig;
+use crate::sts::MfaTokenCodeProvider;
 use aws_credential_types::{
     provider::{self, error::CredentialsError, future, ProvideCredentials},
     Credentials,
@@ -119,6 +120,15 @@ pub(crate) mod repr;
 ///
 /// An external process can be used to provide credentials.
 ///
+/// ### Credentials loaded from a local credentials endpoint
+/// ```ini
+/// [default]
+/// credential_endpoint = unix:///run/secrets-agent/credentials.sock
+/// ```
+///
+/// A loopback HTTP endpoint or Unix domain socket can be used to provide credentials, see
+/// [`credential_endpoint`](crate::credential_endpoint) for the supported endpoints.
+///
 /// ### Loading Credentials from SSO
 /// ```ini
 /// [default]
@@ -142,6 +152,7 @@ pub struct ProfileFileCredentialsProvider {
 struct Config {
     factory: exec::named::NamedProviderFactory,
     provider_config: ProviderConfig,
+    mfa_token_code_provider: Option<MfaTokenCodeProvider>,
 }
 
 impl ProfileFileCredentialsProvider {
@@ -289,6 +300,17 @@ pub enum ProfileFileError {
     /// than in the credentials chain.
     #[non_exhaustive]
     TokenProviderConfig {},
+
+    /// The profile contained a setting with an invalid value
+    #[non_exhaustive]
+    InvalidSetting {
+        /// The name of the profile
+        profile: String,
+        /// The name of the setting
+        setting: String,
+        /// Error message
+        message: Cow<'static, str>,
+    },
 }
 
 impl ProfileFileError {
@@ -367,6 +389,14 @@ impl Display for ProfileFileError {
                      being tracked in https://github.com/awslabs/aws-sdk-rust/issues/703"
                 )
             }
+            ProfileFileError::InvalidSetting {
+                profile,
+                setting,
+                message,
+            } => write!(
+                f,
+                "profile `{profile}` has an invalid `{setting}`: {message}"
+            ),
         }
     }
 }
@@ -379,6 +409,7 @@ pub struct Builder {
     #[allow(deprecated)]
     profile_files: Option<ProfileFiles>,
     custom_providers: HashMap<Cow<'static, str>, Arc<dyn ProvideCredentials>>,
+    mfa_token_code_provider: Option<MfaTokenCodeProvider>,
 }
 
 impl Builder {
@@ -450,6 +481,30 @@ impl Builder {
         self
     }
 
+    /// Set the provider of MFA token codes for profiles that assume a role with `mfa_serial`
+    ///
+    /// Without one, `mfa_serial` is ignored with a warning, and the role is assumed without MFA.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aws_config::profile::ProfileFileCredentialsProvider;
+    /// use aws_config::sts::MfaTokenCodeProvider;
+    ///
+    /// let provider = ProfileFileCredentialsProvider::builder()
+    ///     .mfa_token_code_provider(MfaTokenCodeProvider::new(|serial_number| async move {
+    ///         println!("Enter the MFA code for {serial_number}:");
+    ///         let mut code = String::new();
+    ///         std::io::stdin().read_line(&mut code)?;
+    ///         Ok(code.trim().to_string())
+    ///     }))
+    ///     .build();
+    /// ```
+    pub fn mfa_token_code_provider(mut self, provider: MfaTokenCodeProvider) -> Self {
+        self.mfa_token_code_provider = Some(provider);
+        self
+    }
+
     /// Builds a [`ProfileFileCredentialsProvider`]
     pub fn build(self) -> ProfileFileCredentialsProvider {
         let build_span = tracing::debug_span!("build_profile_provider");
@@ -492,6 +547,7 @@ impl Builder {
             config: Arc::new(Config {
                 factory,
                 provider_config: conf,
+                mfa_token_code_provider: self.mfa_token_code_provider,
             }),
             inner_provider: ErrorTakingOnceCell::new(),
         }
@@ -508,7 +564,12 @@ async fn build_provider_chain(
         .map_err(|parse_err| ProfileFileError::InvalidProfile(parse_err.clone()))?;
     let repr = repr::resolve_chain(profile_set)?;
     tracing::info!(chain = ?repr, "constructed abstract provider from config file");
-    exec::ProviderChain::from_repr(&config.provider_config, repr, &config.factory)
+    exec::ProviderChain::from_repr(
+        &config.provider_config,
+        repr,
+        &config.factory,
+        config.mfa_token_code_provider.as_ref(),
+    )
 }
 
 #[derive(Debug)]
@@ -607,6 +668,7 @@ mod test {
     make_test!(credential_process_failure);
     #[cfg(feature = "credentials-process")]
     make_test!(credential_process_invalid);
+    make_test!(credential_endpoint);
     #[cfg(feature = "sso")]
     make_test!(sso_credentials);
     #[cfg(feature = "sso")]
@@ -4,27 +4,33 @@
  */
 
 use super::repr::{self, BaseProvider};
+use crate::credential_endpoint::CredentialEndpointProvider;
 #[cfg(feature = "credentials-process")]
 use crate::credential_process::CredentialProcessProvider;
 use crate::profile::credentials::ProfileFileError;
 use crate::provider_config::ProviderConfig;
 use crate::sts;
+use crate::sts::mfa::{Mfa, MfaTokenCodeProvider};
 use crate::web_identity_token::{StaticConfiguration, WebIdentityTokenCredentialsProvider};
 use aws_credential_types::provider::{
     self, error::CredentialsError, ProvideCredentials, SharedCredentialsProvider,
 };
 use aws_sdk_sts::config::Credentials;
+use aws_sdk_sts::operation::assume_role::builders::AssumeRoleFluentBuilder;
 use aws_sdk_sts::Client as StsClient;
 use aws_smithy_async::time::SharedTimeSource;
 use aws_types::SdkConfig;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub(super) struct AssumeRoleProvider {
     role_arn: String,
     external_id: Option<String>,
     session_name: Option<String>,
+    session_length: Option<Duration>,
+    mfa: Option<Mfa>,
     time_source: SharedTimeSource,
 }
 
@@ -34,6 +40,16 @@ impl AssumeRoleProvider {
         input_credentials: Credentials,
         sdk_config: &SdkConfig,
     ) -> provider::Result {
+        let duration_seconds = self
+            .session_length
+            .map(|dur| i32::try_from(dur.as_secs()))
+            .transpose()
+            .map_err(|_| {
+                CredentialsError::invalid_configuration(format!(
+                    "the session duration for `{}` is too long",
+                    self.role_arn
+                ))
+            })?;
         let config = sdk_config
             .to_builder()
             .credentials_provider(SharedCredentialsProvider::new(input_credentials))
@@ -42,16 +58,38 @@ impl AssumeRoleProvider {
         let session_name = &self.session_name.as_ref().cloned().unwrap_or_else(|| {
             sts::util::default_session_name("assume-role-from-profile", self.time_source.now())
         });
-        let assume_role_creds = client
+        let fluent_builder = client
             .assume_role()
             .role_arn(&self.role_arn)
             .set_external_id(self.external_id.clone())
             .role_session_name(session_name)
+            .set_duration_seconds(duration_seconds);
+        match &self.mfa {
+            // Credentials assumed with MFA are cached so that the token code isn't asked for on every call
+            Some(mfa) => {
+                mfa.credentials(self.time_source.now(), |token_code| {
+                    Self::assume_role(
+                        fluent_builder
+                            .serial_number(mfa.serial_number())
+                            .token_code(token_code),
+                    )
+                })
+                .await
+            }
+            None => Self::assume_role(fluent_builder).await,
+        }
+    }
+
+    async fn assume_role(fluent_builder: AssumeRoleFluentBuilder) -> provider::Result {
+        let assumed = fluent_builder
             .send()
             .await
-            .map_err(CredentialsError::provider_error)?
-            .credentials;
-        sts::util::into_credentials(assume_role_creds, "AssumeRoleProvider")
+            .map_err(CredentialsError::provider_error)?;
+        sts::util::into_credentials(
+            assumed.credentials,
+            assumed.assumed_role_user,
+            "AssumeRoleProvider",
+        )
     }
 }
 
@@ -76,6 +114,7 @@ impl ProviderChain {
         provider_config: &ProviderConfig,
         repr: repr::ProfileChain<'_>,
         factory: &named::NamedProviderFactory,
+        mfa_token_code_provider: Option<&MfaTokenCodeProvider>,
     ) -> Result<Self, ProfileFileError> {
         let base = match repr.base() {
             BaseProvider::NamedSource(name) => {
@@ -102,6 +141,12 @@ impl ProviderChain {
                     })?
                 }
             }
+            BaseProvider::CredentialEndpoint(endpoint) => Arc::new(
+                CredentialEndpointProvider::builder()
+                    .configure(provider_config)
+                    .endpoint(*endpoint)
+                    .build(),
+            ),
             BaseProvider::WebIdentityTokenRole {
                 role_arn,
                 web_identity_token_file,
@@ -125,6 +170,48 @@ impl ProviderChain {
                 Arc::new(provider)
             }
             #[allow(unused_variables)]
+            BaseProvider::RolesAnywhere {
+                role_arn,
+                trust_anchor_arn,
+                profile_arn,
+                certificate,
+                private_key,
+                certificate_chain,
+                session_name,
+                duration,
+            } => {
+                #[cfg(feature = "roles-anywhere")]
+                {
+                    use crate::roles_anywhere::RolesAnywhereCredentialsProvider;
+
+                    let mut builder = RolesAnywhereCredentialsProvider::builder(
+                        *trust_anchor_arn,
+                        *profile_arn,
+                        *role_arn,
+                    )
+                    .configure(provider_config)
+                    .certificate_file(*certificate)
+                    .private_key_file(*private_key);
+                    if let Some(certificate_chain) = certificate_chain {
+                        builder = builder.certificate_chain_file(*certificate_chain);
+                    }
+                    if let Some(session_name) = session_name {
+                        builder = builder.session_name(*session_name);
+                    }
+                    if let Some(duration) = duration {
+                        builder = builder.session_duration(*duration);
+                    }
+                    Arc::new(builder.build())
+                }
+                #[cfg(not(feature = "roles-anywhere"))]
+                {
+                    Err(ProfileFileError::FeatureNotEnabled {
+                        feature: "roles-anywhere".into(),
+                        message: None,
+                    })?
+                }
+            }
+            #[allow(unused_variables)]
             BaseProvider::Sso {
                 sso_account_id,
                 sso_region,
@@ -166,10 +253,28 @@ impl ProviderChain {
             .iter()
             .map(|role_arn| {
                 tracing::debug!(role_arn = ?role_arn, "which will be used to assume a role");
+                let mfa = role_arn.mfa_serial.and_then(|mfa_serial| {
+                    match mfa_token_code_provider {
+                        Some(provider) => Some(Mfa::new(mfa_serial, provider.clone())),
+                        None => {
+                            // Roles that require MFA were assumed without it before `mfa_serial`
+                            // was supported, so keep doing that rather than failing
+                            tracing::warn!(
+                                role_arn = role_arn.role_arn,
+                                mfa_serial,
+                                "the profile sets `mfa_serial`, but no MFA token code provider was \
+                                 configured, so the role will be assumed without MFA"
+                            );
+                            None
+                        }
+                    }
+                });
                 AssumeRoleProvider {
                     role_arn: role_arn.role_arn.into(),
                     external_id: role_arn.external_id.map(Into::into),
                     session_name: role_arn.session_name.map(Into::into),
+                    session_length: role_arn.duration,
+                    mfa,
                     time_source: provider_config.time_source(),
                 }
             })
@@ -222,9 +327,15 @@ mod test {
     use crate::provider_config::ProviderConfig;
     use crate::test_case::no_traffic_client;
 
+    use super::AssumeRoleProvider;
+    use crate::profile::credentials::repr::RoleArn;
+    use aws_credential_types::provider::error::CredentialsError;
     use aws_credential_types::Credentials;
+    use aws_smithy_async::time::SharedTimeSource;
+    use aws_types::SdkConfig;
     use std::collections::HashMap;
     use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn providers_case_insensitive() {
@@ -250,6 +361,7 @@ mod test {
                 chain: vec![],
             },
             &factory,
+            None,
         );
         let err = chain.expect_err("no source by that name");
         assert!(
@@ -260,4 +372,45 @@ mod test {
             err
         );
     }
+
+    #[test]
+    fn mfa_serial_is_ignored_without_a_token_code_provider() {
+        let chain = ProviderChain::from_repr(
+            &ProviderConfig::empty().with_http_client(no_traffic_client()),
+            ProfileChain {
+                base: BaseProvider::AccessKey(Credentials::for_tests()),
+                chain: vec![RoleArn {
+                    role_arn: "arn:aws:iam::123456789:role/RoleA",
+                    external_id: None,
+                    session_name: None,
+                    mfa_serial: Some("arn:aws:iam::123456789:mfa/user"),
+                    duration: None,
+                }],
+            },
+            &NamedProviderFactory::new(HashMap::new()),
+            None,
+        )
+        .expect("the role is assumed without MFA");
+        assert!(chain.chain()[0].mfa.is_none());
+    }
+
+    #[tokio::test]
+    async fn session_length_that_does_not_fit_duration_seconds_is_a_configuration_error() {
+        let provider = AssumeRoleProvider {
+            role_arn: "arn:aws:iam::123456789:role/RoleA".into(),
+            external_id: None,
+            session_name: None,
+            session_length: Some(Duration::from_secs(u64::from(u32::MAX))),
+            mfa: None,
+            time_source: SharedTimeSource::default(),
+        };
+        let err = provider
+            .credentials(Credentials::for_tests(), &SdkConfig::builder().build())
+            .await
+            .expect_err("the duration is too long");
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "{err:?}"
+        );
+    }
 }
@@ -16,6 +16,7 @@ use crate::profile::credentials::ProfileFileError;
 use crate::profile::{Profile, ProfileSet};
 use crate::sensitive_command::CommandWithSensitiveArgs;
 use aws_credential_types::Credentials;
+use std::time::Duration;
 
 /// Chain of Profile Providers
 ///
@@ -87,12 +88,39 @@ pub(crate) enum BaseProvider<'a> {
         sso_role_name: Option<&'a str>,
     },
 
+    /// A profile that exchanges an X.509 certificate for credentials with IAM Roles Anywhere
+    /// ```ini
+    /// [profile on-premises]
+    /// role_arn = arn:aws:iam::123456789012:role/OnPremises
+    /// roles_anywhere_trust_anchor_arn = arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/abc
+    /// roles_anywhere_profile_arn = arn:aws:rolesanywhere:us-east-1:123456789012:profile/def
+    /// roles_anywhere_certificate = /etc/pki/host/certificate.pem
+    /// roles_anywhere_private_key = /etc/pki/host/private-key.pem
+    /// ```
+    RolesAnywhere {
+        role_arn: &'a str,
+        trust_anchor_arn: &'a str,
+        profile_arn: &'a str,
+        certificate: &'a str,
+        private_key: &'a str,
+        certificate_chain: Option<&'a str>,
+        session_name: Option<&'a str>,
+        duration: Option<Duration>,
+    },
+
     /// A profile that specifies a `credential_process`
     /// ```ini
     /// [profile assume-role]
     /// credential_process = /opt/bin/awscreds-custom --username helen
     /// ```
     CredentialProcess(CommandWithSensitiveArgs<&'a str>),
+
+    /// A profile that specifies a `credential_endpoint`
+    /// ```ini
+    /// [profile secrets-agent]
+    /// credential_endpoint = unix:///run/secrets-agent/credentials.sock
+    /// ```
+    CredentialEndpoint(&'a str),
 }
 
 /// A profile that specifies a role to assume
@@ -108,6 +136,12 @@ pub(crate) struct RoleArn<'a> {
 
     /// session name parameter to pass to the assume role provider
     pub(crate) session_name: Option<&'a str>,
+
+    /// serial number or ARN of the MFA device to assume the role with
+    pub(crate) mfa_serial: Option<&'a str>,
+
+    /// session length to pass to the assume role provider
+    pub(crate) duration: Option<Duration>,
 }
 
 /// Resolve a ProfileChain from a ProfileSet or return an error
@@ -170,7 +204,7 @@ pub(crate) fn resolve_chain(
             // The existence of a `role_arn` is the only signal that multiple profiles will be chained.
             // We check for one here and then process the profile accordingly as either a "chain provider"
             // or a "base provider"
-            if let Some(role_provider) = role_arn_from_profile(profile) {
+            if let Some(role_provider) = role_arn_from_profile(profile)? {
                 let next = chain_provider(profile)?;
                 chain.push(role_provider);
                 next
@@ -205,35 +239,50 @@ pub(crate) fn resolve_chain(
     Ok(ProfileChain { base, chain })
 }
 
-mod role {
-    pub(super) const ROLE_ARN: &str = "role_arn";
-    pub(super) const EXTERNAL_ID: &str = "external_id";
-    pub(super) const SESSION_NAME: &str = "role_session_name";
+pub(crate) mod role {
+    pub(crate) const ROLE_ARN: &str = "role_arn";
+    pub(crate) const EXTERNAL_ID: &str = "external_id";
+    pub(crate) const SESSION_NAME: &str = "role_session_name";
+    pub(crate) const MFA_SERIAL: &str = "mfa_serial";
+    pub(crate) const DURATION_SECONDS: &str = "duration_seconds";
+
+    pub(crate) const CREDENTIAL_SOURCE: &str = "credential_source";
+    pub(crate) const SOURCE_PROFILE: &str = "source_profile";
+}
+
+pub(crate) mod sso {
+    pub(crate) const ACCOUNT_ID: &str = "sso_account_id";
+    pub(crate) const REGION: &str = "sso_region";
+    pub(crate) const ROLE_NAME: &str = "sso_role_name";
+    pub(crate) const START_URL: &str = "sso_start_url";
+    pub(crate) const SESSION_NAME: &str = "sso_session";
+}
 
-    pub(super) const CREDENTIAL_SOURCE: &str = "credential_source";
-    pub(super) const SOURCE_PROFILE: &str = "source_profile";
+pub(crate) mod web_identity_token {
+    pub(crate) const TOKEN_FILE: &str = "web_identity_token_file";
 }
 
-mod sso {
-    pub(super) const ACCOUNT_ID: &str = "sso_account_id";
-    pub(super) const REGION: &str = "sso_region";
-    pub(super) const ROLE_NAME: &str = "sso_role_name";
-    pub(super) const START_URL: &str = "sso_start_url";
-    pub(super) const SESSION_NAME: &str = "sso_session";
+pub(crate) mod roles_anywhere {
+    pub(crate) const TRUST_ANCHOR_ARN: &str = "roles_anywhere_trust_anchor_arn";
+    pub(crate) const PROFILE_ARN: &str = "roles_anywhere_profile_arn";
+    pub(crate) const CERTIFICATE: &str = "roles_anywhere_certificate";
+    pub(crate) const PRIVATE_KEY: &str = "roles_anywhere_private_key";
+    pub(crate) const CERTIFICATE_CHAIN: &str = "roles_anywhere_certificate_chain";
 }
 
-mod web_identity_token {
-    pub(super) const TOKEN_FILE: &str = "web_identity_token_file";
+pub(crate) mod static_credentials {
+    pub(crate) const AWS_ACCESS_KEY_ID: &str = "aws_access_key_id";
+    pub(crate) const AWS_SECRET_ACCESS_KEY: &str = "aws_secret_access_key";
+    pub(crate) const AWS_SESSION_TOKEN: &str = "aws_session_token";
+    pub(crate) const AWS_ACCOUNT_ID: &str = "aws_account_id";
 }
 
-mod static_credentials {
-    pub(super) const AWS_ACCESS_KEY_ID: &str = "aws_access_key_id";
-    pub(super) const AWS_SECRET_ACCESS_KEY: &str = "aws_secret_access_key";
-    pub(super) const AWS_SESSION_TOKEN: &str = "aws_session_token";
+pub(crate) mod credential_process {
+    pub(crate) const CREDENTIAL_PROCESS: &str = "credential_process";
 }
 
-mod credential_process {
-    pub(super) const CREDENTIAL_PROCESS: &str = "credential_process";
+pub(crate) mod credential_endpoint {
+    pub(crate) const CREDENTIAL_ENDPOINT: &str = "credential_endpoint";
 }
 
 const PROVIDER_NAME: &str = "ProfileFile";
@@ -246,8 +295,10 @@ fn base_provider<'a>(
     match profile.get(role::CREDENTIAL_SOURCE) {
         Some(source) => Ok(BaseProvider::NamedSource(source)),
         None => web_identity_token_from_profile(profile)
+            .or_else(|| roles_anywhere_from_profile(profile).transpose())
             .or_else(|| sso_from_profile(profile_set, profile).transpose())
             .or_else(|| credential_process_from_profile(profile))
+            .or_else(|| credential_endpoint_from_profile(profile))
             .unwrap_or_else(|| Ok(BaseProvider::AccessKey(static_creds_from_profile(profile)?))),
     }
 }
@@ -284,19 +335,82 @@ fn chain_provider(profile: &Profile) -> Result<NextProfile<'_>, ProfileFileError
     }
 }
 
-fn role_arn_from_profile(profile: &Profile) -> Option<RoleArn<'_>> {
-    // Web Identity Tokens are root providers, not chained roles
-    if profile.get(web_identity_token::TOKEN_FILE).is_some() {
-        return None;
+fn role_arn_from_profile(profile: &Profile) -> Result<Option<RoleArn<'_>>, ProfileFileError> {
+    // Web Identity Tokens and Roles Anywhere sessions are root providers, not chained roles
+    if profile.get(web_identity_token::TOKEN_FILE).is_some()
+        || profile.get(roles_anywhere::TRUST_ANCHOR_ARN).is_some()
+    {
+        return Ok(None);
     }
-    let role_arn = profile.get(role::ROLE_ARN)?;
+    let Some(role_arn) = profile.get(role::ROLE_ARN) else {
+        return Ok(None);
+    };
     let session_name = profile.get(role::SESSION_NAME);
     let external_id = profile.get(role::EXTERNAL_ID);
-    Some(RoleArn {
+    let mfa_serial = profile.get(role::MFA_SERIAL);
+    let duration = duration_from_profile(profile)?;
+    Ok(Some(RoleArn {
         role_arn,
         external_id,
         session_name,
-    })
+        mfa_serial,
+        duration,
+    }))
+}
+
+fn duration_from_profile(profile: &Profile) -> Result<Option<Duration>, ProfileFileError> {
+    profile
+        .get(role::DURATION_SECONDS)
+        .map(|duration| {
+            duration
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .map_err(|_| ProfileFileError::InvalidSetting {
+                    profile: profile.name().to_string(),
+                    setting: role::DURATION_SECONDS.into(),
+                    message: format!("must be a whole number of seconds, but was `{duration}`")
+                        .into(),
+                })
+        })
+        .transpose()
+}
+
+/// Load IAM Roles Anywhere configuration from a profile
+///
+/// Example:
+/// ```ini
+/// [profile on-premises]
+/// role_arn = arn:aws:iam::123456789012:role/OnPremises
+/// roles_anywhere_trust_anchor_arn = arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/abc
+/// roles_anywhere_profile_arn = arn:aws:rolesanywhere:us-east-1:123456789012:profile/def
+/// roles_anywhere_certificate = /etc/pki/host/certificate.pem
+/// roles_anywhere_private_key = /etc/pki/host/private-key.pem
+/// ```
+fn roles_anywhere_from_profile(
+    profile: &Profile,
+) -> Result<Option<BaseProvider<'_>>, ProfileFileError> {
+    let Some(trust_anchor_arn) = profile.get(roles_anywhere::TRUST_ANCHOR_ARN) else {
+        return Ok(None);
+    };
+    let missing_field = |s| move || ProfileFileError::missing_field(profile, s);
+    Ok(Some(BaseProvider::RolesAnywhere {
+        role_arn: profile
+            .get(role::ROLE_ARN)
+            .ok_or_else(missing_field(role::ROLE_ARN))?,
+        trust_anchor_arn,
+        profile_arn: profile
+            .get(roles_anywhere::PROFILE_ARN)
+            .ok_or_else(missing_field(roles_anywhere::PROFILE_ARN))?,
+        certificate: profile
+            .get(roles_anywhere::CERTIFICATE)
+            .ok_or_else(missing_field(roles_anywhere::CERTIFICATE))?,
+        private_key: profile
+            .get(roles_anywhere::PRIVATE_KEY)
+            .ok_or_else(missing_field(roles_anywhere::PRIVATE_KEY))?,
+        certificate_chain: profile.get(roles_anywhere::CERTIFICATE_CHAIN),
+        session_name: profile.get(role::SESSION_NAME),
+        duration: duration_from_profile(profile)?,
+    }))
 }
 
 fn sso_from_profile<'a>(
@@ -417,6 +531,7 @@ fn web_identity_token_from_profile(
 /// [profile B]
 /// aws_access_key_id = abc123
 /// aws_secret_access_key = def456
+/// aws_account_id = 123456789012
 /// ```
 fn static_creds_from_profile(profile: &Profile) -> Result<Credentials, ProfileFileError> {
     use static_credentials::*;
@@ -438,14 +553,14 @@ fn static_creds_from_profile(profile: &Profile) -> Result<Credentials, ProfileFi
         profile: profile.name().to_string(),
         message: "profile missing aws_secret_access_key".into(),
     })?;
-    // There might not be an active session token so we don't error out if it's missing
-    Ok(Credentials::new(
-        access_key,
-        secret_key,
-        session_token.map(|s| s.to_string()),
-        None,
-        PROVIDER_NAME,
-    ))
+    // There might not be an active session token or account ID so we don't error out if they're missing
+    let mut builder = Credentials::builder()
+        .access_key_id(access_key)
+        .secret_access_key(secret_key)
+        .provider_name(PROVIDER_NAME);
+    builder.set_session_token(session_token.map(|s| s.to_string()));
+    builder.set_account_id(profile.get(AWS_ACCOUNT_ID).map(Into::into));
+    Ok(builder.build())
 }
 
 /// Load credentials from `credential_process`
@@ -467,6 +582,21 @@ fn credential_process_from_profile(
         })
 }
 
+/// Load credentials from `credential_endpoint`
+///
+/// Example:
+/// ```ini
+/// [profile C]
+/// credential_endpoint = http://127.0.0.1:8125/credentials
+/// ```
+fn credential_endpoint_from_profile(
+    profile: &Profile,
+) -> Option<Result<BaseProvider<'_>, ProfileFileError>> {
+    profile
+        .get(credential_endpoint::CREDENTIAL_ENDPOINT)
+        .map(|endpoint| Ok(BaseProvider::CredentialEndpoint(endpoint)))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::profile::credentials::repr::BaseProvider;
@@ -541,10 +671,14 @@ mod tests {
                 access_key_id: creds.access_key_id().into(),
                 secret_access_key: creds.secret_access_key().into(),
                 session_token: creds.session_token().map(|tok| tok.to_string()),
+                account_id: creds.account_id().map(|id| id.as_str().to_string()),
             }),
             BaseProvider::CredentialProcess(credential_process) => output.push(
                 Provider::CredentialProcess(credential_process.unredacted().into()),
             ),
+            BaseProvider::CredentialEndpoint(endpoint) => {
+                output.push(Provider::CredentialEndpoint(endpoint.into()))
+            }
             BaseProvider::WebIdentityTokenRole {
                 role_arn,
                 web_identity_token_file,
@@ -554,6 +688,25 @@ mod tests {
                 web_identity_token_file: web_identity_token_file.into(),
                 role_session_name: session_name.map(|sess| sess.to_string()),
             }),
+            BaseProvider::RolesAnywhere {
+                role_arn,
+                trust_anchor_arn,
+                profile_arn,
+                certificate,
+                private_key,
+                certificate_chain,
+                session_name,
+                duration,
+            } => output.push(Provider::RolesAnywhere {
+                role_arn: role_arn.into(),
+                trust_anchor_arn: trust_anchor_arn.into(),
+                profile_arn: profile_arn.into(),
+                certificate: certificate.into(),
+                private_key: private_key.into(),
+                certificate_chain: certificate_chain.map(ToString::to_string),
+                role_session_name: session_name.map(ToString::to_string),
+                duration_seconds: duration.map(|duration| duration.as_secs()),
+            }),
             BaseProvider::Sso {
                 sso_region,
                 sso_start_url,
@@ -573,6 +726,8 @@ mod tests {
                 role_arn: role.role_arn.into(),
                 external_id: role.external_id.map(ToString::to_string),
                 role_session_name: role.session_name.map(ToString::to_string),
+                mfa_serial: role.mfa_serial.map(ToString::to_string),
+                duration_seconds: role.duration.map(|duration| duration.as_secs()),
             })
         }
         output
@@ -590,19 +745,39 @@ mod tests {
             role_arn: String,
             external_id: Option<String>,
             role_session_name: Option<String>,
+            #[serde(default)]
+            mfa_serial: Option<String>,
+            #[serde(default)]
+            duration_seconds: Option<u64>,
         },
         AccessKey {
             access_key_id: String,
             secret_access_key: String,
             session_token: Option<String>,
+            #[serde(default)]
+            account_id: Option<String>,
         },
         NamedSource(String),
         CredentialProcess(String),
+        CredentialEndpoint(String),
         WebIdentityToken {
             role_arn: String,
             web_identity_token_file: String,
             role_session_name: Option<String>,
         },
+        RolesAnywhere {
+            role_arn: String,
+            trust_anchor_arn: String,
+            profile_arn: String,
+            certificate: String,
+            private_key: String,
+            #[serde(default)]
+            certificate_chain: Option<String>,
+            #[serde(default)]
+            role_session_name: Option<String>,
+            #[serde(default)]
+            duration_seconds: Option<u64>,
+        },
         Sso {
             sso_region: String,
             sso_start_url: String,
@@ -0,0 +1,831 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Load Credentials from IAM Roles Anywhere
+//!
+//! IAM Roles Anywhere lets workloads outside of AWS exchange an X.509 certificate for temporary
+//! credentials. The provider signs a `CreateSession` request with the certificate's private key,
+//! using the `AWS4-X509-RSA-SHA256` or `AWS4-X509-ECDSA-SHA256` algorithm, and returns the
+//! credentials of the role that the session was created for.
+//!
+//! The certificate and private key are PEM encoded. The private key must either be an
+//! unencrypted PKCS#8 key (`BEGIN PRIVATE KEY`) or an RSA key in PKCS#1 format
+//! (`BEGIN RSA PRIVATE KEY`). RSA keys and ECDSA keys on the P-256 curve are supported.
+//! Certificates and keys that are loaded from files are read again every time credentials are
+//! loaded, so that rotated certificates are picked up.
+//!
+//! _Note: This provider requires the `roles-anywhere` feature, and it isn't part of the
+//! [default provider chain](crate::default_provider)._
+//!
+//! ## AWS Profile Configuration
+//! Roles Anywhere credentials can be loaded by the
+//! [`ProfileFileCredentialsProvider`](crate::profile::credentials) from a profile that sets
+//! a role ARN and a trust anchor ARN:
+//!
+//! ```ini
+//! [profile on-premises]
+//! role_arn = arn:aws:iam::123456789012:role/OnPremises
+//! roles_anywhere_trust_anchor_arn = arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/4579702c-8b0e-4e8b-8b5e-c5c9a0d1f4a2
+//! roles_anywhere_profile_arn = arn:aws:rolesanywhere:us-east-1:123456789012:profile/6d3f2f4e-5e0d-4b83-8e3e-5a5a5e5c4d3b
+//! roles_anywhere_certificate = /etc/pki/host/certificate.pem
+//! roles_anywhere_private_key = /etc/pki/host/private-key.pem
+//! # optional
+//! roles_anywhere_certificate_chain = /etc/pki/host/chain.pem
+//! role_session_name = my-host
+//! duration_seconds = 3600
+//! ```
+//!
+//! Such a profile can also be the `source_profile` of another role.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn test() {
+//! use aws_config::roles_anywhere::RolesAnywhereCredentialsProvider;
+//! let provider = RolesAnywhereCredentialsProvider::builder(
+//!     "arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/4579702c-8b0e-4e8b-8b5e-c5c9a0d1f4a2",
+//!     "arn:aws:rolesanywhere:us-east-1:123456789012:profile/6d3f2f4e-5e0d-4b83-8e3e-5a5a5e5c4d3b",
+//!     "arn:aws:iam::123456789012:role/OnPremises",
+//! )
+//! .certificate_file("/etc/pki/host/certificate.pem")
+//! .private_key_file("/etc/pki/host/private-key.pem")
+//! .build();
+//! # }
+//! ```
+
+use crate::json_credentials::InvalidJsonCredentials;
+use crate::provider_config::ProviderConfig;
+use crate::sts::util::account_id_from_arn;
+use aws_credential_types::provider::{self, error::CredentialsError, future, ProvideCredentials};
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{
+    sign, SignableBody, SignableRequest, SigningParams, SigningSettings,
+};
+use aws_sigv4::sign::x509::{self, Certificate, PrivateKey};
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_json::deserialize::{json_token_iter, Token};
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime::client::retries::classifiers::{
+    HttpStatusCodeClassifier, TransientErrorClassifier,
+};
+use aws_smithy_runtime_api::client::interceptors::context::Error;
+use aws_smithy_runtime_api::client::orchestrator::{
+    HttpResponse, OrchestratorError, SensitiveOutput,
+};
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::Layer;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::timeout::TimeoutConfig;
+use aws_smithy_types::DateTime;
+use aws_types::os_shim_internal::Fs;
+use aws_types::region::Region;
+use http::header::{ACCEPT, CONTENT_TYPE};
+use std::borrow::Cow;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const PROVIDER_NAME: &str = "RolesAnywhere";
+const SIGNING_NAME: &str = "rolesanywhere";
+const CREATE_SESSION_PATH: &str = "/sessions";
+
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Credentials provider that exchanges an X.509 certificate for credentials with IAM Roles Anywhere
+///
+/// See the [module documentation](crate::roles_anywhere) for more details.
+#[derive(Debug)]
+pub struct RolesAnywhereCredentialsProvider {
+    trust_anchor_arn: String,
+    profile_arn: String,
+    role_arn: String,
+    certificate: Option<PemSource>,
+    private_key: Option<PemSource>,
+    certificate_chain: Option<PemSource>,
+    session_name: Option<String>,
+    session_duration: Option<Duration>,
+    fs: Fs,
+    time_source: SharedTimeSource,
+    // `None` if the region to call Roles Anywhere in couldn't be determined
+    operation: Option<Operation<CreateSession, Credentials, CredentialsError>>,
+}
+
+impl RolesAnywhereCredentialsProvider {
+    /// Returns a builder for a provider that creates sessions with the given trust anchor,
+    /// Roles Anywhere profile, and role.
+    pub fn builder(
+        trust_anchor_arn: impl Into<String>,
+        profile_arn: impl Into<String>,
+        role_arn: impl Into<String>,
+    ) -> Builder {
+        Builder {
+            trust_anchor_arn: trust_anchor_arn.into(),
+            profile_arn: profile_arn.into(),
+            role_arn: role_arn.into(),
+            certificate: None,
+            private_key: None,
+            certificate_chain: None,
+            session_name: None,
+            session_duration: None,
+            region: None,
+            endpoint_url: None,
+            config: None,
+        }
+    }
+
+    async fn credentials(&self) -> provider::Result {
+        let operation = self.operation.as_ref().ok_or_else(|| {
+            CredentialsError::invalid_configuration(
+                "the region of the Roles Anywhere trust anchor could not be determined. \
+                 Set a region with `RolesAnywhereCredentialsProvider::builder(...).region(...)`",
+            )
+        })?;
+        let certificate = self
+            .load_pem(self.certificate.as_ref(), "certificate")
+            .await?;
+        let private_key = self
+            .load_pem(self.private_key.as_ref(), "private key")
+            .await?;
+        let certificate_chain = match &self.certificate_chain {
+            Some(source) => Certificate::chain_from_pem(source.load(&self.fs).await?)
+                .map_err(CredentialsError::invalid_configuration)?,
+            None => Vec::new(),
+        };
+        let input = CreateSession {
+            certificate: Certificate::from_pem(certificate)
+                .map_err(CredentialsError::invalid_configuration)?,
+            certificate_chain,
+            private_key: PrivateKey::from_pem(private_key)
+                .map_err(CredentialsError::invalid_configuration)?,
+            body: self.request_body(),
+            time: self.time_source.now(),
+        };
+        tracing::debug!(
+            serial_number = input.certificate.serial_number(),
+            role_arn = %self.role_arn,
+            "creating a Roles Anywhere session"
+        );
+        match operation.invoke(input).await {
+            Ok(credentials) => Ok(credentials),
+            Err(SdkError::ServiceError(context)) => Err(context.into_err()),
+            Err(other) => Err(CredentialsError::unhandled(other)),
+        }
+    }
+
+    async fn load_pem(
+        &self,
+        source: Option<&PemSource>,
+        what: &'static str,
+    ) -> Result<Vec<u8>, CredentialsError> {
+        match source {
+            Some(source) => source.load(&self.fs).await,
+            None => Err(CredentialsError::invalid_configuration(format!(
+                "a {what} must be configured to use Roles Anywhere"
+            ))),
+        }
+    }
+
+    fn request_body(&self) -> String {
+        let mut body = String::new();
+        let mut object = JsonObjectWriter::new(&mut body);
+        if let Some(duration) = self.session_duration {
+            object
+                .key("durationSeconds")
+                .number(aws_smithy_types::Number::PosInt(duration.as_secs()));
+        }
+        object.key("profileArn").string(&self.profile_arn);
+        object.key("roleArn").string(&self.role_arn);
+        if let Some(session_name) = &self.session_name {
+            object.key("sessionName").string(session_name);
+        }
+        object.key("trustAnchorArn").string(&self.trust_anchor_arn);
+        object.finish();
+        body
+    }
+}
+
+impl ProvideCredentials for RolesAnywhereCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+/// Builder for [`RolesAnywhereCredentialsProvider`]
+#[derive(Debug)]
+pub struct Builder {
+    trust_anchor_arn: String,
+    profile_arn: String,
+    role_arn: String,
+    certificate: Option<PemSource>,
+    private_key: Option<PemSource>,
+    certificate_chain: Option<PemSource>,
+    session_name: Option<String>,
+    session_duration: Option<Duration>,
+    region: Option<Region>,
+    endpoint_url: Option<String>,
+    config: Option<ProviderConfig>,
+}
+
+impl Builder {
+    /// Configure generic options of the [`RolesAnywhereCredentialsProvider`]
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.config = Some(provider_config.clone());
+        self
+    }
+
+    /// Sets the path of the PEM file that contains the certificate.
+    pub fn certificate_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.certificate = Some(PemSource::File(path.into()));
+        self
+    }
+
+    /// Sets the PEM encoded certificate.
+    pub fn certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.certificate = Some(PemSource::Contents(pem.into()));
+        self
+    }
+
+    /// Sets the path of the PEM file that contains the certificate's private key.
+    pub fn private_key_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.private_key = Some(PemSource::File(path.into()));
+        self
+    }
+
+    /// Sets the PEM encoded private key of the certificate.
+    pub fn private_key(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.private_key = Some(PemSource::Contents(pem.into()));
+        self
+    }
+
+    /// Sets the path of the PEM file that contains the intermediate certificates between the
+    /// certificate and the trust anchor.
+    ///
+    /// This is optional.
+    pub fn certificate_chain_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.certificate_chain = Some(PemSource::File(path.into()));
+        self
+    }
+
+    /// Sets the PEM encoded intermediate certificates between the certificate and the trust anchor.
+    ///
+    /// This is optional.
+    pub fn certificate_chain(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.certificate_chain = Some(PemSource::Contents(pem.into()));
+        self
+    }
+
+    /// Sets the name of the role session.
+    ///
+    /// This is optional, and is only accepted when the Roles Anywhere profile allows custom
+    /// role session names. By default, the session is named after the certificate's serial number.
+    pub fn session_name(mut self, session_name: impl Into<String>) -> Self {
+        self.session_name = Some(session_name.into());
+        self
+    }
+
+    /// Sets how long the credentials are valid for.
+    ///
+    /// This is optional, and defaults to one hour.
+    pub fn session_duration(mut self, duration: Duration) -> Self {
+        self.session_duration = Some(duration);
+        self
+    }
+
+    /// Sets the region to call Roles Anywhere in.
+    ///
+    /// By default, the region of the trust anchor ARN is used.
+    pub fn region(mut self, region: Region) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Overrides the endpoint that sessions are created with.
+    ///
+    /// By default, `https://rolesanywhere.{region}.amazonaws.com` is used.
+    pub fn endpoint_url(mut self, endpoint_url: impl Into<String>) -> Self {
+        self.endpoint_url = Some(endpoint_url.into());
+        self
+    }
+
+    /// Build a [`RolesAnywhereCredentialsProvider`]
+    pub fn build(self) -> RolesAnywhereCredentialsProvider {
+        let conf = self.config.unwrap_or_default();
+        let region = self
+            .region
+            .or_else(|| region_from_arn(&self.trust_anchor_arn))
+            .or_else(|| conf.region());
+        let operation = region.map(|region| {
+            let endpoint_url = self
+                .endpoint_url
+                .unwrap_or_else(|| format!("https://rolesanywhere.{region}.amazonaws.com"));
+            create_session_operation(&conf, region, endpoint_url)
+        });
+        RolesAnywhereCredentialsProvider {
+            trust_anchor_arn: self.trust_anchor_arn,
+            profile_arn: self.profile_arn,
+            role_arn: self.role_arn,
+            certificate: self.certificate,
+            private_key: self.private_key,
+            certificate_chain: self.certificate_chain,
+            session_name: self.session_name,
+            session_duration: self.session_duration,
+            fs: conf.fs(),
+            time_source: conf.time_source(),
+            operation,
+        }
+    }
+}
+
+/// Where a PEM encoded certificate or key is loaded from
+#[derive(Clone)]
+enum PemSource {
+    File(PathBuf),
+    Contents(Vec<u8>),
+}
+
+impl PemSource {
+    async fn load(&self, fs: &Fs) -> Result<Vec<u8>, CredentialsError> {
+        match self {
+            PemSource::File(path) => fs.read_to_end(path).await.map_err(|err| {
+                CredentialsError::invalid_configuration(format!(
+                    "failed to read `{}`: {}",
+                    path.display(),
+                    DisplayErrorContext(&err)
+                ))
+            }),
+            PemSource::Contents(pem) => Ok(pem.clone()),
+        }
+    }
+}
+
+impl fmt::Debug for PemSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PemSource::File(path) => f.debug_tuple("File").field(path).finish(),
+            PemSource::Contents(_) => f.debug_tuple("Contents").field(&"** redacted **").finish(),
+        }
+    }
+}
+
+/// The input of the `CreateSession` operation
+struct CreateSession {
+    certificate: Certificate,
+    certificate_chain: Vec<Certificate>,
+    private_key: PrivateKey,
+    body: String,
+    time: SystemTime,
+}
+
+impl fmt::Debug for CreateSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateSession")
+            .field("certificate", &self.certificate)
+            .field("body", &self.body)
+            .finish_non_exhaustive()
+    }
+}
+
+fn create_session_operation(
+    provider_config: &ProviderConfig,
+    region: Region,
+    endpoint_url: String,
+) -> Operation<CreateSession, Credentials, CredentialsError> {
+    let mut builder = Operation::builder()
+        .service_name("RolesAnywhere")
+        .operation_name("CreateSession")
+        .with_connection_poisoning()
+        .endpoint_url(&endpoint_url)
+        .no_auth()
+        .timeout_config(
+            TimeoutConfig::builder()
+                .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
+                .read_timeout(DEFAULT_READ_TIMEOUT)
+                .build(),
+        )
+        .runtime_plugin(StaticRuntimePlugin::new().with_config({
+            let mut layer = Layer::new("SensitiveOutput");
+            layer.store_put(SensitiveOutput);
+            layer.freeze()
+        }));
+    if let Some(http_client) = provider_config.http_client() {
+        builder = builder.http_client(http_client);
+    }
+    if let Some(sleep_impl) = provider_config.sleep_impl() {
+        builder = builder
+            .standard_retry(&RetryConfig::standard())
+            // Socket errors and network timeouts
+            .retry_classifier(TransientErrorClassifier::<Error>::new())
+            // 5xx errors
+            .retry_classifier(HttpStatusCodeClassifier::default())
+            .sleep_impl(sleep_impl);
+    } else {
+        builder = builder.no_retry();
+    }
+    // The request is signed for the URL that the endpoint resolves it to
+    let signing_url = format!(
+        "{}{CREATE_SESSION_PATH}",
+        endpoint_url.trim_end_matches('/')
+    );
+    builder
+        .serializer(move |input: CreateSession| {
+            let params: SigningParams<'_> = x509::SigningParams::builder()
+                .certificate(&input.certificate)
+                .certificate_chain(&input.certificate_chain)
+                .private_key(&input.private_key)
+                .region(region.as_ref())
+                .name(SIGNING_NAME)
+                .time(input.time)
+                .settings(SigningSettings::default())
+                .build()?
+                .into();
+            let signable = SignableRequest::new(
+                "POST",
+                signing_url.as_str(),
+                [("content-type", "application/json")].into_iter(),
+                SignableBody::Bytes(input.body.as_bytes()),
+            )?;
+            let (instructions, _signature) = sign(signable, &params)?.into_parts();
+            let mut request = http::Request::builder()
+                .method("POST")
+                .uri(CREATE_SESSION_PATH)
+                .header(CONTENT_TYPE, "application/json")
+                .header(ACCEPT, "application/json")
+                .body(SdkBody::from(input.body))?;
+            instructions.apply_to_request_http0x(&mut request);
+            Ok(request.try_into()?)
+        })
+        .deserializer(parse_response)
+        .build()
+}
+
+fn parse_response(
+    response: &HttpResponse,
+) -> Result<Credentials, OrchestratorError<CredentialsError>> {
+    let body = response.body().bytes().expect("non-streaming deserializer");
+    if !response.status().is_success() {
+        let message = error_message(body).unwrap_or_else(|| "no message".into());
+        return Err(OrchestratorError::operation(
+            CredentialsError::provider_error(format!(
+                "failed to create a Roles Anywhere session ({}): {message}",
+                response.status().as_u16()
+            )),
+        ));
+    }
+    parse_create_session_response(body)
+        .map_err(|err| OrchestratorError::operation(CredentialsError::unhandled(err)))
+}
+
+/// Reads the `message` of an error response.
+fn error_message(body: &[u8]) -> Option<String> {
+    let mut message = None;
+    crate::json_credentials::json_parse_loop(body, |key, value| {
+        if let Token::ValueString { value, .. } = value {
+            if key.eq_ignore_ascii_case("message") {
+                message = Some(value.to_unescaped()?.into_owned());
+            }
+        }
+        Ok(())
+    })
+    .ok()?;
+    message
+}
+
+/// Reads the credentials of the first entry of the `credentialSet` in a `CreateSession` response.
+///
+/// The account ID of the credentials is taken from the ARN of the assumed role user.
+///
+/// ```json
+/// {
+///   "credentialSet": [{
+///     "assumedRoleUser": {
+///       "arn": "arn:aws:sts::123456789012:assumed-role/...",
+///       "assumedRoleId": "..."
+///     },
+///     "credentials": {
+///       "accessKeyId": "...",
+///       "expiration": "2024-01-02T04:04:05Z",
+///       "secretAccessKey": "...",
+///       "sessionToken": "..."
+///     },
+///     ...
+///   }],
+///   "subjectArn": "..."
+/// }
+/// ```
+fn parse_create_session_response(body: &[u8]) -> Result<Credentials, InvalidJsonCredentials> {
+    let mut access_key_id = None;
+    let mut secret_access_key = None;
+    let mut session_token = None;
+    let mut expiration = None;
+    let mut assumed_role_arn = None;
+
+    let mut key: Option<Cow<'_, str>> = None;
+    // The object being read along with the depth of nested objects within it, if we're inside of one
+    let mut section: Option<(&str, usize)> = None;
+    let mut found_credentials = false;
+    let mut found_assumed_role_user = false;
+    for token in json_token_iter(body) {
+        match token? {
+            Token::ObjectKey {
+                key: object_key, ..
+            } => {
+                key = Some(object_key.to_unescaped()?);
+                continue;
+            }
+            Token::StartObject { .. } => match section.as_mut() {
+                Some((_, depth)) => *depth += 1,
+                None if !found_credentials && key.as_deref() == Some("credentials") => {
+                    section = Some(("credentials", 0))
+                }
+                None if !found_assumed_role_user && key.as_deref() == Some("assumedRoleUser") => {
+                    section = Some(("assumedRoleUser", 0))
+                }
+                None => {}
+            },
+            Token::EndObject { .. } => match section {
+                Some((name, 0)) => {
+                    section = None;
+                    match name {
+                        "credentials" => found_credentials = true,
+                        _ => found_assumed_role_user = true,
+                    }
+                }
+                Some((name, depth)) => section = Some((name, depth - 1)),
+                None => {}
+            },
+            Token::ValueString { value, .. } => match (section, key.as_deref()) {
+                (Some(("credentials", 0)), Some("accessKeyId")) => {
+                    access_key_id = Some(value.to_unescaped()?)
+                }
+                (Some(("credentials", 0)), Some("secretAccessKey")) => {
+                    secret_access_key = Some(value.to_unescaped()?)
+                }
+                (Some(("credentials", 0)), Some("sessionToken")) => {
+                    session_token = Some(value.to_unescaped()?)
+                }
+                (Some(("credentials", 0)), Some("expiration")) => {
+                    expiration = Some(value.to_unescaped()?)
+                }
+                (Some(("assumedRoleUser", 0)), Some("arn")) => {
+                    assumed_role_arn = Some(value.to_unescaped()?)
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        key = None;
+    }
+    if !found_credentials {
+        return Err(InvalidJsonCredentials::MissingField("credentialSet"));
+    }
+
+    let access_key_id = access_key_id.ok_or(InvalidJsonCredentials::MissingField("accessKeyId"))?;
+    let secret_access_key =
+        secret_access_key.ok_or(InvalidJsonCredentials::MissingField("secretAccessKey"))?;
+    let session_token =
+        session_token.ok_or(InvalidJsonCredentials::MissingField("sessionToken"))?;
+    let expiration = expiration.ok_or(InvalidJsonCredentials::MissingField("expiration"))?;
+    let expiration = SystemTime::try_from(
+        DateTime::from_str(expiration.as_ref(), Format::DateTime).map_err(|err| {
+            InvalidJsonCredentials::InvalidField {
+                field: "expiration",
+                err: err.into(),
+            }
+        })?,
+    )
+    .map_err(|_| {
+        InvalidJsonCredentials::Other(
+            "credential expiration time cannot be represented by a SystemTime".into(),
+        )
+    })?;
+    let mut builder = Credentials::builder()
+        .access_key_id(access_key_id)
+        .secret_access_key(secret_access_key)
+        .session_token(session_token)
+        .expiry(expiration)
+        .provider_name(PROVIDER_NAME);
+    builder.set_account_id(
+        assumed_role_arn
+            .as_deref()
+            .and_then(account_id_from_arn)
+            .map(Into::into),
+    );
+    Ok(builder.build())
+}
+
+/// Returns the region of an ARN such as `arn:aws:rolesanywhere:us-east-1:123456789012:trust-anchor/...`
+fn region_from_arn(arn: &str) -> Option<Region> {
+    match arn.split(':').nth(3) {
+        Some(region) if !region.is_empty() => Some(Region::new(region.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aws_smithy_async::time::StaticTimeSource;
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use std::collections::HashMap;
+    use std::time::UNIX_EPOCH;
+
+    const CERTIFICATE: &str = include_str!("../test-data/roles-anywhere/certificate.pem");
+    const PRIVATE_KEY: &str = include_str!("../test-data/roles-anywhere/private-key.pem");
+    const TRUST_ANCHOR_ARN: &str =
+        "arn:aws:rolesanywhere:us-west-2:123456789012:trust-anchor/4579702c-8b0e-4e8b-8b5e-c5c9a0d1f4a2";
+    const PROFILE_ARN: &str =
+        "arn:aws:rolesanywhere:us-west-2:123456789012:profile/6d3f2f4e-5e0d-4b83-8e3e-5a5a5e5c4d3b";
+    const ROLE_ARN: &str = "arn:aws:iam::123456789012:role/OnPremises";
+
+    const CREATE_SESSION_RESPONSE: &str = r#"{
+        "credentialSet": [{
+            "assumedRoleUser": {
+                "arn": "arn:aws:sts::123456789012:assumed-role/OnPremises/5373003642731685151011",
+                "assumedRoleId": "AROAEXAMPLE:5373003642731685151011"
+            },
+            "credentials": {
+                "accessKeyId": "ASIARTESTID",
+                "expiration": "2024-01-02T04:04:05Z",
+                "secretAccessKey": "TESTSECRETKEY",
+                "sessionToken": "TESTSESSIONTOKEN"
+            },
+            "packedPolicySize": 10,
+            "roleArn": "arn:aws:iam::123456789012:role/OnPremises",
+            "sourceIdentity": "CN=roles-anywhere-test-rsa"
+        }],
+        "subjectArn": "arn:aws:rolesanywhere:us-west-2:123456789012:subject/41cl0bae-6783-40d4-ab20-65dc5d922e45"
+    }"#;
+
+    fn provider_config(http_client: StaticReplayClient) -> ProviderConfig {
+        ProviderConfig::no_configuration()
+            .with_http_client(http_client)
+            .with_time_source(StaticTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1704164645),
+            ))
+            .with_fs(Fs::from_map({
+                let mut fs = HashMap::new();
+                fs.insert("/pki/certificate.pem".to_string(), CERTIFICATE);
+                fs.insert("/pki/private-key.pem".to_string(), PRIVATE_KEY);
+                fs
+            }))
+    }
+
+    #[tokio::test]
+    async fn create_session_against_local_endpoint() {
+        let http_client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .method("POST")
+                .uri("http://localhost:8443/sessions")
+                .header("content-type", "application/json")
+                .header("accept", "application/json")
+                .header("x-amz-date", "20240102T030405Z")
+                .body(SdkBody::from(
+                    r#"{"durationSeconds":900,"profileArn":"arn:aws:rolesanywhere:us-west-2:123456789012:profile/6d3f2f4e-5e0d-4b83-8e3e-5a5a5e5c4d3b","roleArn":"arn:aws:iam::123456789012:role/OnPremises","trustAnchorArn":"arn:aws:rolesanywhere:us-west-2:123456789012:trust-anchor/4579702c-8b0e-4e8b-8b5e-c5c9a0d1f4a2"}"#,
+                ))
+                .unwrap(),
+            http::Response::builder()
+                .status(201)
+                .body(SdkBody::from(CREATE_SESSION_RESPONSE))
+                .unwrap(),
+        )]);
+        let provider =
+            RolesAnywhereCredentialsProvider::builder(TRUST_ANCHOR_ARN, PROFILE_ARN, ROLE_ARN)
+                .certificate_file("/pki/certificate.pem")
+                .private_key_file("/pki/private-key.pem")
+                .session_duration(Duration::from_secs(900))
+                .endpoint_url("http://localhost:8443")
+                .configure(&provider_config(http_client.clone()))
+                .build();
+
+        let credentials = provider.provide_credentials().await.expect("success");
+        assert_eq!("ASIARTESTID", credentials.access_key_id());
+        assert_eq!("TESTSECRETKEY", credentials.secret_access_key());
+        assert_eq!(Some("TESTSESSIONTOKEN"), credentials.session_token());
+        assert_eq!(
+            Some("123456789012"),
+            credentials.account_id().map(|id| id.as_str())
+        );
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(1704168245)),
+            credentials.expiry()
+        );
+
+        http_client.assert_requests_match(&["authorization", "x-amz-x509"]);
+        let request = http_client.actual_requests().next().unwrap();
+        let authorization = request.headers().get("authorization").unwrap();
+        assert!(
+            authorization.starts_with(
+                "AWS4-X509-RSA-SHA256 \
+                 Credential=5373003642731685151011/20240102/us-west-2/rolesanywhere/aws4_request, \
+                 SignedHeaders=accept;content-type;host;x-amz-date;x-amz-x509, Signature="
+            ),
+            "{authorization}"
+        );
+        assert!(request.headers().get("x-amz-x509").is_some());
+    }
+
+    #[tokio::test]
+    async fn error_responses_are_provider_errors() {
+        let http_client = StaticReplayClient::new(vec![ReplayEvent::new(
+            http::Request::builder()
+                .uri("https://rolesanywhere.us-west-2.amazonaws.com/sessions")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(403)
+                .body(SdkBody::from(
+                    r#"{"message":"Untrusted signing certificate."}"#,
+                ))
+                .unwrap(),
+        )]);
+        let provider =
+            RolesAnywhereCredentialsProvider::builder(TRUST_ANCHOR_ARN, PROFILE_ARN, ROLE_ARN)
+                .certificate(CERTIFICATE)
+                .private_key(PRIVATE_KEY)
+                .configure(&provider_config(http_client.clone()))
+                .build();
+
+        let err = provider.provide_credentials().await.unwrap_err();
+        assert!(matches!(err, CredentialsError::ProviderError(_)), "{err:?}");
+        assert!(
+            format!("{}", DisplayErrorContext(&err)).contains("Untrusted signing certificate."),
+            "{}",
+            DisplayErrorContext(&err)
+        );
+        let request = http_client.actual_requests().next().unwrap();
+        assert_eq!(
+            "https://rolesanywhere.us-west-2.amazonaws.com/sessions",
+            request.uri()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_configuration() {
+        let config = provider_config(StaticReplayClient::new(vec![]));
+        let provider =
+            RolesAnywhereCredentialsProvider::builder(TRUST_ANCHOR_ARN, PROFILE_ARN, ROLE_ARN)
+                .certificate_file("/pki/certificate.pem")
+                .configure(&config)
+                .build();
+        let err = provider.provide_credentials().await.unwrap_err();
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "{err:?}"
+        );
+
+        // The private key is not a certificate
+        let provider =
+            RolesAnywhereCredentialsProvider::builder(TRUST_ANCHOR_ARN, PROFILE_ARN, ROLE_ARN)
+                .certificate_file("/pki/private-key.pem")
+                .private_key_file("/pki/private-key.pem")
+                .configure(&config)
+                .build();
+        let err = provider.provide_credentials().await.unwrap_err();
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "{err:?}"
+        );
+
+        // Without a region in the trust anchor ARN or in the provider config
+        let provider =
+            RolesAnywhereCredentialsProvider::builder("not-an-arn", PROFILE_ARN, ROLE_ARN)
+                .certificate_file("/pki/certificate.pem")
+                .private_key_file("/pki/private-key.pem")
+                .configure(&config)
+                .build();
+        let err = provider.provide_credentials().await.unwrap_err();
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration(_)),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn parse_response_without_credentials() {
+        let err = parse_create_session_response(br#"{"credentialSet":[],"subjectArn":"arn"}"#)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            InvalidJsonCredentials::MissingField("credentialSet")
+        ));
+        let err = parse_create_session_response(
+            br#"{"credentialSet":[{"credentials":{"accessKeyId":"AKID"}}]}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            InvalidJsonCredentials::MissingField("secretAccessKey")
+        ));
+    }
+}
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! SSO Credentials and Token providers
+//! SSO Credentials and Token providers, and SSO login
 
 pub mod credentials;
 
@@ -13,4 +13,8 @@ pub mod token;
 
 pub use token::SsoTokenProvider;
 
+pub mod login;
+
+pub use login::SsoLogin;
+
 mod cache;
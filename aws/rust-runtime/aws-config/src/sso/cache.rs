@@ -347,6 +347,15 @@ pub(super) async fn save_cached_token(
 
     let home = home_dir(env, Os::real()).ok_or(CachedSsoTokenError::NoHomeDirectory)?;
     let path = cached_token_path(identifier, &home);
+    if let Some(cache_dir) = path.parent() {
+        fs.create_dir_all(cache_dir)
+            .await
+            .map_err(|err| CachedSsoTokenError::IoError {
+                what: "create directory",
+                path: cache_dir.into(),
+                source: err,
+            })?;
+    }
     fs.write(&path, out.as_bytes())
         .await
         .map_err(|err| CachedSsoTokenError::IoError {
@@ -0,0 +1,884 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! SSO Login
+//!
+//! This performs the initial SSO login with the OAuth 2.0 device authorization flow, the same way
+//! `aws sso login` does. The user is given a URL and a code to approve the login with in a browser,
+//! and the resulting token is written to `~/.aws/sso/cache`, where it can be loaded by the
+//! [`SsoTokenProvider`](super::SsoTokenProvider) and the
+//! [`SsoCredentialsProvider`](super::SsoCredentialsProvider).
+
+use crate::identity::IdentityCache;
+use crate::sso::cache::{load_cached_token, save_cached_token, CachedSsoToken};
+use aws_sdk_ssooidc::operation::create_token::{CreateTokenError, CreateTokenOutput};
+use aws_sdk_ssooidc::Client as SsoOidcClient;
+use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_runtime_api::client::identity::http::Token;
+use aws_types::os_shim_internal::{Env, Fs};
+use aws_types::region::Region;
+use aws_types::SdkConfig;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+use zeroize::Zeroizing;
+
+const DEFAULT_CLIENT_NAME: &str = "aws-sdk-rust";
+const DEFAULT_SCOPE: &str = "sso:account:access";
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// RFC 8628 requires the polling interval to be increased by 5 seconds on every `slow_down` error
+const SLOW_DOWN_INCREMENT: Duration = Duration::from_secs(5);
+// Cached client registrations that expire sooner than this are replaced rather than reused
+const REGISTRATION_BUFFER_TIME: Duration = Duration::from_secs(15 * 60 /* 15 minutes */);
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// A pending device authorization that the user must approve to complete an SSO login.
+///
+/// The user approves it by visiting the [`verification_uri`](Self::verification_uri) and
+/// entering the [`user_code`](Self::user_code), or by visiting the
+/// [`verification_uri_complete`](Self::verification_uri_complete), which includes the code.
+#[derive(Debug)]
+pub struct DeviceAuthorization {
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    user_code: String,
+    expires_at: SystemTime,
+}
+
+impl DeviceAuthorization {
+    /// Returns the URI that the user should visit to approve the login.
+    pub fn verification_uri(&self) -> &str {
+        &self.verification_uri
+    }
+
+    /// Returns the verification URI with the user code already filled in, if SSO OIDC provided one.
+    ///
+    /// This is the URI to open when launching a browser for the user.
+    pub fn verification_uri_complete(&self) -> Option<&str> {
+        self.verification_uri_complete.as_deref()
+    }
+
+    /// Returns the code that the user should enter at the verification URI.
+    pub fn user_code(&self) -> &str {
+        &self.user_code
+    }
+
+    /// Returns when this authorization expires if it hasn't been approved.
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+}
+
+struct Registration {
+    client_id: String,
+    client_secret: Zeroizing<String>,
+    expires_at: Option<SystemTime>,
+}
+
+/// SSO Login
+///
+/// Logs in to an SSO session with the device authorization flow and caches the resulting token
+/// in `~/.aws/sso/cache/<hash>.json`, in the same format that the AWS CLI uses.
+///
+/// The cache file is named after the [`session_name`](Builder::session_name) when it is set, which
+/// is what the [`SsoTokenProvider`](super::SsoTokenProvider) loads for profiles that use an
+/// `sso-session`. Otherwise, it is named after the [`start_url`](Builder::start_url), which is what
+/// the [`SsoCredentialsProvider`](super::SsoCredentialsProvider) loads for legacy SSO profiles.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::sso::login::SsoLogin;
+/// use aws_types::region::Region;
+///
+/// # async fn example() -> Result<(), aws_config::sso::login::SsoLoginError> {
+/// let login = SsoLogin::builder()
+///     .session_name("my-sso")
+///     .start_url("https://d-123.awsapps.com/start")
+///     .region(Region::new("us-east-1"))
+///     .build()
+///     .await;
+/// let token = login
+///     .login(|authorization| {
+///         println!(
+///             "Open {} and enter the code {} to log in",
+///             authorization.verification_uri(),
+///             authorization.user_code()
+///         );
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SsoLogin {
+    env: Env,
+    fs: Fs,
+    region: Region,
+    session_name: Option<String>,
+    start_url: String,
+    client_name: String,
+    scopes: Vec<String>,
+    sdk_config: SdkConfig,
+}
+
+impl SsoLogin {
+    /// Creates a `SsoLogin` builder.
+    pub fn builder() -> Builder {
+        Default::default()
+    }
+
+    /// Logs in and caches the resulting token.
+    ///
+    /// `on_authorization` is called with the [`DeviceAuthorization`] once it has been started, and
+    /// should show the user where to approve it. This then waits until the user approves or denies
+    /// it, or until it expires.
+    pub async fn login(
+        &self,
+        on_authorization: impl FnOnce(&DeviceAuthorization) + Send,
+    ) -> Result<Token, SsoLoginError> {
+        let time_source = self.sdk_config.time_source().ok_or_else(|| {
+            SsoLoginError::invalid_configuration("a time source is required for SSO login")
+        })?;
+        let sleep_impl = self.sdk_config.sleep_impl().ok_or_else(|| {
+            SsoLoginError::invalid_configuration("a sleep implementation is required for SSO login")
+        })?;
+        let config = self
+            .sdk_config
+            .to_builder()
+            .region(Some(self.region.clone()))
+            .identity_cache(IdentityCache::no_cache())
+            .build();
+        let client = SsoOidcClient::new(&config);
+
+        let registration = self.register_client(&client, time_source.now()).await?;
+        let authorization = client
+            .start_device_authorization()
+            .client_id(&registration.client_id)
+            .client_secret(registration.client_secret.as_str())
+            .start_url(&self.start_url)
+            .send()
+            .await
+            .map_err(|err| SsoLoginError::service_error("StartDeviceAuthorization", err))?;
+        let device_code = authorization
+            .device_code
+            .ok_or_else(|| SsoLoginError::invalid_response("missing device code"))?;
+        let expires_in = positive_duration(authorization.expires_in).ok_or_else(|| {
+            SsoLoginError::invalid_response("invalid device authorization expiration")
+        })?;
+        let mut interval =
+            positive_duration(authorization.interval).unwrap_or(DEFAULT_POLL_INTERVAL);
+        let expires_at = time_source.now() + expires_in;
+
+        on_authorization(&DeviceAuthorization {
+            verification_uri: authorization
+                .verification_uri
+                .ok_or_else(|| SsoLoginError::invalid_response("missing verification URI"))?,
+            verification_uri_complete: authorization.verification_uri_complete,
+            user_code: authorization
+                .user_code
+                .ok_or_else(|| SsoLoginError::invalid_response("missing user code"))?,
+            expires_at,
+        });
+
+        let output = loop {
+            sleep_impl.sleep(interval).await;
+            if time_source.now() >= expires_at {
+                return Err(SsoLoginError::AuthorizationExpired);
+            }
+            let result = client
+                .create_token()
+                .grant_type(DEVICE_CODE_GRANT_TYPE)
+                .client_id(&registration.client_id)
+                .client_secret(registration.client_secret.as_str())
+                .device_code(&device_code)
+                .send()
+                .await;
+            match result {
+                Ok(output) => break output,
+                Err(err) => match err.as_service_error() {
+                    Some(CreateTokenError::AuthorizationPendingException(_)) => {
+                        tracing::debug!("device authorization is still pending");
+                    }
+                    Some(CreateTokenError::SlowDownException(_)) => {
+                        interval += SLOW_DOWN_INCREMENT;
+                        tracing::debug!(interval = ?interval, "SSO OIDC asked to slow down polling");
+                    }
+                    Some(CreateTokenError::ExpiredTokenException(_)) => {
+                        return Err(SsoLoginError::AuthorizationExpired);
+                    }
+                    Some(CreateTokenError::AccessDeniedException(_)) => {
+                        return Err(SsoLoginError::AccessDenied);
+                    }
+                    _ => return Err(SsoLoginError::service_error("CreateToken", err)),
+                },
+            }
+        };
+
+        let CreateTokenOutput {
+            access_token: Some(access_token),
+            refresh_token,
+            expires_in,
+            ..
+        } = output
+        else {
+            return Err(SsoLoginError::invalid_response("missing access token"));
+        };
+        let expires_in = positive_duration(expires_in)
+            .ok_or_else(|| SsoLoginError::invalid_response("invalid token expiration"))?;
+        let token = CachedSsoToken {
+            access_token: Zeroizing::new(access_token),
+            client_id: Some(registration.client_id),
+            client_secret: Some(registration.client_secret),
+            expires_at: time_source.now() + expires_in,
+            refresh_token: refresh_token.map(Zeroizing::new),
+            region: Some(self.region.to_string()),
+            registration_expires_at: registration.expires_at,
+            start_url: Some(self.start_url.clone()),
+        };
+        save_cached_token(&self.env, &self.fs, self.cache_identifier(), &token)
+            .await
+            .map_err(|err| SsoLoginError::FailedToCacheToken { source: err.into() })?;
+        tracing::debug!("saved SSO token from login");
+
+        Ok(Token::new(
+            token.access_token.as_str(),
+            Some(token.expires_at),
+        ))
+    }
+
+    fn cache_identifier(&self) -> &str {
+        self.session_name.as_deref().unwrap_or(&self.start_url)
+    }
+
+    /// Reuses the client registration from a previous login if it's still valid, or registers a new client.
+    async fn register_client(
+        &self,
+        client: &SsoOidcClient,
+        now: SystemTime,
+    ) -> Result<Registration, SsoLoginError> {
+        if let Ok(cached) = load_cached_token(&self.env, &self.fs, self.cache_identifier()).await {
+            if let (Some(client_id), Some(client_secret), Some(expires_at)) = (
+                cached.client_id,
+                cached.client_secret,
+                cached.registration_expires_at,
+            ) {
+                if cached.region.as_deref() == Some(self.region.as_ref())
+                    && expires_at > now + REGISTRATION_BUFFER_TIME
+                {
+                    tracing::debug!("reusing cached SSO OIDC client registration");
+                    return Ok(Registration {
+                        client_id,
+                        client_secret,
+                        expires_at: Some(expires_at),
+                    });
+                }
+            }
+        }
+
+        let output = client
+            .register_client()
+            .client_name(&self.client_name)
+            .client_type("public")
+            .set_scopes(Some(self.scopes.clone()).filter(|scopes| !scopes.is_empty()))
+            .send()
+            .await
+            .map_err(|err| SsoLoginError::service_error("RegisterClient", err))?;
+        tracing::debug!("registered SSO OIDC client");
+        Ok(Registration {
+            client_id: output
+                .client_id
+                .ok_or_else(|| SsoLoginError::invalid_response("missing client ID"))?,
+            client_secret: output
+                .client_secret
+                .map(Zeroizing::new)
+                .ok_or_else(|| SsoLoginError::invalid_response("missing client secret"))?,
+            expires_at: u64::try_from(output.client_secret_expires_at)
+                .ok()
+                .filter(|secs| *secs > 0)
+                .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+        })
+    }
+}
+
+fn positive_duration(secs: i32) -> Option<Duration> {
+    u64::try_from(secs)
+        .ok()
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+/// Builder for [`SsoLogin`].
+#[derive(Debug, Default)]
+pub struct Builder {
+    sdk_config: Option<SdkConfig>,
+    region: Option<Region>,
+    session_name: Option<String>,
+    start_url: Option<String>,
+    client_name: Option<String>,
+    scopes: Option<Vec<String>>,
+}
+
+impl Builder {
+    /// Creates a new builder for [`SsoLogin`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Override the configuration used for this login
+    pub fn configure(mut self, sdk_config: &SdkConfig) -> Self {
+        self.sdk_config = Some(sdk_config.clone());
+        self
+    }
+
+    /// Sets the SSO region.
+    ///
+    /// This is a required field.
+    pub fn region(mut self, region: impl Into<Region>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Sets the SSO region.
+    ///
+    /// This is a required field.
+    pub fn set_region(&mut self, region: Option<Region>) -> &mut Self {
+        self.region = region;
+        self
+    }
+
+    /// Sets the SSO session name.
+    ///
+    /// This should be set when logging in for a profile with an `sso-session`.
+    pub fn session_name(mut self, session_name: impl Into<String>) -> Self {
+        self.session_name = Some(session_name.into());
+        self
+    }
+
+    /// Sets the SSO session name.
+    ///
+    /// This should be set when logging in for a profile with an `sso-session`.
+    pub fn set_session_name(&mut self, session_name: Option<String>) -> &mut Self {
+        self.session_name = session_name;
+        self
+    }
+
+    /// Sets the SSO start URL.
+    ///
+    /// This is a required field.
+    pub fn start_url(mut self, start_url: impl Into<String>) -> Self {
+        self.start_url = Some(start_url.into());
+        self
+    }
+
+    /// Sets the SSO start URL.
+    ///
+    /// This is a required field.
+    pub fn set_start_url(&mut self, start_url: Option<String>) -> &mut Self {
+        self.start_url = start_url;
+        self
+    }
+
+    /// Sets the name that the SSO OIDC client is registered with.
+    ///
+    /// Defaults to `aws-sdk-rust`.
+    pub fn client_name(mut self, client_name: impl Into<String>) -> Self {
+        self.client_name = Some(client_name.into());
+        self
+    }
+
+    /// Sets the name that the SSO OIDC client is registered with.
+    ///
+    /// Defaults to `aws-sdk-rust`.
+    pub fn set_client_name(&mut self, client_name: Option<String>) -> &mut Self {
+        self.client_name = client_name;
+        self
+    }
+
+    /// Sets the scopes that the SSO OIDC client is registered with.
+    ///
+    /// This corresponds to `sso_registration_scopes` in an `sso-session`, and defaults to
+    /// `sso:account:access`.
+    pub fn scopes(mut self, scopes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.scopes = Some(scopes.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the scopes that the SSO OIDC client is registered with.
+    ///
+    /// This corresponds to `sso_registration_scopes` in an `sso-session`, and defaults to
+    /// `sso:account:access`.
+    pub fn set_scopes(&mut self, scopes: Option<Vec<String>>) -> &mut Self {
+        self.scopes = scopes;
+        self
+    }
+
+    /// Builds the [`SsoLogin`].
+    ///
+    /// # Panics
+    ///
+    /// This will panic if any of the required fields are not given.
+    pub async fn build(mut self) -> SsoLogin {
+        if self.sdk_config.is_none() {
+            self.sdk_config = Some(crate::load_defaults(crate::BehaviorVersion::latest()).await);
+        }
+        self.build_with(Env::real(), Fs::real())
+    }
+
+    pub(crate) fn build_with(self, env: Env, fs: Fs) -> SsoLogin {
+        SsoLogin {
+            env,
+            fs,
+            region: self.region.expect("region is required"),
+            session_name: self.session_name,
+            start_url: self.start_url.expect("start_url is required"),
+            client_name: self
+                .client_name
+                .unwrap_or_else(|| DEFAULT_CLIENT_NAME.into()),
+            scopes: self.scopes.unwrap_or_else(|| vec![DEFAULT_SCOPE.into()]),
+            sdk_config: self.sdk_config.expect("sdk_config is required"),
+        }
+    }
+}
+
+/// An error logging in with [`SsoLogin`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SsoLoginError {
+    /// The device authorization expired before the user approved it
+    #[non_exhaustive]
+    AuthorizationExpired,
+
+    /// The user denied the device authorization
+    #[non_exhaustive]
+    AccessDenied,
+
+    /// A call to SSO OIDC failed
+    #[non_exhaustive]
+    ServiceError {
+        /// The name of the SSO OIDC operation that failed
+        operation: &'static str,
+        /// The underlying error
+        source: BoxError,
+    },
+
+    /// SSO OIDC responded with missing or invalid data
+    #[non_exhaustive]
+    InvalidResponse {
+        /// Error message
+        message: Cow<'static, str>,
+    },
+
+    /// The configuration is missing something that login requires
+    #[non_exhaustive]
+    InvalidConfiguration {
+        /// Error message
+        message: Cow<'static, str>,
+    },
+
+    /// The token was obtained, but couldn't be written to the SSO token cache
+    #[non_exhaustive]
+    FailedToCacheToken {
+        /// The underlying error
+        source: BoxError,
+    },
+}
+
+impl SsoLoginError {
+    fn service_error(operation: &'static str, source: impl Into<BoxError>) -> Self {
+        Self::ServiceError {
+            operation,
+            source: source.into(),
+        }
+    }
+
+    fn invalid_response(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::InvalidResponse {
+            message: message.into(),
+        }
+    }
+
+    fn invalid_configuration(message: impl Into<Cow<'static, str>>) -> Self {
+        Self::InvalidConfiguration {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SsoLoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AuthorizationExpired => {
+                f.write_str("the SSO login expired before it was approved")
+            }
+            Self::AccessDenied => f.write_str("the SSO login was denied"),
+            Self::ServiceError { operation, .. } => {
+                write!(f, "call to SSO OIDC {operation} failed")
+            }
+            Self::InvalidResponse { message } => {
+                write!(f, "invalid response from SSO OIDC: {message}")
+            }
+            Self::InvalidConfiguration { message } => {
+                write!(f, "invalid SSO login configuration: {message}")
+            }
+            Self::FailedToCacheToken { .. } => f.write_str("failed to cache the SSO token"),
+        }
+    }
+}
+
+impl StdError for SsoLoginError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::ServiceError { source, .. } => Some(source.as_ref()),
+            Self::FailedToCacheToken { source } => Some(source.as_ref()),
+            Self::AuthorizationExpired
+            | Self::AccessDenied
+            | Self::InvalidResponse { .. }
+            | Self::InvalidConfiguration { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_async::rt::sleep::SharedAsyncSleep;
+    use aws_smithy_async::test_util::{instant_time_and_sleep, InstantSleep, ManualTimeSource};
+    use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+    use aws_smithy_runtime::assert_str_contains;
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::date_time::Format;
+    use aws_smithy_types::retry::RetryConfig;
+    use aws_smithy_types::DateTime;
+
+    // SHA-1 of the session name `test`
+    const CACHED_TOKEN_PATH: &str =
+        "/home/user/.aws/sso/cache/a94a8fe5ccb19ba61c4c0873d391e987982fbbd3.json";
+
+    fn time(s: &str) -> SystemTime {
+        SystemTime::try_from(DateTime::from_str(s, Format::DateTime).unwrap()).unwrap()
+    }
+
+    fn response(status: u16, body: &str) -> ReplayEvent {
+        ReplayEvent::new(
+            http::Request::new(SdkBody::from("")), // don't really care what the request looks like
+            http::Response::builder()
+                .status(status)
+                .body(SdkBody::from(body))
+                .unwrap(),
+        )
+    }
+
+    fn error_response(error_type: &str) -> ReplayEvent {
+        ReplayEvent::new(
+            http::Request::new(SdkBody::from("")),
+            http::Response::builder()
+                .status(400)
+                .header("x-amzn-errortype", error_type)
+                .body(SdkBody::from("{}"))
+                .unwrap(),
+        )
+    }
+
+    fn register_client_response() -> ReplayEvent {
+        response(
+            200,
+            r#"
+            { "clientId": "clientid",
+              "clientSecret": "YSBzZWNyZXQ=",
+              "clientIdIssuedAt": 1672531200,
+              "clientSecretExpiresAt": 1680307200 }
+            "#,
+        )
+    }
+
+    fn start_device_authorization_response(interval: u32, expires_in: u32) -> ReplayEvent {
+        response(
+            200,
+            &format!(
+                r#"
+                {{ "deviceCode": "devicecode",
+                  "userCode": "ABCD-EFGH",
+                  "verificationUri": "https://device.sso.us-west-2.amazonaws.com/",
+                  "verificationUriComplete": "https://device.sso.us-west-2.amazonaws.com/?user_code=ABCD-EFGH",
+                  "expiresIn": {expires_in},
+                  "interval": {interval} }}
+                "#
+            ),
+        )
+    }
+
+    fn create_token_response() -> ReplayEvent {
+        response(
+            200,
+            r#"
+            { "tokenType": "Bearer",
+              "accessToken": "newtoken",
+              "expiresIn": 28800,
+              "refreshToken": "newrefreshtoken" }
+            "#,
+        )
+    }
+
+    struct TestHarness {
+        time_source: ManualTimeSource,
+        sleep_impl: InstantSleep,
+        http_client: StaticReplayClient,
+        login: SsoLogin,
+        env: Env,
+        fs: Fs,
+    }
+
+    impl TestHarness {
+        fn new(events: Vec<ReplayEvent>, fs: Fs) -> Self {
+            let env = Env::from_slice(&[("HOME", "/home/user")]);
+            let (time_source, sleep_impl) = instant_time_and_sleep(time("2023-01-01T00:00:00Z"));
+            let http_client = StaticReplayClient::new(events);
+            let config = SdkConfig::builder()
+                .http_client(http_client.clone())
+                .time_source(SharedTimeSource::new(time_source.clone()))
+                .sleep_impl(SharedAsyncSleep::new(sleep_impl.clone()))
+                // disable retry to simplify testing
+                .retry_config(RetryConfig::disabled())
+                .behavior_version(crate::BehaviorVersion::latest())
+                .build();
+            Self {
+                time_source,
+                sleep_impl,
+                http_client,
+                login: SsoLogin::builder()
+                    .configure(&config)
+                    .session_name("test")
+                    .region(Region::new("us-west-2"))
+                    .start_url("https://d-123.awsapps.com/start")
+                    .build_with(env.clone(), fs.clone()),
+                env,
+                fs,
+            }
+        }
+
+        fn request_paths(&self) -> Vec<String> {
+            self.http_client
+                .actual_requests()
+                .map(|req| req.uri().parse::<http::Uri>().unwrap().path().to_string())
+                .collect()
+        }
+    }
+
+    // TODO(https://github.com/awslabs/aws-sdk-rust/issues/1117) This test is ignored on Windows because it uses Unix-style paths
+    #[cfg_attr(windows, ignore)]
+    #[tokio::test]
+    async fn login_polls_until_approved_and_caches_the_token() {
+        let harness = TestHarness::new(
+            vec![
+                register_client_response(),
+                start_device_authorization_response(1, 600),
+                error_response("AuthorizationPendingException"),
+                error_response("SlowDownException"),
+                create_token_response(),
+            ],
+            Fs::from_slice(&[]),
+        );
+
+        let mut authorization_uri = None;
+        let token = harness
+            .login
+            .login(|authorization| {
+                assert_eq!("ABCD-EFGH", authorization.user_code());
+                assert_eq!(
+                    "https://device.sso.us-west-2.amazonaws.com/",
+                    authorization.verification_uri()
+                );
+                assert_eq!(time("2023-01-01T00:10:00Z"), authorization.expires_at());
+                authorization_uri = authorization
+                    .verification_uri_complete()
+                    .map(str::to_string);
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("https://device.sso.us-west-2.amazonaws.com/?user_code=ABCD-EFGH"),
+            authorization_uri.as_deref()
+        );
+
+        // The polling interval increases by 5 seconds after SSO OIDC asks to slow down
+        assert_eq!(
+            vec![
+                Duration::from_secs(1),
+                Duration::from_secs(1),
+                Duration::from_secs(6)
+            ],
+            harness.sleep_impl.logs()
+        );
+        assert_eq!(
+            vec![
+                "/client/register",
+                "/device_authorization",
+                "/token",
+                "/token",
+                "/token"
+            ],
+            harness.request_paths()
+        );
+
+        let now = harness.time_source.now();
+        assert_eq!("newtoken", token.token());
+        assert_eq!(Some(now + Duration::from_secs(28800)), token.expiration());
+
+        let cached = load_cached_token(&harness.env, &harness.fs, "test")
+            .await
+            .unwrap();
+        assert_eq!("newtoken", cached.access_token.as_str());
+        assert_eq!(now + Duration::from_secs(28800), cached.expires_at);
+        assert_eq!(Some("clientid"), cached.client_id.as_deref());
+        assert_eq!(
+            Some("YSBzZWNyZXQ="),
+            cached.client_secret.as_ref().map(|s| s.as_str())
+        );
+        assert_eq!(
+            Some("newrefreshtoken"),
+            cached.refresh_token.as_ref().map(|s| s.as_str())
+        );
+        assert_eq!(
+            Some(time("2023-04-01T00:00:00Z")),
+            cached.registration_expires_at
+        );
+        assert_eq!(Some("us-west-2"), cached.region.as_deref());
+        assert_eq!(
+            Some("https://d-123.awsapps.com/start"),
+            cached.start_url.as_deref()
+        );
+        // The cached token can be refreshed by the token provider
+        assert!(cached.refreshable());
+    }
+
+    // TODO(https://github.com/awslabs/aws-sdk-rust/issues/1117) This test is ignored on Windows because it uses Unix-style paths
+    #[cfg_attr(windows, ignore)]
+    #[tokio::test]
+    async fn login_reuses_a_cached_client_registration() {
+        let fs = Fs::from_slice(&[(
+            CACHED_TOKEN_PATH,
+            r#"
+            { "startUrl": "https://d-123.awsapps.com/start",
+              "region": "us-west-2",
+              "accessToken": "expiredtoken",
+              "expiresAt": "2022-12-25T00:00:00Z",
+              "clientId": "cachedclientid",
+              "clientSecret": "Y2FjaGVkIHNlY3JldA==",
+              "registrationExpiresAt": "2023-02-01T00:00:00Z" }
+            "#,
+        )]);
+        let harness = TestHarness::new(
+            vec![
+                start_device_authorization_response(5, 600),
+                create_token_response(),
+            ],
+            fs,
+        );
+
+        let token = harness.login.login(|_| {}).await.unwrap();
+        assert_eq!("newtoken", token.token());
+        assert_eq!(
+            vec!["/device_authorization", "/token"],
+            harness.request_paths()
+        );
+
+        let cached = load_cached_token(&harness.env, &harness.fs, "test")
+            .await
+            .unwrap();
+        assert_eq!(Some("cachedclientid"), cached.client_id.as_deref());
+        assert_eq!(
+            Some(time("2023-02-01T00:00:00Z")),
+            cached.registration_expires_at
+        );
+    }
+
+    // TODO(https://github.com/awslabs/aws-sdk-rust/issues/1117) This test is ignored on Windows because it uses Unix-style paths
+    #[cfg_attr(windows, ignore)]
+    #[tokio::test]
+    async fn login_registers_a_new_client_when_the_cached_registration_expires_soon() {
+        let fs = Fs::from_slice(&[(
+            CACHED_TOKEN_PATH,
+            r#"
+            { "region": "us-west-2",
+              "accessToken": "expiredtoken",
+              "expiresAt": "2022-12-25T00:00:00Z",
+              "clientId": "cachedclientid",
+              "clientSecret": "Y2FjaGVkIHNlY3JldA==",
+              "registrationExpiresAt": "2023-01-01T00:05:00Z" }
+            "#,
+        )]);
+        let harness = TestHarness::new(
+            vec![
+                register_client_response(),
+                start_device_authorization_response(5, 600),
+                create_token_response(),
+            ],
+            fs,
+        );
+
+        harness.login.login(|_| {}).await.unwrap();
+        assert_eq!(
+            vec!["/client/register", "/device_authorization", "/token"],
+            harness.request_paths()
+        );
+    }
+
+    #[tokio::test]
+    async fn login_fails_when_the_authorization_expires() {
+        let harness = TestHarness::new(
+            vec![
+                register_client_response(),
+                start_device_authorization_response(5, 12),
+                error_response("AuthorizationPendingException"),
+                error_response("AuthorizationPendingException"),
+            ],
+            Fs::from_slice(&[]),
+        );
+
+        let err = harness.login.login(|_| {}).await.unwrap_err();
+        assert!(
+            matches!(err, SsoLoginError::AuthorizationExpired),
+            "{err:?}"
+        );
+        // It stops polling once the device code has expired
+        assert_eq!(4, harness.request_paths().len());
+    }
+
+    #[tokio::test]
+    async fn login_fails_when_the_authorization_is_denied() {
+        let harness = TestHarness::new(
+            vec![
+                register_client_response(),
+                start_device_authorization_response(5, 600),
+                error_response("AuthorizationPendingException"),
+                error_response("AccessDeniedException"),
+            ],
+            Fs::from_slice(&[]),
+        );
+
+        let err = harness.login.login(|_| {}).await.unwrap_err();
+        assert!(matches!(err, SsoLoginError::AccessDenied), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn login_fails_on_unexpected_errors() {
+        let harness = TestHarness::new(
+            vec![
+                register_client_response(),
+                start_device_authorization_response(5, 600),
+                error_response("InvalidClientException"),
+            ],
+            Fs::from_slice(&[]),
+        );
+
+        let err = harness.login.login(|_| {}).await.unwrap_err();
+        assert_str_contains!(err.to_string(), "call to SSO OIDC CreateToken failed");
+    }
+}
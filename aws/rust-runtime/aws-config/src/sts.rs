@@ -6,6 +6,8 @@
 //! Credential provider augmentation through the AWS Security Token Service (STS).
 
 pub use assume_role::{AssumeRoleProvider, AssumeRoleProviderBuilder};
+pub use mfa::MfaTokenCodeProvider;
 
 mod assume_role;
+pub(crate) mod mfa;
 pub(crate) mod util;
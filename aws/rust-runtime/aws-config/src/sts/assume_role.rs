@@ -5,13 +5,15 @@
 
 //! Assume credentials for a role through the AWS Security Token Service (STS).
 
+use super::mfa::{Mfa, MfaTokenCodeProvider};
 use aws_credential_types::provider::{
     self, error::CredentialsError, future, ProvideCredentials, SharedCredentialsProvider,
 };
 use aws_sdk_sts::operation::assume_role::builders::AssumeRoleFluentBuilder;
 use aws_sdk_sts::operation::assume_role::AssumeRoleError;
-use aws_sdk_sts::types::PolicyDescriptorType;
+use aws_sdk_sts::types::{PolicyDescriptorType, Tag};
 use aws_sdk_sts::Client as StsClient;
+use aws_smithy_async::time::SharedTimeSource;
 use aws_smithy_runtime::client::identity::IdentityCache;
 use aws_smithy_runtime_api::client::result::SdkError;
 use aws_smithy_types::error::display::DisplayErrorContext;
@@ -66,6 +68,25 @@ use tracing::Instrument;
 /// }
 /// ```
 ///
+/// Create an AssumeRoleProvider for a role that requires MFA, prompting for the token code:
+/// ```no_run
+/// use aws_config::sts::{AssumeRoleProvider, MfaTokenCodeProvider};
+/// # async fn docs() {
+/// let provider = AssumeRoleProvider::builder("arn:aws:iam::123456789012:role/demo")
+///   .session_name("testAR")
+///   .mfa(
+///     "arn:aws:iam::123456789012:mfa/user",
+///     MfaTokenCodeProvider::new(|serial_number| async move {
+///       println!("Enter the MFA code for {serial_number}:");
+///       let mut code = String::new();
+///       std::io::stdin().read_line(&mut code)?;
+///       Ok(code.trim().to_string())
+///     }),
+///   )
+///   .build().await;
+/// }
+/// ```
+///
 #[derive(Debug)]
 pub struct AssumeRoleProvider {
     inner: Inner,
@@ -74,6 +95,8 @@ pub struct AssumeRoleProvider {
 #[derive(Debug)]
 struct Inner {
     fluent_builder: AssumeRoleFluentBuilder,
+    mfa: Option<Mfa>,
+    time_source: SharedTimeSource,
 }
 
 impl AssumeRoleProvider {
@@ -100,6 +123,10 @@ pub struct AssumeRoleProviderBuilder {
     session_length: Option<Duration>,
     policy: Option<String>,
     policy_arns: Option<Vec<PolicyDescriptorType>>,
+    tags: Option<Vec<Tag>>,
+    transitive_tag_keys: Option<Vec<String>>,
+    source_identity: Option<String>,
+    mfa: Option<(String, MfaTokenCodeProvider)>,
     region_override: Option<Region>,
     sdk_config: Option<SdkConfig>,
 }
@@ -120,6 +147,10 @@ impl AssumeRoleProviderBuilder {
             session_length: None,
             policy: None,
             policy_arns: None,
+            tags: None,
+            transitive_tag_keys: None,
+            source_identity: None,
+            mfa: None,
             sdk_config: None,
             region_override: None,
         }
@@ -171,6 +202,61 @@ impl AssumeRoleProviderBuilder {
         self
     }
 
+    /// Add a session tag to pass to the role session.
+    ///
+    /// Session tags are key-value pairs that can be used to control access to resources. This
+    /// parameter is optional, and can be called multiple times to add multiple tags.
+    /// For more information, see
+    /// [tags](aws_sdk_sts::operation::assume_role::builders::AssumeRoleInputBuilder::tags)
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let tag = Tag::builder()
+            .key(key)
+            .value(value)
+            .build()
+            .expect("key and value are set");
+        self.tags.get_or_insert_with(Vec::new).push(tag);
+        self
+    }
+
+    /// Set the keys of the session tags that persist when this role session is used to assume
+    /// another role.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [transitive_tag_keys](aws_sdk_sts::operation::assume_role::builders::AssumeRoleInputBuilder::transitive_tag_keys)
+    pub fn transitive_tag_keys(mut self, keys: Vec<String>) -> Self {
+        self.transitive_tag_keys = Some(keys);
+        self
+    }
+
+    /// Set the source identity of the role session.
+    ///
+    /// The source identity identifies the user or application that assumed the role, and persists
+    /// across role chaining. This parameter is optional.
+    /// For more information, see
+    /// [source_identity](aws_sdk_sts::operation::assume_role::builders::AssumeRoleInputBuilder::source_identity)
+    pub fn source_identity(mut self, source_identity: impl Into<String>) -> Self {
+        self.source_identity = Some(source_identity.into());
+        self
+    }
+
+    /// Assume the role with multi-factor authentication (MFA).
+    ///
+    /// This is required if the role's trust policy requires MFA. `serial_number` is the serial
+    /// number of a hardware MFA device, or the ARN of a virtual MFA device, and
+    /// `token_code_provider` is called to get the code that the device is currently showing.
+    ///
+    /// Credentials assumed with MFA are cached until they're about to expire, so that a new token
+    /// code is only needed when the role session has to be renewed.
+    pub fn mfa(
+        mut self,
+        serial_number: impl Into<String>,
+        token_code_provider: MfaTokenCodeProvider,
+    ) -> Self {
+        self.mfa = Some((serial_number.into(), token_code_provider));
+        self
+    }
+
     /// Set the expiration time of the role session.
     ///
     /// When unset, this value defaults to 1 hour.
@@ -255,10 +341,24 @@ impl AssumeRoleProviderBuilder {
             .set_role_session_name(Some(session_name))
             .set_policy(self.policy)
             .set_policy_arns(self.policy_arns)
-            .set_duration_seconds(self.session_length.map(|dur| dur.as_secs() as i32));
+            .set_duration_seconds(self.session_length.map(|dur| dur.as_secs() as i32))
+            .set_tags(self.tags)
+            .set_transitive_tag_keys(self.transitive_tag_keys)
+            .set_source_identity(self.source_identity);
+        let mfa = self.mfa.map(|(serial_number, token_code_provider)| {
+            Mfa::new(serial_number, token_code_provider)
+        });
+        let fluent_builder = match &mfa {
+            Some(mfa) => fluent_builder.serial_number(mfa.serial_number()),
+            None => fluent_builder,
+        };
 
         AssumeRoleProvider {
-            inner: Inner { fluent_builder },
+            inner: Inner {
+                fluent_builder,
+                mfa,
+                time_source,
+            },
         }
     }
 
@@ -282,16 +382,32 @@ impl AssumeRoleProviderBuilder {
 
 impl Inner {
     async fn credentials(&self) -> provider::Result {
+        match &self.mfa {
+            Some(mfa) => {
+                mfa.credentials(self.time_source.now(), |token_code| {
+                    self.assume_role(self.fluent_builder.clone().token_code(token_code))
+                })
+                .await
+            }
+            None => self.assume_role(self.fluent_builder.clone()).await,
+        }
+    }
+
+    async fn assume_role(&self, fluent_builder: AssumeRoleFluentBuilder) -> provider::Result {
         tracing::debug!("retrieving assumed credentials");
 
-        let assumed = self.fluent_builder.clone().send().in_current_span().await;
+        let assumed = fluent_builder.send().in_current_span().await;
         match assumed {
             Ok(assumed) => {
                 tracing::debug!(
                     access_key_id = ?assumed.credentials.as_ref().map(|c| &c.access_key_id),
                     "obtained assumed credentials"
                 );
-                super::util::into_credentials(assumed.credentials, "AssumeRoleProvider")
+                super::util::into_credentials(
+                    assumed.credentials,
+                    assumed.assumed_role_user,
+                    "AssumeRoleProvider",
+                )
             }
             Err(SdkError::ServiceError(ref context))
                 if matches!(
@@ -328,7 +444,7 @@ impl ProvideCredentials for AssumeRoleProvider {
 
 #[cfg(test)]
 mod test {
-    use crate::sts::AssumeRoleProvider;
+    use crate::sts::{AssumeRoleProvider, MfaTokenCodeProvider};
     use aws_credential_types::credential_fn::provide_credentials_fn;
     use aws_credential_types::provider::{ProvideCredentials, SharedCredentialsProvider};
     use aws_credential_types::Credentials;
@@ -374,6 +490,102 @@ mod test {
         assert_eq!(req.uri(), "https://sts.us-east-1.amazonaws.com/");
     }
 
+    #[tokio::test]
+    async fn configures_tags_and_source_identity() {
+        let (http_client, request) = capture_request(None);
+        let sdk_config = SdkConfig::builder()
+            .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+            .time_source(StaticTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1234567890 - 120),
+            ))
+            .http_client(http_client)
+            .region(Region::from_static("us-east-1"))
+            .behavior_version(crate::BehaviorVersion::latest())
+            .build();
+        let provider = AssumeRoleProvider::builder("myrole")
+            .configure(&sdk_config)
+            .tag("project", "demo")
+            .tag("team", "sdk")
+            .transitive_tag_keys(vec!["project".into()])
+            .source_identity("alice")
+            .build_from_provider(provide_credentials_fn(|| async {
+                Ok(Credentials::for_tests())
+            }))
+            .await;
+        let _ = provider.provide_credentials().await;
+        let req = request.expect_request();
+        let str_body = std::str::from_utf8(req.body().bytes().unwrap()).unwrap();
+        for expected in [
+            "Tags.member.1.Key=project",
+            "Tags.member.1.Value=demo",
+            "Tags.member.2.Key=team",
+            "Tags.member.2.Value=sdk",
+            "TransitiveTagKeys.member.1=project",
+            "SourceIdentity=alice",
+        ] {
+            assert!(str_body.contains(expected), "{expected} in {str_body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn mfa_credentials_are_cached() {
+        let http_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(http::Request::new(SdkBody::from("request body")),
+            http::Response::builder().status(200).body(SdkBody::from(
+                "<AssumeRoleResponse xmlns=\"https://sts.amazonaws.com/doc/2011-06-15/\">\n  <AssumeRoleResult>\n    <AssumedRoleUser>\n      <AssumedRoleId>AROAR42TAWARILN3MNKUT:assume-role-from-profile-1632246085998</AssumedRoleId>\n      <Arn>arn:aws:sts::130633740322:assumed-role/assume-provider-test/assume-role-from-profile-1632246085998</Arn>\n    </AssumedRoleUser>\n    <Credentials>\n      <AccessKeyId>ASIARCORRECT</AccessKeyId>\n      <SecretAccessKey>secretkeycorrect</SecretAccessKey>\n      <SessionToken>tokencorrect</SessionToken>\n      <Expiration>2009-02-14T00:31:30Z</Expiration>\n    </Credentials>\n  </AssumeRoleResult>\n  <ResponseMetadata>\n    <RequestId>d9d47248-fd55-4686-ad7c-0fb7cd1cddd7</RequestId>\n  </ResponseMetadata>\n</AssumeRoleResponse>\n"
+            )).unwrap()),
+        ]);
+        let (testing_time_source, sleep) = instant_time_and_sleep(
+            UNIX_EPOCH + Duration::from_secs(1234567890 - 120), // 1234567890 since UNIX_EPOCH is 2009-02-13T23:31:30Z
+        );
+        let sdk_config = SdkConfig::builder()
+            .sleep_impl(SharedAsyncSleep::new(sleep))
+            .time_source(testing_time_source.clone())
+            .http_client(http_client.clone())
+            .region(Region::from_static("us-east-1"))
+            .behavior_version(crate::BehaviorVersion::latest())
+            .build();
+        let prompts = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let provider = AssumeRoleProvider::builder("myrole")
+            .configure(&sdk_config)
+            .mfa(
+                "arn:aws:iam::123456789012:mfa/user",
+                MfaTokenCodeProvider::new({
+                    let prompts = prompts.clone();
+                    move |_serial_number| {
+                        prompts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        async { Ok("123456".to_string()) }
+                    }
+                }),
+            )
+            .build_from_provider(provide_credentials_fn(|| async {
+                Ok(Credentials::for_tests())
+            }))
+            .await;
+
+        let creds_first = provider
+            .provide_credentials()
+            .await
+            .expect("should return valid credentials");
+        let req = http_client.actual_requests().next().unwrap();
+        let str_body = std::str::from_utf8(req.body().bytes().unwrap()).unwrap();
+        assert!(
+            str_body.contains("SerialNumber=arn%3Aaws%3Aiam%3A%3A123456789012%3Amfa%2Fuser"),
+            "{str_body}"
+        );
+        assert!(str_body.contains("TokenCode=123456"), "{str_body}");
+
+        // The credentials are reused without prompting or calling STS again
+        testing_time_source.advance(Duration::from_secs(30 * 60));
+        let creds_second = provider
+            .provide_credentials()
+            .await
+            .expect("should return the cached credentials");
+        assert_eq!(creds_first, creds_second);
+        assert_eq!(1, prompts.load(std::sync::atomic::Ordering::SeqCst));
+        assert_eq!(1, http_client.actual_requests().count());
+    }
+
     #[tokio::test]
     async fn loads_region_from_sdk_config() {
         let (http_client, request) = capture_request(None);
@@ -0,0 +1,221 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Token codes for assuming roles that require multi-factor authentication (MFA).
+
+use aws_credential_types::provider::{self, error::CredentialsError};
+use aws_credential_types::Credentials;
+use aws_smithy_runtime::expiring_cache::ExpiringCache;
+use aws_smithy_runtime_api::box_error::BoxError;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// Credentials that were assumed with MFA are reused until they're this close to expiring
+const MFA_CREDENTIALS_BUFFER_TIME: Duration = Duration::from_secs(5 * 60 /* 5 minutes */);
+
+type TokenCodeFuture = Pin<Box<dyn Future<Output = Result<String, BoxError>> + Send>>;
+
+/// Provides token codes from an MFA device.
+///
+/// The provider is called with the serial number (or ARN) of the MFA device whenever a role that
+/// requires MFA needs to be assumed. A CLI would typically prompt the user for the code that their
+/// device is currently showing. Credentials that were assumed with a token code are cached until
+/// they're about to expire, so the user isn't asked for a code on every request.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::sts::MfaTokenCodeProvider;
+///
+/// let provider = MfaTokenCodeProvider::new(|serial_number| async move {
+///     println!("Enter the MFA code for {serial_number}:");
+///     let mut code = String::new();
+///     std::io::stdin().read_line(&mut code)?;
+///     Ok(code.trim().to_string())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct MfaTokenCodeProvider {
+    provide: Arc<dyn Fn(String) -> TokenCodeFuture + Send + Sync>,
+}
+
+impl MfaTokenCodeProvider {
+    /// Creates an `MfaTokenCodeProvider` from an async function that returns a token code for the
+    /// given MFA device serial number.
+    pub fn new<F, Fut>(provide: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, BoxError>> + Send + 'static,
+    {
+        Self {
+            provide: Arc::new(move |serial_number| Box::pin(provide(serial_number))),
+        }
+    }
+
+    async fn token_code(&self, serial_number: &str) -> Result<String, CredentialsError> {
+        (self.provide)(serial_number.to_string())
+            .await
+            .map_err(CredentialsError::provider_error)
+    }
+}
+
+impl fmt::Debug for MfaTokenCodeProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MfaTokenCodeProvider")
+    }
+}
+
+/// An MFA device to assume a role with, and the credentials that were last assumed with it.
+#[derive(Clone, Debug)]
+pub(crate) struct Mfa {
+    serial_number: String,
+    token_code_provider: MfaTokenCodeProvider,
+    credentials: ExpiringCache<Credentials, CredentialsError>,
+}
+
+impl Mfa {
+    pub(crate) fn new(
+        serial_number: impl Into<String>,
+        token_code_provider: MfaTokenCodeProvider,
+    ) -> Self {
+        Self {
+            serial_number: serial_number.into(),
+            token_code_provider,
+            credentials: ExpiringCache::new(MFA_CREDENTIALS_BUFFER_TIME),
+        }
+    }
+
+    pub(crate) fn serial_number(&self) -> &str {
+        &self.serial_number
+    }
+
+    /// Returns the cached credentials if they aren't about to expire. Otherwise, gets a token
+    /// code and passes it to `assume_role` to assume the role again.
+    pub(crate) async fn credentials<F, Fut>(
+        &self,
+        now: SystemTime,
+        assume_role: F,
+    ) -> provider::Result
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = provider::Result>,
+    {
+        if let Some(credentials) = self.credentials.yield_or_clear_if_expired(now).await {
+            tracing::debug!("using cached credentials that were assumed with MFA");
+            return Ok(credentials);
+        }
+        self.credentials
+            .get_or_load(|| async move {
+                let token_code = self
+                    .token_code_provider
+                    .token_code(&self.serial_number)
+                    .await?;
+                let credentials = assume_role(token_code).await?;
+                let expiry = credentials.expiry().ok_or_else(|| {
+                    CredentialsError::unhandled(
+                        "assumed role credentials didn't have an expiration",
+                    )
+                })?;
+                Ok((credentials, expiry))
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::UNIX_EPOCH;
+
+    fn credentials(expiry: SystemTime) -> Credentials {
+        Credentials::new("akid", "secret", None, Some(expiry), "test")
+    }
+
+    #[tokio::test]
+    async fn mfa_credentials_are_cached_until_they_expire_soon() {
+        let prompts = Arc::new(AtomicUsize::new(0));
+        let mfa = Mfa::new(
+            "arn:aws:iam::123456789012:mfa/user",
+            MfaTokenCodeProvider::new({
+                let prompts = prompts.clone();
+                move |serial_number| {
+                    let prompt = prompts.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        assert_eq!("arn:aws:iam::123456789012:mfa/user", serial_number);
+                        Ok(format!("12345{prompt}"))
+                    }
+                }
+            }),
+        );
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let expiry = now + Duration::from_secs(3600);
+
+        let first = mfa
+            .credentials(now, |token_code| async move {
+                assert_eq!("123450", token_code);
+                Ok(credentials(expiry))
+            })
+            .await
+            .unwrap();
+        let second = mfa
+            .credentials(now + Duration::from_secs(60), |_| async {
+                Err(CredentialsError::unhandled(
+                    "the cached credentials should be used",
+                ))
+            })
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(1, prompts.load(Ordering::SeqCst));
+
+        // Within the buffer time of the expiration, a new token code is needed
+        let third = mfa
+            .credentials(expiry - Duration::from_secs(60), |token_code| async move {
+                assert_eq!("123451", token_code);
+                Ok(credentials(expiry + Duration::from_secs(3600)))
+            })
+            .await
+            .unwrap();
+        assert_ne!(first, third);
+        assert_eq!(2, prompts.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn failures_are_not_cached() {
+        let mfa = Mfa::new(
+            "serial",
+            MfaTokenCodeProvider::new(|_| async { Err("no code was entered".into()) }),
+        );
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let err = mfa
+            .credentials(now, |_| async {
+                Err(CredentialsError::unhandled("no token code was provided"))
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CredentialsError::ProviderError(_)), "{err:?}");
+
+        let mfa = Mfa::new(
+            "serial",
+            MfaTokenCodeProvider::new(|_| async { Ok("123456".into()) }),
+        );
+        assert!(mfa
+            .credentials(now, |_| async {
+                Err(CredentialsError::provider_error("STS refused"))
+            })
+            .await
+            .is_err());
+        assert!(mfa
+            .credentials(now, |_| async {
+                Ok(credentials(now + Duration::from_secs(3600)))
+            })
+            .await
+            .is_ok());
+    }
+}
@@ -5,13 +5,16 @@
 
 use aws_credential_types::provider::{self, error::CredentialsError};
 use aws_credential_types::Credentials as AwsCredentials;
-use aws_sdk_sts::types::Credentials as StsCredentials;
+use aws_sdk_sts::types::{AssumedRoleUser, Credentials as StsCredentials};
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Convert STS credentials to aws_auth::Credentials
+///
+/// The account ID of the credentials is taken from the ARN of the assumed role user when STS returns one.
 pub(crate) fn into_credentials(
     sts_credentials: Option<StsCredentials>,
+    assumed_role_user: Option<AssumedRoleUser>,
     provider_name: &'static str,
 ) -> provider::Result {
     let sts_credentials = sts_credentials
@@ -21,13 +24,27 @@ pub(crate) fn into_credentials(
             "credential expiration time cannot be represented by a SystemTime",
         )
     })?;
-    Ok(AwsCredentials::new(
-        sts_credentials.access_key_id,
-        sts_credentials.secret_access_key,
-        Some(sts_credentials.session_token),
-        Some(expiration),
-        provider_name,
-    ))
+    let mut builder = AwsCredentials::builder()
+        .access_key_id(sts_credentials.access_key_id)
+        .secret_access_key(sts_credentials.secret_access_key)
+        .session_token(sts_credentials.session_token)
+        .expiry(expiration)
+        .provider_name(provider_name);
+    builder.set_account_id(
+        assumed_role_user
+            .as_ref()
+            .and_then(|user| account_id_from_arn(&user.arn))
+            .map(Into::into),
+    );
+    Ok(builder.build())
+}
+
+/// Returns the account ID from an ARN such as `arn:aws:sts::123456789012:assumed-role/role/session`
+pub(crate) fn account_id_from_arn(arn: &str) -> Option<&str> {
+    match arn.split(':').nth(4) {
+        Some(account_id) if !account_id.is_empty() => Some(account_id),
+        _ => None,
+    }
 }
 
 /// Create a default STS session name
@@ -39,3 +56,18 @@ pub(crate) fn default_session_name(base: &str, ts: SystemTime) -> String {
     let now = ts.duration_since(UNIX_EPOCH).expect("post epoch");
     format!("{}-{}", base, now.as_millis())
 }
+
+#[cfg(test)]
+mod test {
+    use super::account_id_from_arn;
+
+    #[test]
+    fn account_id_from_assumed_role_arn() {
+        assert_eq!(
+            Some("123456789012"),
+            account_id_from_arn("arn:aws:sts::123456789012:assumed-role/Role/session")
+        );
+        assert_eq!(None, account_id_from_arn("arn:aws:s3:::bucket"));
+        assert_eq!(None, account_id_from_arn("not-an-arn"));
+    }
+}
@@ -48,6 +48,8 @@ pub(crate) struct Credentials {
     pub(crate) secret_access_key: String,
     pub(crate) session_token: Option<String>,
     pub(crate) expiry: Option<u64>,
+    #[serde(default)]
+    pub(crate) account_id: Option<String>,
 }
 
 impl Secrets for Credentials {
@@ -69,6 +71,9 @@ impl From<&aws_credential_types::Credentials> for Credentials {
             expiry: credentials
                 .expiry()
                 .map(|t| t.duration_since(UNIX_EPOCH).unwrap().as_secs()),
+            account_id: credentials
+                .account_id()
+                .map(|account_id| account_id.as_str().to_string()),
         }
     }
 }
@@ -112,6 +117,17 @@ impl From<aws_credential_types::Token> for Token {
     }
 }
 
+/// Test case output for providers of arbitrary data, compared as JSON
+#[derive(Deserialize, Debug, Eq, PartialEq)]
+#[serde(transparent)]
+pub(crate) struct Json(pub(crate) serde_json::Value);
+
+impl Secrets for Json {
+    fn secrets(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
 /// Connector which expects no traffic
 pub(crate) fn no_traffic_client() -> SharedHttpClient {
     ReplayingClient::new(Vec::new()).into_shared()
@@ -225,6 +241,19 @@ where
     })
 }
 
+pub(crate) fn test_json_provider<F, Fut, E>(
+    run_provider_fn: F,
+) -> impl RunTestProvider<Output = Json, Error = E>
+where
+    F: Fn(ProviderConfig) -> Fut + Send + Clone + 'static,
+    Fut: Future<Output = Result<serde_json::Value, E>> + Send,
+{
+    StaticTestProvider::<Json, E>::new(move |config| {
+        let run_provider_fn = run_provider_fn.clone();
+        Box::pin(async move { (run_provider_fn)(config).await.map(Json) })
+    })
+}
+
 #[cfg(feature = "sso")]
 pub(crate) fn test_token_provider<F, Fut, E>(
     run_provider_fn: F,
@@ -0,0 +1,196 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! In-process emulators of the credential endpoints that `aws-config` talks to
+//!
+//! The [`ImdsEmulator`](imds::ImdsEmulator) and [`EcsEmulator`](ecs::EcsEmulator) implement
+//! [`HttpClient`](aws_smithy_runtime_api::client::http::HttpClient), so they can replace the HTTP
+//! client of a [`ProviderConfig`](crate::provider_config::ProviderConfig) to test code that uses
+//! the IMDS client, the IMDS credentials provider or the ECS credentials provider without network
+//! access or hand-written request/response pairs.
+//!
+//! The [`CredentialEndpointServer`](credential_endpoint::CredentialEndpointServer) is a stand-in for
+//! a local credentials endpoint used by the
+//! [`CredentialEndpointProvider`](crate::credential_endpoint::CredentialEndpointProvider). Unlike
+//! the emulators, it listens on a real loopback port or Unix domain socket.
+//!
+//! All of them issue credentials that rotate as they approach expiration, according to their time
+//! source. Faults can be injected with [`Fault`] to test error handling.
+//!
+//! _Note: This module is only available with the `test-util` feature._
+
+use aws_credential_types::Credentials;
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::DateTime;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+pub mod credential_endpoint;
+pub mod ecs;
+pub mod imds;
+
+/// Credentials are rotated when they are within this duration of expiring
+///
+/// This mirrors IMDS and ECS, which make new credentials available before the previous
+/// credentials expire.
+const ROTATION_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+const DEFAULT_CREDENTIALS_LIFETIME: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A fault to inject into the responses of an emulator
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fault {
+    /// Respond with `500 Internal Server Error`
+    ServerError,
+
+    /// Fail the request with a timeout, as if no response was received
+    Timeout,
+
+    /// Respond with credentials that have already expired
+    ///
+    /// This only affects requests for credentials. Other requests are answered normally.
+    StaleCredentials,
+}
+
+/// Faults waiting to be injected, in the order they were added
+#[derive(Debug, Default)]
+struct FaultQueue {
+    faults: VecDeque<(String, Fault, usize)>,
+}
+
+impl FaultQueue {
+    fn push(&mut self, path: String, fault: Fault, times: usize) {
+        if times > 0 {
+            self.faults.push_back((path, fault, times));
+        }
+    }
+
+    /// Takes the next fault to inject into a request for `path`, if any
+    fn take(&mut self, path: &str) -> Option<Fault> {
+        let index = self
+            .faults
+            .iter()
+            .position(|(fault_path, _, _)| fault_path == path)?;
+        let (_, fault, remaining) = &mut self.faults[index];
+        let fault = fault.clone();
+        *remaining -= 1;
+        if *remaining == 0 {
+            self.faults.remove(index);
+        }
+        Some(fault)
+    }
+}
+
+/// Credentials that are replaced with new credentials as they approach expiration
+#[derive(Debug)]
+struct RotatingCredentials {
+    access_key_prefix: &'static str,
+    lifetime: Duration,
+    account_id: Option<String>,
+    generation: u32,
+    expiry: Option<SystemTime>,
+}
+
+impl RotatingCredentials {
+    fn new(access_key_prefix: &'static str) -> Self {
+        Self {
+            access_key_prefix,
+            lifetime: DEFAULT_CREDENTIALS_LIFETIME,
+            account_id: None,
+            generation: 0,
+            expiry: None,
+        }
+    }
+
+    /// Returns the credentials served at `now`, issuing new credentials if necessary
+    fn current(&mut self, now: SystemTime) -> Credentials {
+        let expiry = match self.expiry {
+            Some(expiry) if now + ROTATION_WINDOW < expiry => expiry,
+            _ => {
+                self.generation += 1;
+                *self.expiry.insert(now + self.lifetime)
+            }
+        };
+        self.credentials(expiry)
+    }
+
+    /// Returns the most recently issued credentials with an expiration in the past
+    fn stale(&self, now: SystemTime) -> Credentials {
+        self.credentials(now - Duration::from_secs(60))
+    }
+
+    /// Causes the next request for credentials to receive new credentials
+    fn rotate(&mut self) {
+        self.expiry = None;
+    }
+
+    fn credentials(&self, expiry: SystemTime) -> Credentials {
+        let generation = self.generation;
+        let mut builder = Credentials::builder()
+            .access_key_id(format!("{}{generation:08}", self.access_key_prefix))
+            .secret_access_key(format!("emulated-secret-access-key-{generation}"))
+            .session_token(format!("emulated-session-token-{generation}"))
+            .expiry(expiry)
+            .provider_name("Emulator");
+        if let Some(account_id) = &self.account_id {
+            builder = builder.account_id(account_id);
+        }
+        builder.build()
+    }
+}
+
+/// Serializes credentials in the format returned by IMDS and ECS
+fn credentials_json(credentials: &Credentials, last_updated: SystemTime) -> String {
+    let timestamp = |time: SystemTime| {
+        DateTime::from(time)
+            .fmt(Format::DateTime)
+            .expect("emulated timestamps are valid")
+    };
+    let mut out = String::new();
+    let mut writer = JsonObjectWriter::new(&mut out);
+    writer.key("Code").string("Success");
+    writer.key("LastUpdated").string(&timestamp(last_updated));
+    writer.key("Type").string("AWS-HMAC");
+    writer
+        .key("AccessKeyId")
+        .string(credentials.access_key_id());
+    writer
+        .key("SecretAccessKey")
+        .string(credentials.secret_access_key());
+    if let Some(session_token) = credentials.session_token() {
+        writer.key("Token").string(session_token);
+    }
+    if let Some(expiry) = credentials.expiry() {
+        writer.key("Expiration").string(&timestamp(expiry));
+    }
+    if let Some(account_id) = credentials.account_id() {
+        writer.key("AccountId").string(account_id.as_str());
+    }
+    writer.finish();
+    out
+}
+
+fn response(status: u16, body: impl Into<SdkBody>) -> Result<HttpResponse, ConnectorError> {
+    Ok(HttpResponse::new(
+        status.try_into().expect("valid status code"),
+        body.into(),
+    ))
+}
+
+/// Returns the response for an injected fault, or `None` if the request should be answered normally
+fn fault_response(fault: &Fault) -> Option<Result<HttpResponse, ConnectorError>> {
+    match fault {
+        Fault::ServerError => Some(response(500, "Internal Server Error")),
+        Fault::Timeout => Some(Err(ConnectorError::timeout(
+            "emulated timeout injected by the test".into(),
+        ))),
+        Fault::StaleCredentials => None,
+    }
+}
@@ -0,0 +1,505 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A stand-in for a local credentials endpoint, such as a secrets agent
+
+use super::{credentials_json, Fault, FaultQueue, RotatingCredentials};
+use aws_credential_types::Credentials;
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+const DEFAULT_PATH: &str = "/credentials";
+
+/// Requests larger than this are rejected
+const MAX_REQUEST_SIZE: usize = 16 * 1024;
+
+/// A credentials endpoint served on the loopback interface or a Unix domain socket
+///
+/// The server answers requests in the format expected by the
+/// [`CredentialEndpointProvider`](crate::credential_endpoint::CredentialEndpointProvider). When an
+/// authorization token is configured, requests must send it in the `Authorization` header or are
+/// rejected with a 401. The credentials rotate five minutes before they expire, according to the
+/// server's time source.
+///
+/// The server stops accepting connections when it is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::credential_endpoint::CredentialEndpointProvider;
+/// use aws_config::test_util::credential_endpoint::CredentialEndpointServer;
+/// use aws_credential_types::provider::ProvideCredentials;
+///
+/// # async fn docs() {
+/// let server = CredentialEndpointServer::builder()
+///     .authorization_token("Bearer secret")
+///     .serve_loopback()
+///     .await
+///     .unwrap();
+/// let provider = CredentialEndpointProvider::builder()
+///     .endpoint(server.endpoint())
+///     .authorization_token("Bearer secret")
+///     .build();
+/// let credentials = provider.provide_credentials().await.unwrap();
+/// assert_eq!(
+///     server.current_credentials().access_key_id(),
+///     credentials.access_key_id()
+/// );
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CredentialEndpointServer {
+    endpoint: String,
+    inner: Arc<Inner>,
+    accept_task: JoinHandle<()>,
+    _shutdown: watch::Sender<()>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    time_source: SharedTimeSource,
+    path: String,
+    authorization_token: Option<String>,
+    chunked: bool,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    credentials: RotatingCredentials,
+    faults: FaultQueue,
+    requests: Vec<String>,
+}
+
+impl CredentialEndpointServer {
+    /// Builder for [`CredentialEndpointServer`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the endpoint to configure the credentials provider with
+    ///
+    /// This is an `http://127.0.0.1:<port>` URL for servers on the loopback interface, and a
+    /// `unix://` URL for servers on a Unix domain socket.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Returns the credentials that the server currently serves
+    pub fn current_credentials(&self) -> Credentials {
+        let now = self.inner.time_source.now();
+        self.inner.state().credentials.current(now)
+    }
+
+    /// Replaces the served credentials with new credentials
+    pub fn rotate_credentials(&self) {
+        self.inner.state().credentials.rotate();
+    }
+
+    /// Injects `fault` into the next `times` requests for credentials
+    ///
+    /// Faults are injected in the order they were added. Requests that receive
+    /// [`Fault::Timeout`] are never answered.
+    pub fn inject_fault(&self, fault: Fault, times: usize) {
+        let path = self.inner.path.clone();
+        self.inner.state().faults.push(path, fault, times);
+    }
+
+    /// Returns the requests received by the server, formatted as `<method> <path>`
+    pub fn requests(&self) -> Vec<String> {
+        self.inner.state().requests.clone()
+    }
+}
+
+impl Drop for CredentialEndpointServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+impl Inner {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Returns the status and body of the response, or `None` if the request should not be answered
+    fn handle(&self, request: &str) -> Option<(u16, String)> {
+        let mut lines = request.split("\r\n");
+        let mut request_line = lines.next().unwrap_or_default().split(' ');
+        let (method, path) = match (request_line.next(), request_line.next()) {
+            (Some(method), Some(path)) => (method, path),
+            _ => return Some((400, String::new())),
+        };
+        let authorization = lines.find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("authorization")
+                .then(|| value.trim())
+        });
+
+        let now = self.time_source.now();
+        let mut state = self.state();
+        state.requests.push(format!("{method} {path}"));
+
+        let fault = state.faults.take(path);
+        match fault {
+            Some(Fault::ServerError) => return Some((500, "Internal Server Error".into())),
+            Some(Fault::Timeout) => return None,
+            _ => {}
+        }
+        if path != self.path {
+            return Some((404, String::new()));
+        }
+        if method != "GET" {
+            return Some((405, String::new()));
+        }
+        if let Some(token) = &self.authorization_token {
+            if authorization != Some(token.as_str()) {
+                return Some((401, String::new()));
+            }
+        }
+        let credentials = if fault == Some(Fault::StaleCredentials) {
+            state.credentials.stale(now)
+        } else {
+            state.credentials.current(now)
+        };
+        Some((200, credentials_json(&credentials, now)))
+    }
+
+    async fn serve_connection(
+        &self,
+        mut stream: impl AsyncRead + AsyncWrite + Unpin,
+        mut shutdown: watch::Receiver<()>,
+    ) -> io::Result<()> {
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let read = stream.read(&mut buf).await?;
+            if read == 0 || request.len() + read > MAX_REQUEST_SIZE {
+                return Ok(());
+            }
+            request.extend_from_slice(&buf[..read]);
+        }
+        let response = self.handle(&String::from_utf8_lossy(&request));
+        let (status, body) = match response {
+            Some(response) => response,
+            None => {
+                // Hold the connection open without responding until the server is dropped
+                let _ = shutdown.changed().await;
+                return Ok(());
+            }
+        };
+        let response = if self.chunked {
+            format!(
+                "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\nTrailer: X-Checksum\r\nConnection: close\r\n\r\n{}",
+                reason(status),
+                chunked(&body)
+            )
+        } else {
+            format!(
+                "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                reason(status),
+                body.len()
+            )
+        };
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+}
+
+/// Encode `body` with chunked transfer encoding, using chunk extensions and a trailer
+fn chunked(body: &str) -> String {
+    let mut encoded = String::new();
+    for (index, chunk) in body.as_bytes().chunks(16).enumerate() {
+        encoded.push_str(&format!("{:x};index={index}\r\n", chunk.len()));
+        encoded.push_str(std::str::from_utf8(chunk).expect("bodies are ASCII"));
+        encoded.push_str("\r\n");
+    }
+    encoded.push_str("0;last\r\nX-Checksum: ignored\r\n\r\n");
+    encoded
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Builder for [`CredentialEndpointServer`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    time_source: Option<SharedTimeSource>,
+    path: Option<String>,
+    authorization_token: Option<String>,
+    chunked: bool,
+    credentials_lifetime: Option<Duration>,
+    account_id: Option<String>,
+}
+
+impl Builder {
+    /// Override the time source used to expire credentials
+    ///
+    /// Defaults to the system clock.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = Some(SharedTimeSource::new(time_source));
+        self
+    }
+
+    /// Override the path that credentials are served at on the loopback interface
+    ///
+    /// Defaults to `/credentials`. Servers on a Unix domain socket always serve credentials at `/`.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Require requests to send `token` in the `Authorization` header
+    pub fn authorization_token(mut self, token: impl Into<String>) -> Self {
+        self.authorization_token = Some(token.into());
+        self
+    }
+
+    /// Send responses with `Transfer-Encoding: chunked`
+    ///
+    /// The body is split into small chunks that carry chunk extensions, and is followed by a
+    /// trailer. Responses are sent with a `Content-Length` by default.
+    pub fn chunked_responses(mut self) -> Self {
+        self.chunked = true;
+        self
+    }
+
+    /// Override how long issued credentials are valid for
+    ///
+    /// Defaults to 6 hours.
+    pub fn credentials_lifetime(mut self, lifetime: Duration) -> Self {
+        self.credentials_lifetime = Some(lifetime);
+        self
+    }
+
+    /// Include an account ID in the issued credentials
+    pub fn account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+
+    /// Start serving credentials on an ephemeral port of `127.0.0.1`
+    pub async fn serve_loopback(self) -> io::Result<CredentialEndpointServer> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let port = listener.local_addr()?.port();
+        let path = self
+            .path
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PATH.to_string());
+        let endpoint = format!("http://127.0.0.1:{port}{path}");
+        Ok(
+            self.serve(endpoint, path, move |inner, shutdown| async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let (inner, shutdown) = (inner.clone(), shutdown.clone());
+                    tokio::spawn(async move { inner.serve_connection(stream, shutdown).await });
+                }
+            }),
+        )
+    }
+
+    /// Start serving credentials on a Unix domain socket bound at `socket`
+    ///
+    /// `socket` must not exist, and is not removed when the server is dropped.
+    #[cfg(unix)]
+    pub async fn serve_unix(
+        self,
+        socket: impl AsRef<std::path::Path>,
+    ) -> io::Result<CredentialEndpointServer> {
+        let socket = socket.as_ref();
+        let listener = tokio::net::UnixListener::bind(socket)?;
+        let endpoint = format!("unix://{}", socket.display());
+        Ok(
+            self.serve(endpoint, "/".into(), move |inner, shutdown| async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let (inner, shutdown) = (inner.clone(), shutdown.clone());
+                    tokio::spawn(async move { inner.serve_connection(stream, shutdown).await });
+                }
+            }),
+        )
+    }
+
+    fn serve<F>(
+        self,
+        endpoint: String,
+        path: String,
+        accept: impl FnOnce(Arc<Inner>, watch::Receiver<()>) -> F,
+    ) -> CredentialEndpointServer
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let mut credentials = RotatingCredentials::new("ASIAENDPOINTSTANDIN");
+        if let Some(lifetime) = self.credentials_lifetime {
+            credentials.lifetime = lifetime;
+        }
+        credentials.account_id = self.account_id;
+        let inner = Arc::new(Inner {
+            time_source: self.time_source.unwrap_or_default(),
+            path,
+            authorization_token: self.authorization_token,
+            chunked: self.chunked,
+            state: Mutex::new(State {
+                credentials,
+                faults: FaultQueue::default(),
+                requests: Vec::new(),
+            }),
+        });
+        let (shutdown, shutdown_rx) = watch::channel(());
+        let accept_task = tokio::spawn(accept(inner.clone(), shutdown_rx));
+        CredentialEndpointServer {
+            endpoint,
+            inner,
+            accept_task,
+            _shutdown: shutdown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CredentialEndpointServer;
+    use crate::credential_endpoint::CredentialEndpointProvider;
+    use aws_credential_types::provider::error::CredentialsError;
+    use aws_credential_types::provider::ProvideCredentials;
+
+    #[tokio::test]
+    async fn serve_loopback() {
+        let server = CredentialEndpointServer::builder()
+            .authorization_token("Bearer secret")
+            .account_id("123456789012")
+            .serve_loopback()
+            .await
+            .unwrap();
+        let provider = CredentialEndpointProvider::builder()
+            .endpoint(server.endpoint())
+            .authorization_token("Bearer secret")
+            .build();
+
+        let first = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(
+            server.current_credentials().access_key_id(),
+            first.access_key_id()
+        );
+        assert_eq!(
+            Some("123456789012"),
+            first.account_id().map(|id| id.as_str())
+        );
+
+        server.rotate_credentials();
+        let second = provider.provide_credentials().await.expect("valid creds");
+        assert_ne!(first.access_key_id(), second.access_key_id());
+        assert_eq!(
+            vec!["GET /credentials", "GET /credentials"],
+            server.requests()
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_missing_authorization() {
+        let server = CredentialEndpointServer::builder()
+            .authorization_token("Bearer secret")
+            .serve_loopback()
+            .await
+            .unwrap();
+        let provider = CredentialEndpointProvider::builder()
+            .endpoint(server.endpoint())
+            .build();
+        let err = provider.provide_credentials().await.expect_err("401");
+        assert!(matches!(err, CredentialsError::ProviderError(_)), "{err:?}");
+    }
+
+    #[cfg(feature = "credential-endpoint-unix")]
+    #[tokio::test]
+    async fn serve_unix() {
+        use crate::test_util::Fault;
+        use std::time::Duration;
+
+        let socket = std::env::temp_dir().join(format!(
+            "aws-config-credential-endpoint-{}-{}.sock",
+            std::process::id(),
+            fastrand::u32(..)
+        ));
+        let server = CredentialEndpointServer::builder()
+            .authorization_token("Bearer secret")
+            .serve_unix(&socket)
+            .await
+            .unwrap();
+        let provider = CredentialEndpointProvider::builder()
+            .endpoint(server.endpoint())
+            .authorization_token("Bearer secret")
+            .read_timeout(Duration::from_millis(100))
+            .build();
+
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(
+            server.current_credentials().access_key_id(),
+            creds.access_key_id()
+        );
+
+        server.inject_fault(Fault::ServerError, 1);
+        let err = provider.provide_credentials().await.expect_err("500");
+        assert!(matches!(err, CredentialsError::ProviderError(_)), "{err:?}");
+
+        server.inject_fault(Fault::Timeout, 1);
+        let err = provider.provide_credentials().await.expect_err("timeout");
+        assert!(
+            matches!(err, CredentialsError::ProviderTimedOut(_)),
+            "{err:?}"
+        );
+        assert_eq!(vec!["GET /"; 3], server.requests());
+        drop(server);
+        std::fs::remove_file(socket).unwrap();
+    }
+
+    #[cfg(feature = "credential-endpoint-unix")]
+    #[tokio::test]
+    async fn serve_unix_chunked() {
+        use crate::test_util::Fault;
+
+        let socket = std::env::temp_dir().join(format!(
+            "aws-config-credential-endpoint-{}-{}.sock",
+            std::process::id(),
+            fastrand::u32(..)
+        ));
+        let server = CredentialEndpointServer::builder()
+            .account_id("123456789012")
+            .chunked_responses()
+            .serve_unix(&socket)
+            .await
+            .unwrap();
+        let provider = CredentialEndpointProvider::builder()
+            .endpoint(server.endpoint())
+            .build();
+
+        let creds = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(
+            server.current_credentials().access_key_id(),
+            creds.access_key_id()
+        );
+        assert_eq!(
+            Some("123456789012"),
+            creds.account_id().map(|id| id.as_str())
+        );
+
+        server.inject_fault(Fault::ServerError, 1);
+        let err = provider.provide_credentials().await.expect_err("500");
+        assert!(matches!(err, CredentialsError::ProviderError(_)), "{err:?}");
+        drop(server);
+        std::fs::remove_file(socket).unwrap();
+    }
+}
@@ -0,0 +1,381 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! An in-process emulator of the ECS and EKS container credentials endpoint
+
+use super::{credentials_json, fault_response, response, Fault, FaultQueue, RotatingCredentials};
+use crate::provider_config::ProviderConfig;
+use aws_credential_types::Credentials;
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_runtime_api::client::connector_metadata::ConnectorMetadata;
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::body::SdkBody;
+use aws_types::os_shim_internal::Env;
+use http::Uri;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const FULL_URI_HOST: &str = "http://127.0.0.1";
+const DEFAULT_PATH: &str = "/v2/credentials/emulated";
+
+/// An in-process emulator of the container credentials endpoint
+///
+/// The emulator serves credentials at a single path, for requests to any host. When an
+/// authorization token is configured, requests must send it in the `Authorization` header or are
+/// rejected with a 401. The credentials rotate five minutes before they expire, according to the
+/// emulator's time source.
+///
+/// [`EcsEmulator::configure`] points the ECS credentials provider of a `ProviderConfig` at the
+/// emulator.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::ecs::EcsCredentialsProvider;
+/// use aws_config::provider_config::ProviderConfig;
+/// use aws_config::test_util::ecs::EcsEmulator;
+/// use aws_credential_types::provider::ProvideCredentials;
+///
+/// # async fn docs() {
+/// let ecs = EcsEmulator::builder()
+///     .authorization_token("Basic secret")
+///     .build();
+/// let provider = EcsCredentialsProvider::builder()
+///     .configure(&ecs.configure(ProviderConfig::default()))
+///     .build();
+/// let credentials = provider.provide_credentials().await.unwrap();
+/// assert_eq!(
+///     ecs.current_credentials().access_key_id(),
+///     credentials.access_key_id()
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct EcsEmulator {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    time_source: SharedTimeSource,
+    path: String,
+    authorization_token: Option<String>,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    credentials: RotatingCredentials,
+    faults: FaultQueue,
+    requests: Vec<String>,
+}
+
+impl EcsEmulator {
+    /// Builder for [`EcsEmulator`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Configures `provider_config` to load container credentials from this emulator
+    ///
+    /// The HTTP client is replaced with the emulator, and the environment is replaced with one that
+    /// only contains `AWS_CONTAINER_CREDENTIALS_FULL_URI`, pointing at the emulator's path on the
+    /// loopback interface, and `AWS_CONTAINER_AUTHORIZATION_TOKEN` if an authorization token was
+    /// configured.
+    pub fn configure(&self, provider_config: ProviderConfig) -> ProviderConfig {
+        provider_config
+            .with_env(self.env())
+            .with_http_client(self.clone())
+    }
+
+    fn env(&self) -> Env {
+        let mut vars = HashMap::new();
+        vars.insert(
+            "AWS_CONTAINER_CREDENTIALS_FULL_URI".to_string(),
+            format!("{FULL_URI_HOST}{}", self.inner.path),
+        );
+        if let Some(token) = &self.inner.authorization_token {
+            vars.insert(
+                "AWS_CONTAINER_AUTHORIZATION_TOKEN".to_string(),
+                token.clone(),
+            );
+        }
+        Env::from(vars)
+    }
+
+    /// Returns the credentials that the emulator currently serves
+    pub fn current_credentials(&self) -> Credentials {
+        let now = self.inner.time_source.now();
+        self.state().credentials.current(now)
+    }
+
+    /// Replaces the served credentials with new credentials
+    pub fn rotate_credentials(&self) {
+        self.state().credentials.rotate();
+    }
+
+    /// Injects `fault` into the next `times` requests for credentials
+    ///
+    /// Faults are injected in the order they were added.
+    pub fn inject_fault(&self, fault: Fault, times: usize) {
+        let path = self.inner.path.clone();
+        self.state().faults.push(path, fault, times);
+    }
+
+    /// Returns the requests received by the emulator, formatted as `<method> <path>`
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap()
+    }
+
+    fn handle(&self, request: &HttpRequest) -> Result<HttpResponse, ConnectorError> {
+        let path = match request.uri().parse::<Uri>() {
+            Ok(uri) => uri.path().to_string(),
+            Err(_) => return response(400, SdkBody::empty()),
+        };
+        let now = self.inner.time_source.now();
+        let mut state = self.state();
+        state
+            .requests
+            .push(format!("{} {}", request.method(), path));
+
+        let fault = state.faults.take(&path);
+        if let Some(result) = fault.as_ref().and_then(fault_response) {
+            return result;
+        }
+        if path != self.inner.path {
+            return response(404, SdkBody::empty());
+        }
+        if request.method() != "GET" {
+            return response(405, SdkBody::empty());
+        }
+        if let Some(token) = &self.inner.authorization_token {
+            if request.headers().get("authorization") != Some(token.as_str()) {
+                return response(401, SdkBody::empty());
+            }
+        }
+        let credentials = if fault == Some(Fault::StaleCredentials) {
+            state.credentials.stale(now)
+        } else {
+            state.credentials.current(now)
+        };
+        response(200, credentials_json(&credentials, now))
+    }
+}
+
+impl HttpConnector for EcsEmulator {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        HttpConnectorFuture::ready(self.handle(&request))
+    }
+}
+
+impl HttpClient for EcsEmulator {
+    fn http_connector(
+        &self,
+        _: &HttpConnectorSettings,
+        _: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        self.clone().into_shared()
+    }
+
+    fn connector_metadata(&self) -> Option<ConnectorMetadata> {
+        Some(ConnectorMetadata::new("ecs-emulator", None))
+    }
+}
+
+/// Builder for [`EcsEmulator`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    time_source: Option<SharedTimeSource>,
+    path: Option<String>,
+    authorization_token: Option<String>,
+    credentials_lifetime: Option<Duration>,
+    account_id: Option<String>,
+}
+
+impl Builder {
+    /// Override the time source used to expire credentials
+    ///
+    /// Defaults to the system clock.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = Some(SharedTimeSource::new(time_source));
+        self
+    }
+
+    /// Override the path that credentials are served at
+    ///
+    /// Defaults to `/v2/credentials/emulated`.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Require requests to send `token` in the `Authorization` header
+    pub fn authorization_token(mut self, token: impl Into<String>) -> Self {
+        self.authorization_token = Some(token.into());
+        self
+    }
+
+    /// Override how long issued credentials are valid for
+    ///
+    /// Defaults to 6 hours.
+    pub fn credentials_lifetime(mut self, lifetime: Duration) -> Self {
+        self.credentials_lifetime = Some(lifetime);
+        self
+    }
+
+    /// Include an account ID in the issued credentials
+    pub fn account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+
+    /// Create an [`EcsEmulator`] from this builder
+    pub fn build(self) -> EcsEmulator {
+        let mut credentials = RotatingCredentials::new("ASIAECSEMULATED");
+        if let Some(lifetime) = self.credentials_lifetime {
+            credentials.lifetime = lifetime;
+        }
+        credentials.account_id = self.account_id;
+        EcsEmulator {
+            inner: Arc::new(Inner {
+                time_source: self.time_source.unwrap_or_default(),
+                path: self.path.unwrap_or_else(|| DEFAULT_PATH.to_string()),
+                authorization_token: self.authorization_token,
+                state: Mutex::new(State {
+                    credentials,
+                    faults: FaultQueue::default(),
+                    requests: Vec::new(),
+                }),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::EcsCredentialsProvider;
+    use aws_credential_types::provider::ProvideCredentials;
+    use aws_smithy_async::test_util::{instant_time_and_sleep, InstantSleep, ManualTimeSource};
+    use std::time::UNIX_EPOCH;
+
+    const TOKEN: &str = "Basic emulated-token";
+
+    fn provider(
+        ecs: &EcsEmulator,
+        time_source: ManualTimeSource,
+        sleep: InstantSleep,
+    ) -> EcsCredentialsProvider {
+        let provider_config = ProviderConfig::no_configuration()
+            .with_sleep_impl(sleep)
+            .with_time_source(time_source);
+        EcsCredentialsProvider::builder()
+            .configure(&ecs.configure(provider_config))
+            .build()
+    }
+
+    fn request(path: &str, authorization: Option<&str>) -> HttpRequest {
+        let mut request = http::Request::builder().uri(format!("{FULL_URI_HOST}{path}"));
+        if let Some(authorization) = authorization {
+            request = request.header("authorization", authorization);
+        }
+        request.body(SdkBody::empty()).unwrap().try_into().unwrap()
+    }
+
+    async fn status(ecs: &EcsEmulator, request: HttpRequest) -> u16 {
+        ecs.call(request).await.unwrap().status().as_u16()
+    }
+
+    #[tokio::test]
+    async fn serves_credentials_with_authorization_token() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let ecs = EcsEmulator::builder()
+            .time_source(time_source.clone())
+            .authorization_token(TOKEN)
+            .account_id("123456789012")
+            .build();
+
+        let credentials = provider(&ecs, time_source, sleep)
+            .provide_credentials()
+            .await
+            .expect("valid creds");
+        assert_eq!("ASIAECSEMULATED00000001", credentials.access_key_id());
+        assert_eq!(
+            Some("123456789012"),
+            credentials.account_id().map(|id| id.as_str())
+        );
+        assert_eq!(vec![format!("GET {DEFAULT_PATH}")], ecs.requests());
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_the_authorization_token() {
+        let ecs = EcsEmulator::builder().authorization_token(TOKEN).build();
+
+        assert_eq!(401, status(&ecs, request(DEFAULT_PATH, None)).await);
+        assert_eq!(
+            401,
+            status(&ecs, request(DEFAULT_PATH, Some("Basic wrong"))).await
+        );
+        assert_eq!(
+            404,
+            status(&ecs, request("/v2/credentials/other", Some(TOKEN))).await
+        );
+        assert_eq!(200, status(&ecs, request(DEFAULT_PATH, Some(TOKEN))).await);
+    }
+
+    #[tokio::test]
+    async fn serves_stale_credentials() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH + Duration::from_secs(3600));
+        let ecs = EcsEmulator::builder()
+            .time_source(time_source.clone())
+            .build();
+        ecs.inject_fault(Fault::StaleCredentials, 1);
+        let provider = provider(&ecs, time_source.clone(), sleep);
+
+        let stale = provider.provide_credentials().await.expect("valid creds");
+        assert!(stale.expiry().unwrap() < time_source.now());
+        let fresh = provider.provide_credentials().await.expect("valid creds");
+        assert!(fresh.expiry().unwrap() > time_source.now());
+    }
+
+    #[tokio::test]
+    async fn rotated_credentials_are_served() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let ecs = EcsEmulator::builder()
+            .time_source(time_source.clone())
+            .build();
+        let provider = provider(&ecs, time_source, sleep);
+
+        let first = provider.provide_credentials().await.expect("valid creds");
+        ecs.rotate_credentials();
+        let second = provider.provide_credentials().await.expect("valid creds");
+        assert_ne!(first.access_key_id(), second.access_key_id());
+    }
+
+    #[tokio::test]
+    async fn injected_timeouts_fail_the_provider() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let ecs = EcsEmulator::builder()
+            .time_source(time_source.clone())
+            .build();
+        ecs.inject_fault(Fault::Timeout, 10);
+
+        assert!(provider(&ecs, time_source, sleep)
+            .provide_credentials()
+            .await
+            .is_err());
+    }
+}
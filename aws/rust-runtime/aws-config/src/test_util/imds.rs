@@ -0,0 +1,517 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! An in-process emulator of the EC2 instance metadata service
+
+use super::{credentials_json, fault_response, response, Fault, FaultQueue, RotatingCredentials};
+use aws_credential_types::Credentials;
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_runtime_api::client::connector_metadata::ConnectorMetadata;
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::body::SdkBody;
+use http::Uri;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+const TOKEN_PATH: &str = "/latest/api/token";
+const CREDENTIALS_PATH: &str = "/latest/meta-data/iam/security-credentials";
+const TOKEN_HEADER: &str = "x-aws-ec2-metadata-token";
+const TOKEN_TTL_HEADER: &str = "x-aws-ec2-metadata-token-ttl-seconds";
+const MAX_TOKEN_TTL_SECONDS: u64 = 21_600;
+const DEFAULT_ROLE_NAME: &str = "emulated-instance-role";
+
+/// An in-process emulator of IMDSv2
+///
+/// The emulator answers requests for any endpoint, so it works with the default IMDS endpoint and
+/// with endpoint overrides. It implements the IMDSv2 contract:
+/// - Session tokens are issued by `PUT /latest/api/token` for the TTL given in the
+///   `x-aws-ec2-metadata-token-ttl-seconds` header, which must be between 1 and 21600 seconds.
+/// - `GET` requests without a valid, unexpired token are rejected with a 401.
+/// - `/latest/meta-data/iam/security-credentials/` lists the instance role, and
+///   `/latest/meta-data/iam/security-credentials/<role>` returns its credentials. The credentials
+///   rotate five minutes before they expire.
+/// - Metadata registered with [`Builder::metadata`] is served at its path. Other paths return a 404.
+///
+/// Token and credential expiration follow the emulator's time source. When testing with a
+/// simulated time source, give the same time source to the emulator and the `ProviderConfig`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::imds::credentials::ImdsCredentialsProvider;
+/// use aws_config::provider_config::ProviderConfig;
+/// use aws_config::test_util::imds::ImdsEmulator;
+/// use aws_config::test_util::Fault;
+/// use aws_credential_types::provider::ProvideCredentials;
+///
+/// # async fn docs() {
+/// let imds = ImdsEmulator::builder()
+///     .metadata("/latest/meta-data/placement/region", "us-west-2")
+///     .build();
+/// // The first credentials request fails. The provider's retries will succeed.
+/// imds.inject_fault(
+///     "/latest/meta-data/iam/security-credentials/emulated-instance-role",
+///     Fault::ServerError,
+///     1,
+/// );
+///
+/// let provider = ImdsCredentialsProvider::builder()
+///     .configure(&ProviderConfig::default().with_http_client(imds.clone()))
+///     .build();
+/// let credentials = provider.provide_credentials().await.unwrap();
+/// assert_eq!(
+///     imds.current_credentials().access_key_id(),
+///     credentials.access_key_id()
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ImdsEmulator {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    time_source: SharedTimeSource,
+    hop_limit_exceeded: bool,
+    metadata: HashMap<String, String>,
+    role_name: Option<String>,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: HashMap<String, SystemTime>,
+    issued_tokens: u32,
+    credentials: RotatingCredentials,
+    faults: FaultQueue,
+    requests: Vec<String>,
+}
+
+impl ImdsEmulator {
+    /// Builder for [`ImdsEmulator`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the credentials that the emulator currently serves for the instance role
+    pub fn current_credentials(&self) -> Credentials {
+        let now = self.inner.time_source.now();
+        self.state().credentials.current(now)
+    }
+
+    /// Replaces the credentials of the instance role with new credentials
+    pub fn rotate_credentials(&self) {
+        self.state().credentials.rotate();
+    }
+
+    /// Injects `fault` into the next `times` requests for `path`
+    ///
+    /// `path` is matched exactly against the path of the request URI, e.g. `/latest/api/token`.
+    /// Faults are injected in the order they were added.
+    pub fn inject_fault(&self, path: impl Into<String>, fault: Fault, times: usize) {
+        self.state().faults.push(path.into(), fault, times);
+    }
+
+    /// Returns the requests received by the emulator, formatted as `<method> <path>`
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap()
+    }
+
+    fn handle(&self, request: &HttpRequest) -> Result<HttpResponse, ConnectorError> {
+        let path = match request.uri().parse::<Uri>() {
+            Ok(uri) => uri.path().to_string(),
+            Err(_) => return response(400, SdkBody::empty()),
+        };
+        let now = self.inner.time_source.now();
+        let mut state = self.state();
+        state
+            .requests
+            .push(format!("{} {}", request.method(), path));
+
+        let fault = state.faults.take(&path);
+        if let Some(result) = fault.as_ref().and_then(fault_response) {
+            return result;
+        }
+
+        if path == TOKEN_PATH {
+            if request.method() != "PUT" {
+                return response(405, SdkBody::empty());
+            }
+            if self.inner.hop_limit_exceeded {
+                return Err(ConnectorError::timeout(
+                    "the token response exceeded the hop limit".into(),
+                ));
+            }
+            let ttl = request
+                .headers()
+                .get(TOKEN_TTL_HEADER)
+                .and_then(|ttl| ttl.parse::<u64>().ok());
+            let ttl = match ttl {
+                Some(ttl) if (1..=MAX_TOKEN_TTL_SECONDS).contains(&ttl) => ttl,
+                _ => return response(400, SdkBody::empty()),
+            };
+            state.issued_tokens += 1;
+            let token = format!("emulated-imds-token-{}", state.issued_tokens);
+            state
+                .tokens
+                .insert(token.clone(), now + Duration::from_secs(ttl));
+            let mut token_response = response(200, token)?;
+            token_response
+                .headers_mut()
+                .insert(TOKEN_TTL_HEADER, ttl.to_string());
+            return Ok(token_response);
+        }
+
+        if request.method() != "GET" {
+            return response(405, SdkBody::empty());
+        }
+        let token_is_valid = request
+            .headers()
+            .get(TOKEN_HEADER)
+            .and_then(|token| state.tokens.get(token))
+            .map(|expiry| now < *expiry)
+            .unwrap_or(false);
+        if !token_is_valid {
+            return response(401, SdkBody::empty());
+        }
+
+        if let Some(role) = path.strip_prefix(CREDENTIALS_PATH) {
+            return match (role, &self.inner.role_name) {
+                ("" | "/", Some(role_name)) => response(200, role_name.clone()),
+                (role, Some(role_name)) if role.strip_prefix('/') == Some(role_name.as_str()) => {
+                    let credentials = if fault == Some(Fault::StaleCredentials) {
+                        state.credentials.stale(now)
+                    } else {
+                        state.credentials.current(now)
+                    };
+                    response(200, credentials_json(&credentials, now))
+                }
+                _ => response(404, SdkBody::empty()),
+            };
+        }
+        match self.inner.metadata.get(&path) {
+            Some(value) => response(200, value.clone()),
+            None => response(404, SdkBody::empty()),
+        }
+    }
+}
+
+impl HttpConnector for ImdsEmulator {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        HttpConnectorFuture::ready(self.handle(&request))
+    }
+}
+
+impl HttpClient for ImdsEmulator {
+    fn http_connector(
+        &self,
+        _: &HttpConnectorSettings,
+        _: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        self.clone().into_shared()
+    }
+
+    fn connector_metadata(&self) -> Option<ConnectorMetadata> {
+        Some(ConnectorMetadata::new("imds-emulator", None))
+    }
+}
+
+/// Builder for [`ImdsEmulator`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    time_source: Option<SharedTimeSource>,
+    hop_limit_exceeded: bool,
+    metadata: HashMap<String, String>,
+    role_name: Option<Option<String>>,
+    credentials_lifetime: Option<Duration>,
+    account_id: Option<String>,
+}
+
+impl Builder {
+    /// Override the time source used to expire tokens and credentials
+    ///
+    /// Defaults to the system clock.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = Some(SharedTimeSource::new(time_source));
+        self
+    }
+
+    /// Serve `value` for `GET` requests to `path`, e.g. `/latest/meta-data/placement/region`
+    pub fn metadata(mut self, path: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(path.into(), value.into());
+        self
+    }
+
+    /// Override the name of the instance role
+    ///
+    /// Defaults to `emulated-instance-role`.
+    pub fn role_name(mut self, role_name: impl Into<String>) -> Self {
+        self.role_name = Some(Some(role_name.into()));
+        self
+    }
+
+    /// Emulate an instance without an instance profile
+    ///
+    /// Requests for credentials return a 404.
+    pub fn without_role(mut self) -> Self {
+        self.role_name = Some(None);
+        self
+    }
+
+    /// Override how long issued credentials are valid for
+    ///
+    /// Defaults to 6 hours.
+    pub fn credentials_lifetime(mut self, lifetime: Duration) -> Self {
+        self.credentials_lifetime = Some(lifetime);
+        self
+    }
+
+    /// Include an account ID in the issued credentials
+    pub fn account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id = Some(account_id.into());
+        self
+    }
+
+    /// Emulate a request path with more network hops than the instance's hop limit allows
+    ///
+    /// Responses to token requests are dropped by the network, so token requests time out. This is
+    /// what happens when a container without host networking calls IMDS with the default hop limit
+    /// of 1.
+    pub fn hop_limit_exceeded(mut self) -> Self {
+        self.hop_limit_exceeded = true;
+        self
+    }
+
+    /// Create an [`ImdsEmulator`] from this builder
+    pub fn build(self) -> ImdsEmulator {
+        let mut credentials = RotatingCredentials::new("ASIAIMDSEMULATED");
+        if let Some(lifetime) = self.credentials_lifetime {
+            credentials.lifetime = lifetime;
+        }
+        credentials.account_id = self.account_id;
+        ImdsEmulator {
+            inner: Arc::new(Inner {
+                time_source: self.time_source.unwrap_or_default(),
+                hop_limit_exceeded: self.hop_limit_exceeded,
+                metadata: self.metadata,
+                role_name: self
+                    .role_name
+                    .unwrap_or_else(|| Some(DEFAULT_ROLE_NAME.to_string())),
+                state: Mutex::new(State {
+                    tokens: HashMap::new(),
+                    issued_tokens: 0,
+                    credentials,
+                    faults: FaultQueue::default(),
+                    requests: Vec::new(),
+                }),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::imds::credentials::ImdsCredentialsProvider;
+    use crate::provider_config::ProviderConfig;
+    use aws_credential_types::provider::ProvideCredentials;
+    use aws_smithy_async::test_util::{instant_time_and_sleep, InstantSleep, ManualTimeSource};
+    use std::time::UNIX_EPOCH;
+
+    const REGION_PATH: &str = "/latest/meta-data/placement/region";
+    const ROLE_PATH: &str = "/latest/meta-data/iam/security-credentials/emulated-instance-role";
+
+    fn provider_config(
+        imds: &ImdsEmulator,
+        time_source: ManualTimeSource,
+        sleep: InstantSleep,
+    ) -> ProviderConfig {
+        ProviderConfig::no_configuration()
+            .with_http_client(imds.clone())
+            .with_sleep_impl(sleep)
+            .with_time_source(time_source)
+    }
+
+    fn request(method: &str, path: &str, headers: &[(&str, &str)]) -> HttpRequest {
+        let mut request = http::Request::builder()
+            .method(method)
+            .uri(format!("http://169.254.169.254{path}"));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(SdkBody::empty()).unwrap().try_into().unwrap()
+    }
+
+    async fn status(imds: &ImdsEmulator, request: HttpRequest) -> u16 {
+        imds.call(request).await.unwrap().status().as_u16()
+    }
+
+    #[tokio::test]
+    async fn serves_metadata_with_session_tokens() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let imds = ImdsEmulator::builder()
+            .time_source(time_source.clone())
+            .metadata(REGION_PATH, "us-west-2")
+            .build();
+        let client = crate::imds::Client::builder()
+            .configure(&provider_config(&imds, time_source, sleep))
+            .build();
+
+        let region = client.get(REGION_PATH).await.expect("success");
+        assert_eq!("us-west-2", region.as_ref());
+        assert!(client.get("/latest/meta-data/missing").await.is_err());
+        assert_eq!(
+            vec![
+                "PUT /latest/api/token".to_string(),
+                format!("GET {REGION_PATH}"),
+                "GET /latest/meta-data/missing".to_string(),
+            ],
+            imds.requests()
+        );
+    }
+
+    #[tokio::test]
+    async fn tokens_expire_after_their_ttl() {
+        let (time_source, _) = instant_time_and_sleep(UNIX_EPOCH);
+        let imds = ImdsEmulator::builder()
+            .time_source(time_source.clone())
+            .metadata(REGION_PATH, "us-west-2")
+            .build();
+
+        let invalid_ttl = request("PUT", TOKEN_PATH, &[(TOKEN_TTL_HEADER, "0")]);
+        assert_eq!(400, status(&imds, invalid_ttl).await);
+        assert_eq!(401, status(&imds, request("GET", REGION_PATH, &[])).await);
+
+        let token = imds
+            .call(request("PUT", TOKEN_PATH, &[(TOKEN_TTL_HEADER, "60")]))
+            .await
+            .unwrap();
+        assert_eq!(Some("60"), token.headers().get(TOKEN_TTL_HEADER));
+        let token = std::str::from_utf8(token.body().bytes().unwrap())
+            .unwrap()
+            .to_string();
+        let get_region = || request("GET", REGION_PATH, &[(TOKEN_HEADER, token.as_str())]);
+        assert_eq!(200, status(&imds, get_region()).await);
+
+        time_source.advance(Duration::from_secs(60));
+        assert_eq!(401, status(&imds, get_region()).await);
+    }
+
+    #[tokio::test]
+    async fn credentials_rotate_before_they_expire() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let imds = ImdsEmulator::builder()
+            .time_source(time_source.clone())
+            .credentials_lifetime(Duration::from_secs(60 * 60))
+            .account_id("123456789012")
+            .build();
+        let provider = ImdsCredentialsProvider::builder()
+            .configure(&provider_config(&imds, time_source.clone(), sleep))
+            .build();
+
+        let first = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!("ASIAIMDSEMULATED00000001", first.access_key_id());
+        assert_eq!(
+            Some("123456789012"),
+            first.account_id().map(|id| id.as_str())
+        );
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(60 * 60)),
+            first.expiry()
+        );
+
+        time_source.advance(Duration::from_secs(56 * 60));
+        let second = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!("ASIAIMDSEMULATED00000002", second.access_key_id());
+        assert_eq!(
+            imds.current_credentials().access_key_id(),
+            second.access_key_id()
+        );
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let imds = ImdsEmulator::builder()
+            .time_source(time_source.clone())
+            .build();
+        imds.inject_fault(ROLE_PATH, Fault::ServerError, 1);
+        let provider = ImdsCredentialsProvider::builder()
+            .configure(&provider_config(&imds, time_source, sleep))
+            .build();
+
+        let credentials = provider.provide_credentials().await.expect("valid creds");
+        assert_eq!(
+            imds.current_credentials().access_key_id(),
+            credentials.access_key_id()
+        );
+        let role_requests = imds
+            .requests()
+            .iter()
+            .filter(|request| request.ends_with(ROLE_PATH))
+            .count();
+        assert_eq!(2, role_requests);
+    }
+
+    #[tokio::test]
+    async fn stale_credentials_are_extended_by_the_provider() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH + Duration::from_secs(3600));
+        let imds = ImdsEmulator::builder()
+            .time_source(time_source.clone())
+            .build();
+        imds.inject_fault(ROLE_PATH, Fault::StaleCredentials, 1);
+        let provider = ImdsCredentialsProvider::builder()
+            .configure(&provider_config(&imds, time_source.clone(), sleep))
+            .build();
+
+        let credentials = provider.provide_credentials().await.expect("valid creds");
+        assert!(credentials.expiry().unwrap() > time_source.now());
+    }
+
+    #[tokio::test]
+    async fn hop_limit_exceeded_fails_token_requests() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let imds = ImdsEmulator::builder()
+            .time_source(time_source.clone())
+            .metadata(REGION_PATH, "us-west-2")
+            .hop_limit_exceeded()
+            .build();
+        let client = crate::imds::Client::builder()
+            .configure(&provider_config(&imds, time_source, sleep))
+            .build();
+
+        assert!(client.get(REGION_PATH).await.is_err());
+        assert!(imds
+            .requests()
+            .iter()
+            .all(|request| request == "PUT /latest/api/token"));
+    }
+
+    #[tokio::test]
+    async fn instances_without_a_role_have_no_credentials() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let imds = ImdsEmulator::builder()
+            .time_source(time_source.clone())
+            .without_role()
+            .build();
+        let provider = ImdsCredentialsProvider::builder()
+            .configure(&provider_config(&imds, time_source, sleep))
+            .build();
+
+        assert!(provider.provide_credentials().await.is_err());
+    }
+}
@@ -0,0 +1,42 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Attributes that describe the principal credentials belong to.
+
+use std::fmt;
+
+/// The AWS account ID that credentials belong to.
+///
+/// Credentials providers set this when the account is known, for example from the ARN of an
+/// assumed role, and it's then available to services that route requests with it through the
+/// `AWS::Auth::AccountId` endpoint parameter.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AccountId {
+    inner: String,
+}
+
+impl AccountId {
+    /// Returns the account ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.inner)
+    }
+}
+
+impl<T> From<T> for AccountId
+where
+    T: Into<String>,
+{
+    fn from(value: T) -> Self {
+        Self {
+            inner: value.into(),
+        }
+    }
+}
@@ -12,6 +12,8 @@ use zeroize::Zeroizing;
 
 use aws_smithy_runtime_api::client::identity::Identity;
 
+use crate::attributes::AccountId;
+
 /// AWS SDK Credentials
 ///
 /// An opaque struct representing credentials that may be used in an AWS SDK, modeled on
@@ -37,6 +39,9 @@ struct Inner {
     /// If these credentials never expire, this value will be set to `None`
     expires_after: Option<SystemTime>,
 
+    /// The AWS account the credentials belong to, if the provider knows it
+    account_id: Option<AccountId>,
+
     provider_name: &'static str,
 }
 
@@ -60,6 +65,9 @@ impl Debug for Credentials {
         } else {
             creds.field("expires_after", &"never");
         }
+        if let Some(account_id) = &self.0.account_id {
+            creds.field("account_id", &account_id.as_str());
+        }
         creds.finish()
     }
 }
@@ -84,10 +92,32 @@ impl Credentials {
             secret_access_key: Zeroizing::new(secret_access_key.into()),
             session_token: Zeroizing::new(session_token),
             expires_after,
+            account_id: None,
             provider_name,
         }))
     }
 
+    /// Creates a builder for `Credentials`.
+    ///
+    /// The builder is useful when credentials carry optional attributes, such as the account ID,
+    /// that [`Credentials::new`] doesn't accept. The access key ID, secret access key and provider
+    /// name must be set, or [`build`](CredentialsBuilder::build) panics.
+    ///
+    /// ```rust
+    /// use aws_credential_types::Credentials;
+    ///
+    /// let creds = Credentials::builder()
+    ///     .access_key_id("akid")
+    ///     .secret_access_key("secret")
+    ///     .account_id("123456789012")
+    ///     .provider_name("my-provider")
+    ///     .build();
+    /// assert_eq!(Some("123456789012"), creds.account_id().map(|id| id.as_str()));
+    /// ```
+    pub fn builder() -> CredentialsBuilder {
+        CredentialsBuilder::default()
+    }
+
     /// Creates `Credentials` from hardcoded access key, secret key, and session token.
     ///
     /// _Note: In general, you should prefer to use the credential providers that come
@@ -168,6 +198,99 @@ impl Credentials {
     pub fn session_token(&self) -> Option<&str> {
         self.0.session_token.as_deref()
     }
+
+    /// Returns the AWS account ID the credentials belong to, if known.
+    pub fn account_id(&self) -> Option<&AccountId> {
+        self.0.account_id.as_ref()
+    }
+}
+
+/// Builder for [`Credentials`]
+///
+/// The access key ID, secret access key, and provider name are required.
+#[derive(Debug, Default)]
+pub struct CredentialsBuilder {
+    access_key_id: Option<Zeroizing<String>>,
+    secret_access_key: Option<Zeroizing<String>>,
+    session_token: Zeroizing<Option<String>>,
+    expires_after: Option<SystemTime>,
+    account_id: Option<AccountId>,
+    provider_name: Option<&'static str>,
+}
+
+impl CredentialsBuilder {
+    /// Sets the access key ID.
+    pub fn access_key_id(mut self, access_key_id: impl Into<String>) -> Self {
+        self.access_key_id = Some(Zeroizing::new(access_key_id.into()));
+        self
+    }
+
+    /// Sets the secret access key.
+    pub fn secret_access_key(mut self, secret_access_key: impl Into<String>) -> Self {
+        self.secret_access_key = Some(Zeroizing::new(secret_access_key.into()));
+        self
+    }
+
+    /// Sets the session token.
+    pub fn session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.set_session_token(Some(session_token.into()));
+        self
+    }
+
+    /// Sets the session token.
+    pub fn set_session_token(&mut self, session_token: Option<String>) {
+        self.session_token = Zeroizing::new(session_token);
+    }
+
+    /// Sets the time when the credentials will expire.
+    pub fn expiry(mut self, expiry: SystemTime) -> Self {
+        self.set_expiry(Some(expiry));
+        self
+    }
+
+    /// Sets the time when the credentials will expire.
+    pub fn set_expiry(&mut self, expiry: Option<SystemTime>) {
+        self.expires_after = expiry;
+    }
+
+    /// Sets the AWS account ID the credentials belong to.
+    pub fn account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        self.set_account_id(Some(account_id.into()));
+        self
+    }
+
+    /// Sets the AWS account ID the credentials belong to.
+    pub fn set_account_id(&mut self, account_id: Option<AccountId>) {
+        self.account_id = account_id;
+    }
+
+    /// Sets the name of the provider that loaded the credentials.
+    pub fn provider_name(mut self, provider_name: &'static str) -> Self {
+        self.provider_name = Some(provider_name);
+        self
+    }
+
+    /// Builds [`Credentials`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the access key ID, secret access key, or provider name weren't set.
+    pub fn build(self) -> Credentials {
+        Credentials(Arc::new(Inner {
+            access_key_id: self
+                .access_key_id
+                .expect("access_key_id is required to build credentials"),
+            secret_access_key: self
+                .secret_access_key
+                .expect("secret_access_key is required to build credentials"),
+            session_token: self.session_token,
+            expires_after: self.expires_after,
+            account_id: self.account_id,
+            provider_name: self
+                .provider_name
+                .expect("provider_name is required to build credentials"),
+        }))
+    }
 }
 
 #[cfg(feature = "test-util")]
@@ -198,13 +321,20 @@ impl Credentials {
 impl From<Credentials> for Identity {
     fn from(val: Credentials) -> Self {
         let expiry = val.expiry();
-        Identity::new(val, expiry)
+        let account_id = val.account_id().cloned();
+        let identity = Identity::new(val, expiry);
+        match account_id {
+            Some(account_id) => identity.with_property(account_id),
+            None => identity,
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::attributes::AccountId;
     use crate::Credentials;
+    use aws_smithy_runtime_api::client::identity::Identity;
     use std::time::{Duration, UNIX_EPOCH};
 
     #[test]
@@ -221,4 +351,36 @@ mod test {
             r#"Credentials { provider_name: "debug tester", access_key_id: "akid", secret_access_key: "** redacted **", expires_after: "2009-02-13T23:31:30Z" }"#
         );
     }
+
+    #[test]
+    fn builder_with_account_id() {
+        let creds = Credentials::builder()
+            .access_key_id("akid")
+            .secret_access_key("secret")
+            .session_token("token")
+            .expiry(UNIX_EPOCH + Duration::from_secs(1234567890))
+            .account_id("123456789012")
+            .provider_name("debug tester")
+            .build();
+        assert_eq!(Some("token"), creds.session_token());
+        assert_eq!(
+            format!("{:?}", creds),
+            r#"Credentials { provider_name: "debug tester", access_key_id: "akid", secret_access_key: "** redacted **", expires_after: "2009-02-13T23:31:30Z", account_id: "123456789012" }"#
+        );
+
+        let identity = Identity::from(creds);
+        assert_eq!(
+            Some("123456789012"),
+            identity.property::<AccountId>().map(AccountId::as_str)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "secret_access_key is required")]
+    fn builder_requires_secret_access_key() {
+        Credentials::builder()
+            .access_key_id("akid")
+            .provider_name("test")
+            .build();
+    }
 }
@@ -12,6 +12,7 @@ use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 
+pub mod document;
 pub mod error;
 pub mod file;
 mod normalize;
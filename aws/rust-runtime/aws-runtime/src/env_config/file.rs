@@ -58,7 +58,7 @@ impl Default for EnvConfigFiles {
 }
 
 /// Profile file type (config or credentials)
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum EnvConfigFileKind {
     /// The SDK config file that typically resides in `~/.aws/config`
     Config,
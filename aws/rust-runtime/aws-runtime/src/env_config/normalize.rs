@@ -5,7 +5,7 @@
 
 use crate::env_config::file::EnvConfigFileKind;
 use crate::env_config::parse::{RawProfileSet, WHITESPACE};
-use crate::env_config::property::{PropertiesKey, Property};
+use crate::env_config::property::{PropertiesKey, Property, PropertySource};
 use crate::env_config::section::{EnvConfigSections, Profile, Section, SsoSession};
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -99,16 +99,21 @@ impl<'a> SectionPair<'a> {
 /// - Profile names are validated (see `validate_profile_name`)
 /// - A profile named `profile default` takes priority over a profile named `default`.
 /// - Profiles with identical names are merged
+///
+/// Every merged property records the file it was read from (`source`).
 pub(super) fn merge_in(
     base: &mut EnvConfigSections,
     raw_profile_set: RawProfileSet<'_>,
-    kind: EnvConfigFileKind,
+    source: &PropertySource,
 ) {
     // parse / validate sections
     let validated_sections = raw_profile_set
         .into_iter()
         .map(|(section_key, properties)| {
-            (SectionPair::parse(section_key).valid_for(kind), properties)
+            (
+                SectionPair::parse(section_key).valid_for(source.kind()),
+                properties,
+            )
         });
 
     // remove invalid profiles & emit a warning
@@ -179,15 +184,22 @@ pub(super) fn merge_in(
                 continue;
             }
         };
-        merge_into_base(section, raw_profile)
+        merge_into_base(section, raw_profile, source)
     }
 }
 
-fn merge_into_base(target: &mut dyn Section, profile: HashMap<Cow<'_, str>, Cow<'_, str>>) {
+fn merge_into_base(
+    target: &mut dyn Section,
+    profile: HashMap<Cow<'_, str>, Cow<'_, str>>,
+    source: &PropertySource,
+) {
     for (k, v) in profile {
         match validate_identifier(k.as_ref()) {
             Ok(k) => {
-                target.insert(k.to_owned(), Property::new(k.to_owned(), v.into()));
+                target.insert(
+                    k.to_owned(),
+                    Property::new(k.to_owned(), v.into()).with_source(source.clone()),
+                );
             }
             Err(_) => {
                 tracing::warn!(profile = %target.name(), key = ?k, "key ignored because `{k}` was not a valid identifier");
@@ -199,7 +211,7 @@ fn merge_into_base(target: &mut dyn Section, profile: HashMap<Cow<'_, str>, Cow<
 /// Validate that a string is a valid identifier
 ///
 /// Identifiers must match `[A-Za-z0-9_\-/.%@:\+]+`
-fn validate_identifier(input: &str) -> Result<&str, ()> {
+pub(super) fn validate_identifier(input: &str) -> Result<&str, ()> {
     input
         .chars()
         .all(|ch| {
@@ -233,6 +245,7 @@ mod tests {
     use crate::env_config::file::EnvConfigFileKind;
     use crate::env_config::normalize::{merge_in, validate_identifier, SectionPair};
     use crate::env_config::parse::RawProfileSet;
+    use crate::env_config::property::PropertySource;
     use crate::env_config::section::{EnvConfigSections, Section};
     use std::borrow::Cow;
     use std::collections::HashMap;
@@ -361,7 +374,11 @@ mod tests {
             out
         });
         let mut base = EnvConfigSections::default();
-        merge_in(&mut base, profile, EnvConfigFileKind::Config);
+        merge_in(
+            &mut base,
+            profile,
+            &PropertySource::new(EnvConfigFileKind::Config, None),
+        );
         assert!(base
             .get_profile("default")
             .expect("contains default profile")
@@ -379,7 +396,7 @@ mod tests {
         merge_in(
             &mut EnvConfigSections::default(),
             profile,
-            EnvConfigFileKind::Config,
+            &PropertySource::new(EnvConfigFileKind::Config, None),
         );
         assert!(logs_contain("profile [foo] ignored"));
     }
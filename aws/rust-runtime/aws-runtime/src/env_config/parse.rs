@@ -26,7 +26,7 @@ pub(super) type RawProfileSet<'a> = HashMap<&'a str, HashMap<Cow<'a, str>, Cow<'
 /// Profile parsing is actually quite strict about what is and is not whitespace, so use this instead
 /// of `.is_whitespace()` / `.trim()`
 pub(crate) const WHITESPACE: &[char] = &[' ', '\t'];
-const COMMENT: &[char] = &['#', ';'];
+pub(super) const COMMENT: &[char] = &['#', ';'];
 
 /// Location for use during error reporting
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -72,11 +72,11 @@ fn validate_subproperty(value: &str, location: Location) -> Result<(), EnvConfig
     }
 }
 
-fn is_empty_line(line: &str) -> bool {
+pub(super) fn is_empty_line(line: &str) -> bool {
     line.trim_matches(WHITESPACE).is_empty()
 }
 
-fn is_comment_line(line: &str) -> bool {
+pub(super) fn is_comment_line(line: &str) -> bool {
     line.starts_with(COMMENT)
 }
 
@@ -221,7 +221,7 @@ impl<'a> Parser<'a> {
 
 /// Error encountered while parsing a property
 #[derive(Debug, Eq, PartialEq)]
-enum PropertyError {
+pub(super) enum PropertyError {
     NoEquals,
     NoName,
 }
@@ -246,7 +246,7 @@ impl PropertyError {
 }
 
 /// Parse a property line into a key-value pair
-fn parse_property_line(line: &str) -> Result<(Cow<'_, str>, &str), PropertyError> {
+pub(super) fn parse_property_line(line: &str) -> Result<(Cow<'_, str>, &str), PropertyError> {
     let line = prepare_line(line, true);
     let (k, v) = line.split_once('=').ok_or(PropertyError::NoEquals)?;
     let k = k.trim_matches(WHITESPACE);
@@ -279,7 +279,7 @@ pub(crate) fn to_ascii_lowercase(s: &str) -> Cow<'_, str> {
 ///
 /// Depending on context, comment characters may need to be preceded by whitespace to be considered
 /// comments.
-fn prepare_line(line: &str, comments_need_whitespace: bool) -> &str {
+pub(super) fn prepare_line(line: &str, comments_need_whitespace: bool) -> &str {
     let line = line.trim_matches(WHITESPACE);
     let mut prev_char_whitespace = false;
     let mut comment_idx = None;
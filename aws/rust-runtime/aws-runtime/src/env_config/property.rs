@@ -5,6 +5,7 @@
 
 //! Sections within an AWS config profile.
 
+use crate::env_config::file::EnvConfigFileKind;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -13,6 +14,7 @@ use std::fmt;
 pub struct Property {
     key: String,
     value: String,
+    source: Option<PropertySource>,
 }
 
 impl Property {
@@ -26,9 +28,50 @@ impl Property {
         &self.key
     }
 
+    /// The config file this property was loaded from
+    ///
+    /// This is `None` for properties that were not loaded from a config file.
+    pub fn source(&self) -> Option<&PropertySource> {
+        self.source.as_ref()
+    }
+
     /// Creates a new property
     pub fn new(key: String, value: String) -> Self {
-        Property { key, value }
+        Property {
+            key,
+            value,
+            source: None,
+        }
+    }
+
+    pub(crate) fn with_source(mut self, source: PropertySource) -> Self {
+        self.source = Some(source);
+        self
+    }
+}
+
+/// The config file that a [`Property`] was loaded from
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PropertySource {
+    kind: EnvConfigFileKind,
+    path: Option<String>,
+}
+
+impl PropertySource {
+    pub(crate) fn new(kind: EnvConfigFileKind, path: Option<String>) -> Self {
+        Self { kind, path }
+    }
+
+    /// The kind of config file (config or credentials)
+    pub fn kind(&self) -> EnvConfigFileKind {
+        self.kind
+    }
+
+    /// The path of the config file
+    ///
+    /// This is `None` when the contents of the file were provided directly rather than read from disk.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
     }
 }
 
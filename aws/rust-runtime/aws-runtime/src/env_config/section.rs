@@ -7,7 +7,7 @@
 
 use crate::env_config::normalize;
 use crate::env_config::parse::{parse_profile_file, EnvConfigParseError};
-use crate::env_config::property::{Properties, Property};
+use crate::env_config::property::{Properties, Property, PropertySource};
 use crate::env_config::source::Source;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -86,6 +86,11 @@ impl Profile {
     pub fn get(&self, name: &str) -> Option<&str> {
         self.0.get(name)
     }
+
+    /// Returns the full property named `name`, including the file it was loaded from
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.0.properties.get(name.to_ascii_lowercase().as_str())
+    }
 }
 
 impl Section for Profile {
@@ -264,7 +269,8 @@ impl EnvConfigSections {
         };
 
         for file in source.files {
-            normalize::merge_in(&mut base, parse_profile_file(&file)?, file.kind);
+            let property_source = PropertySource::new(file.kind, file.path.clone());
+            normalize::merge_in(&mut base, parse_profile_file(&file)?, &property_source);
         }
         Ok(base)
     }
@@ -323,6 +329,30 @@ mod test {
         assert_eq!(profile_names, vec!["bar", "foo"]);
     }
 
+    #[test]
+    fn properties_record_their_source_file() {
+        let source = make_source(ParserInput {
+            config_file: Some("[profile foo]\nregion = us-east-1".to_string()),
+            credentials_file: Some("[foo]\naws_access_key_id = AKID".to_string()),
+        });
+
+        let profile_set = EnvConfigSections::parse(source).expect("profiles loaded");
+        let profile = profile_set.get_profile("foo").expect("profile exists");
+
+        let region = profile.property("REGION").expect("region is set");
+        assert_eq!("us-east-1", region.value());
+        let region_source = region.source().expect("loaded from a file");
+        assert_eq!(EnvConfigFileKind::Config, region_source.kind());
+        assert_eq!(Some("~/.aws/config"), region_source.path());
+
+        let access_key = profile
+            .property("aws_access_key_id")
+            .and_then(|property| property.source())
+            .expect("loaded from a file");
+        assert_eq!(EnvConfigFileKind::Credentials, access_key.kind());
+        assert_eq!(Some("~/.aws/credentials"), access_key.path());
+    }
+
     /// Run all tests from the fuzzing corpus to validate coverage
     #[test]
     #[ignore]
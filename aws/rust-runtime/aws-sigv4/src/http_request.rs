@@ -68,6 +68,8 @@ pub(crate) mod test;
 use crate::sign::v4;
 #[cfg(feature = "sigv4a")]
 use crate::sign::v4a;
+#[cfg(feature = "x509")]
+use crate::sign::x509;
 use crate::SignatureVersion;
 use aws_credential_types::Credentials;
 pub use error::SigningError;
@@ -88,6 +90,9 @@ pub enum SigningParams<'a> {
     #[cfg(feature = "sigv4a")]
     /// Sign with the SigV4a algorithm
     V4a(v4a::SigningParams<'a, SigningSettings>),
+    #[cfg(feature = "x509")]
+    /// Sign with an X.509 certificate
+    X509(x509::SigningParams<'a, SigningSettings>),
 }
 
 impl<'a> From<v4::SigningParams<'a, SigningSettings>> for SigningParams<'a> {
@@ -103,6 +108,13 @@ impl<'a> From<v4a::SigningParams<'a, SigningSettings>> for SigningParams<'a> {
     }
 }
 
+#[cfg(feature = "x509")]
+impl<'a> From<x509::SigningParams<'a, SigningSettings>> for SigningParams<'a> {
+    fn from(value: x509::SigningParams<'a, SigningSettings>) -> Self {
+        Self::X509(value)
+    }
+}
+
 impl<'a> SigningParams<'a> {
     /// Return the credentials within the signing params.
     pub(crate) fn credentials(&self) -> Result<&Credentials, SigningError> {
@@ -110,6 +122,8 @@ impl<'a> SigningParams<'a> {
             Self::V4(v4::SigningParams { identity, .. }) => identity,
             #[cfg(feature = "sigv4a")]
             Self::V4a(v4a::SigningParams { identity, .. }) => identity,
+            #[cfg(feature = "x509")]
+            Self::X509(_) => return Err(SigningError::unsupported_identity_type()),
         };
 
         identity
@@ -117,10 +131,12 @@ impl<'a> SigningParams<'a> {
             .ok_or_else(SigningError::unsupported_identity_type)
     }
 
-    /// If the signing params are for SigV4, return the region. Otherwise, return `None`.
+    /// If the signing params are for SigV4 or X.509, return the region. Otherwise, return `None`.
     pub fn region(&self) -> Option<&str> {
         match self {
             SigningParams::V4(v4::SigningParams { region, .. }) => Some(region),
+            #[cfg(feature = "x509")]
+            SigningParams::X509(x509::SigningParams { region, .. }) => Some(region),
             #[allow(unreachable_patterns)]
             _ => None,
         }
@@ -141,6 +157,8 @@ impl<'a> SigningParams<'a> {
             Self::V4(v4::SigningParams { settings, .. }) => settings,
             #[cfg(feature = "sigv4a")]
             Self::V4a(v4a::SigningParams { settings, .. }) => settings,
+            #[cfg(feature = "x509")]
+            Self::X509(x509::SigningParams { settings, .. }) => settings,
         }
     }
 
@@ -150,6 +168,8 @@ impl<'a> SigningParams<'a> {
             Self::V4(v4::SigningParams { settings, .. }) => settings,
             #[cfg(feature = "sigv4a")]
             Self::V4a(v4a::SigningParams { settings, .. }) => settings,
+            #[cfg(feature = "x509")]
+            Self::X509(x509::SigningParams { settings, .. }) => settings,
         }
     }
 
@@ -175,6 +195,8 @@ impl<'a> SigningParams<'a> {
             Self::V4(v4::SigningParams { time, .. }) => time,
             #[cfg(feature = "sigv4a")]
             Self::V4a(v4a::SigningParams { time, .. }) => time,
+            #[cfg(feature = "x509")]
+            Self::X509(x509::SigningParams { time, .. }) => time,
         }
     }
 
@@ -184,6 +206,8 @@ impl<'a> SigningParams<'a> {
             Self::V4(v4::SigningParams { name, .. }) => name,
             #[cfg(feature = "sigv4a")]
             Self::V4a(v4a::SigningParams { name, .. }) => name,
+            #[cfg(feature = "x509")]
+            Self::X509(x509::SigningParams { name, .. }) => name,
         }
     }
 
@@ -193,6 +217,8 @@ impl<'a> SigningParams<'a> {
             Self::V4(params) => params.algorithm(),
             #[cfg(feature = "sigv4a")]
             Self::V4a(params) => params.algorithm(),
+            #[cfg(feature = "x509")]
+            Self::X509(params) => params.algorithm(),
         }
     }
 
@@ -202,6 +228,8 @@ impl<'a> SigningParams<'a> {
             Self::V4(..) => SignatureVersion::V4,
             #[cfg(feature = "sigv4a")]
             Self::V4a(..) => SignatureVersion::V4a,
+            #[cfg(feature = "x509")]
+            Self::X509(..) => SignatureVersion::X509,
         }
     }
 }
@@ -14,6 +14,7 @@ use crate::http_request::{PayloadChecksumKind, SignableBody, SignatureLocation,
 use crate::http_request::{PercentEncodingMode, SigningSettings};
 use crate::sign::v4::sha256_hex_string;
 use crate::SignatureVersion;
+use aws_credential_types::Credentials;
 use aws_smithy_http::query_writer::QueryWriter;
 use http0::header::{AsHeaderName, HeaderName, HOST};
 use http0::{HeaderMap, HeaderValue, Uri};
@@ -31,6 +32,10 @@ pub(crate) mod header {
     pub(crate) const X_AMZ_DATE: &str = "x-amz-date";
     pub(crate) const X_AMZ_SECURITY_TOKEN: &str = "x-amz-security-token";
     pub(crate) const X_AMZ_USER_AGENT: &str = "x-amz-user-agent";
+    #[cfg(feature = "x509")]
+    pub(crate) const X_AMZ_X509: &str = "x-amz-x509";
+    #[cfg(feature = "x509")]
+    pub(crate) const X_AMZ_X509_CHAIN: &str = "x-amz-x509-chain";
 }
 
 pub(crate) mod param {
@@ -137,9 +142,6 @@ impl<'a> CanonicalRequest<'a> {
         req: &'b SignableRequest<'b>,
         params: &'b SigningParams<'b>,
     ) -> Result<CanonicalRequest<'b>, CanonicalRequestError> {
-        let creds = params
-            .credentials()
-            .map_err(|_| CanonicalRequestError::unsupported_identity_type())?;
         // Path encoding: if specified, re-encode % as %25
         // Set method and path into CanonicalRequest
         let path = req.uri().path();
@@ -160,7 +162,7 @@ impl<'a> CanonicalRequest<'a> {
         let signed_headers = SignedHeaders::new(signed_headers);
 
         let security_token = match params.settings().session_token_mode {
-            SessionTokenMode::Include => creds.session_token(),
+            SessionTokenMode::Include => Self::session_token(params)?,
             SessionTokenMode::Exclude => None,
         };
 
@@ -176,6 +178,10 @@ impl<'a> CanonicalRequest<'a> {
             SignatureLocation::QueryParams => {
                 let credential = match params {
                     SigningParams::V4(params) => {
+                        let creds = params
+                            .identity
+                            .data::<Credentials>()
+                            .ok_or_else(CanonicalRequestError::unsupported_identity_type)?;
                         format!(
                             "{}/{}/{}/{}/aws4_request",
                             creds.access_key_id(),
@@ -186,6 +192,10 @@ impl<'a> CanonicalRequest<'a> {
                     }
                     #[cfg(feature = "sigv4a")]
                     SigningParams::V4a(params) => {
+                        let creds = params
+                            .identity
+                            .data::<Credentials>()
+                            .ok_or_else(CanonicalRequestError::unsupported_identity_type)?;
                         format!(
                             "{}/{}/{}/aws4_request",
                             creds.access_key_id(),
@@ -193,6 +203,16 @@ impl<'a> CanonicalRequest<'a> {
                             params.name,
                         )
                     }
+                    #[cfg(feature = "x509")]
+                    SigningParams::X509(params) => {
+                        format!(
+                            "{}/{}/{}/{}/aws4_request",
+                            params.certificate.serial_number(),
+                            format_date(params.time),
+                            params.region,
+                            params.name,
+                        )
+                    }
                 };
 
                 SignatureValues::QueryParams(QueryParamValues {
@@ -255,12 +275,9 @@ impl<'a> CanonicalRequest<'a> {
             .unwrap_or(header::X_AMZ_SECURITY_TOKEN);
 
         if params.settings().signature_location == SignatureLocation::Headers {
-            let creds = params
-                .credentials()
-                .map_err(|_| CanonicalRequestError::unsupported_identity_type())?;
             Self::insert_date_header(&mut canonical_headers, date_time);
 
-            if let Some(security_token) = creds.session_token() {
+            if let Some(security_token) = Self::session_token(params)? {
                 let mut sec_header = HeaderValue::from_str(security_token)?;
                 sec_header.set_sensitive(true);
                 canonical_headers.insert(token_header_name, sec_header);
@@ -276,6 +293,21 @@ impl<'a> CanonicalRequest<'a> {
                 let header = HeaderValue::from_str(region_set)?;
                 canonical_headers.insert(sigv4a::header::X_AMZ_REGION_SET, header);
             }
+
+            #[cfg(feature = "x509")]
+            if let SigningParams::X509(params) = params {
+                let header = HeaderValue::from_str(&params.certificate.to_base64())?;
+                canonical_headers.insert(header::X_AMZ_X509, header);
+                if !params.certificate_chain.is_empty() {
+                    let chain: Vec<_> = params
+                        .certificate_chain
+                        .iter()
+                        .map(|certificate| certificate.to_base64())
+                        .collect();
+                    let header = HeaderValue::from_str(&chain.join(","))?;
+                    canonical_headers.insert(header::X_AMZ_X509_CHAIN, header);
+                }
+            }
         }
 
         let mut signed_headers = Vec::with_capacity(canonical_headers.len());
@@ -304,6 +336,22 @@ impl<'a> CanonicalRequest<'a> {
         Ok((signed_headers, canonical_headers))
     }
 
+    /// Returns the session token of the credentials that the request is signed with.
+    ///
+    /// Requests that are signed with an X.509 certificate don't have a session token.
+    fn session_token<'b>(
+        params: &'b SigningParams<'b>,
+    ) -> Result<Option<&'b str>, CanonicalRequestError> {
+        #[cfg(feature = "x509")]
+        if let SigningParams::X509(_) = params {
+            return Ok(None);
+        }
+        let creds = params
+            .credentials()
+            .map_err(|_| CanonicalRequestError::unsupported_identity_type())?;
+        Ok(creds.session_token())
+    }
+
     fn payload_hash<'b>(body: &'b SignableBody<'b>) -> Cow<'b, str> {
         // Payload hash computation
         //
@@ -606,6 +654,30 @@ impl<'a> StringToSign<'a> {
             signature_version: SignatureVersion::V4a,
         }
     }
+
+    #[cfg(feature = "x509")]
+    pub(crate) fn new_x509(
+        algorithm: &'static str,
+        time: SystemTime,
+        region: &'a str,
+        service: &'a str,
+        hashed_creq: &'a str,
+    ) -> Self {
+        let scope = SigningScope {
+            time,
+            region,
+            service,
+        };
+        Self {
+            algorithm,
+            scope,
+            time,
+            region,
+            service,
+            hashed_creq,
+            signature_version: SignatureVersion::X509,
+        }
+    }
 }
 
 impl<'a> fmt::Display for StringToSign<'a> {
@@ -616,7 +688,7 @@ impl<'a> fmt::Display for StringToSign<'a> {
             self.algorithm,
             format_date_time(self.time),
             match self.signature_version {
-                SignatureVersion::V4 => self.scope.to_string(),
+                SignatureVersion::V4 | SignatureVersion::X509 => self.scope.to_string(),
                 SignatureVersion::V4a => self.scope.v4a_display(),
             },
             self.hashed_creq
@@ -10,8 +10,15 @@ use std::fmt;
 
 #[derive(Debug)]
 enum SigningErrorKind {
-    FailedToCreateCanonicalRequest { source: CanonicalRequestError },
+    FailedToCreateCanonicalRequest {
+        source: CanonicalRequestError,
+    },
     UnsupportedIdentityType,
+    UnsupportedSignatureLocation,
+    #[cfg(feature = "x509")]
+    FailedToSignWithCertificate {
+        source: crate::sign::x509::X509Error,
+    },
 }
 
 /// Error signing request
@@ -26,6 +33,13 @@ impl SigningError {
             kind: SigningErrorKind::UnsupportedIdentityType,
         }
     }
+
+    #[allow(dead_code)] // Unused when compiling without certain features
+    pub(crate) fn unsupported_signature_location() -> Self {
+        Self {
+            kind: SigningErrorKind::UnsupportedSignatureLocation,
+        }
+    }
 }
 
 impl fmt::Display for SigningError {
@@ -37,6 +51,16 @@ impl fmt::Display for SigningError {
             SigningErrorKind::UnsupportedIdentityType => {
                 write!(f, "only 'AWS credentials' are supported for signing")
             }
+            SigningErrorKind::UnsupportedSignatureLocation => {
+                write!(
+                    f,
+                    "the signature location isn't supported by this signing algorithm"
+                )
+            }
+            #[cfg(feature = "x509")]
+            SigningErrorKind::FailedToSignWithCertificate { .. } => {
+                write!(f, "failed to sign with the X.509 certificate's private key")
+            }
         }
     }
 }
@@ -45,7 +69,10 @@ impl Error for SigningError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match &self.kind {
             SigningErrorKind::FailedToCreateCanonicalRequest { source } => Some(source),
-            SigningErrorKind::UnsupportedIdentityType => None,
+            SigningErrorKind::UnsupportedIdentityType
+            | SigningErrorKind::UnsupportedSignatureLocation => None,
+            #[cfg(feature = "x509")]
+            SigningErrorKind::FailedToSignWithCertificate { source } => Some(source),
         }
     }
 }
@@ -58,6 +85,15 @@ impl From<CanonicalRequestError> for SigningError {
     }
 }
 
+#[cfg(feature = "x509")]
+impl From<crate::sign::x509::X509Error> for SigningError {
+    fn from(source: crate::sign::x509::X509Error) -> Self {
+        Self {
+            kind: SigningErrorKind::FailedToSignWithCertificate { source },
+        }
+    }
+}
+
 #[derive(Debug)]
 enum CanonicalRequestErrorKind {
     InvalidHeaderName { source: InvalidHeaderName },
@@ -13,6 +13,8 @@ use crate::http_request::SigningParams;
 use crate::sign::v4;
 #[cfg(feature = "sigv4a")]
 use crate::sign::v4a;
+#[cfg(feature = "x509")]
+use crate::sign::x509;
 use crate::{SignatureVersion, SigningOutput};
 use http0::Uri;
 use std::borrow::Cow;
@@ -243,6 +245,8 @@ impl SigningInstructions {
 
 /// Produces a signature for the given `request` and returns instructions
 /// that can be used to apply that signature to an HTTP request.
+///
+/// Requests that are signed with an X.509 certificate can only be signed with headers.
 pub fn sign<'a>(
     request: SignableRequest<'a>,
     params: &'a SigningParams<'a>,
@@ -273,6 +277,10 @@ fn calculate_signing_params<'a>(
     request: &'a SignableRequest<'a>,
     params: &'a SigningParams<'a>,
 ) -> Result<(CalculatedParams, String), SigningError> {
+    #[cfg(feature = "x509")]
+    if let SigningParams::X509(_) = params {
+        return Err(SigningError::unsupported_signature_location());
+    }
     let creds = params.credentials()?;
     let creq = CanonicalRequest::from(request, params)?;
     let encoded_creq = &v4::sha256_hex_string(creq.to_string().as_bytes());
@@ -302,6 +310,8 @@ fn calculate_signing_params<'a>(
             let signature = v4a::calculate_signature(&secret_key, string_to_sign.as_bytes());
             (signature, string_to_sign)
         }
+        #[cfg(feature = "x509")]
+        SigningParams::X509(_) => unreachable!("presigning with X.509 certificates was rejected"),
     };
     tracing::trace!(canonical_request = %creq, string_to_sign = %string_to_sign, "calculated signing parameters");
 
@@ -351,7 +361,7 @@ fn calculate_signing_headers<'a>(
     request: &'a SignableRequest<'a>,
     params: &'a SigningParams<'a>,
 ) -> Result<SigningOutput<Vec<Header>>, SigningError> {
-    let creds = params.credentials()?;
+    let creds = params.credentials();
 
     // Step 1: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-create-canonical-request.html.
     let creq = CanonicalRequest::from(request, params)?;
@@ -362,6 +372,7 @@ fn calculate_signing_headers<'a>(
 
     let signature = match params {
         SigningParams::V4(params) => {
+            let creds = creds?;
             let sts = StringToSign::new_v4(
                 params.time,
                 params.region,
@@ -416,6 +427,7 @@ fn calculate_signing_headers<'a>(
         }
         #[cfg(feature = "sigv4a")]
         SigningParams::V4a(params) => {
+            let creds = creds?;
             let sts = StringToSign::new_v4a(
                 params.time,
                 params.region_set,
@@ -466,6 +478,50 @@ fn calculate_signing_headers<'a>(
             }
             signature
         }
+        #[cfg(feature = "x509")]
+        SigningParams::X509(params) => {
+            let sts = StringToSign::new_x509(
+                params.algorithm(),
+                params.time,
+                params.region,
+                params.name,
+                encoded_creq.as_str(),
+            );
+
+            let signature =
+                x509::calculate_signature(params.private_key, sts.to_string().as_bytes())?;
+
+            let values = creq.values.as_headers().expect("signing with headers");
+            add_header(&mut headers, header::X_AMZ_DATE, &values.date_time, false);
+            // The certificate and its chain were added to the canonical request
+            for name in [header::X_AMZ_X509, header::X_AMZ_X509_CHAIN] {
+                if let Some(value) = creq.headers.get(name) {
+                    let value = value.to_str().expect("base64 is a valid header value");
+                    add_header(&mut headers, name, value, false);
+                }
+            }
+
+            headers.push(Header {
+                key: "authorization",
+                value: build_authorization_header(
+                    params.certificate.serial_number(),
+                    &creq,
+                    sts,
+                    &signature,
+                    SignatureVersion::X509,
+                ),
+                sensitive: false,
+            });
+            if params.settings.payload_checksum_kind == PayloadChecksumKind::XAmzSha256 {
+                add_header(
+                    &mut headers,
+                    header::X_AMZ_CONTENT_SHA_256,
+                    &values.content_sha256,
+                    false,
+                );
+            }
+            signature
+        }
     };
 
     Ok(SigningOutput::new(headers, signature))
@@ -489,7 +545,7 @@ fn build_authorization_header(
     signature_version: SignatureVersion,
 ) -> String {
     let scope = match signature_version {
-        SignatureVersion::V4 => sts.scope.to_string(),
+        SignatureVersion::V4 | SignatureVersion::X509 => sts.scope.to_string(),
         SignatureVersion::V4a => sts.scope.v4a_display(),
     };
     format!(
@@ -1165,4 +1221,141 @@ mod tests {
         let sut = SignableBody::StreamingUnsignedPayloadTrailer;
         assert_eq!("StreamingUnsignedPayloadTrailer", format!("{sut:?}"));
     }
+
+    #[cfg(feature = "x509")]
+    mod x509_tests {
+        use super::*;
+        use crate::http_request::SignatureLocation;
+        use crate::sign::x509::{self, Certificate, PrivateKey};
+        use pretty_assertions::assert_eq;
+
+        const RSA_CERTIFICATE: &str = include_str!("../../test-data/x509/rsa.crt");
+        const RSA_PRIVATE_KEY: &str = include_str!("../../test-data/x509/rsa.key");
+        const EC_CERTIFICATE: &str = include_str!("../../test-data/x509/ec.crt");
+        const EC_PRIVATE_KEY: &str = include_str!("../../test-data/x509/ec.key");
+
+        fn create_session_request() -> Request<&'static str> {
+            Request::builder()
+                .method("POST")
+                .uri("https://rolesanywhere.us-east-1.amazonaws.com/sessions")
+                .header("content-type", "application/json")
+                .body(r#"{"durationSeconds":3600}"#)
+                .unwrap()
+        }
+
+        fn sign_request(
+            certificate: &Certificate,
+            certificate_chain: &[Certificate],
+            private_key: &PrivateKey,
+            settings: SigningSettings,
+        ) -> Result<(Request<&'static str>, String), crate::http_request::SigningError> {
+            let params = x509::SigningParams::builder()
+                .certificate(certificate)
+                .certificate_chain(certificate_chain)
+                .private_key(private_key)
+                .region("us-east-1")
+                .name("rolesanywhere")
+                .time(parse_date_time("20240102T030405Z").unwrap())
+                .settings(settings)
+                .build()
+                .unwrap()
+                .into();
+            let request = create_session_request();
+            let signable = SignableRequest::new(
+                request.method().as_str(),
+                request.uri().to_string(),
+                request
+                    .headers()
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), std::str::from_utf8(v.as_bytes()).unwrap())),
+                SignableBody::Bytes(request.body().as_bytes()),
+            )?;
+            let (instructions, signature) = sign(signable, &params)?.into_parts();
+            let mut signed = create_session_request();
+            instructions.apply_to_request_http0x(&mut signed);
+            Ok((signed, signature))
+        }
+
+        #[test]
+        fn sign_with_rsa_certificate() {
+            let certificate = Certificate::from_pem(RSA_CERTIFICATE).unwrap();
+            let private_key = PrivateKey::from_pem(RSA_PRIVATE_KEY).unwrap();
+            let (signed, signature) =
+                sign_request(&certificate, &[], &private_key, SigningSettings::default()).unwrap();
+
+            assert_eq!("20240102T030405Z", signed.headers()["x-amz-date"]);
+            assert_eq!(
+                certificate.to_base64(),
+                signed.headers()["x-amz-x509"].to_str().unwrap()
+            );
+            assert!(signed.headers().get("x-amz-x509-chain").is_none());
+            assert!(signed.headers().get("x-amz-security-token").is_none());
+            assert_eq!(
+                format!(
+                    "AWS4-X509-RSA-SHA256 \
+                     Credential=5373003642731685151011/20240102/us-east-1/rolesanywhere/aws4_request, \
+                     SignedHeaders=content-type;host;x-amz-date;x-amz-x509, \
+                     Signature={signature}"
+                ),
+                signed.headers()["authorization"].to_str().unwrap()
+            );
+            // RSA PKCS#1 v1.5 signatures are deterministic
+            assert_eq!(
+                "56727436e68b4560292e36bde05b18dcb0bbf2892205c3711939c8836fdb323a\
+                 83f530a05791029b183ee0f55393c2a9539e379d8d35b73c40450bf6b9e691fe\
+                 c3632f806d0159eaad82b297cc31986e0d9dd1ea1d4a639c9ac097d915c1de91\
+                 cb4793362e93232d6b90058a2e051c562dfd30b530543014655acbf4f728a8e0\
+                 aa885a397496334c869aafe7ea4501a464cfa1947376f3ddb4c66ca5e972c642\
+                 cad531e6910538d3a2b84efd40fa0b5299904d2d500a4d116e25206f2d87e115\
+                 b505f7f36d5a9907d5289f5b74f0d8812f0cac3a5c34f6701181bf1d4fb1d1c1\
+                 0f7fb877f23f462e4632f1c921695c92d7799497115732f63aaf5840c1e65e92",
+                signature
+            );
+        }
+
+        #[test]
+        fn sign_with_ecdsa_certificate_and_chain() {
+            let certificate = Certificate::from_pem(EC_CERTIFICATE).unwrap();
+            let chain = Certificate::chain_from_pem(RSA_CERTIFICATE).unwrap();
+            let private_key = PrivateKey::from_pem(EC_PRIVATE_KEY).unwrap();
+            let (signed, _) = sign_request(
+                &certificate,
+                &chain,
+                &private_key,
+                SigningSettings::default(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                chain[0].to_base64(),
+                signed.headers()["x-amz-x509-chain"].to_str().unwrap()
+            );
+            let authorization = signed.headers()["authorization"].to_str().unwrap();
+            assert!(
+                authorization.starts_with(
+                    "AWS4-X509-ECDSA-SHA256 \
+                     Credential=1234567/20240102/us-east-1/rolesanywhere/aws4_request, \
+                     SignedHeaders=content-type;host;x-amz-date;x-amz-x509;x-amz-x509-chain, \
+                     Signature="
+                ),
+                "{authorization}"
+            );
+        }
+
+        #[test]
+        fn presigning_is_unsupported() {
+            let certificate = Certificate::from_pem(RSA_CERTIFICATE).unwrap();
+            let private_key = PrivateKey::from_pem(RSA_PRIVATE_KEY).unwrap();
+            let settings = SigningSettings {
+                signature_location: SignatureLocation::QueryParams,
+                expires_in: Some(Duration::from_secs(30)),
+                ..Default::default()
+            };
+            let err = sign_request(&certificate, &[], &private_key, settings).unwrap_err();
+            assert_eq!(
+                "the signature location isn't supported by this signing algorithm",
+                err.to_string()
+            );
+        }
+    }
 }
@@ -38,6 +38,8 @@ pub enum SignatureVersion {
     V4,
     /// The SigV4a signing algorithm.
     V4a,
+    /// Signing with an X.509 certificate, as used by IAM Roles Anywhere.
+    X509,
 }
 
 impl fmt::Display for SignatureVersion {
@@ -45,6 +47,7 @@ impl fmt::Display for SignatureVersion {
         match self {
             SignatureVersion::V4 => write!(f, "SigV4"),
             SignatureVersion::V4a => write!(f, "SigV4a"),
+            SignatureVersion::X509 => write!(f, "X.509"),
         }
     }
 }
@@ -32,3 +32,6 @@ pub mod v4;
 /// Support for Sigv4a signing
 #[cfg(feature = "sigv4a")]
 pub mod v4a;
+
+#[cfg(feature = "x509")]
+pub mod x509;
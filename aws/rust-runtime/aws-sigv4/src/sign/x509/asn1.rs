@@ -0,0 +1,248 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Just enough BER, DER and PEM decoding to pick apart X.509 certificates and PKCS#7 signed data.
+//!
+//! Only single-byte tags and lengths of up to four bytes are supported. Indefinite lengths, which
+//! BER allows and DER doesn't, are accepted. This is shared with other AWS runtime crates and isn't
+//! part of the public API.
+
+use aws_smithy_types::base64;
+
+/// The tag of an `INTEGER`
+pub const INTEGER: u8 = 0x02;
+/// The tag of a `BIT STRING`
+pub const BIT_STRING: u8 = 0x03;
+/// The tag of a primitive `OCTET STRING`
+pub const OCTET_STRING: u8 = 0x04;
+/// The tag of an `OBJECT IDENTIFIER`
+pub const OID: u8 = 0x06;
+/// The tag of a constructed `OCTET STRING`, whose contents are octet strings to be joined
+pub const CONSTRUCTED_OCTET_STRING: u8 = 0x24;
+/// The tag of a `SEQUENCE`
+pub const SEQUENCE: u8 = 0x30;
+/// The tag of a `SET`
+pub const SET: u8 = 0x31;
+/// The tag of a constructed, context-specific field numbered 0
+pub const CONTEXT_0: u8 = 0xa0;
+/// The tag of a constructed, context-specific field numbered 1
+pub const CONTEXT_1: u8 = 0xa1;
+
+/// The error returned for input that can't be decoded
+pub const MALFORMED: &str = "malformed ASN.1 structure";
+
+/// How deeply indefinite-length elements may be nested
+///
+/// Certificates and signatures are nowhere near this deep, and the limit keeps malformed input from
+/// exhausting the stack.
+pub const MAX_DEPTH: usize = 32;
+
+/// An encoded element
+#[derive(Clone, Copy, Debug)]
+pub struct Element<'a> {
+    /// The tag of the element
+    pub tag: u8,
+    /// The contents of the element, without its tag and length
+    pub contents: &'a [u8],
+    /// The complete encoding of the element, including its tag and length
+    pub encoded: &'a [u8],
+}
+
+/// Consecutive encoded elements, such as the contents of a `SEQUENCE`
+#[derive(Debug)]
+pub struct Elements<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Elements<'a> {
+    /// Reads the elements encoded in `input`
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input }
+    }
+
+    /// Returns the next element, or `None` if there are no elements left
+    pub fn next_optional(&mut self) -> Result<Option<Element<'a>>, &'static str> {
+        if self.input.is_empty() {
+            return Ok(None);
+        }
+        let (element, rest) = read_element(self.input).ok_or(MALFORMED)?;
+        self.input = rest;
+        Ok(Some(element))
+    }
+
+    /// Returns the next element
+    pub fn next_element(&mut self) -> Result<Element<'a>, &'static str> {
+        self.next_optional()?.ok_or(MALFORMED)
+    }
+
+    /// Returns the next element, which must have the given tag
+    pub fn expect(&mut self, tag: u8) -> Result<Element<'a>, &'static str> {
+        match self.next_element()? {
+            element if element.tag == tag => Ok(element),
+            _ => Err(MALFORMED),
+        }
+    }
+
+    /// Returns the next element if it has the given tag
+    pub fn optional(&mut self, tag: u8) -> Result<Option<Element<'a>>, &'static str> {
+        if self.input.first() == Some(&tag) {
+            self.next_element().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Reads one element, returning it and the remaining input
+pub fn read_element(input: &[u8]) -> Option<(Element<'_>, &[u8])> {
+    read_nested_element(input, 0)
+}
+
+/// Reads one element nested `depth` indefinite-length elements deep
+fn read_nested_element(input: &[u8], depth: usize) -> Option<(Element<'_>, &[u8])> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let (&tag, after_tag) = input.split_first()?;
+    if tag & 0x1f == 0x1f {
+        return None;
+    }
+    let (&length, after_length) = after_tag.split_first()?;
+    let (contents, rest) = if length == 0x80 {
+        // Indefinite length: the contents are terminated by two zero bytes
+        if tag & 0x20 == 0 {
+            return None;
+        }
+        let mut remaining = after_length;
+        while !remaining.starts_with(&[0, 0]) {
+            remaining = read_nested_element(remaining, depth + 1)?.1;
+        }
+        let contents = &after_length[..after_length.len() - remaining.len()];
+        (contents, &remaining[2..])
+    } else if length & 0x80 == 0 {
+        let length = length as usize;
+        (after_length.get(..length)?, after_length.get(length..)?)
+    } else {
+        let length_bytes = (length & 0x7f) as usize;
+        if length_bytes > 4 {
+            return None;
+        }
+        let length = after_length
+            .get(..length_bytes)?
+            .iter()
+            .fold(0usize, |length, byte| length << 8 | *byte as usize);
+        let after_length = &after_length[length_bytes..];
+        (after_length.get(..length)?, after_length.get(length..)?)
+    };
+    let encoded = &input[..input.len() - rest.len()];
+    Some((
+        Element {
+            tag,
+            contents,
+            encoded,
+        },
+        rest,
+    ))
+}
+
+/// Returns the labels and decoded contents of the PEM blocks in `pem`
+pub fn pem_blocks(pem: &[u8]) -> Result<Vec<(String, Vec<u8>)>, &'static str> {
+    let pem = std::str::from_utf8(pem).map_err(|_| "not valid UTF-8")?;
+    let mut blocks = Vec::new();
+    let mut current: Option<(&str, String)> = None;
+    for line in pem.lines().map(str::trim) {
+        match current.take() {
+            None => {
+                if let Some(label) = line
+                    .strip_prefix("-----BEGIN ")
+                    .and_then(|rest| rest.strip_suffix("-----"))
+                {
+                    current = Some((label, String::new()));
+                }
+            }
+            Some((label, mut contents)) => {
+                if let Some(end) = line
+                    .strip_prefix("-----END ")
+                    .and_then(|rest| rest.strip_suffix("-----"))
+                {
+                    if end != label {
+                        return Err("mismatched BEGIN and END lines");
+                    }
+                    let der = base64::decode(&contents).map_err(|_| "invalid base64")?;
+                    blocks.push((label.to_string(), der));
+                } else {
+                    contents.push_str(line);
+                    current = Some((label, contents));
+                }
+            }
+        }
+    }
+    if current.is_some() {
+        return Err("missing END line");
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pem_blocks, read_element, Elements, INTEGER, MAX_DEPTH, SEQUENCE};
+
+    #[test]
+    fn definite_and_indefinite_lengths() {
+        // SEQUENCE { INTEGER 5 } with a short, a long and an indefinite length
+        for input in [
+            &[0x30, 0x03, 0x02, 0x01, 0x05][..],
+            &[0x30, 0x81, 0x03, 0x02, 0x01, 0x05],
+            &[0x30, 0x80, 0x02, 0x01, 0x05, 0x00, 0x00],
+        ] {
+            let mut elements = Elements::new(input);
+            let sequence = elements.expect(SEQUENCE).unwrap();
+            assert_eq!(input, sequence.encoded);
+            let mut contents = Elements::new(sequence.contents);
+            assert_eq!(&[5], contents.expect(INTEGER).unwrap().contents);
+            assert!(contents.next_optional().unwrap().is_none());
+            assert!(elements.next_optional().unwrap().is_none());
+        }
+
+        // Truncated contents, an indefinite length on a primitive type, and a multi-byte tag
+        for input in [
+            &[0x30, 0x04, 0x02, 0x01, 0x05][..],
+            &[0x02, 0x80],
+            &[0x1f, 0x01, 0x00],
+        ] {
+            assert!(read_element(input).is_none());
+        }
+    }
+
+    #[test]
+    fn deeply_nested_indefinite_lengths_are_rejected() {
+        let nested = |depth: usize| {
+            let mut input = [0x30, 0x80].repeat(depth);
+            input.extend(vec![0; depth * 2]);
+            input
+        };
+        assert!(read_element(&nested(MAX_DEPTH + 1)).is_some());
+        assert!(read_element(&nested(MAX_DEPTH + 2)).is_none());
+        // Deep enough to overflow the stack without a limit
+        assert!(read_element(&nested(1_000_000)).is_none());
+    }
+
+    #[test]
+    fn pem() {
+        let pem = "junk\n-----BEGIN A-----\naGVs\nbG8=\n-----END A-----\n-----BEGIN B-----\n-----END B-----";
+        assert_eq!(
+            vec![
+                ("A".to_string(), b"hello".to_vec()),
+                ("B".to_string(), vec![])
+            ],
+            pem_blocks(pem.as_bytes()).unwrap()
+        );
+        assert_eq!(
+            Err("mismatched BEGIN and END lines"),
+            pem_blocks(b"-----BEGIN A-----\n-----END B-----")
+        );
+        assert_eq!(Err("missing END line"), pem_blocks(b"-----BEGIN A-----\n"));
+    }
+}
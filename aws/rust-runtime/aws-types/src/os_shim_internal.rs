@@ -184,6 +184,30 @@ impl Fs {
         }
         Ok(())
     }
+
+    /// Creates a directory and all of its missing parents.
+    ///
+    /// This is a no-op for in-memory file systems, since they don't have directories.
+    pub async fn create_dir_all(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        use fs::Inner;
+        match &self.0 {
+            // TODO(https://github.com/awslabs/aws-sdk-rust/issues/867): Use async IO below
+            Inner::Real => std::fs::create_dir_all(path),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(_) => Ok(()),
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => {
+                    let actual_path = path
+                        .as_ref()
+                        .strip_prefix(namespaced_to)
+                        .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+                    std::fs::create_dir_all(real_path.join(actual_path))
+                }
+            },
+        }
+    }
 }
 
 mod fs {
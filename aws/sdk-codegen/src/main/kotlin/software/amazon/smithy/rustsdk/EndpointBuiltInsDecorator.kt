@@ -212,6 +212,37 @@ fun decoratorForBuiltIn(
     }
 }
 
+/**
+ * Resolves the `AWS::Auth::AccountId` builtIn from the account ID attached to the resolved credentials when it
+ * has not been set on the service config
+ */
+class AccountIdBuiltInDecorator : ClientCodegenDecorator {
+    override val name: String = "AccountIdBuiltIn"
+    override val order: Byte = 0
+
+    override fun endpointCustomizations(codegenContext: ClientCodegenContext): List<EndpointCustomization> {
+        val accountId = codegenContext.getBuiltIn(AwsBuiltIns.ACCOUNT_ID) ?: return listOf()
+        return listOf(
+            object : EndpointCustomization {
+                override fun finalizeParams(codegenContext: ClientCodegenContext): Writable =
+                    writable {
+                        val field = accountId.name.rustName()
+                        rustTemplate(
+                            """
+                            if params.$field.is_none() {
+                                params.$field = identity.property::<#{AccountId}>().map(|id| id.as_str().to_string());
+                            }
+                            """,
+                            "AccountId" to
+                                AwsRuntimeType.awsCredentialTypes(codegenContext.runtimeConfig)
+                                    .resolve("attributes::AccountId"),
+                        )
+                    }
+            },
+        )
+    }
+}
+
 private val endpointUrlDocs =
     writable {
         rust(
@@ -249,4 +280,5 @@ val PromotedBuiltInsDecorators =
         ),
         decoratorForBuiltIn(AwsBuiltIns.ACCOUNT_ID_ENDPOINT_MODE, null, false),
         decoratorForBuiltIn(AwsBuiltIns.ACCOUNT_ID, null, false),
+        AccountIdBuiltInDecorator(),
     ).toTypedArray()
@@ -97,6 +97,63 @@ class EndpointBuiltInsDecoratorTest {
         }
         """.asSmithyModel()
 
+    private val accountIdModel =
+        """
+        namespace test
+
+        use aws.api#service
+        use aws.auth#sigv4
+        use aws.protocols#restJson1
+        use smithy.rules#endpointRuleSet
+
+        @service(sdkId: "dontcare")
+        @restJson1
+        @sigv4(name: "dontcare")
+        @auth([sigv4])
+        @suppress(["RuleSetAwsBuiltIn.AWS::Auth::AccountId"])
+        @endpointRuleSet({
+            "version": "1.0"
+            "parameters": {
+                "region": { "required": false, "type": "String", "builtIn": "AWS::Region" },
+                "accountId": { "required": false, "type": "String", "builtIn": "AWS::Auth::AccountId" },
+            }
+            "rules": [
+                {
+                    "type": "endpoint"
+                    "conditions": [
+                        {"fn": "isSet", "argv": [{"ref": "region"}]},
+                        {"fn": "isSet", "argv": [{"ref": "accountId"}]}
+                    ],
+                    "endpoint": {
+                        "url": "https://{accountId}.example.com"
+                        "properties": {
+                            "authSchemes": [{"name": "sigv4","signingRegion": "{region}", "signingName": "dontcare"}]
+                        }
+                    }
+                },
+                {
+                    "type": "endpoint"
+                    "conditions": [
+                        {"fn": "isSet", "argv": [{"ref": "region"}]},
+                    ],
+                    "endpoint": {
+                        "url": "https://WRONG/"
+                        "properties": {
+                            "authSchemes": [{"name": "sigv4", "signingRegion": "{region}", "signingName": "dontcare"}]
+                        }
+                    }
+                }
+            ]
+        })
+        service TestService {
+            version: "2023-01-01",
+            operations: [SomeOperation]
+        }
+
+        @http(uri: "/SomeOperation", method: "GET")
+        operation SomeOperation {}
+        """.asSmithyModel()
+
     @Test
     fun endpointUrlBuiltInWorksEndToEnd() {
         awsSdkIntegrationTest(endpointUrlModel) { codegenContext, rustCrate ->
@@ -139,4 +196,119 @@ class EndpointBuiltInsDecoratorTest {
             }
         }
     }
+
+    @Test
+    fun accountIdBuiltInIsResolvedFromCredentials() {
+        awsSdkIntegrationTest(accountIdModel) { codegenContext, rustCrate ->
+            rustCrate.integrationTest("account_id_built_in") {
+                val module = codegenContext.moduleUseName()
+                rustTemplate(
+                    """
+                    use $module::config::{Credentials, Region, SharedCredentialsProvider};
+                    use $module::{Client, Config};
+
+                    fn credentials(account_id: Option<&str>) -> SharedCredentialsProvider {
+                        SharedCredentialsProvider::new(credentials_for(account_id))
+                    }
+
+                    fn credentials_for(account_id: Option<&str>) -> Credentials {
+                        let mut builder = Credentials::builder()
+                            .access_key_id("ANOTREAL")
+                            .secret_access_key("notrealrnrELgWzOk3IfjzDKtFBhDby")
+                            .provider_name("test");
+                        builder.set_account_id(account_id.map(Into::into));
+                        builder.build()
+                    }
+
+                    ##[#{tokio}::test]
+                    async fn account_id_from_credentials() {
+                        let (http_client, rx) = #{capture_request}(None);
+                        let config = Config::builder()
+                            .http_client(http_client)
+                            .region(Region::new("us-east-1"))
+                            .credentials_provider(credentials(Some("123456789012")))
+                            .build();
+                        let client = Client::from_conf(config);
+                        let _ = dbg!(client.some_operation().send().await);
+                        let request = rx.expect_request();
+                        assert_eq!("https://123456789012.example.com/SomeOperation", request.uri());
+                    }
+
+                    ##[#{tokio}::test]
+                    async fn account_id_on_config_takes_precedence() {
+                        let (http_client, rx) = #{capture_request}(None);
+                        let config = Config::builder()
+                            .http_client(http_client)
+                            .region(Region::new("us-east-1"))
+                            .account_id("210987654321")
+                            .credentials_provider(credentials(Some("123456789012")))
+                            .build();
+                        let client = Client::from_conf(config);
+                        let _ = dbg!(client.some_operation().send().await);
+                        let request = rx.expect_request();
+                        assert_eq!("https://210987654321.example.com/SomeOperation", request.uri());
+                    }
+
+                    ##[#{tokio}::test]
+                    async fn credentials_without_account_id() {
+                        let (http_client, rx) = #{capture_request}(None);
+                        let config = Config::builder()
+                            .http_client(http_client)
+                            .region(Region::new("us-east-1"))
+                            .credentials_provider(credentials(None))
+                            .build();
+                        let client = Client::from_conf(config);
+                        let _ = dbg!(client.some_operation().send().await);
+                        let request = rx.expect_request();
+                        assert_eq!("https://WRONG/SomeOperation", request.uri());
+                    }
+
+                    ##[#{tokio}::test]
+                    async fn account_id_is_resolved_again_on_retry() {
+                        fn event(uri: &str, status: u16) -> #{ReplayEvent} {
+                            #{ReplayEvent}::new(
+                                http::Request::builder().uri(uri).body(#{SdkBody}::empty()).unwrap(),
+                                http::Response::builder().status(status).body(#{SdkBody}::empty()).unwrap(),
+                            )
+                        }
+                        let http_client = #{StaticReplayClient}::new(vec![
+                            event("https://111111111111.example.com/SomeOperation", 500),
+                            event("https://222222222222.example.com/SomeOperation", 200),
+                        ]);
+                        // Each attempt loads new credentials with a different account ID
+                        let account_ids = std::sync::Mutex::new(vec!["222222222222", "111111111111"]);
+                        let config = Config::builder()
+                            .http_client(http_client.clone())
+                            .region(Region::new("us-east-1"))
+                            .retry_config(#{RetryConfig}::standard())
+                            .identity_cache(#{IdentityCache}::no_cache())
+                            .credentials_provider(#{provide_credentials_fn}(move || {
+                                let account_id = account_ids.lock().unwrap().pop();
+                                async move { Ok(credentials_for(account_id)) }
+                            }))
+                            .build();
+                        let client = Client::from_conf(config);
+                        client.some_operation().send().await.expect("success");
+                        http_client.assert_requests_match(&[]);
+                    }
+                    """,
+                    "tokio" to CargoDependency.Tokio.toDevDependency().withFeature("rt").withFeature("macros").toType(),
+                    "capture_request" to RuntimeType.captureRequest(codegenContext.runtimeConfig),
+                    "StaticReplayClient" to
+                        CargoDependency.smithyRuntimeTestUtil(codegenContext.runtimeConfig).toType()
+                            .resolve("client::http::test_util::StaticReplayClient"),
+                    "ReplayEvent" to
+                        CargoDependency.smithyRuntimeTestUtil(codegenContext.runtimeConfig).toType()
+                            .resolve("client::http::test_util::ReplayEvent"),
+                    "SdkBody" to RuntimeType.sdkBody(codegenContext.runtimeConfig),
+                    "RetryConfig" to RuntimeType.smithyTypes(codegenContext.runtimeConfig).resolve("retry::RetryConfig"),
+                    "IdentityCache" to
+                        RuntimeType.smithyRuntime(codegenContext.runtimeConfig).resolve("client::identity::IdentityCache"),
+                    "provide_credentials_fn" to
+                        AwsRuntimeType.awsCredentialTypes(codegenContext.runtimeConfig)
+                            .resolve("credential_fn::provide_credentials_fn"),
+                )
+            }
+        }
+    }
 }
@@ -0,0 +1,47 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.util.isInputEventStream
+
+/**
+ * Reports the failures of an event stream operation's response to the `ChannelSender`s of its input,
+ * so that sending fails with the reason the stream failed.
+ */
+class EventStreamFailureGenerator(
+    private val codegenContext: ClientCodegenContext,
+    private val operationShape: OperationShape,
+) : OperationCustomization() {
+    private val runtimeConfig = codegenContext.runtimeConfig
+
+    override fun section(section: OperationSection): Writable {
+        if (!operationShape.isInputEventStream(codegenContext.model)) {
+            return emptySection
+        }
+        return when (section) {
+            is OperationSection.AdditionalInterceptors ->
+                writable {
+                    section.registerInterceptor(runtimeConfig, this) {
+                        rustTemplate(
+                            "#{EventStreamFailureInterceptor}::default()",
+                            "EventStreamFailureInterceptor" to
+                                CargoDependency.smithyRuntime(runtimeConfig).withFeature("event-stream").toType()
+                                    .resolve("client::event_stream::EventStreamFailureInterceptor"),
+                        )
+                    }
+                }
+            else -> emptySection
+        }
+    }
+}
@@ -9,6 +9,7 @@ import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.traits.RequestCompressionTrait
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
@@ -17,8 +18,10 @@ import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.util.getTrait
 import java.util.logging.Logger
 
-// Currently, gzip is the only supported encoding.
-fun isSupportedEncoding(encoding: String): Boolean = encoding == "gzip"
+// The supported encodings, and the `CompressionAlgorithm` variant for each of them.
+private val supportedEncodings = mapOf("gzip" to "Gzip", "zstd" to "Zstd", "br" to "Brotli")
+
+fun isSupportedEncoding(encoding: String): Boolean = supportedEncodings.containsKey(encoding)
 
 fun firstSupportedEncoding(encodings: List<String>): String? = encodings.firstOrNull { isSupportedEncoding(it) }
 
@@ -35,18 +38,22 @@ class RequestCompressionGenerator(
                 logger.warning { "No encodings were specified for the requestCompressionTrait on ${operationShape.id}" }
                 return emptySection
             }
+            // Encodings are listed in order of preference, so the first one that's supported is used.
             val encoding = firstSupportedEncoding(requestCompressionTrait.encodings) ?: return emptySection
-            // We can remove this once we start supporting other algorithms.
-            // Until then, we shouldn't see anything else coming up here.
-            assert(encoding == "gzip") { "Only gzip is supported but encoding was `$encoding`" }
             val runtimeConfig = codegenContext.runtimeConfig
             val compression = RuntimeType.clientRequestCompression(runtimeConfig)
+            val compressionAlgorithm =
+                CargoDependency.smithyCompression(runtimeConfig).toType().resolve("CompressionAlgorithm")
 
             return writable {
                 when (section) {
                     is OperationSection.AdditionalRuntimePlugins ->
                         section.addOperationRuntimePlugin(this) {
-                            rust("#T::new()", compression.resolve("RequestCompressionRuntimePlugin"))
+                            rust(
+                                "#T::new(#T::${supportedEncodings.getValue(encoding)})",
+                                compression.resolve("RequestCompressionRuntimePlugin"),
+                                compressionAlgorithm,
+                            )
                         }
 
                     else -> {}
@@ -0,0 +1,162 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.knowledge.TopDownIndex
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.util.hasStreamingMember
+import software.amazon.smithy.rust.codegen.core.util.outputShape
+
+/**
+ * Streaming outputs (blobs and event streams) are passed through as-is, since their
+ * `Content-Encoding` usually describes the stored data rather than the transfer
+ * (e.g. an S3 object uploaded with `Content-Encoding: gzip`).
+ */
+fun supportsResponseDecompression(
+    codegenContext: CodegenContext,
+    operationShape: OperationShape,
+): Boolean = !operationShape.outputShape(codegenContext.model).hasStreamingMember(codegenContext.model)
+
+/**
+ * Adds opt-in response decompression to every operation with a non-streaming output.
+ *
+ * Once enabled with `enable_response_decompression` on the service config, `Accept-Encoding` is
+ * negotiated automatically, and responses with a supported `Content-Encoding` are decompressed
+ * before they're deserialized.
+ */
+class ResponseDecompressionGenerator(
+    private val codegenContext: CodegenContext,
+    private val operationShape: OperationShape,
+) : OperationCustomization() {
+    override fun section(section: OperationSection): Writable {
+        if (!supportsResponseDecompression(codegenContext, operationShape)) {
+            return emptySection
+        }
+        val decompression = RuntimeType.clientResponseDecompression(codegenContext.runtimeConfig)
+
+        return writable {
+            when (section) {
+                is OperationSection.AdditionalRuntimePlugins ->
+                    section.addOperationRuntimePlugin(this) {
+                        rust("#T::new()", decompression.resolve("ResponseDecompressionRuntimePlugin"))
+                    }
+
+                else -> {}
+            }
+        }
+    }
+}
+
+class ResponseDecompressionConfigCustomization(private val codegenContext: ClientCodegenContext) :
+    ConfigCustomization() {
+    private val codegenScope =
+        arrayOf(
+            "EnableResponseDecompression" to
+                RuntimeType.clientResponseDecompression(codegenContext.runtimeConfig)
+                    .resolve("EnableResponseDecompression"),
+            "ResponseMaxDecompressedSizeBytes" to
+                RuntimeType.clientResponseDecompression(codegenContext.runtimeConfig)
+                    .resolve("ResponseMaxDecompressedSizeBytes"),
+            *preludeScope,
+        )
+
+    private fun usesResponseDecompression(): Boolean {
+        val index = TopDownIndex.of(codegenContext.model)
+        return index.getContainedOperations(codegenContext.serviceShape.id)
+            .any { supportsResponseDecompression(codegenContext, it) }
+    }
+
+    override fun section(section: ServiceConfig) =
+        writable {
+            if (!usesResponseDecompression()) {
+                return@writable
+            }
+            when (section) {
+                ServiceConfig.ConfigImpl -> {
+                    rustTemplate(
+                        """
+                        /// Returns the `enable response decompression` setting, if it was provided.
+                        pub fn enable_response_decompression(&self) -> #{Option}<bool> {
+                            self.config.load::<#{EnableResponseDecompression}>().map(|it| it.0)
+                        }
+
+                        /// Returns the `response max decompressed size bytes` setting, if it was provided.
+                        pub fn response_max_decompressed_size_bytes(&self) -> #{Option}<u64> {
+                            self.config.load::<#{ResponseMaxDecompressedSizeBytes}>().map(|it| it.0)
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                ServiceConfig.BuilderImpl -> {
+                    rustTemplate(
+                        """
+                        /// Sets whether compressed responses are requested and transparently decompressed.
+                        ///
+                        /// Response decompression is disabled by default. When it's enabled, zstd, brotli and
+                        /// gzip encoded responses are requested with `Accept-Encoding`, unless that header was
+                        /// already set, and decompressed before they're deserialized.
+                        ///
+                        /// This can also be set for a single operation with `customize().config_override(...)`.
+                        pub fn enable_response_decompression(mut self, enable_response_decompression: impl #{Into}<#{Option}<bool>>) -> Self {
+                            self.set_enable_response_decompression(enable_response_decompression.into());
+                            self
+                        }
+
+                        /// Sets whether compressed responses are requested and transparently decompressed.
+                        pub fn set_enable_response_decompression(&mut self, enable_response_decompression: #{Option}<bool>) -> &mut Self {
+                            self.config.store_or_unset::<#{EnableResponseDecompression}>(enable_response_decompression.map(Into::into));
+                            self
+                        }
+
+                        /// Sets the maximum size of a decompressed response body, in bytes.
+                        ///
+                        /// Reading a response body fails once it has decompressed to more than this.
+                        /// The default is 256 MiB.
+                        pub fn response_max_decompressed_size_bytes(mut self, response_max_decompressed_size_bytes: impl #{Into}<#{Option}<u64>>) -> Self {
+                            self.set_response_max_decompressed_size_bytes(response_max_decompressed_size_bytes.into());
+                            self
+                        }
+
+                        /// Sets the maximum size of a decompressed response body, in bytes.
+                        pub fn set_response_max_decompressed_size_bytes(&mut self, response_max_decompressed_size_bytes: #{Option}<u64>) -> &mut Self {
+                            self.config.store_or_unset::<#{ResponseMaxDecompressedSizeBytes}>(response_max_decompressed_size_bytes.map(Into::into));
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                is ServiceConfig.BuilderFromConfigBag -> {
+                    rustTemplate(
+                        """
+                        ${section.builder}.set_enable_response_decompression(
+                            ${section.configBag}.load::<#{EnableResponseDecompression}>().cloned().map(|it| it.0));
+                        ${section.builder}.set_response_max_decompressed_size_bytes(
+                            ${section.configBag}.load::<#{ResponseMaxDecompressedSizeBytes}>().cloned().map(|it| it.0));
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                else -> emptySection
+            }
+        }
+}
@@ -9,6 +9,7 @@ import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.ConnectionPoisoningRuntimePluginCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.EventStreamFailureGenerator
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.HttpChecksumRequiredGenerator
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.IdentityCacheConfigCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.InterceptorConfigCustomization
@@ -16,6 +17,8 @@ import software.amazon.smithy.rust.codegen.client.smithy.customizations.Metadata
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RequestCompressionGenerator
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.ResiliencyConfigCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.ResiliencyReExportCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.ResponseDecompressionConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.ResponseDecompressionGenerator
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryClassifierConfigCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryClassifierOperationCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryClassifierServiceRuntimePluginCustomization
@@ -54,7 +57,9 @@ class RequiredCustomizations : ClientCodegenDecorator {
             MetadataCustomization(codegenContext, operation) +
             HttpChecksumRequiredGenerator(codegenContext, operation) +
             RetryClassifierOperationCustomization(codegenContext, operation) +
-            RequestCompressionGenerator(codegenContext, operation)
+            RequestCompressionGenerator(codegenContext, operation) +
+            ResponseDecompressionGenerator(codegenContext, operation) +
+            EventStreamFailureGenerator(codegenContext, operation)
 
     override fun configCustomizations(
         codegenContext: ClientCodegenContext,
@@ -65,7 +70,8 @@ class RequiredCustomizations : ClientCodegenDecorator {
             IdentityCacheConfigCustomization(codegenContext) +
             InterceptorConfigCustomization(codegenContext) +
             TimeSourceCustomization(codegenContext) +
-            RetryClassifierConfigCustomization(codegenContext)
+            RetryClassifierConfigCustomization(codegenContext) +
+            ResponseDecompressionConfigCustomization(codegenContext)
 
     override fun libRsCustomizations(
         codegenContext: ClientCodegenContext,
@@ -79,6 +79,25 @@ interface EndpointCustomization {
      * Provide a list of additional endpoints standard library functions that rules can use
      */
     fun customRuntimeFunctions(codegenContext: ClientCodegenContext): List<CustomRuntimeFunction> = listOf()
+
+    /**
+     * Set endpoint parameters from the resolved identity before the endpoint is resolved. If this customization
+     * does not apply, return null.
+     *
+     * The writable has access to `params`, a `&mut crate::config::endpoint::Params`, and `identity`, the
+     * `&Identity` that will be used to sign the request. This runs before every attempt, and `params` is reset to
+     * the params created for the operation each time, so values set from an earlier attempt's identity aren't kept.
+     *
+     * Example:
+     * ```kotlin
+     * override fun finalizeParams(codegenContext: ClientCodegenContext): Writable? {
+     *     return writable {
+     *         rust("if params.account_id.is_none() { params.account_id = identity.property::<AccountId>().map(|id| id.as_str().to_string()); }")
+     *     }
+     * }
+     * ```
+     */
+    fun finalizeParams(codegenContext: ClientCodegenContext): Writable? = null
 }
 
 /**
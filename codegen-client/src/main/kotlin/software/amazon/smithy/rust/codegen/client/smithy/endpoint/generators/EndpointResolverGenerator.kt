@@ -394,9 +394,16 @@ internal class EndpointResolverGenerator(
 
 fun ClientCodegenContext.serviceSpecificEndpointResolver(): RuntimeType {
     val generator = EndpointTypesGenerator.fromContext(this)
+    val codegenContext = this
     return RuntimeType.forInlineFun("ResolveEndpoint", ClientRustModule.Config.endpoint) {
         val ctx =
-            arrayOf(*preludeScope, "Params" to generator.paramsStruct(), *Types(runtimeConfig).toArray(), "Debug" to RuntimeType.Debug)
+            arrayOf(
+                *preludeScope,
+                "Params" to generator.paramsStruct(),
+                *Types(runtimeConfig).toArray(),
+                "Debug" to RuntimeType.Debug,
+                "FinalizeParams" to finalizeParams(codegenContext),
+            )
         rustTemplate(
             """
             /// Endpoint resolver trait specific to ${serviceShape.serviceNameOrDefault("this service")}
@@ -428,6 +435,8 @@ fun ClientCodegenContext.serviceSpecificEndpointResolver(): RuntimeType {
                     };
                     ep
                 }
+
+                #{FinalizeParams}
             }
 
             """,
@@ -435,3 +444,57 @@ fun ClientCodegenContext.serviceSpecificEndpointResolver(): RuntimeType {
         )
     }
 }
+
+/**
+ * Renders `finalize_params` for the service-specific resolver when any [EndpointCustomization] sets endpoint
+ * parameters from the resolved identity
+ */
+private fun finalizeParams(codegenContext: ClientCodegenContext): Writable {
+    val customizations =
+        codegenContext.rootDecorator.endpointCustomizations(codegenContext)
+            .mapNotNull { it.finalizeParams(codegenContext) }
+    if (customizations.isEmpty()) {
+        return writable {}
+    }
+    val runtimeConfig = codegenContext.runtimeConfig
+    return writable {
+        rustTemplate(
+            """
+            fn finalize_params<'a>(&'a self, params: &'a mut #{EndpointResolverParams}) -> #{Result}<(), #{BoxError}> {
+                // Cloning the identity ends the borrow of `params` so that the service-specific params can be
+                // borrowed mutably below
+                let identity = params.get_property::<#{Identity}>().cloned();
+                // Params are finalized before every attempt, so they're reset to the params they were created with
+                // first, rather than keeping values resolved from an earlier attempt's identity
+                let initial = match params.get_property::<#{Params}>() {
+                    #{Some}(initial) => initial.clone(),
+                    #{None} => match params.get::<#{Params}>() {
+                        #{Some}(initial) => {
+                            let initial = initial.clone();
+                            params.set_property(initial.clone());
+                            initial
+                        }
+                        #{None} => return #{Err}("service-specific endpoint params was not present".into()),
+                    },
+                };
+                let params = params.get_mut::<#{Params}>().expect("checked above");
+                *params = initial;
+                if let #{Some}(identity) = identity.as_ref() {
+                    #{customizations}
+                }
+                #{Ok}(())
+            }
+
+            fn finalize_params_uses_identity(&self) -> bool {
+                true
+            }
+            """,
+            *preludeScope,
+            *Types(runtimeConfig).toArray(),
+            "BoxError" to RuntimeType.boxError(runtimeConfig),
+            "Identity" to RuntimeType.smithyRuntimeApiClient(runtimeConfig).resolve("client::identity::Identity"),
+            "Params" to EndpointTypesGenerator.fromContext(codegenContext).paramsStruct(),
+            "customizations" to customizations.join("\n"),
+        )
+    }
+}
@@ -0,0 +1,152 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.generators.config
+
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.configReexport
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.customize.NamedCustomization
+
+class BandwidthLimitDecorator : ClientCodegenDecorator {
+    override val name: String = "BandwidthLimit"
+    override val order: Byte = 0
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> {
+        return baseCustomizations + BandwidthLimitConfigCustomization(codegenContext)
+    }
+
+    override fun operationCustomizations(
+        codegenContext: ClientCodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>,
+    ): List<OperationCustomization> {
+        return baseCustomizations + BandwidthLimitOperationCustomization(codegenContext)
+    }
+}
+
+/**
+ * Add a `bandwidth_limit` field to Service config.
+ */
+class BandwidthLimitConfigCustomization(codegenContext: ClientCodegenContext) : NamedCustomization<ServiceConfig>() {
+    private val rc = codegenContext.runtimeConfig
+    private val moduleUseName = codegenContext.moduleUseName()
+    private val bandwidthLimitModule = RuntimeType.smithyRuntime(rc).resolve("client::bandwidth_limit")
+    private val codegenScope =
+        arrayOf(
+            *preludeScope,
+            "BandwidthLimitConfig" to configReexport(bandwidthLimitModule.resolve("BandwidthLimitConfig")),
+            "BandwidthLimiter" to configReexport(bandwidthLimitModule.resolve("BandwidthLimiter")),
+            "Throughput" to configReexport(bandwidthLimitModule.resolve("Throughput")),
+        )
+
+    override fun section(section: ServiceConfig): Writable {
+        return when (section) {
+            ServiceConfig.ConfigImpl ->
+                writable {
+                    rustTemplate(
+                        """
+                        /// Return a reference to the bandwidth limit configuration contained in this config, if any.
+                        pub fn bandwidth_limit(&self) -> #{Option}<&#{BandwidthLimitConfig}> {
+                            self.config.load::<#{BandwidthLimitConfig}>()
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+            ServiceConfig.BuilderImpl ->
+                writable {
+                    rustTemplate(
+                        """
+                        /// Set the [`BandwidthLimitConfig`](#{BandwidthLimitConfig}) to limit the throughput
+                        /// of request and/or response bodies.
+                        ///
+                        /// This can be set on the client config to limit every operation, or in a config
+                        /// override to limit a single operation invocation. Share a [`BandwidthLimiter`](#{BandwidthLimiter})
+                        /// between clients or operations to limit their combined [`Throughput`](#{Throughput}).
+                        ///
+                        /// ## Examples
+                        /// ```no_run
+                        /// use $moduleUseName::config::{BandwidthLimitConfig, BandwidthLimiter, Config, Throughput};
+                        ///
+                        /// let limiter = BandwidthLimiter::new(Throughput::new_megabytes_per_second(10));
+                        /// let config = Config::builder()
+                        ///     .bandwidth_limit(BandwidthLimitConfig::new().with_upload_limiter(limiter))
+                        ///     .build();
+                        /// ```
+                        pub fn bandwidth_limit(
+                            mut self,
+                            bandwidth_limit_config: #{BandwidthLimitConfig}
+                        ) -> Self {
+                            self.set_bandwidth_limit(#{Some}(bandwidth_limit_config));
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+
+                    rustTemplate(
+                        """
+                        /// Set the [`BandwidthLimitConfig`](#{BandwidthLimitConfig}) to limit the throughput
+                        /// of request and/or response bodies.
+                        pub fn set_bandwidth_limit(
+                            &mut self,
+                            bandwidth_limit_config: #{Option}<#{BandwidthLimitConfig}>
+                        ) -> &mut Self {
+                            self.config.store_or_unset(bandwidth_limit_config);
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+            is ServiceConfig.BuilderFromConfigBag ->
+                writable {
+                    rustTemplate(
+                        "${section.builder}.set_bandwidth_limit(${section.configBag}.load::<#{BandwidthLimitConfig}>().cloned());",
+                        *codegenScope,
+                    )
+                }
+
+            else -> emptySection
+        }
+    }
+}
+
+class BandwidthLimitOperationCustomization(
+    codegenContext: ClientCodegenContext,
+) : OperationCustomization() {
+    private val rc = codegenContext.runtimeConfig
+
+    override fun section(section: OperationSection): Writable =
+        writable {
+            when (section) {
+                is OperationSection.AdditionalInterceptors -> {
+                    val bandwidthLimitModule = RuntimeType.smithyRuntime(rc).resolve("client::bandwidth_limit")
+                    section.registerInterceptor(rc, this) {
+                        rustTemplate(
+                            """
+                            #{BandwidthLimitInterceptor}::default()
+                            """,
+                            "BandwidthLimitInterceptor" to bandwidthLimitModule.resolve("BandwidthLimitInterceptor"),
+                        )
+                    }
+                }
+                else -> { }
+            }
+        }
+}
@@ -29,6 +29,9 @@ class ClientHttpBoundProtocolPayloadGenerator(
                     _cfg.interceptor_state().store_put(signer_sender);
                     let adapter: #{aws_smithy_http}::event_stream::MessageStreamAdapter<_, _> =
                         ${params.outerName}.${params.memberName}.into_body_stream(marshaller, error_marshaller, signer);
+                    if let Some(failure_reporter) = adapter.failure_reporter() {
+                        _cfg.interceptor_state().store_put(failure_reporter);
+                    }
                     #{SdkBody}::from_body_0_4(#{hyper}::Body::wrap_stream(adapter))
                 }
                 """,
@@ -0,0 +1,120 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+class RequestCompressionGeneratorTest {
+    // Smithy's validator only knows about gzip, so validation is disabled to list other encodings.
+    private val model =
+        """
+        namespace test
+
+        use aws.protocols#restJson1
+
+        @restJson1
+        service TestService {
+            version: "2023-01-01",
+            operations: [PrefersZstd, PrefersBrotli, FallsBackToGzip]
+        }
+
+        @http(uri: "/PrefersZstd", method: "POST")
+        @requestCompression(encodings: ["zstd", "gzip"])
+        operation PrefersZstd {
+            input: PayloadInput
+        }
+
+        @http(uri: "/PrefersBrotli", method: "POST")
+        @requestCompression(encodings: ["br", "gzip"])
+        operation PrefersBrotli {
+            input: PayloadInput
+        }
+
+        @http(uri: "/FallsBackToGzip", method: "POST")
+        @requestCompression(encodings: ["unsupported", "gzip"])
+        operation FallsBackToGzip {
+            input: PayloadInput
+        }
+
+        structure PayloadInput {
+            @httpPayload
+            @required
+            body: Blob
+        }
+        """.asSmithyModel(disableValidation = true)
+
+    @Test
+    fun `requests are compressed with the first supported encoding listed by the model`() {
+        clientIntegrationTest(model) { clientCodegenContext, rustCrate ->
+            val runtimeConfig = clientCodegenContext.runtimeConfig
+            val codegenScope =
+                arrayOf(
+                    *preludeScope,
+                    "Blob" to RuntimeType.blob(runtimeConfig),
+                    "capture_request" to RuntimeType.captureRequest(runtimeConfig),
+                )
+            rustCrate.testModule {
+                addDependency(CargoDependency.Tokio.toDevDependency().withFeature("test-util"))
+                tokioTest("test_request_compression_encoding_is_chosen_from_the_model") {
+                    rustTemplate(
+                        """
+                        // Bodies smaller than the default minimum compression size aren't compressed.
+                        let body = #{Blob}::new(vec![b'a'; 10240]);
+
+                        let (http_client, captured_request) = #{capture_request}(#{None});
+                        let client = crate::client::Client::from_conf(
+                            crate::config::Config::builder()
+                                .endpoint_url("http://localhost:1234")
+                                .http_client(http_client)
+                                .build(),
+                        );
+                        let _ = client.prefers_zstd().body(body.clone()).send().await;
+                        assert_eq!(
+                            #{Some}("zstd"),
+                            captured_request.expect_request().headers().get("content-encoding")
+                        );
+
+                        let (http_client, captured_request) = #{capture_request}(#{None});
+                        let client = crate::client::Client::from_conf(
+                            crate::config::Config::builder()
+                                .endpoint_url("http://localhost:1234")
+                                .http_client(http_client)
+                                .build(),
+                        );
+                        let _ = client.prefers_brotli().body(body.clone()).send().await;
+                        assert_eq!(
+                            #{Some}("br"),
+                            captured_request.expect_request().headers().get("content-encoding")
+                        );
+
+                        let (http_client, captured_request) = #{capture_request}(#{None});
+                        let client = crate::client::Client::from_conf(
+                            crate::config::Config::builder()
+                                .endpoint_url("http://localhost:1234")
+                                .http_client(http_client)
+                                .build(),
+                        );
+                        let _ = client.falls_back_to_gzip().body(body).send().await;
+                        assert_eq!(
+                            #{Some}("gzip"),
+                            captured_request.expect_request().headers().get("content-encoding")
+                        );
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
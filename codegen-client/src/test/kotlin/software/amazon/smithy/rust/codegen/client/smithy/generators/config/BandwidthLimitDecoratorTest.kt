@@ -0,0 +1,69 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.generators.config
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.testutil.BasicTestModels
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+
+internal class BandwidthLimitDecoratorTest {
+    @Test
+    fun `bandwidth limit can be set on the config and overridden per operation`() {
+        clientIntegrationTest(BasicTestModels.AwsJson10TestModel) { codegenContext, rustCrate ->
+            val codegenScope =
+                arrayOf(
+                    *preludeScope,
+                    "capture_request" to RuntimeType.captureRequest(codegenContext.runtimeConfig),
+                )
+            rustCrate.testModule {
+                addDependency(CargoDependency.Tokio.toDevDependency().withFeature("test-util"))
+                tokioTest("test_bandwidth_limit_config") {
+                    rustTemplate(
+                        """
+                        use crate::config::{BandwidthLimitConfig, BandwidthLimiter, Throughput};
+
+                        let limiter = BandwidthLimiter::new(Throughput::new_megabytes_per_second(10));
+                        let config = crate::config::Config::builder()
+                            .bandwidth_limit(BandwidthLimitConfig::new().with_upload_limiter(limiter.clone()))
+                            .build();
+                        let bandwidth_limit = config.bandwidth_limit().expect("set on the builder");
+                        assert!(bandwidth_limit.upload_limiter().is_some());
+                        assert!(bandwidth_limit.download_limiter().is_none());
+
+                        // The setting survives a round trip through `to_builder`
+                        let (http_client, captured_request) = #{capture_request}(#{None});
+                        let config = config
+                            .to_builder()
+                            .endpoint_url("http://localhost:1234")
+                            .http_client(http_client)
+                            .build();
+                        assert!(config.bandwidth_limit().is_some());
+
+                        let client = crate::client::Client::from_conf(config);
+                        let _ = client
+                            .say_hello()
+                            .customize()
+                            .config_override(
+                                crate::config::Config::builder()
+                                    .bandwidth_limit(BandwidthLimitConfig::new().with_download_limiter(limiter)),
+                            )
+                            .send()
+                            .await;
+                        captured_request.expect_request();
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+}
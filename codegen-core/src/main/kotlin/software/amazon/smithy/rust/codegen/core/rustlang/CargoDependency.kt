@@ -104,7 +104,9 @@ class InlineDependency(
         fun eventReceiver(runtimeConfig: RuntimeConfig) =
             forInlineableRustFile(
                 "event_receiver",
+                CargoDependency.smithyAsync(runtimeConfig),
                 CargoDependency.smithyHttp(runtimeConfig),
+                CargoDependency.smithyRuntime(runtimeConfig).withFeature("event-stream"),
                 CargoDependency.smithyRuntimeApi(runtimeConfig),
                 CargoDependency.smithyTypes(runtimeConfig),
             )
@@ -135,7 +137,23 @@ class InlineDependency(
                 CargoDependency.Tracing,
                 CargoDependency.Flate2,
                 CargoDependency.Tokio.toDevDependency(),
-                CargoDependency.smithyCompression(runtimeConfig).withFeature("http-body-0-4-x"),
+                CargoDependency.smithyCompression(runtimeConfig).withFeature("http-body-0-4-x")
+                    .withFeature("zstd").withFeature("brotli"),
+                CargoDependency.smithyRuntimeApiClient(runtimeConfig),
+                CargoDependency.smithyTypes(runtimeConfig).withFeature("http-body-0-4-x"),
+            )
+
+        fun clientResponseDecompression(runtimeConfig: RuntimeConfig) =
+            forInlineableRustFile(
+                "client_response_decompression",
+                CargoDependency.Http,
+                CargoDependency.HttpBody,
+                CargoDependency.Tracing,
+                CargoDependency.Bytes.toDevDependency(),
+                CargoDependency.Tokio.toDevDependency(),
+                CargoDependency.smithyChecksums(runtimeConfig).toDevDependency(),
+                CargoDependency.smithyCompression(runtimeConfig).withFeature("http-body-0-4-x").withFeature("zstd"),
+                CargoDependency.smithyRuntime(runtimeConfig).toDevDependency(),
                 CargoDependency.smithyRuntimeApiClient(runtimeConfig),
                 CargoDependency.smithyTypes(runtimeConfig).withFeature("http-body-0-4-x"),
             )
@@ -533,5 +533,8 @@ data class RuntimeType(val path: String, val dependency: RustDependency? = null)
 
         fun clientRequestCompression(runtimeConfig: RuntimeConfig) =
             forInlineDependency(InlineDependency.clientRequestCompression(runtimeConfig))
+
+        fun clientResponseDecompression(runtimeConfig: RuntimeConfig) =
+            forInlineDependency(InlineDependency.clientResponseDecompression(runtimeConfig))
     }
 }
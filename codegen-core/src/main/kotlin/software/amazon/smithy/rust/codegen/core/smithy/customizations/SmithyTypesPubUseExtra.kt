@@ -108,16 +108,20 @@ fun pubUseSmithyPrimitivesEventStream(
         if (codegenContext.serviceShape.hasEventStreamOperations(model)) {
             rustTemplate(
                 """
+                pub use #{ChannelSender};
                 pub use #{EventReceiver};
                 pub use #{Header};
                 pub use #{HeaderValue};
                 pub use #{Message};
+                pub use #{SendError};
                 pub use #{StrBytes};
                 """,
+                "ChannelSender" to RuntimeType.smithyHttp(rc).resolve("event_stream::ChannelSender"),
                 "EventReceiver" to eventReceiver(rc),
                 "Header" to RuntimeType.smithyTypes(rc).resolve("event_stream::Header"),
                 "HeaderValue" to RuntimeType.smithyTypes(rc).resolve("event_stream::HeaderValue"),
                 "Message" to RuntimeType.smithyTypes(rc).resolve("event_stream::Message"),
+                "SendError" to RuntimeType.smithyHttp(rc).resolve("event_stream::SendError"),
                 "StrBytes" to RuntimeType.smithyTypes(rc).resolve("str_bytes::StrBytes"),
             )
         }
@@ -0,0 +1,571 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Schema-driven CBOR serializer and deserializer for the `rpcv2Cbor` protocol.
+//!
+//! Structures are keyed by member name and timestamps are always written as epoch-seconds
+//! values tagged with tag 1, so the only schema information needed is the member names.
+
+use crate::data::Type;
+use crate::decode::DeserializeError;
+use crate::{Decoder, Encoder};
+use aws_smithy_types::schema::serde::{SerializableStruct, ShapeDeserializer, ShapeSerializer};
+use aws_smithy_types::schema::Schema;
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+use bytes::BufMut;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+/// A [`ShapeSerializer`] that writes CBOR with an [`Encoder`].
+///
+/// Structures are written as indefinite-length maps since the number of set members isn't
+/// known up front. Lists and maps use their definite length.
+#[derive(Debug)]
+pub struct CborShapeSerializer<W = Vec<u8>> {
+    encoder: Encoder<W>,
+    // Whether each enclosing aggregate is a structure, whose values must be preceded by a key.
+    in_struct: Vec<bool>,
+}
+
+impl<W: BufMut> CborShapeSerializer<W> {
+    /// Creates a serializer writing with `encoder`.
+    pub fn new(encoder: Encoder<W>) -> Self {
+        Self {
+            encoder,
+            in_struct: Vec::new(),
+        }
+    }
+
+    /// Returns the written bytes.
+    pub fn into_writer(self) -> W {
+        self.encoder.into_writer()
+    }
+
+    /// Writes the member key if the value is a structure member, and returns the encoder.
+    fn value(&mut self, schema: &Schema) -> &mut Encoder<W> {
+        if let Some(true) = self.in_struct.last() {
+            let name = schema.member_name().unwrap_or_else(|| schema.id().name());
+            self.encoder.str(name);
+        }
+        &mut self.encoder
+    }
+
+    fn document(&mut self, value: &Document) {
+        match value {
+            Document::Object(values) => {
+                self.encoder.map(values.len());
+                for (key, value) in values {
+                    self.encoder.str(key);
+                    self.document(value);
+                }
+            }
+            Document::Array(values) => {
+                self.encoder.array(values.len());
+                for value in values {
+                    self.document(value);
+                }
+            }
+            Document::Number(Number::PosInt(value)) => {
+                self.encoder.unsigned_long(*value);
+            }
+            Document::Number(Number::NegInt(value)) => {
+                self.encoder.long(*value);
+            }
+            Document::Number(Number::Float(value)) => {
+                self.encoder.double(*value);
+            }
+            Document::String(value) => {
+                self.encoder.str(value);
+            }
+            Document::Bool(value) => {
+                self.encoder.boolean(*value);
+            }
+            Document::Null => {
+                self.encoder.null();
+            }
+        }
+    }
+}
+
+impl<W: BufMut> ShapeSerializer for CborShapeSerializer<W> {
+    type Error = Infallible;
+
+    fn write_struct<T>(&mut self, schema: &Schema, value: &T) -> Result<(), Self::Error>
+    where
+        T: SerializableStruct + ?Sized,
+    {
+        self.value(schema).begin_map();
+        self.in_struct.push(true);
+        let result = value.serialize_members(self);
+        self.in_struct.pop();
+        self.encoder.end();
+        result
+    }
+
+    fn write_list<F>(
+        &mut self,
+        schema: &Schema,
+        len: usize,
+        write_elements: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        self.value(schema).array(len);
+        self.in_struct.push(false);
+        let result = write_elements(self);
+        self.in_struct.pop();
+        result
+    }
+
+    fn write_map<F>(
+        &mut self,
+        schema: &Schema,
+        len: usize,
+        write_entries: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        self.value(schema).map(len);
+        self.in_struct.push(false);
+        let result = write_entries(self);
+        self.in_struct.pop();
+        result
+    }
+
+    fn write_map_key(&mut self, _schema: &Schema, key: &str) -> Result<(), Self::Error> {
+        self.encoder.str(key);
+        Ok(())
+    }
+
+    fn write_boolean(&mut self, schema: &Schema, value: bool) -> Result<(), Self::Error> {
+        self.value(schema).boolean(value);
+        Ok(())
+    }
+
+    fn write_byte(&mut self, schema: &Schema, value: i8) -> Result<(), Self::Error> {
+        self.value(schema).byte(value);
+        Ok(())
+    }
+
+    fn write_short(&mut self, schema: &Schema, value: i16) -> Result<(), Self::Error> {
+        self.value(schema).short(value);
+        Ok(())
+    }
+
+    fn write_integer(&mut self, schema: &Schema, value: i32) -> Result<(), Self::Error> {
+        self.value(schema).integer(value);
+        Ok(())
+    }
+
+    fn write_long(&mut self, schema: &Schema, value: i64) -> Result<(), Self::Error> {
+        self.value(schema).long(value);
+        Ok(())
+    }
+
+    fn write_float(&mut self, schema: &Schema, value: f32) -> Result<(), Self::Error> {
+        self.value(schema).float(value);
+        Ok(())
+    }
+
+    fn write_double(&mut self, schema: &Schema, value: f64) -> Result<(), Self::Error> {
+        self.value(schema).double(value);
+        Ok(())
+    }
+
+    fn write_string(&mut self, schema: &Schema, value: &str) -> Result<(), Self::Error> {
+        self.value(schema).str(value);
+        Ok(())
+    }
+
+    fn write_blob(&mut self, schema: &Schema, value: &Blob) -> Result<(), Self::Error> {
+        self.value(schema).blob(value);
+        Ok(())
+    }
+
+    fn write_timestamp(&mut self, schema: &Schema, value: &DateTime) -> Result<(), Self::Error> {
+        self.value(schema).timestamp(value);
+        Ok(())
+    }
+
+    fn write_document(&mut self, schema: &Schema, value: &Document) -> Result<(), Self::Error> {
+        self.value(schema);
+        self.document(value);
+        Ok(())
+    }
+
+    fn write_null(&mut self, schema: &Schema) -> Result<(), Self::Error> {
+        self.value(schema).null();
+        Ok(())
+    }
+}
+
+/// A [`ShapeDeserializer`] that reads CBOR with a [`Decoder`].
+///
+/// Null members of structures are treated as absent and are never passed to the consumer.
+#[derive(Debug)]
+pub struct CborShapeDeserializer<'b> {
+    decoder: Decoder<'b>,
+}
+
+impl<'b> CborShapeDeserializer<'b> {
+    /// Creates a deserializer reading from `bytes`.
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self {
+            decoder: Decoder::new(bytes),
+        }
+    }
+
+    /// Returns the underlying decoder.
+    pub fn into_decoder(self) -> Decoder<'b> {
+        self.decoder
+    }
+
+    /// Reads a string, borrowing it from the input unless it's an indefinite-length string.
+    ///
+    /// Unlike [`read_string`](ShapeDeserializer::read_string), this doesn't allocate for
+    /// definite-length strings.
+    pub fn read_str(&mut self, _schema: &Schema) -> Result<Cow<'b, str>, DeserializeError> {
+        self.decoder.str()
+    }
+
+    /// Reads a blob, borrowing it from the input unless it's an indefinite-length byte string.
+    ///
+    /// Unlike [`read_blob`](ShapeDeserializer::read_blob), this doesn't allocate for
+    /// definite-length byte strings.
+    pub fn read_bytes(&mut self, _schema: &Schema) -> Result<Cow<'b, [u8]>, DeserializeError> {
+        self.decoder.bytes()
+    }
+
+    /// Invokes `read_item` once per element of a definite or indefinite-length collection whose
+    /// header returned `len`.
+    fn read_items<T, F>(
+        &mut self,
+        len: Option<u64>,
+        mut state: T,
+        mut read_item: F,
+    ) -> Result<T, DeserializeError>
+    where
+        F: FnMut(T, &mut Self) -> Result<T, DeserializeError>,
+    {
+        match len {
+            None => loop {
+                match self.decoder.datatype()? {
+                    Type::Break => {
+                        self.decoder.skip()?;
+                        return Ok(state);
+                    }
+                    _ => state = read_item(state, self)?,
+                }
+            },
+            Some(n) => {
+                for _ in 0..n {
+                    state = read_item(state, self)?;
+                }
+                Ok(state)
+            }
+        }
+    }
+
+    fn document(&mut self, depth: usize) -> Result<Document, DeserializeError> {
+        const MAX_DOCUMENT_RECURSION: usize = 256;
+        if depth >= MAX_DOCUMENT_RECURSION {
+            return Err(DeserializeError::custom(
+                "exceeded max recursion depth while parsing document",
+                self.decoder.position(),
+            ));
+        }
+        Ok(match self.decoder.datatype()? {
+            Type::Null | Type::Undefined => {
+                self.decoder.skip()?;
+                Document::Null
+            }
+            Type::Bool => Document::Bool(self.decoder.boolean()?),
+            Type::U8 | Type::U16 | Type::U32 | Type::U64 => {
+                Document::Number(Number::PosInt(self.decoder.unsigned_long()?))
+            }
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::Int => {
+                Document::Number(Number::NegInt(self.decoder.long()?))
+            }
+            Type::F16 | Type::F32 | Type::F64 => {
+                Document::Number(Number::Float(self.decoder.double()?))
+            }
+            Type::String | Type::StringIndef => Document::String(self.decoder.str()?.into_owned()),
+            Type::Array | Type::ArrayIndef => {
+                let len = self.decoder.list()?;
+                Document::Array(self.read_items(len, Vec::new(), |mut items, de| {
+                    items.push(de.document(depth + 1)?);
+                    Ok(items)
+                })?)
+            }
+            Type::Map | Type::MapIndef => {
+                let len = self.decoder.map()?;
+                Document::Object(self.read_items(len, HashMap::new(), |mut map, de| {
+                    let key = de.decoder.str()?.into_owned();
+                    map.insert(key, de.document(depth + 1)?);
+                    Ok(map)
+                })?)
+            }
+            other => {
+                return Err(DeserializeError::custom(
+                    format!("unexpected {:?} in document", other),
+                    self.decoder.position(),
+                ))
+            }
+        })
+    }
+}
+
+impl<'b> ShapeDeserializer for CborShapeDeserializer<'b> {
+    type Error = DeserializeError;
+
+    fn read_struct<T, F>(
+        &mut self,
+        schema: &Schema,
+        state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &Schema, &mut Self) -> Result<T, Self::Error>,
+    {
+        let len = self.decoder.map()?;
+        self.read_items(len, state, |state, de| {
+            let key = de.decoder.str()?;
+            let member = schema
+                .members()
+                .iter()
+                .find(|member| member.member_name() == Some(key.as_ref()));
+            match member {
+                Some(member) if !de.is_null() => consumer(state, member, de),
+                _ => {
+                    de.decoder.skip()?;
+                    Ok(state)
+                }
+            }
+        })
+    }
+
+    fn read_list<T, F>(&mut self, _schema: &Schema, state: T, consumer: F) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &mut Self) -> Result<T, Self::Error>,
+    {
+        let len = self.decoder.list()?;
+        self.read_items(len, state, consumer)
+    }
+
+    fn read_map<T, F>(
+        &mut self,
+        _schema: &Schema,
+        state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, String, &mut Self) -> Result<T, Self::Error>,
+    {
+        let len = self.decoder.map()?;
+        self.read_items(len, state, |state, de| {
+            let key = de.decoder.str()?.into_owned();
+            consumer(state, key, de)
+        })
+    }
+
+    fn read_boolean(&mut self, _schema: &Schema) -> Result<bool, Self::Error> {
+        self.decoder.boolean()
+    }
+
+    fn read_byte(&mut self, _schema: &Schema) -> Result<i8, Self::Error> {
+        self.decoder.byte()
+    }
+
+    fn read_short(&mut self, _schema: &Schema) -> Result<i16, Self::Error> {
+        self.decoder.short()
+    }
+
+    fn read_integer(&mut self, _schema: &Schema) -> Result<i32, Self::Error> {
+        self.decoder.integer()
+    }
+
+    fn read_long(&mut self, _schema: &Schema) -> Result<i64, Self::Error> {
+        self.decoder.long()
+    }
+
+    fn read_float(&mut self, _schema: &Schema) -> Result<f32, Self::Error> {
+        self.decoder.float()
+    }
+
+    fn read_double(&mut self, _schema: &Schema) -> Result<f64, Self::Error> {
+        self.decoder.double()
+    }
+
+    fn read_string(&mut self, schema: &Schema) -> Result<String, Self::Error> {
+        Ok(self.read_str(schema)?.into_owned())
+    }
+
+    fn read_blob(&mut self, schema: &Schema) -> Result<Blob, Self::Error> {
+        Ok(Blob::new(self.read_bytes(schema)?.into_owned()))
+    }
+
+    fn read_timestamp(&mut self, _schema: &Schema) -> Result<DateTime, Self::Error> {
+        self.decoder.timestamp()
+    }
+
+    fn read_document(&mut self, _schema: &Schema) -> Result<Document, Self::Error> {
+        self.document(0)
+    }
+
+    fn is_null(&mut self) -> bool {
+        matches!(self.decoder.datatype(), Ok(Type::Null))
+    }
+
+    fn read_null(&mut self) -> Result<(), Self::Error> {
+        self.decoder.null()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CborShapeDeserializer, CborShapeSerializer};
+    use crate::Encoder;
+    use aws_smithy_types::schema::serde::{SerializableStruct, ShapeDeserializer, ShapeSerializer};
+    use aws_smithy_types::schema::{Schema, ShapeId, ShapeType, Traits};
+    use aws_smithy_types::DateTime;
+    use std::borrow::Cow;
+
+    const ITEM_ID: ShapeId = ShapeId::new("com.example", "Item");
+
+    static ITEM: Schema = Schema::new(ITEM_ID, ShapeType::Structure).with_members(&ITEM_MEMBERS);
+    static ITEM_MEMBERS: [Schema; 2] = [
+        Schema::new_member(ITEM_ID.with_member("sizes"), 0, ShapeType::List)
+            .with_members(&SIZES_MEMBERS),
+        Schema::new_member(ITEM_ID.with_member("at"), 1, ShapeType::Timestamp),
+    ];
+    static SIZES_MEMBERS: [Schema; 1] = [Schema::new_member(
+        ShapeId::new("com.example", "Sizes").with_member("member"),
+        0,
+        ShapeType::Integer,
+    )
+    .with_traits(Traits::new().with_sparse())];
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Item {
+        sizes: Option<Vec<Option<i32>>>,
+        at: Option<DateTime>,
+    }
+
+    impl SerializableStruct for Item {
+        fn serialize_members<S: ShapeSerializer>(&self, ser: &mut S) -> Result<(), S::Error> {
+            if let Some(sizes) = &self.sizes {
+                ser.write_list(&ITEM_MEMBERS[0], sizes.len(), |ser| {
+                    for size in sizes {
+                        match size {
+                            Some(size) => ser.write_integer(&SIZES_MEMBERS[0], *size)?,
+                            None => ser.write_null(&SIZES_MEMBERS[0])?,
+                        }
+                    }
+                    Ok(())
+                })?;
+            }
+            if let Some(at) = &self.at {
+                ser.write_timestamp(&ITEM_MEMBERS[1], at)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn deserialize_item<D: ShapeDeserializer>(de: &mut D) -> Result<Item, D::Error> {
+        de.read_struct(&ITEM, Item::default(), |mut item, member, de| {
+            match member.member_index() {
+                0 => {
+                    item.sizes = Some(de.read_list(member, Vec::new(), |mut sizes, de| {
+                        if de.is_null() {
+                            de.read_null()?;
+                            sizes.push(None);
+                        } else {
+                            sizes.push(Some(de.read_integer(&SIZES_MEMBERS[0])?));
+                        }
+                        Ok(sizes)
+                    })?)
+                }
+                1 => item.at = Some(de.read_timestamp(member)?),
+                _ => unreachable!(),
+            }
+            Ok(item)
+        })
+    }
+
+    #[test]
+    fn sparse_lists_tagged_timestamps_and_indefinite_lengths() {
+        let item = Item {
+            sizes: Some(vec![Some(1), None]),
+            at: Some(DateTime::from_secs(0)),
+        };
+        let mut ser = CborShapeSerializer::new(Encoder::new(Vec::new()));
+        ser.write_struct(&ITEM, &item).unwrap();
+        let bytes = ser.into_writer();
+        // {_ "sizes": [1, null], "at": 1(0.0)}
+        assert_eq!(
+            [
+                &[0xbf, 0x65][..],
+                b"sizes",
+                &[0x82, 0x01, 0xf6, 0x62],
+                b"at",
+                &[0xc1, 0xfb, 0, 0, 0, 0, 0, 0, 0, 0, 0xff],
+            ]
+            .concat(),
+            bytes
+        );
+        let mut de = CborShapeDeserializer::new(&bytes);
+        assert_eq!(item, deserialize_item(&mut de).unwrap());
+
+        // {"unknown": [1, 2], "at": null, "sizes": [_ 7]}
+        let bytes = [
+            0xa3, 0x67, b'u', b'n', b'k', b'n', b'o', b'w', b'n', 0x82, 0x01, 0x02, 0x62, b'a',
+            b't', 0xf6, 0x65, b's', b'i', b'z', b'e', b's', 0x9f, 0x07, 0xff,
+        ];
+        let mut de = CborShapeDeserializer::new(&bytes);
+        assert_eq!(
+            Item {
+                sizes: Some(vec![Some(7)]),
+                at: None,
+            },
+            deserialize_item(&mut de).unwrap()
+        );
+        assert_eq!(bytes.len(), de.into_decoder().position());
+    }
+
+    #[test]
+    fn strings_and_blobs_are_borrowed_from_the_input() {
+        let schema = &ITEM_MEMBERS[0];
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.str("name").blob(&b"payload"[..].into());
+        let bytes = encoder.into_writer();
+        let mut de = CborShapeDeserializer::new(&bytes);
+        assert!(matches!(
+            de.read_str(schema).unwrap(),
+            Cow::Borrowed("name")
+        ));
+        assert!(matches!(
+            de.read_bytes(schema).unwrap(),
+            Cow::Borrowed(b"payload")
+        ));
+
+        // Indefinite-length strings are split into chunks, so they're joined into an owned value
+        // (_ "na", "me"), (_ h'01', h'02')
+        let bytes = [
+            0x7f, 0x62, b'n', b'a', 0x62, b'm', b'e', 0xff, 0x5f, 0x41, 0x01, 0x41, 0x02, 0xff,
+        ];
+        let mut de = CborShapeDeserializer::new(&bytes);
+        assert_eq!(
+            Cow::<str>::Owned("name".into()),
+            de.read_str(schema).unwrap()
+        );
+        assert_eq!(
+            Cow::<[u8]>::Owned(vec![1, 2]),
+            de.read_bytes(schema).unwrap()
+        );
+    }
+}
@@ -161,6 +161,11 @@ impl<'b> Decoder<'b> {
         map => map(Option<u64>);
     }
 
+    /// Reads an unsigned long at the current position.
+    pub(crate) fn unsigned_long(&mut self) -> Result<u64, DeserializeError> {
+        self.decoder.u64().map_err(DeserializeError::new)
+    }
+
     /// Returns the current position of the buffer, which will be decoded when any of the methods is called.
     pub fn position(&self) -> usize {
         self.decoder.position()
@@ -186,6 +191,8 @@ impl<'b> Decoder<'b> {
 
     /// Allocates and returns a `String` if the element at the current position in the buffer is either a
     /// definite-length or an indefinite-length string. Otherwise, an error is returned if the element is not a string type.
+    ///
+    /// Use [`str`](Decoder::str) to borrow definite-length strings from the input instead.
     pub fn string(&mut self) -> Result<String, DeserializeError> {
         let mut iter = self.decoder.str_iter().map_err(DeserializeError::new)?;
         let head = iter.next();
@@ -204,19 +211,35 @@ impl<'b> Decoder<'b> {
         Ok(decoded_string)
     }
 
+    /// Returns a `Cow::Borrowed(&[u8])` if the element at the current position in the buffer is a definite
+    /// length byte string. Otherwise, it returns a `Cow::Owned(Vec<u8>)` if the element at the current position
+    /// is an indefinite-length byte string. An error is returned if the element is neither a definite length nor
+    /// an indefinite-length byte string.
+    pub fn bytes(&mut self) -> Result<Cow<'b, [u8]>, DeserializeError> {
+        let bookmark = self.decoder.position();
+        match self.decoder.bytes() {
+            Ok(bytes) => Ok(Cow::Borrowed(bytes)),
+            Err(e) if e.is_type_mismatch() => {
+                // Move the position back to the start of the CBOR element and then try
+                // decoding it as an indefinite length byte string.
+                self.decoder.set_position(bookmark);
+                let mut combined_chunks = Vec::new();
+                for chunk in self.decoder.bytes_iter().map_err(DeserializeError::new)? {
+                    combined_chunks.extend_from_slice(chunk.map_err(DeserializeError::new)?);
+                }
+                Ok(Cow::Owned(combined_chunks))
+            }
+            Err(e) => Err(DeserializeError::new(e)),
+        }
+    }
+
     /// Returns a `blob` if the element at the current position in the buffer is a byte string. Otherwise,
     /// a `DeserializeError` error is returned.
+    ///
+    /// This always copies the bytes, since `Blob` owns its data. Use [`bytes`](Decoder::bytes) to
+    /// borrow definite-length byte strings from the input instead.
     pub fn blob(&mut self) -> Result<Blob, DeserializeError> {
-        let iter = self.decoder.bytes_iter().map_err(DeserializeError::new)?;
-        let parts: Vec<&[u8]> = iter
-            .collect::<Result<_, _>>()
-            .map_err(DeserializeError::new)?;
-
-        Ok(if parts.len() == 1 {
-            Blob::new(parts[0]) // Directly convert &[u8] to Blob if there's only one part.
-        } else {
-            Blob::new(parts.concat()) // Concatenate all parts into a single Blob.
-        })
+        Ok(Blob::new(self.bytes()?.into_owned()))
     }
 
     /// Returns a `DateTime` if the element at the current position in the buffer is a `timestamp`. Otherwise,
@@ -334,6 +357,24 @@ mod tests {
         assert_eq!(member, "");
     }
 
+    #[test]
+    fn test_definite_bytes_are_cow_borrowed() {
+        let definite_bytes = [0x43, 0x01, 0x02, 0x03];
+        let mut decoder = Decoder::new(&definite_bytes);
+        let member = decoder.bytes().expect("could not decode bytes");
+        assert_eq!(member.as_ref(), &[1, 2, 3]);
+        assert!(matches!(member, std::borrow::Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_indefinite_bytes_are_cow_owned() {
+        let indefinite_bytes = [0x5f, 0x41, 0x01, 0x42, 0x02, 0x03, 0xff];
+        let mut decoder = Decoder::new(&indefinite_bytes);
+        let member = decoder.bytes().expect("could not decode bytes");
+        assert_eq!(member.as_ref(), &[1, 2, 3]);
+        assert!(matches!(member, std::borrow::Cow::Owned(_)));
+    }
+
     #[test]
     fn test_empty_blob_works() {
         let bytes = [0x40];
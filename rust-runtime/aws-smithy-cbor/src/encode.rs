@@ -4,6 +4,8 @@
  */
 
 use aws_smithy_types::{Blob, DateTime};
+use bytes::BufMut;
+use std::convert::Infallible;
 
 /// Macro for delegating method calls to the encoder.
 ///
@@ -31,26 +33,49 @@ macro_rules! delegate_method {
     };
 }
 
+/// Adapts a [`BufMut`] to `minicbor`'s `Write` trait.
 #[derive(Debug, Clone)]
-pub struct Encoder {
-    encoder: minicbor::Encoder<Vec<u8>>,
+struct BufMutWriter<W>(W);
+
+impl<W: BufMut> minicbor::encode::Write for BufMutWriter<W> {
+    type Error = Infallible;
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.0.put_slice(buf);
+        Ok(())
+    }
+}
+
+/// Encodes CBOR into a [`BufMut`], which is a `Vec<u8>` by default.
+///
+/// Writers that can't grow, such as `&mut [u8]`, panic when they run out of space, as documented
+/// in [`BufMut::put_slice`].
+#[derive(Debug, Clone)]
+pub struct Encoder<W = Vec<u8>> {
+    encoder: minicbor::Encoder<BufMutWriter<W>>,
 }
 
-/// We always write to a `Vec<u8>`, which is infallible in `minicbor`.
-/// <https://docs.rs/minicbor/latest/minicbor/encode/write/trait.Write.html#impl-Write-for-Vec%3Cu8%3E>
+/// Writing to a [`BufMut`] is infallible.
 const INFALLIBLE_WRITE: &str = "write failed";
 
-impl Encoder {
-    pub fn new(writer: Vec<u8>) -> Self {
+impl<W: BufMut> Encoder<W> {
+    pub fn new(writer: W) -> Self {
         Self {
-            encoder: minicbor::Encoder::new(writer),
+            encoder: minicbor::Encoder::new(BufMutWriter(writer)),
         }
     }
 
     delegate_method! {
+        /// Begins an indefinite length map, which must be terminated with [`end`](Encoder::end).
+        ///
         /// Used when it's not cheap to calculate the size, i.e. when the struct has one or more
         /// `Option`al members.
         begin_map => begin_map();
+        /// Begins an indefinite length array, which must be terminated with [`end`](Encoder::end).
+        ///
+        /// Used when the number of elements isn't known up front, e.g. when streaming them from
+        /// an iterator.
+        begin_array => begin_array();
         /// Writes a definite length string.
         str => str(x: &str);
         /// Writes a boolean value.
@@ -69,10 +94,16 @@ impl Encoder {
         double => f64(x: f64);
         /// Writes a null tag.
         null => null();
-        /// Writes an end tag.
+        /// Writes the end tag of an indefinite length array or map.
         end => end();
     }
 
+    /// Writes an unsigned long value.
+    pub(crate) fn unsigned_long(&mut self, x: u64) -> &mut Self {
+        self.encoder.u64(x).expect(INFALLIBLE_WRITE);
+        self
+    }
+
     pub fn blob(&mut self, x: &Blob) -> &mut Self {
         self.encoder.bytes(x.as_ref()).expect(INFALLIBLE_WRITE);
         self
@@ -111,7 +142,42 @@ impl Encoder {
         self
     }
 
-    pub fn into_writer(self) -> Vec<u8> {
-        self.encoder.into_writer()
+    pub fn into_writer(self) -> W {
+        self.encoder.into_writer().0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decoder, Encoder};
+    use bytes::BytesMut;
+
+    #[test]
+    fn indefinite_length_collections() {
+        let mut encoder = Encoder::new(Vec::new());
+        encoder.begin_array();
+        for value in 1..=3 {
+            encoder.integer(value);
+        }
+        encoder.end().begin_map().str("k").boolean(true).end();
+        let bytes = encoder.into_writer();
+        assert_eq!(
+            &[0x9f, 0x01, 0x02, 0x03, 0xff, 0xbf, 0x61, 0x6b, 0xf5, 0xff],
+            bytes.as_slice()
+        );
+
+        let mut decoder = Decoder::new(&bytes);
+        assert_eq!(None, decoder.list().unwrap());
+    }
+
+    #[test]
+    fn encode_into_buf_mut() {
+        let mut encoder = Encoder::new(BytesMut::new());
+        encoder.map(1).str("key").str("value");
+        let bytes = encoder.into_writer().freeze();
+
+        let mut expected = Encoder::new(Vec::new());
+        expected.map(1).str("key").str("value");
+        assert_eq!(expected.into_writer(), bytes);
     }
 }
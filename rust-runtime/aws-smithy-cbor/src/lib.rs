@@ -9,6 +9,7 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 /* End of automatically managed default lints */
 
+pub mod codec;
 pub mod data;
 pub mod decode;
 pub mod encode;
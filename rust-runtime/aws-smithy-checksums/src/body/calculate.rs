@@ -8,13 +8,16 @@
 use crate::http::HttpChecksum;
 
 use aws_smithy_http::header::append_merge_header_maps;
+use aws_smithy_types::base64;
 use aws_smithy_types::body::SdkBody;
 
-use http::HeaderMap;
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue};
 use http_body::SizeHint;
 use pin_project_lite::pin_project;
 
 use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
 use std::task::{Context, Poll};
 
 pin_project! {
@@ -23,6 +26,8 @@ pin_project! {
             #[pin]
             body: InnerBody,
             checksum: Option<Box<dyn HttpChecksum>>,
+            content_length: u64,
+            calculated_checksum: CalculatedChecksum,
     }
 }
 
@@ -32,10 +37,43 @@ impl ChecksumBody<SdkBody> {
         Self {
             body,
             checksum: Some(checksum),
+            content_length: 0,
+            calculated_checksum: CalculatedChecksum::default(),
         }
     }
 }
 
+impl<InnerBody> ChecksumBody<InnerBody> {
+    /// Return a handle to the checksum and length of this body, which are available once the
+    /// body has been read to the end and its trailers have been polled.
+    ///
+    /// The handle can be kept after the body is handed off to be sent, e.g. to combine the
+    /// checksums of an object's parts with [`FullObjectChecksum`](crate::multipart::FullObjectChecksum).
+    pub fn calculated_checksum(&self) -> CalculatedChecksum {
+        self.calculated_checksum.clone()
+    }
+}
+
+/// The raw checksum and length of the data read through a [`ChecksumBody`].
+#[derive(Debug, Clone, Default)]
+pub struct CalculatedChecksum {
+    inner: Arc<OnceLock<(Bytes, u64)>>,
+}
+
+impl CalculatedChecksum {
+    /// The checksum, as returned by [`Checksum::finalize`](crate::Checksum::finalize), or `None`
+    /// if the body hasn't been read to the end yet.
+    pub fn checksum(&self) -> Option<Bytes> {
+        self.inner.get().map(|(checksum, _)| checksum.clone())
+    }
+
+    /// The number of bytes the checksum was calculated from, or `None` if the body hasn't been
+    /// read to the end yet.
+    pub fn content_length(&self) -> Option<u64> {
+        self.inner.get().map(|(_, content_length)| *content_length)
+    }
+}
+
 impl http_body::Body for ChecksumBody<SdkBody> {
     type Data = bytes::Bytes;
     type Error = aws_smithy_types::body::Error;
@@ -50,6 +88,7 @@ impl http_body::Body for ChecksumBody<SdkBody> {
                 let poll_res = this.body.poll_data(cx);
                 if let Poll::Ready(Some(Ok(data))) = &poll_res {
                     checksum.update(data);
+                    *this.content_length += data.len() as u64;
                 }
 
                 poll_res
@@ -67,7 +106,19 @@ impl http_body::Body for ChecksumBody<SdkBody> {
 
         if let Poll::Ready(Ok(maybe_inner_trailers)) = poll_res {
             let checksum_headers = if let Some(checksum) = this.checksum.take() {
-                checksum.headers()
+                let header_name = checksum.header_name();
+                let checksum = checksum.finalize();
+                let mut checksum_headers = HeaderMap::new();
+                checksum_headers.insert(
+                    header_name,
+                    HeaderValue::from_str(&base64::encode(&checksum[..]))
+                        .expect("base64 encoded bytes are always valid header values"),
+                );
+                let _ = this
+                    .calculated_checksum
+                    .inner
+                    .set((checksum, *this.content_length));
+                checksum_headers
             } else {
                 return Poll::Ready(Ok(None));
             };
@@ -98,6 +149,7 @@ impl http_body::Body for ChecksumBody<SdkBody> {
 #[cfg(test)]
 mod tests {
     use super::ChecksumBody;
+    use crate::multipart::FullObjectChecksum;
     use crate::{http::CRC_32_HEADER_NAME, ChecksumAlgorithm, CRC_32_NAME};
     use aws_smithy_types::base64;
     use aws_smithy_types::body::SdkBody;
@@ -155,4 +207,44 @@ mod tests {
         // Known correct checksum for the input "This is some test text for an SdkBody"
         assert_eq!("0x99B01F72", checksum_trailer);
     }
+
+    #[tokio::test]
+    async fn test_part_checksums_combine_into_full_object_checksum() {
+        let parts = ["The quick brown ", "fox jumps over ", "the lazy dog"];
+        for algorithm in [
+            ChecksumAlgorithm::Crc32,
+            ChecksumAlgorithm::Crc32c,
+            ChecksumAlgorithm::Crc64Nvme,
+        ] {
+            let mut full_object_checksum = FullObjectChecksum::new(algorithm).unwrap();
+            for part in parts {
+                let mut body = ChecksumBody::new(SdkBody::from(part), algorithm.into_impl());
+                let calculated_checksum = body.calculated_checksum();
+                assert_eq!(None, calculated_checksum.checksum());
+                while let Some(buf) = body.data().await {
+                    buf.unwrap();
+                }
+                body.trailers().await.unwrap();
+
+                assert_eq!(
+                    Some(part.len() as u64),
+                    calculated_checksum.content_length()
+                );
+                full_object_checksum
+                    .add_part(
+                        &calculated_checksum.checksum().unwrap(),
+                        calculated_checksum.content_length().unwrap(),
+                    )
+                    .unwrap();
+            }
+
+            let mut whole_object = algorithm.into_impl();
+            whole_object.update(parts.concat().as_bytes());
+            assert_eq!(
+                whole_object.finalize(),
+                full_object_checksum.finalize(),
+                "{algorithm:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,164 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Combining CRCs of consecutive pieces of data, without the data.
+//!
+//! This uses the same approach as zlib's `crc32_combine`: appending `len` bytes of data to a
+//! message multiplies the message's CRC by `x^(8 * len)` modulo the CRC polynomial, so the CRC
+//! of both pieces is `crc1 * x^(8 * len2) mod p` xor'd with `crc2`. All of the CRCs supported
+//! here are reflected and start from (and are xor'd with) all ones, which cancel out.
+
+use crate::error::CombineChecksumsError;
+use bytes::Bytes;
+
+/// The parameters of a reflected CRC.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CrcParams {
+    /// The width of the CRC in bits.
+    width: u32,
+    /// The reflected polynomial.
+    poly: u64,
+}
+
+pub(crate) const CRC_32: CrcParams = CrcParams {
+    width: 32,
+    poly: 0xEDB8_8320,
+};
+
+pub(crate) const CRC_32_C: CrcParams = CrcParams {
+    width: 32,
+    poly: 0x82F6_3B78,
+};
+
+pub(crate) const CRC_64_NVME: CrcParams = CrcParams {
+    width: 64,
+    poly: 0x9A6C_9329_AC4B_C9B5,
+};
+
+impl CrcParams {
+    /// The size of the CRC in bytes.
+    fn size(&self) -> usize {
+        self.width as usize / 8
+    }
+
+    /// `x^0`, which is the most significant bit since the CRC is reflected.
+    fn one(&self) -> u64 {
+        1 << (self.width - 1)
+    }
+
+    /// Decode a big-endian CRC, as returned by `Checksum::finalize`.
+    pub(crate) fn decode(
+        &self,
+        algorithm: &'static str,
+        checksum: &[u8],
+    ) -> Result<u64, CombineChecksumsError> {
+        if checksum.len() != self.size() {
+            return Err(CombineChecksumsError::invalid_length(
+                algorithm,
+                self.size(),
+                checksum.len(),
+            ));
+        }
+        Ok(checksum
+            .iter()
+            .fold(0, |crc, byte| (crc << 8) | u64::from(*byte)))
+    }
+
+    /// Encode a CRC as big-endian bytes, as returned by `Checksum::finalize`.
+    pub(crate) fn encode(&self, crc: u64) -> Bytes {
+        Bytes::copy_from_slice(&crc.to_be_bytes()[8 - self.size()..])
+    }
+
+    /// Return `a * b mod p`.
+    fn multiply(&self, a: u64, mut b: u64) -> u64 {
+        let mut product = 0;
+        let mut m = self.one();
+        while m != 0 {
+            if a & m != 0 {
+                product ^= b;
+            }
+            // Multiply `b` by `x`
+            b = if b & 1 != 0 {
+                (b >> 1) ^ self.poly
+            } else {
+                b >> 1
+            };
+            m >>= 1;
+        }
+        product
+    }
+
+    /// Return `x^(8 * len) mod p`.
+    fn x_pow_8n(&self, mut len: u64) -> u64 {
+        let mut result = self.one();
+        // `x^8`, which is squared to get `x^(8 * 2^k)` for each bit `k` of `len`
+        let mut power = self.one() >> 8;
+        while len != 0 {
+            if len & 1 != 0 {
+                result = self.multiply(power, result);
+            }
+            power = self.multiply(power, power);
+            len >>= 1;
+        }
+        result
+    }
+
+    /// Return the CRC of two consecutive pieces of data, given the CRC of each and the length
+    /// of the second.
+    pub(crate) fn combine(&self, crc1: u64, crc2: u64, len2: u64) -> u64 {
+        self.multiply(self.x_pow_8n(len2), crc1) ^ crc2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CRC_32, CRC_32_C, CRC_64_NVME};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn combine_matches_crc_of_concatenation(data: Vec<u8>, split in 0usize..1024) {
+            let split = split.min(data.len());
+            let (first, second) = data.split_at(split);
+            let len2 = second.len() as u64;
+
+            let crc32 = crc32fast::hash;
+            prop_assert_eq!(
+                u64::from(crc32(&data)),
+                CRC_32.combine(u64::from(crc32(first)), u64::from(crc32(second)), len2)
+            );
+
+            let crc32c = crc32c::crc32c;
+            prop_assert_eq!(
+                u64::from(crc32c(&data)),
+                CRC_32_C.combine(u64::from(crc32c(first)), u64::from(crc32c(second)), len2)
+            );
+
+            let crc64 = |data: &[u8]| {
+                let mut digest = crc64fast_nvme::Digest::new();
+                digest.write(data);
+                digest.sum64()
+            };
+            prop_assert_eq!(
+                crc64(&data),
+                CRC_64_NVME.combine(crc64(first), crc64(second), len2)
+            );
+        }
+    }
+
+    #[test]
+    fn combine_large_lengths() {
+        // The CRC of a megabyte of zeroes, combined from 1024 kilobytes of zeroes
+        let kilobyte = vec![0u8; 1024];
+        let crc_of_kilobyte = u64::from(crc32fast::hash(&kilobyte));
+        let combined = (1..1024).fold(crc_of_kilobyte, |crc, _| {
+            CRC_32.combine(crc, crc_of_kilobyte, 1024)
+        });
+        assert_eq!(
+            u64::from(crc32fast::hash(&vec![0u8; 1024 * 1024])),
+            combined
+        );
+    }
+}
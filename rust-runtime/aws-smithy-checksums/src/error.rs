@@ -29,10 +29,93 @@ impl fmt::Display for UnknownChecksumAlgorithmError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            r#"unknown checksum algorithm "{}", please pass a known algorithm name ("crc32", "crc32c", "sha1", "sha256", "md5")"#,
+            r#"unknown checksum algorithm "{}", please pass a known algorithm name ("crc32", "crc32c", "crc64nvme", "sha1", "sha256", "md5")"#,
             self.checksum_algorithm
         )
     }
 }
 
 impl Error for UnknownChecksumAlgorithmError {}
+
+#[derive(Debug)]
+enum CombineChecksumsErrorKind {
+    UnsupportedAlgorithm {
+        algorithm: &'static str,
+    },
+    InvalidLength {
+        algorithm: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+/// Checksums couldn't be combined
+#[derive(Debug)]
+pub struct CombineChecksumsError {
+    kind: CombineChecksumsErrorKind,
+}
+
+impl CombineChecksumsError {
+    pub(crate) fn unsupported_algorithm(algorithm: &'static str) -> Self {
+        Self {
+            kind: CombineChecksumsErrorKind::UnsupportedAlgorithm { algorithm },
+        }
+    }
+
+    pub(crate) fn invalid_length(algorithm: &'static str, expected: usize, actual: usize) -> Self {
+        Self {
+            kind: CombineChecksumsErrorKind::InvalidLength {
+                algorithm,
+                expected,
+                actual,
+            },
+        }
+    }
+}
+
+impl fmt::Display for CombineChecksumsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            CombineChecksumsErrorKind::UnsupportedAlgorithm { algorithm } => write!(
+                f,
+                "`{algorithm}` checksums can't be combined, only CRC checksums can"
+            ),
+            CombineChecksumsErrorKind::InvalidLength {
+                algorithm,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "`{algorithm}` checksums are {expected} bytes long, but one was {actual} bytes long"
+            ),
+        }
+    }
+}
+
+impl Error for CombineChecksumsError {}
+
+/// A composite checksum couldn't be parsed
+#[derive(Debug)]
+pub struct InvalidCompositeChecksumError {
+    value: String,
+}
+
+impl InvalidCompositeChecksumError {
+    pub(crate) fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl fmt::Display for InvalidCompositeChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` is not a valid composite checksum, expected `<base64 checksum>-<part count>`",
+            self.value
+        )
+    }
+}
+
+impl Error for InvalidCompositeChecksumError {}
@@ -16,17 +16,20 @@
 
 //! Checksum calculation and verification callbacks.
 
-use crate::error::UnknownChecksumAlgorithmError;
+use crate::error::{CombineChecksumsError, UnknownChecksumAlgorithmError};
 use bytes::Bytes;
 use std::str::FromStr;
 
 pub mod body;
+mod crc_combine;
 pub mod error;
 pub mod http;
+pub mod multipart;
 
 // Valid checksum algorithm names
 pub const CRC_32_NAME: &str = "crc32";
 pub const CRC_32_C_NAME: &str = "crc32c";
+pub const CRC_64_NVME_NAME: &str = "crc64nvme";
 pub const SHA_1_NAME: &str = "sha1";
 pub const SHA_256_NAME: &str = "sha256";
 pub const MD5_NAME: &str = "md5";
@@ -36,6 +39,7 @@ pub const MD5_NAME: &str = "md5";
 pub enum ChecksumAlgorithm {
     Crc32,
     Crc32c,
+    Crc64Nvme,
     Md5,
     Sha1,
     Sha256,
@@ -47,6 +51,7 @@ impl FromStr for ChecksumAlgorithm {
     /// Create a new `ChecksumAlgorithm` from an algorithm name. Valid algorithm names are:
     /// - "crc32"
     /// - "crc32c"
+    /// - "crc64nvme"
     /// - "sha1"
     /// - "sha256"
     /// - "md5"
@@ -57,6 +62,8 @@ impl FromStr for ChecksumAlgorithm {
             Ok(Self::Crc32)
         } else if checksum_algorithm.eq_ignore_ascii_case(CRC_32_C_NAME) {
             Ok(Self::Crc32c)
+        } else if checksum_algorithm.eq_ignore_ascii_case(CRC_64_NVME_NAME) {
+            Ok(Self::Crc64Nvme)
         } else if checksum_algorithm.eq_ignore_ascii_case(SHA_1_NAME) {
             Ok(Self::Sha1)
         } else if checksum_algorithm.eq_ignore_ascii_case(SHA_256_NAME) {
@@ -75,6 +82,7 @@ impl ChecksumAlgorithm {
         match self {
             Self::Crc32 => Box::<Crc32>::default(),
             Self::Crc32c => Box::<Crc32c>::default(),
+            Self::Crc64Nvme => Box::<Crc64Nvme>::default(),
             Self::Md5 => Box::<Md5>::default(),
             Self::Sha1 => Box::<Sha1>::default(),
             Self::Sha256 => Box::<Sha256>::default(),
@@ -86,11 +94,53 @@ impl ChecksumAlgorithm {
         match self {
             Self::Crc32 => CRC_32_NAME,
             Self::Crc32c => CRC_32_C_NAME,
+            Self::Crc64Nvme => CRC_64_NVME_NAME,
             Self::Md5 => MD5_NAME,
             Self::Sha1 => SHA_1_NAME,
             Self::Sha256 => SHA_256_NAME,
         }
     }
+
+    /// Return true if checksums calculated with this algorithm can be [combined](Self::combine).
+    ///
+    /// This is true for the CRC algorithms, and false for the cryptographic hash algorithms.
+    pub fn is_combinable(&self) -> bool {
+        self.crc_params().is_some()
+    }
+
+    /// Combine the checksums of two consecutive pieces of data into the checksum of both pieces
+    /// together, without needing the data itself.
+    ///
+    /// `first` and `second` are checksums as returned by [`Checksum::finalize`] (i.e. the
+    /// decoded value of a checksum header or trailer), and `second_len` is the length in bytes
+    /// of the data that `second` was calculated from.
+    ///
+    /// This makes it possible to calculate the checksum of a whole object from the checksums
+    /// of its parts, as computed by a [`ChecksumBody`](crate::body::calculate::ChecksumBody) for
+    /// each part. Returns an error if this algorithm isn't [combinable](Self::is_combinable),
+    /// or if either checksum has the wrong length.
+    pub fn combine(
+        &self,
+        first: &[u8],
+        second: &[u8],
+        second_len: u64,
+    ) -> Result<Bytes, CombineChecksumsError> {
+        let params = self
+            .crc_params()
+            .ok_or_else(|| CombineChecksumsError::unsupported_algorithm(self.as_str()))?;
+        let first = params.decode(self.as_str(), first)?;
+        let second = params.decode(self.as_str(), second)?;
+        Ok(params.encode(params.combine(first, second, second_len)))
+    }
+
+    fn crc_params(&self) -> Option<crc_combine::CrcParams> {
+        match self {
+            Self::Crc32 => Some(crc_combine::CRC_32),
+            Self::Crc32c => Some(crc_combine::CRC_32_C),
+            Self::Crc64Nvme => Some(crc_combine::CRC_64_NVME),
+            Self::Md5 | Self::Sha1 | Self::Sha256 => None,
+        }
+    }
 }
 
 /// Types implementing this trait can calculate checksums.
@@ -183,6 +233,45 @@ impl Checksum for Crc32c {
     }
 }
 
+#[derive(Default)]
+struct Crc64Nvme {
+    hasher: crc64fast_nvme::Digest,
+}
+
+// `crc64fast_nvme::Digest` doesn't implement `Debug`
+impl std::fmt::Debug for Crc64Nvme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Crc64Nvme").finish()
+    }
+}
+
+impl Crc64Nvme {
+    fn update(&mut self, bytes: &[u8]) {
+        self.hasher.write(bytes);
+    }
+
+    fn finalize(self) -> Bytes {
+        Bytes::copy_from_slice(self.hasher.sum64().to_be_bytes().as_slice())
+    }
+
+    // Size of the checksum in bytes
+    fn size() -> u64 {
+        8
+    }
+}
+
+impl Checksum for Crc64Nvme {
+    fn update(&mut self, bytes: &[u8]) {
+        Self::update(self, bytes)
+    }
+    fn finalize(self: Box<Self>) -> Bytes {
+        Self::finalize(*self)
+    }
+    fn size(&self) -> u64 {
+        Self::size()
+    }
+}
+
 #[derive(Debug, Default)]
 struct Sha1 {
     hasher: sha1::Sha1,
@@ -293,10 +382,10 @@ impl Checksum for Md5 {
 mod tests {
     use super::{
         http::{
-            CRC_32_C_HEADER_NAME, CRC_32_HEADER_NAME, MD5_HEADER_NAME, SHA_1_HEADER_NAME,
-            SHA_256_HEADER_NAME,
+            CRC_32_C_HEADER_NAME, CRC_32_HEADER_NAME, CRC_64_NVME_HEADER_NAME, MD5_HEADER_NAME,
+            SHA_1_HEADER_NAME, SHA_256_HEADER_NAME,
         },
-        Crc32, Crc32c, Md5, Sha1, Sha256,
+        Crc32, Crc32c, Crc64Nvme, Md5, Sha1, Sha256,
     };
 
     use crate::http::HttpChecksum;
@@ -349,6 +438,19 @@ mod tests {
         assert_eq!(decoded_checksum, expected_checksum);
     }
 
+    #[test]
+    fn test_crc64nvme_checksum() {
+        let mut checksum = Crc64Nvme::default();
+        checksum.update(TEST_DATA.as_bytes());
+        let checksum_result = Box::new(checksum).headers();
+        let encoded_checksum = checksum_result.get(CRC_64_NVME_HEADER_NAME).unwrap();
+        let decoded_checksum = base64_encoded_checksum_to_hex_string(encoded_checksum);
+
+        let expected_checksum = "0xAECAF3AF9C98A855";
+
+        assert_eq!(decoded_checksum, expected_checksum);
+    }
+
     #[test]
     fn test_sha1_checksum() {
         let mut checksum = Sha1::default();
@@ -399,4 +501,55 @@ mod tests {
             error.checksum_algorithm()
         );
     }
+
+    #[test]
+    fn test_combine_crc_checksums() {
+        let (first, second) = TEST_DATA.as_bytes().split_at(4);
+        for algorithm in [
+            ChecksumAlgorithm::Crc32,
+            ChecksumAlgorithm::Crc32c,
+            ChecksumAlgorithm::Crc64Nvme,
+        ] {
+            let checksum_of = |data: &[u8]| {
+                let mut checksum = algorithm.into_impl();
+                checksum.update(data);
+                checksum.finalize()
+            };
+            assert!(algorithm.is_combinable());
+            let combined = algorithm
+                .combine(
+                    &checksum_of(first),
+                    &checksum_of(second),
+                    second.len() as u64,
+                )
+                .unwrap();
+            assert_eq!(checksum_of(TEST_DATA.as_bytes()), combined, "{algorithm:?}");
+
+            // Combining with the checksum of no data doesn't change the checksum
+            let combined = algorithm
+                .combine(&checksum_of(first), &checksum_of(b""), 0)
+                .unwrap();
+            assert_eq!(checksum_of(first), combined, "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn test_combine_errors() {
+        let error = ChecksumAlgorithm::Sha256
+            .combine(&[0; 32], &[0; 32], 1)
+            .expect_err("hashes can't be combined");
+        assert_eq!(
+            "`sha256` checksums can't be combined, only CRC checksums can",
+            error.to_string()
+        );
+        assert!(!ChecksumAlgorithm::Sha256.is_combinable());
+
+        let error = ChecksumAlgorithm::Crc32
+            .combine(&[0; 4], &[0; 8], 1)
+            .expect_err("the second checksum is too long");
+        assert_eq!(
+            "`crc32` checksums are 4 bytes long, but one was 8 bytes long",
+            error.to_string()
+        );
+    }
 }
@@ -0,0 +1,243 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Checksums of objects that were uploaded in multiple parts.
+//!
+//! The checksum of a multipart object is either:
+//! - A *full object* checksum, which is the checksum of the whole object. Since CRCs can be
+//!   [combined](crate::ChecksumAlgorithm::combine), it can be calculated from the checksums of
+//!   the parts with [`FullObjectChecksum`], and compared with a checksum of the whole object
+//!   calculated while reading it, without reading it again.
+//! - A *composite* checksum, which is the checksum of the concatenated part checksums, followed
+//!   by `-` and the number of parts (e.g. `"a3Jmlw==-3"`). It can be calculated from the
+//!   checksums of the parts with [`CompositeChecksum`].
+
+use crate::error::{CombineChecksumsError, InvalidCompositeChecksumError};
+use crate::http::HttpChecksum;
+use crate::ChecksumAlgorithm;
+use aws_smithy_types::base64;
+use bytes::Bytes;
+use std::fmt;
+use std::str::FromStr;
+
+/// Calculates the full object checksum of a multipart object from the checksums of its parts.
+///
+/// Only CRC checksums can be combined into a full object checksum.
+#[derive(Debug, Clone)]
+pub struct FullObjectChecksum {
+    algorithm: ChecksumAlgorithm,
+    checksum: Bytes,
+}
+
+impl FullObjectChecksum {
+    /// Create a new `FullObjectChecksum` for the given algorithm, or return an error if the
+    /// algorithm isn't [combinable](ChecksumAlgorithm::is_combinable).
+    pub fn new(algorithm: ChecksumAlgorithm) -> Result<Self, CombineChecksumsError> {
+        if !algorithm.is_combinable() {
+            return Err(CombineChecksumsError::unsupported_algorithm(
+                algorithm.as_str(),
+            ));
+        }
+        Ok(Self {
+            algorithm,
+            // The checksum of no data, which combining the first part will replace
+            checksum: algorithm.into_impl().finalize(),
+        })
+    }
+
+    /// Add the next part, given its checksum and length in bytes.
+    ///
+    /// Parts must be added in order.
+    pub fn add_part(
+        &mut self,
+        part_checksum: &[u8],
+        part_len: u64,
+    ) -> Result<(), CombineChecksumsError> {
+        self.checksum = self
+            .algorithm
+            .combine(&self.checksum, part_checksum, part_len)?;
+        Ok(())
+    }
+
+    /// Return the checksum of the whole object.
+    pub fn finalize(self) -> Bytes {
+        self.checksum
+    }
+}
+
+/// Calculates the composite checksum of a multipart object from the checksums of its parts.
+pub struct CompositeChecksum {
+    checksum: Box<dyn HttpChecksum>,
+    part_count: u64,
+}
+
+impl fmt::Debug for CompositeChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeChecksum")
+            .field("part_count", &self.part_count)
+            .finish()
+    }
+}
+
+impl CompositeChecksum {
+    /// Create a new `CompositeChecksum` for the given algorithm.
+    pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+        Self {
+            checksum: algorithm.into_impl(),
+            part_count: 0,
+        }
+    }
+
+    /// Add the checksum of the next part.
+    ///
+    /// Parts must be added in order.
+    pub fn add_part(&mut self, part_checksum: &[u8]) {
+        self.checksum.update(part_checksum);
+        self.part_count += 1;
+    }
+
+    /// Return the composite checksum of the parts that were added.
+    pub fn finalize(self) -> CompositeChecksumValue {
+        CompositeChecksumValue {
+            checksum: self.checksum.finalize(),
+            part_count: self.part_count,
+        }
+    }
+}
+
+/// A composite checksum, which displays and parses as `<base64 checksum>-<part count>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeChecksumValue {
+    checksum: Bytes,
+    part_count: u64,
+}
+
+impl CompositeChecksumValue {
+    /// Create a new `CompositeChecksumValue` from the checksum of the part checksums and the
+    /// number of parts.
+    pub fn new(checksum: impl Into<Bytes>, part_count: u64) -> Self {
+        Self {
+            checksum: checksum.into(),
+            part_count,
+        }
+    }
+
+    /// The checksum of the concatenated part checksums.
+    pub fn checksum(&self) -> &Bytes {
+        &self.checksum
+    }
+
+    /// The number of parts.
+    pub fn part_count(&self) -> u64 {
+        self.part_count
+    }
+}
+
+impl fmt::Display for CompositeChecksumValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            base64::encode(&self.checksum[..]),
+            self.part_count
+        )
+    }
+}
+
+impl FromStr for CompositeChecksumValue {
+    type Err = InvalidCompositeChecksumError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (checksum, part_count) = value
+            .rsplit_once('-')
+            .ok_or_else(|| InvalidCompositeChecksumError::new(value))?;
+        let part_count = part_count
+            .parse::<u64>()
+            .map_err(|_| InvalidCompositeChecksumError::new(value))?;
+        let checksum =
+            base64::decode(checksum).map_err(|_| InvalidCompositeChecksumError::new(value))?;
+        Ok(Self::new(checksum, part_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompositeChecksum, CompositeChecksumValue, FullObjectChecksum};
+    use crate::ChecksumAlgorithm;
+    use bytes::Bytes;
+    use pretty_assertions::assert_eq;
+
+    const PARTS: [&[u8]; 3] = [b"The quick brown ", b"fox jumps over ", b"the lazy dog"];
+
+    fn checksum_of(algorithm: ChecksumAlgorithm, data: &[u8]) -> Bytes {
+        let mut checksum = algorithm.into_impl();
+        checksum.update(data);
+        checksum.finalize()
+    }
+
+    #[test]
+    fn full_object_checksum_matches_checksum_of_whole_object() {
+        let whole_object = PARTS.concat();
+        for algorithm in [
+            ChecksumAlgorithm::Crc32,
+            ChecksumAlgorithm::Crc32c,
+            ChecksumAlgorithm::Crc64Nvme,
+        ] {
+            let mut full_object_checksum = FullObjectChecksum::new(algorithm).unwrap();
+            for part in PARTS {
+                full_object_checksum
+                    .add_part(&checksum_of(algorithm, part), part.len() as u64)
+                    .unwrap();
+            }
+            assert_eq!(
+                checksum_of(algorithm, &whole_object),
+                full_object_checksum.finalize(),
+                "{algorithm:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn full_object_checksum_requires_a_crc() {
+        FullObjectChecksum::new(ChecksumAlgorithm::Sha1).expect_err("SHA-1 can't be combined");
+    }
+
+    #[test]
+    fn composite_checksum() {
+        let mut composite_checksum = CompositeChecksum::new(ChecksumAlgorithm::Crc32);
+        let mut part_checksums = Vec::new();
+        for part in PARTS {
+            let part_checksum = checksum_of(ChecksumAlgorithm::Crc32, part);
+            composite_checksum.add_part(&part_checksum);
+            part_checksums.extend_from_slice(&part_checksum);
+        }
+        let composite_checksum = composite_checksum.finalize();
+        assert_eq!(3, composite_checksum.part_count());
+        assert_eq!(
+            &checksum_of(ChecksumAlgorithm::Crc32, &part_checksums),
+            composite_checksum.checksum()
+        );
+
+        let displayed = composite_checksum.to_string();
+        assert!(displayed.ends_with("-3"), "{displayed}");
+        assert_eq!(
+            composite_checksum,
+            displayed.parse::<CompositeChecksumValue>().unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_composite_checksum() {
+        let value = "3aSGzw==-10".parse::<CompositeChecksumValue>().unwrap();
+        assert_eq!(10, value.part_count());
+        assert_eq!(&[0xdd, 0xa4, 0x86, 0xcf][..], &value.checksum()[..]);
+
+        for invalid in ["3aSGzw==", "3aSGzw==-", "3aSGzw==-x", "!!!-1", ""] {
+            invalid
+                .parse::<CompositeChecksumValue>()
+                .expect_err(invalid);
+        }
+    }
+}
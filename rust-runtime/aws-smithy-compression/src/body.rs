@@ -3,12 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! HTTP body-wrappers that perform request compression
+//! HTTP body-wrappers that perform request compression and response decompression
+
+// `http-body` 1.x delivers trailers as a frame, which has to be held back when (de)compression
+// still has data to return at the end of the stream.
+#[cfg(feature = "http-body-1-x")]
+type PendingTrailers = http_1_0::HeaderMap;
+#[cfg(not(feature = "http-body-1-x"))]
+type PendingTrailers = std::convert::Infallible;
 
-// Putting this in a `mod` since I expect we'll have to handle response
-// decompression some day.
 /// Functionality for compressing an HTTP request body.
 pub mod compress {
+    use super::PendingTrailers;
     use aws_smithy_types::body::SdkBody;
     use pin_project_lite::pin_project;
 
@@ -17,11 +23,16 @@ pub mod compress {
         ///
         /// Compression options may disable request compression for small data payload, or entirely.
         /// Additionally, some services may not support compression.
+        ///
+        /// Data is compressed as it arrives, so streaming request bodies can be compressed too.
+        /// Once the inner body is exhausted, the compressor is finished and any data it was still
+        /// holding on to is returned.
         pub struct CompressedBody<InnerBody, CompressionImpl> {
             #[pin]
             body: InnerBody,
             compress_request: CompressionImpl,
             is_end_stream: bool,
+            pending_trailers: Option<PendingTrailers>,
         }
     }
 
@@ -32,6 +43,7 @@ pub mod compress {
                 body,
                 compress_request,
                 is_end_stream: false,
+                pending_trailers: None,
             }
         }
     }
@@ -46,7 +58,7 @@ pub mod compress {
         use http_0_2::HeaderMap;
         use http_body_0_4::{Body, SizeHint};
         use std::pin::Pin;
-        use std::task::{Context, Poll};
+        use std::task::{ready, Context, Poll};
 
         impl Body for CompressedBody<SdkBody, Box<dyn CompressRequest>> {
             type Data = bytes::Bytes;
@@ -56,18 +68,27 @@ pub mod compress {
                 self: Pin<&mut Self>,
                 cx: &mut Context<'_>,
             ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
-                let this = self.project();
-                match this.body.poll_data(cx)? {
-                    Poll::Ready(Some(data)) => {
-                        let mut out = Vec::new();
-                        this.compress_request.compress_bytes(&data[..], &mut out)?;
-                        Poll::Ready(Some(Ok(out.into())))
+                let mut this = self.project();
+                loop {
+                    if *this.is_end_stream {
+                        return Poll::Ready(None);
                     }
-                    Poll::Ready(None) => {
-                        *this.is_end_stream = true;
-                        Poll::Ready(None)
+                    let mut out = Vec::new();
+                    match ready!(this.body.as_mut().poll_data(cx)?) {
+                        Some(data) => {
+                            this.compress_request.compress_bytes(&data[..], &mut out)?;
+                        }
+                        None => {
+                            *this.is_end_stream = true;
+                            this.compress_request.finish(&mut out)?;
+                        }
+                    }
+                    // Streaming compressors may hold on to data until they have enough to
+                    // compress well, and empty chunks shouldn't be returned, so keep polling
+                    // until there's compressed data.
+                    if !out.is_empty() {
+                        return Poll::Ready(Some(Ok(out.into())));
                     }
-                    Poll::Pending => Poll::Pending,
                 }
             }
 
@@ -101,6 +122,7 @@ pub mod compress {
 
                 self.compress_request
                     .compress_bytes(bytes, &mut compressed_body)?;
+                self.compress_request.finish(&mut compressed_body)?;
                 Ok(SdkBody::from(compressed_body))
             }
         }
@@ -124,31 +146,44 @@ pub mod compress {
                 mut self: Pin<&mut Self>,
                 cx: &mut Context<'_>,
             ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
-                let this = self.as_mut().project();
-                Poll::Ready(match ready!(this.body.poll_frame(cx)) {
-                    Some(Ok(f)) => {
-                        if f.is_data() {
-                            let d = f.into_data().expect("we checked for data first");
-                            let mut out = Vec::new();
-                            this.compress_request.compress_bytes(&d, &mut out)?;
-                            Some(Ok(Frame::data(out.into())))
-                        } else if f.is_trailers() {
-                            // Trailers don't get compressed.
-                            Some(Ok(f))
-                        } else {
-                            unreachable!("Frame is either data or trailers")
+                let mut this = self.as_mut().project();
+                loop {
+                    if let Some(trailers) = this.pending_trailers.take() {
+                        return Poll::Ready(Some(Ok(Frame::trailers(trailers))));
+                    }
+                    if *this.is_end_stream {
+                        return Poll::Ready(None);
+                    }
+                    let mut out = Vec::new();
+                    match ready!(this.body.as_mut().poll_frame(cx)?) {
+                        Some(frame) => match frame.into_data() {
+                            Ok(data) => {
+                                this.compress_request.compress_bytes(&data, &mut out)?;
+                            }
+                            // Trailers don't get compressed, but they follow the last data
+                            // frame, so compression is finished before they're returned.
+                            Err(frame) => {
+                                let trailers = frame
+                                    .into_trailers()
+                                    .map_err(|_| "Frame is either data or trailers")?;
+                                *this.pending_trailers = Some(trailers);
+                                *this.is_end_stream = true;
+                                this.compress_request.finish(&mut out)?;
+                            }
+                        },
+                        None => {
+                            *this.is_end_stream = true;
+                            this.compress_request.finish(&mut out)?;
                         }
                     }
-                    None => {
-                        *this.is_end_stream = true;
-                        None
+                    if !out.is_empty() {
+                        return Poll::Ready(Some(Ok(Frame::data(out.into()))));
                     }
-                    other => other,
-                })
+                }
             }
 
             fn is_end_stream(&self) -> bool {
-                self.is_end_stream
+                self.is_end_stream && self.pending_trailers.is_none()
             }
 
             fn size_hint(&self) -> SizeHint {
@@ -160,6 +195,213 @@ pub mod compress {
     }
 }
 
+/// Functionality for decompressing an HTTP response body.
+pub mod decompress {
+    use super::PendingTrailers;
+    use aws_smithy_types::body::SdkBody;
+    use pin_project_lite::pin_project;
+
+    pin_project! {
+        /// A `Body` that decompresses its data with a `DecompressResponse` implementor.
+        ///
+        /// Data is decompressed as it arrives, so streaming response bodies are never buffered in
+        /// full. Once the inner body is exhausted, an error is returned if the compressed data
+        /// was incomplete.
+        ///
+        /// A small amount of compressed data can expand into a very large amount of decompressed
+        /// data, so a maximum decompressed size can be set with
+        /// [`with_max_decompressed_size`](DecompressedBody::with_max_decompressed_size).
+        pub struct DecompressedBody<InnerBody, DecompressionImpl> {
+            #[pin]
+            body: InnerBody,
+            decompress_response: DecompressionImpl,
+            is_end_stream: bool,
+            pending_trailers: Option<PendingTrailers>,
+            decompressed_size: u64,
+            max_decompressed_size: Option<u64>,
+        }
+    }
+
+    impl<DR> DecompressedBody<SdkBody, DR> {
+        /// Given an [`SdkBody`] and a `Box<dyn DecompressResponse>`, create a new `DecompressedBody<SdkBody, DR>`.
+        pub fn new(body: SdkBody, decompress_response: DR) -> Self {
+            Self {
+                body,
+                decompress_response,
+                is_end_stream: false,
+                pending_trailers: None,
+                decompressed_size: 0,
+                max_decompressed_size: None,
+            }
+        }
+    }
+
+    impl<InnerBody, DR> DecompressedBody<InnerBody, DR> {
+        /// Fail with an error once more than `max_decompressed_size` bytes have been decompressed.
+        pub fn with_max_decompressed_size(mut self, max_decompressed_size: u64) -> Self {
+            self.max_decompressed_size = Some(max_decompressed_size);
+            self
+        }
+    }
+
+    /// Add `len` newly decompressed bytes to `decompressed_size`, returning an error if that
+    /// exceeds `max_decompressed_size`.
+    #[cfg(any(feature = "http-body-0-4-x", feature = "http-body-1-x"))]
+    fn record_decompressed(
+        decompressed_size: &mut u64,
+        max_decompressed_size: Option<u64>,
+        len: usize,
+    ) -> Result<(), aws_smithy_types::body::Error> {
+        *decompressed_size = decompressed_size.saturating_add(len as u64);
+        match max_decompressed_size {
+            Some(max) if *decompressed_size > max => Err(format!(
+                "decompressed response body exceeded the maximum size of {max} bytes"
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Support for the `http-body-0-4` and `http-0-2` crates.
+    #[cfg(feature = "http-body-0-4-x")]
+    pub mod http_body_0_4_x {
+        use super::{record_decompressed, DecompressedBody};
+        use crate::http::http_body_0_4_x::DecompressResponse;
+        use aws_smithy_types::body::SdkBody;
+        use http_0_2::HeaderMap;
+        use http_body_0_4::{Body, SizeHint};
+        use std::pin::Pin;
+        use std::task::{ready, Context, Poll};
+
+        impl Body for DecompressedBody<SdkBody, Box<dyn DecompressResponse>> {
+            type Data = bytes::Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_data(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+                let mut this = self.project();
+                loop {
+                    if *this.is_end_stream {
+                        return Poll::Ready(None);
+                    }
+                    let mut out = Vec::new();
+                    match ready!(this.body.as_mut().poll_data(cx)?) {
+                        Some(data) => {
+                            this.decompress_response
+                                .decompress_bytes(&data[..], &mut out)?;
+                        }
+                        None => {
+                            *this.is_end_stream = true;
+                            this.decompress_response.finish(&mut out)?;
+                        }
+                    }
+                    record_decompressed(
+                        this.decompressed_size,
+                        *this.max_decompressed_size,
+                        out.len(),
+                    )?;
+                    // A chunk of compressed data doesn't always produce decompressed data, and
+                    // empty chunks shouldn't be returned, so keep polling until it does.
+                    if !out.is_empty() {
+                        return Poll::Ready(Some(Ok(out.into())));
+                    }
+                }
+            }
+
+            fn poll_trailers(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+                let this = self.project();
+                this.body.poll_trailers(cx)
+            }
+
+            fn is_end_stream(&self) -> bool {
+                self.is_end_stream
+            }
+
+            fn size_hint(&self) -> SizeHint {
+                // We can't return a hint because we don't know exactly how
+                // decompression will affect the content length
+                SizeHint::default()
+            }
+        }
+    }
+
+    /// Support for the `http-body-1-0` and `http-1-0` crates.
+    #[cfg(feature = "http-body-1-x")]
+    pub mod http_body_1_x {
+        use crate::body::decompress::{record_decompressed, DecompressedBody};
+        use crate::http::http_body_1_x::DecompressResponse;
+        use aws_smithy_types::body::SdkBody;
+        use http_body_1_0::{Body, Frame, SizeHint};
+        use std::pin::Pin;
+        use std::task::{ready, Context, Poll};
+
+        impl Body for DecompressedBody<SdkBody, Box<dyn DecompressResponse>> {
+            type Data = bytes::Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_frame(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+                let mut this = self.as_mut().project();
+                loop {
+                    if let Some(trailers) = this.pending_trailers.take() {
+                        return Poll::Ready(Some(Ok(Frame::trailers(trailers))));
+                    }
+                    if *this.is_end_stream {
+                        return Poll::Ready(None);
+                    }
+                    let mut out = Vec::new();
+                    match ready!(this.body.as_mut().poll_frame(cx)?) {
+                        Some(frame) => match frame.into_data() {
+                            Ok(data) => {
+                                this.decompress_response.decompress_bytes(&data, &mut out)?;
+                            }
+                            // Trailers follow the last data frame, so decompression is finished
+                            // before they're returned.
+                            Err(frame) => {
+                                let trailers = frame
+                                    .into_trailers()
+                                    .map_err(|_| "Frame is either data or trailers")?;
+                                *this.pending_trailers = Some(trailers);
+                                *this.is_end_stream = true;
+                                this.decompress_response.finish(&mut out)?;
+                            }
+                        },
+                        None => {
+                            *this.is_end_stream = true;
+                            this.decompress_response.finish(&mut out)?;
+                        }
+                    }
+                    record_decompressed(
+                        this.decompressed_size,
+                        *this.max_decompressed_size,
+                        out.len(),
+                    )?;
+                    if !out.is_empty() {
+                        return Poll::Ready(Some(Ok(Frame::data(out.into()))));
+                    }
+                }
+            }
+
+            fn is_end_stream(&self) -> bool {
+                self.is_end_stream && self.pending_trailers.is_none()
+            }
+
+            fn size_hint(&self) -> SizeHint {
+                // We can't return a hint because we don't know exactly how
+                // decompression will affect the content length
+                SizeHint::default()
+            }
+        }
+    }
+}
+
 #[cfg(any(feature = "http-body-0-4-x", feature = "http-body-1-x"))]
 #[cfg(test)]
 mod test {
@@ -178,7 +420,110 @@ mod test {
     #[cfg(feature = "http-body-0-4-x")]
     mod http_body_0_4_x {
         use super::*;
+        use crate::body::decompress::DecompressedBody;
+        use http_0_2::HeaderMap;
         use http_body_0_4::Body;
+        use std::collections::VecDeque;
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        /// A streaming body that yields its data one byte at a time.
+        struct Chunks(VecDeque<bytes::Bytes>);
+
+        impl Body for Chunks {
+            type Data = bytes::Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_data(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+                Poll::Ready(self.0.pop_front().map(Ok))
+            }
+
+            fn poll_trailers(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+                Poll::Ready(Ok(None))
+            }
+        }
+
+        fn streaming_body(data: &[u8]) -> SdkBody {
+            SdkBody::from_body_0_4(Chunks(
+                data.iter()
+                    .map(|byte| bytes::Bytes::from(vec![*byte]))
+                    .collect(),
+            ))
+        }
+
+        async fn decompress(body: SdkBody) -> Result<Vec<u8>, aws_smithy_types::body::Error> {
+            decompress_with(CompressionAlgorithm::Gzip, body).await
+        }
+
+        async fn decompress_with(
+            algorithm: CompressionAlgorithm,
+            body: SdkBody,
+        ) -> Result<Vec<u8>, aws_smithy_types::body::Error> {
+            let decompress_response = algorithm.into_decompress_impl_http_body_0_4_x();
+            let mut decompressed_body = DecompressedBody::new(body, decompress_response);
+            let mut output = Vec::new();
+            while let Some(buf) = decompressed_body.data().await {
+                let buf = buf?;
+                assert!(!buf.is_empty(), "empty chunks shouldn't be returned");
+                output.extend_from_slice(&buf);
+            }
+            assert!(decompressed_body.is_end_stream());
+            Ok(output)
+        }
+
+        #[tokio::test]
+        async fn test_body_is_decompressed() {
+            let output = decompress(SdkBody::from(COMPRESSED_OUTPUT)).await.unwrap();
+            assert_eq!(UNCOMPRESSED_INPUT, output);
+        }
+
+        #[tokio::test]
+        async fn test_streaming_body_is_decompressed() {
+            let output = decompress(streaming_body(COMPRESSED_OUTPUT)).await.unwrap();
+            assert_eq!(UNCOMPRESSED_INPUT, output);
+        }
+
+        #[tokio::test]
+        async fn test_max_decompressed_size_is_enforced() {
+            async fn decompress_at_most(
+                max: u64,
+            ) -> Result<Vec<u8>, aws_smithy_types::body::Error> {
+                let decompress_response =
+                    CompressionAlgorithm::Gzip.into_decompress_impl_http_body_0_4_x();
+                let mut decompressed_body =
+                    DecompressedBody::new(streaming_body(COMPRESSED_OUTPUT), decompress_response)
+                        .with_max_decompressed_size(max);
+                let mut output = Vec::new();
+                while let Some(buf) = decompressed_body.data().await {
+                    output.extend_from_slice(&buf?);
+                }
+                Ok(output)
+            }
+
+            let max = UNCOMPRESSED_INPUT.len() as u64;
+            assert_eq!(UNCOMPRESSED_INPUT, decompress_at_most(max).await.unwrap());
+            let error = decompress_at_most(max - 1)
+                .await
+                .expect_err("the body is larger than the maximum");
+            assert_eq!(
+                "decompressed response body exceeded the maximum size of 10 bytes",
+                error.to_string()
+            );
+        }
+
+        #[tokio::test]
+        async fn test_truncated_body_is_an_error() {
+            let truncated = &COMPRESSED_OUTPUT[..COMPRESSED_OUTPUT.len() - 1];
+            decompress(SdkBody::from(truncated))
+                .await
+                .expect_err("the body was truncated");
+        }
 
         #[tokio::test]
         async fn test_body_is_compressed() {
@@ -204,6 +549,27 @@ mod test {
             assert_eq!(COMPRESSED_OUTPUT, actual_output);
         }
 
+        #[cfg(any(feature = "zstd", feature = "brotli"))]
+        #[tokio::test]
+        async fn test_streaming_body_is_compressed_as_a_single_stream() {
+            let algorithms = [
+                #[cfg(feature = "zstd")]
+                CompressionAlgorithm::Zstd,
+                #[cfg(feature = "brotli")]
+                CompressionAlgorithm::Brotli,
+            ];
+            for algorithm in algorithms {
+                let compress_request =
+                    algorithm.into_impl_http_body_0_4_x(&CompressionOptions::default());
+                let compressed_body = SdkBody::from_body_0_4(CompressedBody::new(
+                    streaming_body(UNCOMPRESSED_INPUT),
+                    compress_request,
+                ));
+                let output = decompress_with(algorithm, compressed_body).await.unwrap();
+                assert_eq!(UNCOMPRESSED_INPUT, output, "{algorithm:?}");
+            }
+        }
+
         #[tokio::test]
         async fn test_into_compressed_sdk_body() {
             let compression_options = CompressionOptions::default()
@@ -227,8 +593,19 @@ mod test {
     #[cfg(feature = "http-body-1-x")]
     mod http_body_1_x {
         use super::*;
+        use crate::body::decompress::DecompressedBody;
         use http_body_util::BodyExt;
 
+        #[tokio::test]
+        async fn test_body_is_decompressed() {
+            let decompress_response =
+                CompressionAlgorithm::Gzip.into_decompress_impl_http_body_1_x();
+            let body = SdkBody::from(COMPRESSED_OUTPUT);
+            let decompressed_body = DecompressedBody::new(body, decompress_response);
+            let output = decompressed_body.collect().await.unwrap().to_bytes();
+            assert_eq!(UNCOMPRESSED_INPUT, output);
+        }
+
         #[tokio::test]
         async fn test_body_is_compressed() {
             let compression_options = CompressionOptions::default()
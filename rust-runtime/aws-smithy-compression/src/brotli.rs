@@ -0,0 +1,261 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::{Compress, CompressionOptions, Decompress};
+use ::brotli::{CompressorWriter, DecompressorWriter};
+use aws_smithy_runtime_api::box_error::BoxError;
+use std::fmt;
+use std::io::prelude::*;
+
+/// The size of the buffers used by the brotli encoder and decoder.
+const BUFFER_SIZE: usize = 4096;
+
+/// The base-2 logarithm of the sliding window size, which is what most brotli encoders default to.
+const LG_WINDOW_SIZE: u32 = 22;
+
+/// Compresses data as a single brotli stream, no matter how many chunks it's passed in.
+pub(crate) struct Brotli {
+    quality: u32,
+    /// The stream being compressed, which is started by the first chunk of data.
+    encoder: Option<CompressorWriter<Vec<u8>>>,
+}
+
+impl Brotli {
+    fn new(level: u32) -> Self {
+        Self {
+            // Brotli's quality ranges from 0 to 11, so compression levels map to it directly
+            quality: level,
+            encoder: None,
+        }
+    }
+
+    fn encoder(&mut self) -> &mut CompressorWriter<Vec<u8>> {
+        let quality = self.quality;
+        self.encoder.get_or_insert_with(|| {
+            CompressorWriter::new(Vec::new(), BUFFER_SIZE, quality, LG_WINDOW_SIZE)
+        })
+    }
+}
+
+// A clone starts a new stream with the same settings.
+impl Clone for Brotli {
+    fn clone(&self) -> Self {
+        Self::new(self.quality)
+    }
+}
+
+impl fmt::Debug for Brotli {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Brotli")
+            .field("quality", &self.quality)
+            .finish()
+    }
+}
+
+impl Compress for Brotli {
+    fn compress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError> {
+        let encoder = self.encoder();
+        encoder.write_all(bytes)?;
+        let compressed = encoder.get_mut();
+        writer.write_all(compressed)?;
+        compressed.clear();
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        // Even empty data has to be written as an (empty) brotli stream
+        self.encoder();
+        let encoder = self.encoder.take().expect("set above");
+        writer.write_all(&encoder.into_inner())?;
+        Ok(())
+    }
+}
+
+/// Incrementally decompresses a brotli stream.
+pub(crate) struct BrotliDecoder {
+    decoder: DecompressorWriter<Vec<u8>>,
+    received_data: bool,
+}
+
+impl Default for BrotliDecoder {
+    fn default() -> Self {
+        Self {
+            decoder: DecompressorWriter::new(Vec::new(), BUFFER_SIZE),
+            received_data: false,
+        }
+    }
+}
+
+impl fmt::Debug for BrotliDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BrotliDecoder").finish()
+    }
+}
+
+impl BrotliDecoder {
+    fn drain_into(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        let decompressed = self.decoder.get_mut();
+        writer.write_all(decompressed)?;
+        decompressed.clear();
+        Ok(())
+    }
+}
+
+impl Decompress for BrotliDecoder {
+    fn decompress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError> {
+        self.received_data |= !bytes.is_empty();
+        self.decoder.write_all(bytes)?;
+        self.drain_into(writer)
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        // Responses without a body (e.g. to `HEAD` requests) may still be labeled as brotli
+        if !self.received_data {
+            return Ok(());
+        }
+        self.decoder
+            .close()
+            .map_err(|err| format!("brotli response body was truncated or corrupt: {err}"))?;
+        self.drain_into(writer)
+    }
+}
+
+#[cfg(feature = "http-body-0-4-x")]
+mod http_body_0_4_x {
+    use crate::http::http_body_0_4_x::{CompressRequest, DecompressResponse};
+
+    impl CompressRequest for super::Brotli {
+        fn header_value(&self) -> http_0_2::HeaderValue {
+            http_0_2::HeaderValue::from_static("br")
+        }
+    }
+
+    impl DecompressResponse for super::BrotliDecoder {
+        fn header_value(&self) -> http_0_2::HeaderValue {
+            http_0_2::HeaderValue::from_static("br")
+        }
+    }
+}
+
+#[cfg(feature = "http-body-1-x")]
+mod http_body_1_x {
+    use crate::http::http_body_1_x::{CompressRequest, DecompressResponse};
+
+    impl CompressRequest for super::Brotli {
+        fn header_value(&self) -> http_1_0::HeaderValue {
+            http_1_0::HeaderValue::from_static("br")
+        }
+    }
+
+    impl DecompressResponse for super::BrotliDecoder {
+        fn header_value(&self) -> http_1_0::HeaderValue {
+            http_1_0::HeaderValue::from_static("br")
+        }
+    }
+}
+
+impl From<&CompressionOptions> for Brotli {
+    fn from(options: &CompressionOptions) -> Self {
+        Brotli::new(options.level)
+    }
+}
+
+impl From<CompressionOptions> for Brotli {
+    fn from(options: CompressionOptions) -> Self {
+        Brotli::new(options.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Brotli, BrotliDecoder};
+    use crate::{Compress, CompressionOptions, Decompress};
+    use pretty_assertions::assert_eq;
+    use std::io::Read;
+
+    fn gettysburg_address() -> &'static [u8] {
+        include_bytes!("../test-data/gettysburg_address.txt")
+    }
+
+    fn compress_in_chunks(brotli: &mut Brotli, data: &[u8], chunk_size: usize) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        for chunk in data.chunks(chunk_size) {
+            brotli.compress_bytes(chunk, &mut compressed).unwrap();
+        }
+        brotli.finish(&mut compressed).unwrap();
+        compressed
+    }
+
+    fn decompress(compressed: &[u8]) -> Vec<u8> {
+        let mut decompressed = Vec::new();
+        ::brotli::Decompressor::new(compressed, 4096)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        decompressed
+    }
+
+    #[test]
+    fn test_brotli_compression_in_chunks() {
+        let mut brotli = Brotli::from(&CompressionOptions::default());
+        let compressed = compress_in_chunks(&mut brotli, gettysburg_address(), 7);
+        assert!(compressed.len() < gettysburg_address().len());
+        assert_eq!(gettysburg_address(), &decompress(&compressed)[..]);
+    }
+
+    #[test]
+    fn test_brotli_compression_of_empty_data() {
+        let mut brotli = Brotli::from(&CompressionOptions::default());
+        let compressed = compress_in_chunks(&mut brotli, b"", 1);
+        assert!(!compressed.is_empty(), "an empty stream is still written");
+        assert!(decompress(&compressed).is_empty());
+    }
+
+    #[test]
+    fn test_brotli_clone_starts_a_new_stream() {
+        let mut brotli = Brotli::from(&CompressionOptions::default());
+        brotli
+            .compress_bytes(b"discarded", &mut Vec::new())
+            .unwrap();
+        let mut clone = brotli.clone();
+        let compressed = compress_in_chunks(&mut clone, b"hello world", 3);
+        assert_eq!(b"hello world", &decompress(&compressed)[..]);
+    }
+
+    #[test]
+    fn test_brotli_decompression_in_chunks() {
+        let mut brotli = Brotli::from(&CompressionOptions::default());
+        let compressed = compress_in_chunks(&mut brotli, gettysburg_address(), 100);
+
+        let mut decoder = BrotliDecoder::default();
+        let mut decompressed = Vec::new();
+        for chunk in compressed.chunks(7) {
+            decoder.decompress_bytes(chunk, &mut decompressed).unwrap();
+        }
+        decoder.finish(&mut decompressed).unwrap();
+        assert_eq!(gettysburg_address(), &decompressed[..]);
+    }
+
+    #[test]
+    fn test_brotli_decompression_of_truncated_stream() {
+        let mut brotli = Brotli::from(&CompressionOptions::default());
+        let compressed = compress_in_chunks(&mut brotli, gettysburg_address(), 100);
+        let mut decoder = BrotliDecoder::default();
+        let mut decompressed = Vec::new();
+        decoder
+            .decompress_bytes(&compressed[..compressed.len() / 2], &mut decompressed)
+            .unwrap();
+        decoder
+            .finish(&mut decompressed)
+            .expect_err("the brotli stream is incomplete");
+    }
+
+    #[test]
+    fn test_brotli_decompression_of_empty_body() {
+        let mut decoder = BrotliDecoder::default();
+        let mut decompressed = Vec::new();
+        decoder.finish(&mut decompressed).unwrap();
+        assert!(decompressed.is_empty());
+    }
+}
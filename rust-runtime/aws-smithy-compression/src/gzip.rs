@@ -3,9 +3,10 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::{Compress, CompressionOptions};
+use crate::{Compress, CompressionOptions, Decompress};
 use aws_smithy_runtime_api::box_error::BoxError;
-use flate2::write::GzEncoder;
+use flate2::write::{GzEncoder, MultiGzDecoder};
+use std::fmt;
 use std::io::prelude::*;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -29,26 +30,87 @@ impl Compress for Gzip {
     }
 }
 
+/// Incrementally decompresses a gzip stream, which may contain multiple gzip members.
+pub(crate) struct GzipDecoder {
+    decoder: MultiGzDecoder<Vec<u8>>,
+    received_data: bool,
+}
+
+impl Default for GzipDecoder {
+    fn default() -> Self {
+        Self {
+            decoder: MultiGzDecoder::new(Vec::new()),
+            received_data: false,
+        }
+    }
+}
+
+impl fmt::Debug for GzipDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GzipDecoder").finish()
+    }
+}
+
+impl GzipDecoder {
+    fn drain_into(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        let decompressed = self.decoder.get_mut();
+        writer.write_all(decompressed)?;
+        decompressed.clear();
+        Ok(())
+    }
+}
+
+impl Decompress for GzipDecoder {
+    fn decompress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError> {
+        self.received_data |= !bytes.is_empty();
+        self.decoder.write_all(bytes)?;
+        self.drain_into(writer)
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        // Responses without a body (e.g. to `HEAD` requests) may still be labeled as gzip
+        if !self.received_data {
+            return Ok(());
+        }
+        self.decoder
+            .try_finish()
+            .map_err(|err| format!("gzip response body was truncated or corrupt: {err}"))?;
+        self.drain_into(writer)
+    }
+}
+
 #[cfg(feature = "http-body-0-4-x")]
 mod http_body_0_4_x {
-    use crate::http::http_body_0_4_x::CompressRequest;
+    use crate::http::http_body_0_4_x::{CompressRequest, DecompressResponse};
 
     impl CompressRequest for super::Gzip {
         fn header_value(&self) -> http_0_2::HeaderValue {
             http_0_2::HeaderValue::from_static("gzip")
         }
     }
+
+    impl DecompressResponse for super::GzipDecoder {
+        fn header_value(&self) -> http_0_2::HeaderValue {
+            http_0_2::HeaderValue::from_static("gzip")
+        }
+    }
 }
 
 #[cfg(feature = "http-body-1-x")]
 mod http_body_1_x {
-    use crate::http::http_body_1_x::CompressRequest;
+    use crate::http::http_body_1_x::{CompressRequest, DecompressResponse};
 
     impl CompressRequest for super::Gzip {
         fn header_value(&self) -> http_1_0::HeaderValue {
             http_1_0::HeaderValue::from_static("gzip")
         }
     }
+
+    impl DecompressResponse for super::GzipDecoder {
+        fn header_value(&self) -> http_1_0::HeaderValue {
+            http_1_0::HeaderValue::from_static("gzip")
+        }
+    }
 }
 
 impl From<&CompressionOptions> for Gzip {
@@ -70,8 +132,8 @@ impl From<CompressionOptions> for Gzip {
 // Windows line-endings will cause the compression test to fail.
 #[cfg(all(test, not(windows)))]
 mod tests {
-    use super::Gzip;
-    use crate::CompressionOptions;
+    use super::{Gzip, GzipDecoder};
+    use crate::{CompressionOptions, Decompress};
     use flate2::read::GzDecoder;
     use pretty_assertions::assert_eq;
     use std::io::Read;
@@ -110,4 +172,56 @@ mod tests {
 
         assert_eq!(uncompressed_expected, uncompressed_actual);
     }
+
+    #[test]
+    fn test_gzip_decompression_in_chunks() {
+        let mut decoder = GzipDecoder::default();
+        let mut decompressed = Vec::new();
+        for chunk in gzip_compressed_gettysburg_address().chunks(7) {
+            decoder
+                .decompress_bytes(chunk, &mut decompressed)
+                .expect("decompression succeeds");
+        }
+        decoder
+            .finish(&mut decompressed)
+            .expect("stream is complete");
+        assert_eq!(gettysburg_address(), &decompressed[..]);
+    }
+
+    #[test]
+    fn test_gzip_decompression_of_multiple_members() {
+        let gzip = Gzip::from(&CompressionOptions::default());
+        let mut compressed = Vec::new();
+        gzip.compress_bytes(b"hello ", &mut compressed).unwrap();
+        gzip.compress_bytes(b"world", &mut compressed).unwrap();
+
+        let mut decoder = GzipDecoder::default();
+        let mut decompressed = Vec::new();
+        decoder
+            .decompress_bytes(&compressed, &mut decompressed)
+            .unwrap();
+        decoder.finish(&mut decompressed).unwrap();
+        assert_eq!(b"hello world", &decompressed[..]);
+    }
+
+    #[test]
+    fn test_gzip_decompression_of_truncated_stream() {
+        let compressed = gzip_compressed_gettysburg_address();
+        let mut decoder = GzipDecoder::default();
+        let mut decompressed = Vec::new();
+        decoder
+            .decompress_bytes(&compressed[..compressed.len() - 4], &mut decompressed)
+            .unwrap();
+        decoder
+            .finish(&mut decompressed)
+            .expect_err("the gzip trailer is incomplete");
+    }
+
+    #[test]
+    fn test_gzip_decompression_of_empty_body() {
+        let mut decoder = GzipDecoder::default();
+        let mut decompressed = Vec::new();
+        decoder.finish(&mut decompressed).unwrap();
+        assert!(decompressed.is_empty());
+    }
 }
@@ -3,12 +3,12 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! Checksum support for HTTP requests and responses.
+//! Compression support for HTTP requests and responses.
 
 /// Support for the `http-body-0-4` and `http-0-2` crates.
 #[cfg(feature = "http-body-0-4-x")]
 pub mod http_body_0_4_x {
-    use crate::Compress;
+    use crate::{Compress, Decompress};
     use http_0_2::header::{HeaderName, HeaderValue};
 
     /// Implementors of this trait can be used to compress HTTP requests.
@@ -42,12 +42,25 @@ pub mod http_body_0_4_x {
             self.clone_request_compressor()
         }
     }
+
+    /// Implementors of this trait can be used to decompress HTTP responses.
+    pub trait DecompressResponse: Decompress {
+        /// Return the header name for the accept-encoding header.
+        fn header_name(&self) -> HeaderName {
+            HeaderName::from_static("accept-encoding")
+        }
+
+        /// Return the header value for the accept-encoding header.
+        ///
+        /// This is also the `content-encoding` of the responses this implementor can decompress.
+        fn header_value(&self) -> HeaderValue;
+    }
 }
 
 /// Support for the `http-body-1-0` and `http-1-0` crates.
 #[cfg(feature = "http-body-1-x")]
 pub mod http_body_1_x {
-    use crate::Compress;
+    use crate::{Compress, Decompress};
     use http_1_0::header::{HeaderName, HeaderValue};
 
     /// Implementors of this trait can be used to compress HTTP requests.
@@ -81,4 +94,17 @@ pub mod http_body_1_x {
             self.clone_request_compressor()
         }
     }
+
+    /// Implementors of this trait can be used to decompress HTTP responses.
+    pub trait DecompressResponse: Decompress {
+        /// Return the header name for the accept-encoding header.
+        fn header_name(&self) -> HeaderName {
+            HeaderName::from_static("accept-encoding")
+        }
+
+        /// Return the header value for the accept-encoding header.
+        ///
+        /// This is also the `content-encoding` of the responses this implementor can decompress.
+        fn header_value(&self) -> HeaderValue;
+    }
 }
@@ -15,6 +15,13 @@
 )]
 
 //! Compression-related code.
+//!
+//! Request bodies can be compressed with [`body::compress::CompressedBody`], and response
+//! bodies that were compressed by the server (as indicated by their `content-encoding`) can be
+//! decompressed with [`body::decompress::DecompressedBody`].
+//!
+//! `gzip` is always supported. `zstd` and `brotli` are supported when the crate features of the
+//! same name are enabled.
 
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
@@ -22,12 +29,20 @@ use std::io::Write;
 use std::str::FromStr;
 
 pub mod body;
+#[cfg(feature = "brotli")]
+mod brotli;
 mod gzip;
 pub mod http;
+#[cfg(feature = "zstd")]
+mod zstd;
 
 // Valid compression algorithm names
 /// The name of the `gzip` algorithm.
 pub const GZIP_NAME: &str = "gzip";
+/// The name of the `zstd` algorithm.
+pub const ZSTD_NAME: &str = "zstd";
+/// The name of the `brotli` algorithm.
+pub const BROTLI_NAME: &str = "br";
 
 /// The maximum-allowable value per internal standards is 10 Megabytes.
 const MAX_MIN_COMPRESSION_SIZE_BYTES: u32 = 10_485_760;
@@ -42,6 +57,35 @@ pub trait Compress: Send + Sync {
     /// bytes to the writer until done.
     // I wanted to use `impl Write` but that's not object-safe
     fn compress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError>;
+
+    /// Finish compressing after the last chunk was passed to [`Compress::compress_bytes`],
+    /// writing any remaining compressed data to the writer.
+    ///
+    /// Streaming compressors may hold on to data between chunks to compress it better, and
+    /// must write it out (along with the end of the compressed stream) here. Compressors that
+    /// write a complete compressed stream for every chunk don't need to do anything, which is
+    /// the default.
+    fn finish(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        let _ = writer;
+        Ok(())
+    }
+}
+
+/// Types implementing this trait can decompress data.
+///
+/// Unlike [`Compress`], decompression is incremental so that streaming response bodies can be
+/// decompressed as they arrive. Compressed data is passed in chunk by chunk, and decompressed
+/// data is written out as soon as it's available.
+pub trait Decompress: Send + Sync {
+    /// Given the next chunk of compressed bytes and a [Write] implementor, decompress the chunk
+    /// and write any decompressed data that is available to the writer.
+    fn decompress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError>;
+
+    /// Finish decompressing after the last chunk was passed to [`Decompress::decompress_bytes`],
+    /// writing any remaining decompressed data to the writer.
+    ///
+    /// Returns an error if the compressed data was incomplete.
+    fn finish(&mut self, writer: &mut dyn Write) -> Result<(), BoxError>;
 }
 
 /// Options for configuring request compression.
@@ -145,6 +189,12 @@ impl Storable for CompressionOptions {
 pub enum CompressionAlgorithm {
     /// The [gzip](https://en.wikipedia.org/wiki/Gzip) compression algorithm
     Gzip,
+    /// The [zstd](https://en.wikipedia.org/wiki/Zstd) compression algorithm
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// The [brotli](https://en.wikipedia.org/wiki/Brotli) compression algorithm
+    #[cfg(feature = "brotli")]
+    Brotli,
 }
 
 impl FromStr for CompressionAlgorithm {
@@ -154,14 +204,23 @@ impl FromStr for CompressionAlgorithm {
     ///
     /// Valid algorithm names are:
     /// - "gzip"
+    /// - "zstd" (requires the `zstd` feature)
+    /// - "br" (requires the `brotli` feature)
     ///
     /// Passing an invalid name will return an error.
     fn from_str(compression_algorithm: &str) -> Result<Self, Self::Err> {
         if compression_algorithm.eq_ignore_ascii_case(GZIP_NAME) {
-            Ok(Self::Gzip)
-        } else {
-            Err(format!("unknown compression algorithm `{compression_algorithm}`").into())
+            return Ok(Self::Gzip);
+        }
+        #[cfg(feature = "zstd")]
+        if compression_algorithm.eq_ignore_ascii_case(ZSTD_NAME) {
+            return Ok(Self::Zstd);
         }
+        #[cfg(feature = "brotli")]
+        if compression_algorithm.eq_ignore_ascii_case(BROTLI_NAME) {
+            return Ok(Self::Brotli);
+        }
+        Err(format!("unknown compression algorithm `{compression_algorithm}`").into())
     }
 }
 
@@ -174,6 +233,10 @@ impl CompressionAlgorithm {
     ) -> Box<dyn http::http_body_0_4_x::CompressRequest> {
         match self {
             Self::Gzip => Box::new(gzip::Gzip::from(options)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::new(zstd::Zstd::from(options)),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::new(brotli::Brotli::from(options)),
         }
     }
 
@@ -185,6 +248,38 @@ impl CompressionAlgorithm {
     ) -> Box<dyn http::http_body_1_x::CompressRequest> {
         match self {
             Self::Gzip => Box::new(gzip::Gzip::from(options)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::new(zstd::Zstd::from(options)),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::new(brotli::Brotli::from(options)),
+        }
+    }
+
+    #[cfg(feature = "http-body-0-4-x")]
+    /// Return the `DecompressResponse` implementor for this algorithm.
+    pub fn into_decompress_impl_http_body_0_4_x(
+        self,
+    ) -> Box<dyn http::http_body_0_4_x::DecompressResponse> {
+        match self {
+            Self::Gzip => Box::<gzip::GzipDecoder>::default(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::<zstd::ZstdDecoder>::default(),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::<brotli::BrotliDecoder>::default(),
+        }
+    }
+
+    #[cfg(feature = "http-body-1-x")]
+    /// Return the `DecompressResponse` implementor for this algorithm.
+    pub fn into_decompress_impl_http_body_1_x(
+        self,
+    ) -> Box<dyn http::http_body_1_x::DecompressResponse> {
+        match self {
+            Self::Gzip => Box::<gzip::GzipDecoder>::default(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::<zstd::ZstdDecoder>::default(),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::<brotli::BrotliDecoder>::default(),
         }
     }
 
@@ -192,6 +287,10 @@ impl CompressionAlgorithm {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Gzip { .. } => GZIP_NAME,
+            #[cfg(feature = "zstd")]
+            Self::Zstd => ZSTD_NAME,
+            #[cfg(feature = "brotli")]
+            Self::Brotli => BROTLI_NAME,
         }
     }
 }
@@ -217,4 +316,20 @@ mod tests {
         let algo = "gzip".parse::<CompressionAlgorithm>().unwrap();
         assert_eq!("gzip", algo.as_str());
     }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_compression_algorithm_from_str_zstd() {
+        let algo = "zstd".parse::<CompressionAlgorithm>().unwrap();
+        assert_eq!(CompressionAlgorithm::Zstd, algo);
+        assert_eq!("zstd", algo.as_str());
+    }
+
+    #[cfg(feature = "brotli")]
+    #[test]
+    fn test_compression_algorithm_from_str_brotli() {
+        let algo = "br".parse::<CompressionAlgorithm>().unwrap();
+        assert_eq!(CompressionAlgorithm::Brotli, algo);
+        assert_eq!("br", algo.as_str());
+    }
 }
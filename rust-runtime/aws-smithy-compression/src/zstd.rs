@@ -0,0 +1,261 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::{Compress, CompressionOptions, Decompress};
+use ::zstd::stream::raw;
+use ::zstd::stream::write::Encoder;
+use ::zstd::stream::zio;
+use aws_smithy_runtime_api::box_error::BoxError;
+use std::fmt;
+use std::io::prelude::*;
+
+/// Compresses data as a single zstd stream, no matter how many chunks it's passed in.
+pub(crate) struct Zstd {
+    level: i32,
+    /// The stream being compressed, which is started by the first chunk of data.
+    encoder: Option<Encoder<'static, Vec<u8>>>,
+}
+
+impl Zstd {
+    fn new(level: u32) -> Self {
+        Self {
+            // Level 0 means "the default level" to zstd, which has no uncompressed mode, so the
+            // fastest level is used instead.
+            level: level.max(1) as i32,
+            encoder: None,
+        }
+    }
+
+    fn encoder(&mut self) -> Result<&mut Encoder<'static, Vec<u8>>, BoxError> {
+        if self.encoder.is_none() {
+            self.encoder = Some(Encoder::new(Vec::new(), self.level)?);
+        }
+        Ok(self.encoder.as_mut().expect("set above"))
+    }
+}
+
+// A clone starts a new stream with the same settings.
+impl Clone for Zstd {
+    fn clone(&self) -> Self {
+        Self {
+            level: self.level,
+            encoder: None,
+        }
+    }
+}
+
+impl fmt::Debug for Zstd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Zstd").field("level", &self.level).finish()
+    }
+}
+
+impl Compress for Zstd {
+    fn compress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError> {
+        let encoder = self.encoder()?;
+        encoder.write_all(bytes)?;
+        let compressed = encoder.get_mut();
+        writer.write_all(compressed)?;
+        compressed.clear();
+        Ok(())
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        // Even empty data has to be written as a (empty) zstd frame
+        self.encoder()?;
+        let encoder = self.encoder.take().expect("set above");
+        writer.write_all(&encoder.finish()?)?;
+        Ok(())
+    }
+}
+
+/// Incrementally decompresses a zstd stream, which may contain multiple zstd frames.
+#[derive(Default)]
+pub(crate) struct ZstdDecoder {
+    /// The decoder, which is created when the first chunk of data is received.
+    decoder: Option<zio::Writer<Vec<u8>, raw::Decoder<'static>>>,
+}
+
+impl fmt::Debug for ZstdDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZstdDecoder").finish()
+    }
+}
+
+fn drain_into(
+    decoder: &mut zio::Writer<Vec<u8>, raw::Decoder<'static>>,
+    writer: &mut dyn Write,
+) -> Result<(), BoxError> {
+    let decompressed = decoder.writer_mut();
+    writer.write_all(decompressed)?;
+    decompressed.clear();
+    Ok(())
+}
+
+impl Decompress for ZstdDecoder {
+    fn decompress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        if self.decoder.is_none() {
+            self.decoder = Some(zio::Writer::new(Vec::new(), raw::Decoder::new()?));
+        }
+        let decoder = self.decoder.as_mut().expect("set above");
+        decoder.write_all(bytes)?;
+        // Decompressed data is buffered by the decoder until it's flushed
+        decoder.flush()?;
+        drain_into(decoder, writer)
+    }
+
+    fn finish(&mut self, writer: &mut dyn Write) -> Result<(), BoxError> {
+        // Responses without a body (e.g. to `HEAD` requests) may still be labeled as zstd
+        let Some(decoder) = self.decoder.as_mut() else {
+            return Ok(());
+        };
+        decoder
+            .finish()
+            .map_err(|err| format!("zstd response body was truncated or corrupt: {err}"))?;
+        drain_into(decoder, writer)
+    }
+}
+
+#[cfg(feature = "http-body-0-4-x")]
+mod http_body_0_4_x {
+    use crate::http::http_body_0_4_x::{CompressRequest, DecompressResponse};
+
+    impl CompressRequest for super::Zstd {
+        fn header_value(&self) -> http_0_2::HeaderValue {
+            http_0_2::HeaderValue::from_static("zstd")
+        }
+    }
+
+    impl DecompressResponse for super::ZstdDecoder {
+        fn header_value(&self) -> http_0_2::HeaderValue {
+            http_0_2::HeaderValue::from_static("zstd")
+        }
+    }
+}
+
+#[cfg(feature = "http-body-1-x")]
+mod http_body_1_x {
+    use crate::http::http_body_1_x::{CompressRequest, DecompressResponse};
+
+    impl CompressRequest for super::Zstd {
+        fn header_value(&self) -> http_1_0::HeaderValue {
+            http_1_0::HeaderValue::from_static("zstd")
+        }
+    }
+
+    impl DecompressResponse for super::ZstdDecoder {
+        fn header_value(&self) -> http_1_0::HeaderValue {
+            http_1_0::HeaderValue::from_static("zstd")
+        }
+    }
+}
+
+impl From<&CompressionOptions> for Zstd {
+    fn from(options: &CompressionOptions) -> Self {
+        Zstd::new(options.level)
+    }
+}
+
+impl From<CompressionOptions> for Zstd {
+    fn from(options: CompressionOptions) -> Self {
+        Zstd::new(options.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Zstd, ZstdDecoder};
+    use crate::{Compress, CompressionOptions, Decompress};
+    use pretty_assertions::assert_eq;
+
+    fn gettysburg_address() -> &'static [u8] {
+        include_bytes!("../test-data/gettysburg_address.txt")
+    }
+
+    fn compress_in_chunks(zstd: &mut Zstd, data: &[u8], chunk_size: usize) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        for chunk in data.chunks(chunk_size) {
+            zstd.compress_bytes(chunk, &mut compressed).unwrap();
+        }
+        zstd.finish(&mut compressed).unwrap();
+        compressed
+    }
+
+    #[test]
+    fn test_zstd_compression_in_chunks() {
+        let mut zstd = Zstd::from(&CompressionOptions::default());
+        let compressed = compress_in_chunks(&mut zstd, gettysburg_address(), 7);
+        assert!(compressed.len() < gettysburg_address().len());
+        // The chunks are compressed as a single frame
+        assert_eq!(
+            gettysburg_address(),
+            &::zstd::stream::decode_all(&compressed[..]).unwrap()[..]
+        );
+    }
+
+    #[test]
+    fn test_zstd_compression_of_empty_data() {
+        let mut zstd = Zstd::from(&CompressionOptions::default());
+        let compressed = compress_in_chunks(&mut zstd, b"", 1);
+        assert!(!compressed.is_empty(), "an empty frame is still written");
+        assert!(::zstd::stream::decode_all(&compressed[..])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_zstd_clone_starts_a_new_stream() {
+        let mut zstd = Zstd::from(&CompressionOptions::default());
+        zstd.compress_bytes(b"discarded", &mut Vec::new()).unwrap();
+        let mut clone = zstd.clone();
+        let compressed = compress_in_chunks(&mut clone, b"hello world", 3);
+        assert_eq!(
+            b"hello world",
+            &::zstd::stream::decode_all(&compressed[..]).unwrap()[..]
+        );
+    }
+
+    #[test]
+    fn test_zstd_decompression_in_chunks() {
+        let mut zstd = Zstd::from(&CompressionOptions::default());
+        let mut compressed = compress_in_chunks(&mut zstd, b"hello ", 100);
+        compressed.extend(compress_in_chunks(&mut zstd, gettysburg_address(), 100));
+
+        let mut decoder = ZstdDecoder::default();
+        let mut decompressed = Vec::new();
+        for chunk in compressed.chunks(7) {
+            decoder.decompress_bytes(chunk, &mut decompressed).unwrap();
+        }
+        decoder.finish(&mut decompressed).unwrap();
+        let mut expected = b"hello ".to_vec();
+        expected.extend_from_slice(gettysburg_address());
+        assert_eq!(expected, decompressed);
+    }
+
+    #[test]
+    fn test_zstd_decompression_of_truncated_stream() {
+        let mut zstd = Zstd::from(&CompressionOptions::default());
+        let compressed = compress_in_chunks(&mut zstd, gettysburg_address(), 100);
+        let mut decoder = ZstdDecoder::default();
+        let mut decompressed = Vec::new();
+        decoder
+            .decompress_bytes(&compressed[..compressed.len() - 4], &mut decompressed)
+            .unwrap();
+        decoder
+            .finish(&mut decompressed)
+            .expect_err("the zstd frame is incomplete");
+    }
+
+    #[test]
+    fn test_zstd_decompression_of_empty_body() {
+        let mut decoder = ZstdDecoder::default();
+        let mut decompressed = Vec::new();
+        decoder.finish(&mut decompressed).unwrap();
+        assert!(decompressed.is_empty());
+    }
+}
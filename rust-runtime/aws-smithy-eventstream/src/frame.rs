@@ -18,6 +18,8 @@ use std::fmt;
 use std::mem::size_of;
 use std::sync::{mpsc, Mutex};
 
+pub mod view;
+
 const PRELUDE_LENGTH_BYTES: u32 = 3 * size_of::<u32>() as u32;
 const PRELUDE_LENGTH_BYTES_USIZE: usize = PRELUDE_LENGTH_BYTES as usize;
 const MESSAGE_CRC_LENGTH_BYTES: u32 = size_of::<u32>() as u32;
@@ -388,14 +390,51 @@ pub fn read_message_from<B: Buf>(mut buffer: B) -> Result<Message, Error> {
     Ok(Message::new_from_parts(headers, payload))
 }
 
-/// Writes the `message` to the given `buffer`.
-pub fn write_message_to(message: &Message, buffer: &mut dyn BufMut) -> Result<(), Error> {
-    let mut headers = Vec::new();
-    for header in message.headers() {
-        write_header_to(header, &mut headers)?;
+/// Returns the number of bytes the header takes up when encoded, validating it along the way.
+fn encoded_header_len(header: &Header) -> Result<usize, Error> {
+    use HeaderValue::*;
+    let name_len = header.name().as_bytes().len();
+    if name_len > MAX_HEADER_NAME_LEN {
+        return Err(ErrorKind::InvalidHeaderNameLength.into());
     }
+    let value_len = match header.value() {
+        Bool(_) => 0,
+        Byte(_) => size_of::<i8>(),
+        Int16(_) => size_of::<i16>(),
+        Int32(_) => size_of::<i32>(),
+        Int64(_) => size_of::<i64>(),
+        ByteArray(val) => {
+            size_of::<u16>()
+                + checked::<u16, _>(val.len(), ErrorKind::HeaderValueTooLong.into())? as usize
+        }
+        String(val) => {
+            size_of::<u16>()
+                + checked::<u16, _>(val.as_bytes().len(), ErrorKind::HeaderValueTooLong.into())?
+                    as usize
+        }
+        Timestamp(time) => {
+            time.to_millis()
+                .map_err(|_| ErrorKind::TimestampValueTooLarge(*time))?;
+            size_of::<i64>()
+        }
+        Uuid(_) => size_of::<u128>(),
+        _ => {
+            panic!("matched on unexpected variant in `aws_smithy_types::event_stream::HeaderValue`")
+        }
+    };
+    // Name length, name, value type, and value
+    Ok(1 + name_len + 1 + value_len)
+}
 
-    let headers_len = checked(headers.len(), ErrorKind::HeadersTooLong.into())?;
+/// Writes the `message` to the given `buffer`.
+///
+/// The message is validated before anything is written, and then its prelude, headers,
+/// payload, and checksums are written in a single pass without any intermediate buffering.
+pub fn write_message_to(message: &Message, buffer: &mut dyn BufMut) -> Result<(), Error> {
+    let headers_len = message.headers().iter().try_fold(0usize, |acc, header| {
+        Ok::<_, Error>(acc.saturating_add(encoded_header_len(header)?))
+    })?;
+    let headers_len = checked(headers_len, ErrorKind::HeadersTooLong.into())?;
     let payload_len = checked(message.payload().len(), ErrorKind::PayloadTooLong.into())?;
     let message_len = [
         PRELUDE_LENGTH_BYTES,
@@ -413,7 +452,7 @@ pub fn write_message_to(message: &Message, buffer: &mut dyn BufMut) -> Result<()
     crc_buffer.put_u32(message_len);
     crc_buffer.put_u32(headers_len);
     crc_buffer.put_crc();
-    crc_buffer.put(&headers[..]);
+    write_headers_to(message.headers(), &mut crc_buffer)?;
     crc_buffer.put(&message.payload()[..]);
     crc_buffer.put_crc();
     Ok(())
@@ -604,6 +643,29 @@ mod message_tests {
         assert_eq!(message.headers(), result.headers());
         assert_eq!(message.payload().as_ref(), result.payload().as_ref());
     }
+
+    #[test]
+    fn invalid_messages_are_not_partially_written() {
+        let invalid_headers = [
+            Header::new("a".repeat(256), HeaderValue::Bool(true)),
+            Header::new(
+                "str",
+                HeaderValue::String("a".repeat(u16::MAX as usize + 1).into()),
+            ),
+            Header::new(
+                "time",
+                HeaderValue::Timestamp(DateTime::from_secs(i64::MAX)),
+            ),
+        ];
+        for invalid_header in invalid_headers {
+            let message = Message::new(&b"some payload"[..])
+                .add_header(Header::new("valid", HeaderValue::Int32(5)))
+                .add_header(invalid_header);
+            let mut actual = Vec::new();
+            write_message_to(&message, &mut actual).unwrap_err();
+            assert!(actual.is_empty());
+        }
+    }
 }
 
 /// Return value from [`MessageFrameDecoder`].
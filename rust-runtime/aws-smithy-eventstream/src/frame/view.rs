@@ -0,0 +1,445 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Zero-copy decoding of event stream messages.
+//!
+//! [`MessageView::decode`] validates a message in place. Its headers are read from the input
+//! as they're iterated, and its payload is a slice of the input [`Bytes`], so decoding a message
+//! neither copies nor allocates.
+//!
+//! When reading from a stream, append incoming data to a [`BytesMut`](bytes::BytesMut) and split
+//! complete frames off of it:
+//!
+//! ```
+//! use aws_smithy_eventstream::frame::view::{peek_frame_len, MessageView};
+//! use bytes::BytesMut;
+//!
+//! # fn dox(buffer: &mut BytesMut) -> Result<(), aws_smithy_eventstream::error::Error> {
+//! while let Some(frame_len) = peek_frame_len(buffer)? {
+//!     let frame = buffer.split_to(frame_len).freeze();
+//!     let message = MessageView::decode(&frame)?.expect("the frame is complete");
+//!     if let Some(event_type) = message.header(":event-type") {
+//!         println!("{event_type:?}: {} bytes", message.payload().len());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{
+    max_header_len, payload_len, MESSAGE_CRC_LENGTH_BYTES, MIN_HEADER_LEN, PRELUDE_LENGTH_BYTES,
+    PRELUDE_LENGTH_BYTES_USIZE, TYPE_BYTE, TYPE_BYTE_ARRAY, TYPE_FALSE, TYPE_INT16, TYPE_INT32,
+    TYPE_INT64, TYPE_STRING, TYPE_TIMESTAMP, TYPE_TRUE, TYPE_UUID,
+};
+use crate::error::{Error, ErrorKind};
+use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
+use aws_smithy_types::str_bytes::StrBytes;
+use aws_smithy_types::DateTime;
+use bytes::Bytes;
+use std::mem::size_of;
+
+/// A header value borrowed from the frame it was decoded from.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeaderValueView<'a> {
+    /// Represents a boolean value.
+    Bool(bool),
+    /// Represents a byte value.
+    Byte(i8),
+    /// Represents an int16 value.
+    Int16(i16),
+    /// Represents an int32 value.
+    Int32(i32),
+    /// Represents an int64 value.
+    Int64(i64),
+    /// Represents a byte array value.
+    ByteArray(&'a [u8]),
+    /// Represents a string value.
+    String(&'a str),
+    /// Represents a timestamp value.
+    Timestamp(DateTime),
+    /// Represents a uuid value.
+    Uuid(u128),
+}
+
+impl HeaderValueView<'_> {
+    /// Returns the value as an owned [`HeaderValue`], copying any borrowed data.
+    pub fn to_header_value(&self) -> HeaderValue {
+        match *self {
+            Self::Bool(val) => HeaderValue::Bool(val),
+            Self::Byte(val) => HeaderValue::Byte(val),
+            Self::Int16(val) => HeaderValue::Int16(val),
+            Self::Int32(val) => HeaderValue::Int32(val),
+            Self::Int64(val) => HeaderValue::Int64(val),
+            Self::ByteArray(val) => HeaderValue::ByteArray(Bytes::copy_from_slice(val)),
+            Self::String(val) => HeaderValue::String(StrBytes::from(val.to_owned())),
+            Self::Timestamp(val) => HeaderValue::Timestamp(val),
+            Self::Uuid(val) => HeaderValue::Uuid(val),
+        }
+    }
+}
+
+/// A header borrowed from the frame it was decoded from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeaderView<'a> {
+    name: &'a str,
+    value: HeaderValueView<'a>,
+}
+
+impl<'a> HeaderView<'a> {
+    /// Returns the header name.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the header value.
+    pub fn value(&self) -> HeaderValueView<'a> {
+        self.value
+    }
+
+    /// Returns the header as an owned [`Header`], copying any borrowed data.
+    pub fn to_header(&self) -> Header {
+        Header::new(self.name.to_owned(), self.value.to_header_value())
+    }
+}
+
+/// An event stream message that borrows its headers and payload from the frame it was decoded
+/// from.
+#[derive(Clone, Debug)]
+pub struct MessageView<'a> {
+    headers: &'a [u8],
+    payload: Bytes,
+    frame_len: usize,
+}
+
+impl<'a> MessageView<'a> {
+    /// Decodes the message frame at the start of `buffer` without copying it.
+    ///
+    /// Returns `Ok(None)` if `buffer` doesn't contain a complete frame yet. Any data after the
+    /// frame is ignored; use [`frame_len`](MessageView::frame_len) to find out where it starts.
+    pub fn decode(buffer: &'a Bytes) -> Result<Option<Self>, Error> {
+        let (total_len, header_len) = match read_prelude(buffer)? {
+            Some(prelude) => prelude,
+            None => return Ok(None),
+        };
+        let frame_len = total_len as usize;
+        if buffer.len() < frame_len {
+            return Ok(None);
+        }
+
+        let headers_end = PRELUDE_LENGTH_BYTES_USIZE + header_len as usize;
+        let payload_end = headers_end + payload_len(total_len, header_len)? as usize;
+        let headers = &buffer[PRELUDE_LENGTH_BYTES_USIZE..headers_end];
+        for header in HeaderViews::new(headers) {
+            header?;
+        }
+
+        let expected_crc = crc32fast::hash(&buffer[..payload_end]);
+        let message_crc = read_u32(&buffer[payload_end..]);
+        if expected_crc != message_crc {
+            return Err(ErrorKind::MessageChecksumMismatch(expected_crc, message_crc).into());
+        }
+
+        Ok(Some(MessageView {
+            headers,
+            payload: buffer.slice(headers_end..payload_end),
+            frame_len,
+        }))
+    }
+
+    /// Returns an iterator over the message's headers.
+    pub fn headers(&self) -> impl Iterator<Item = HeaderView<'a>> {
+        HeaderViews::new(self.headers).map(|header| header.expect("validated in `decode`"))
+    }
+
+    /// Returns the value of the first header with the given name, if any.
+    pub fn header(&self, name: &str) -> Option<HeaderValueView<'a>> {
+        self.headers()
+            .find(|header| header.name() == name)
+            .map(|header| header.value())
+    }
+
+    /// Returns the message payload, which shares its memory with the decoded frame.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Returns the length of the frame the message was decoded from, in bytes.
+    pub fn frame_len(&self) -> usize {
+        self.frame_len
+    }
+
+    /// Returns the message as an owned [`Message`].
+    ///
+    /// The headers are copied, but the payload still shares its memory with the decoded frame.
+    pub fn to_message(&self) -> Message {
+        Message::new_from_parts(
+            self.headers().map(|header| header.to_header()).collect(),
+            self.payload.clone(),
+        )
+    }
+}
+
+/// Returns the length of the message frame at the start of `buffer` if all of it is in `buffer`.
+///
+/// Returns `Ok(None)` if more data is needed. The frame's prelude is validated as soon as it's
+/// available, so a corrupt stream is detected before the rest of the frame arrives.
+pub fn peek_frame_len(buffer: &[u8]) -> Result<Option<usize>, Error> {
+    Ok(match read_prelude(buffer)? {
+        Some((total_len, _)) if buffer.len() >= total_len as usize => Some(total_len as usize),
+        _ => None,
+    })
+}
+
+// Returns (total_len, header_len), or `None` if the prelude isn't complete
+fn read_prelude(buffer: &[u8]) -> Result<Option<(u32, u32)>, Error> {
+    if buffer.len() < PRELUDE_LENGTH_BYTES_USIZE {
+        return Ok(None);
+    }
+    let total_len = read_u32(buffer);
+    let header_len = read_u32(&buffer[4..]);
+    let expected_crc = crc32fast::hash(&buffer[..8]);
+    let prelude_crc = read_u32(&buffer[8..]);
+    if expected_crc != prelude_crc {
+        return Err(ErrorKind::PreludeChecksumMismatch(expected_crc, prelude_crc).into());
+    }
+    if total_len < PRELUDE_LENGTH_BYTES + MESSAGE_CRC_LENGTH_BYTES {
+        return Err(ErrorKind::InvalidMessageLength.into());
+    }
+    // The header length can be 0 or >= 2, but must fit within the frame size
+    if header_len == 1 || header_len > max_header_len(total_len)? {
+        return Err(ErrorKind::InvalidHeadersLength.into());
+    }
+    Ok(Some((total_len, header_len)))
+}
+
+fn read_u32(buffer: &[u8]) -> u32 {
+    u32::from_be_bytes(buffer[..4].try_into().expect("4 bytes"))
+}
+
+/// Parses headers out of a message's header bytes.
+struct HeaderViews<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> HeaderViews<'a> {
+    fn new(headers: &'a [u8]) -> Self {
+        Self { remaining: headers }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.remaining.len() < len {
+            return Err(ErrorKind::InvalidHeaderValue.into());
+        }
+        let (taken, remaining) = self.remaining.split_at(len);
+        self.remaining = remaining;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().expect("N bytes"))
+    }
+
+    fn read_header(&mut self) -> Result<HeaderView<'a>, Error> {
+        if self.remaining.len() < MIN_HEADER_LEN {
+            return Err(ErrorKind::InvalidHeadersLength.into());
+        }
+        let name_len = self.take(1)?[0] as usize;
+        if name_len >= self.remaining.len() {
+            return Err(ErrorKind::InvalidHeaderNameLength.into());
+        }
+        let name =
+            std::str::from_utf8(self.take(name_len)?).map_err(|_| ErrorKind::InvalidUtf8String)?;
+        let value = self.read_header_value()?;
+        Ok(HeaderView { name, value })
+    }
+
+    fn read_header_value(&mut self) -> Result<HeaderValueView<'a>, Error> {
+        let value_type = self.take(1)?[0];
+        Ok(match value_type {
+            TYPE_TRUE => HeaderValueView::Bool(true),
+            TYPE_FALSE => HeaderValueView::Bool(false),
+            TYPE_BYTE => HeaderValueView::Byte(i8::from_be_bytes(self.take_array()?)),
+            TYPE_INT16 => HeaderValueView::Int16(i16::from_be_bytes(self.take_array()?)),
+            TYPE_INT32 => HeaderValueView::Int32(i32::from_be_bytes(self.take_array()?)),
+            TYPE_INT64 => HeaderValueView::Int64(i64::from_be_bytes(self.take_array()?)),
+            TYPE_BYTE_ARRAY | TYPE_STRING => {
+                if self.remaining.len() <= size_of::<u16>() {
+                    return Err(ErrorKind::InvalidHeaderValue.into());
+                }
+                let len = u16::from_be_bytes(self.take_array()?) as usize;
+                let bytes = self.take(len)?;
+                if value_type == TYPE_STRING {
+                    HeaderValueView::String(
+                        std::str::from_utf8(bytes).map_err(|_| ErrorKind::InvalidUtf8String)?,
+                    )
+                } else {
+                    HeaderValueView::ByteArray(bytes)
+                }
+            }
+            TYPE_TIMESTAMP => HeaderValueView::Timestamp(DateTime::from_millis(
+                i64::from_be_bytes(self.take_array()?),
+            )),
+            TYPE_UUID => HeaderValueView::Uuid(u128::from_be_bytes(self.take_array()?)),
+            _ => return Err(ErrorKind::InvalidHeaderValueType(value_type).into()),
+        })
+    }
+}
+
+impl<'a> Iterator for HeaderViews<'a> {
+    type Item = Result<HeaderView<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let result = self.read_header();
+        if result.is_err() {
+            self.remaining = &[];
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{peek_frame_len, HeaderValueView, MessageView};
+    use crate::error::ErrorKind;
+    use crate::frame::{read_message_from, write_message_to};
+    use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
+    use bytes::{Bytes, BytesMut};
+
+    const VALID_MESSAGES: &[&[u8]] = &[
+        include_bytes!("../../test_data/valid_empty_payload"),
+        include_bytes!("../../test_data/valid_no_headers"),
+        include_bytes!("../../test_data/valid_with_all_headers_and_payload"),
+    ];
+
+    #[test]
+    fn decodes_the_same_as_read_message_from() {
+        for data in VALID_MESSAGES {
+            let buffer = Bytes::from_static(data);
+            let view = MessageView::decode(&buffer).unwrap().unwrap();
+            let message = read_message_from(&mut &data[..]).unwrap();
+            assert_eq!(message.headers(), view.to_message().headers());
+            assert_eq!(message.payload(), view.payload());
+            assert_eq!(data.len(), view.frame_len());
+        }
+    }
+
+    #[test]
+    fn invalid_messages_fail_like_read_message_from() {
+        let invalid_messages: &[&[u8]] = &[
+            include_bytes!("../../test_data/invalid_header_string_value_length"),
+            include_bytes!("../../test_data/invalid_header_string_length_cut_off"),
+            include_bytes!("../../test_data/invalid_header_value_type"),
+            include_bytes!("../../test_data/invalid_header_name_length"),
+            include_bytes!("../../test_data/invalid_headers_length"),
+            include_bytes!("../../test_data/invalid_prelude_checksum"),
+            include_bytes!("../../test_data/invalid_message_checksum"),
+            include_bytes!("../../test_data/invalid_header_name_length_too_long"),
+        ];
+        for data in invalid_messages {
+            // Some of the test data has trailing bytes that `read_message_from` would read
+            // past the end of the frame, so only give it the frame
+            let frame_len = u32::from_be_bytes(data[..4].try_into().unwrap()) as usize;
+            let data = &data[..frame_len.min(data.len())];
+            let expected = read_message_from(&mut &data[..]).unwrap_err();
+            let actual = MessageView::decode(&Bytes::copy_from_slice(data)).unwrap_err();
+            assert_eq!(
+                format!("{:?}", expected.kind()),
+                format!("{:?}", actual.kind())
+            );
+        }
+    }
+
+    #[test]
+    fn payload_is_not_copied() {
+        let data = VALID_MESSAGES[2];
+        let buffer = Bytes::from_static(data);
+        let view = MessageView::decode(&buffer).unwrap().unwrap();
+        let payload_start = data.len() - 4 - view.payload().len();
+        assert_eq!(data[payload_start..].as_ptr(), view.payload().as_ptr());
+    }
+
+    #[test]
+    fn header_values_borrow_from_the_frame() {
+        let message = Message::new(&b"some payload"[..])
+            .add_header(Header::new("str", HeaderValue::String("a string".into())))
+            .add_header(Header::new(
+                "bytes",
+                HeaderValue::ByteArray(Bytes::from_static(b"some bytes")),
+            ))
+            .add_header(Header::new("int", HeaderValue::Int32(5)));
+        let mut buffer = Vec::new();
+        write_message_to(&message, &mut buffer).unwrap();
+        let buffer = Bytes::from(buffer);
+
+        let view = MessageView::decode(&buffer).unwrap().unwrap();
+        assert_eq!(
+            Some(HeaderValueView::String("a string")),
+            view.header("str")
+        );
+        assert_eq!(
+            Some(HeaderValueView::ByteArray(b"some bytes")),
+            view.header("bytes")
+        );
+        assert_eq!(Some(HeaderValueView::Int32(5)), view.header("int"));
+        assert_eq!(None, view.header("missing"));
+        match view.header("str") {
+            Some(HeaderValueView::String(value)) => {
+                assert!(buffer.as_ptr_range().contains(&value.as_ptr()))
+            }
+            other => panic!("unexpected header value: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn incomplete_frames() {
+        let data = VALID_MESSAGES[2];
+        for len in 0..data.len() {
+            let buffer = Bytes::copy_from_slice(&data[..len]);
+            assert!(MessageView::decode(&buffer).unwrap().is_none());
+            assert_eq!(None, peek_frame_len(&buffer).unwrap());
+        }
+        assert_eq!(Some(data.len()), peek_frame_len(data).unwrap());
+    }
+
+    #[test]
+    fn corrupt_preludes_are_detected_before_the_frame_is_complete() {
+        let data = include_bytes!("../../test_data/invalid_prelude_checksum");
+        let err = peek_frame_len(&data[..12]).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::PreludeChecksumMismatch(0x8BB495FB, 0xDEADBEEF)
+        ));
+    }
+
+    #[test]
+    fn streaming_decode() {
+        let mut stream = Vec::new();
+        for data in VALID_MESSAGES {
+            stream.extend_from_slice(data);
+        }
+
+        // Feed the stream in small chunks
+        let mut buffer = BytesMut::new();
+        let mut decoded = Vec::new();
+        for chunk in stream.chunks(7) {
+            buffer.extend_from_slice(chunk);
+            while let Some(frame_len) = peek_frame_len(&buffer).unwrap() {
+                let frame = buffer.split_to(frame_len).freeze();
+                let view = MessageView::decode(&frame).unwrap().unwrap();
+                decoded.push(view.to_message());
+            }
+        }
+
+        assert!(buffer.is_empty());
+        assert_eq!(VALID_MESSAGES.len(), decoded.len());
+        for (data, message) in VALID_MESSAGES.iter().zip(decoded) {
+            assert_eq!(read_message_from(&mut &data[..]).unwrap(), message);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+pub mod rejection;
+pub mod router;
+pub mod runtime_error;
+
+/// [AWS Query](https://smithy.io/2.0/aws/protocols/aws-query-protocol.html) protocol.
+pub struct AwsQuery;
@@ -0,0 +1,373 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use aws_smithy_query::decode::{find_parameter, QueryDecodeError};
+use bytes::Bytes;
+use http_body::{Body as HttpBody, LengthLimitError, Limited};
+use tower::Layer;
+use tower::Service;
+use tower::ServiceExt;
+
+use crate::body::{empty, BoxBody};
+use crate::error::BoxError;
+use crate::extension::RuntimeErrorExtension;
+use crate::protocol::aws_json::router::ROUTE_CUTOFF;
+use crate::protocol::content_type_header_classifier;
+use crate::rejection::MissingContentTypeReason;
+use crate::response::IntoResponse;
+use crate::routing::tiny_map::TinyMap;
+use crate::routing::Router;
+use crate::routing::{method_disallowed, Route, UNKNOWN_OPERATION_EXCEPTION};
+
+use super::runtime_error::RuntimeError;
+use super::AwsQuery;
+
+use thiserror::Error;
+
+/// An AWS Query routing error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Relative URI was not "/".
+    #[error("relative URI is not \"/\"")]
+    NotRootUrl,
+    /// Method was not `POST`.
+    #[error("method not POST")]
+    MethodNotAllowed,
+    /// `Content-Type` was not `application/x-www-form-urlencoded`.
+    #[error("unsupported media type: {0}")]
+    UnsupportedMediaType(MissingContentTypeReason),
+    /// The request body was larger than the router's limit.
+    #[error("request body is larger than {0} bytes")]
+    BodyTooLarge(usize),
+    /// Failed to buffer the request body.
+    #[error("failed to buffer the request body: {0}")]
+    BufferBody(crate::Error),
+    /// The request body is not a form-urlencoded query string.
+    #[error("failed to parse the request body: {0}")]
+    InvalidBody(QueryDecodeError),
+    /// Missing the `Action` parameter.
+    #[error("missing the \"Action\" parameter")]
+    MissingAction,
+    /// Operation not found.
+    #[error("operation not found")]
+    NotFound,
+}
+
+// TODO(https://github.com/smithy-lang/smithy/issues/2348): As with AWS JSON, there are no
+// protocol tests to pin the routing error responses against.
+impl IntoResponse<AwsQuery> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        let status = match self {
+            Error::MethodNotAllowed => return method_disallowed(),
+            Error::UnsupportedMediaType(_) => {
+                return IntoResponse::<AwsQuery>::into_response(RuntimeError::UnsupportedMediaType)
+            }
+            Error::BodyTooLarge(_) => http::StatusCode::PAYLOAD_TOO_LARGE,
+            Error::BufferBody(_) | Error::InvalidBody(_) => http::StatusCode::BAD_REQUEST,
+            Error::NotRootUrl | Error::MissingAction | Error::NotFound => http::StatusCode::NOT_FOUND,
+        };
+        http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "text/xml")
+            .extension(RuntimeErrorExtension::new(UNKNOWN_OPERATION_EXCEPTION.to_string()))
+            .body(empty())
+            .expect("invalid HTTP response for AWS Query routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues")
+    }
+}
+
+const CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// The default limit on the size of request bodies, see [`AwsQueryRouter::max_body_size`].
+pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Returns the value of the `Action` parameter of a form-urlencoded request body.
+///
+/// Only the `Action` parameter is decoded; the rest of the body is decoded by the operation.
+#[allow(clippy::result_large_err)]
+fn action(body: &[u8]) -> Result<String, Error> {
+    let body = std::str::from_utf8(body).map_err(|err| Error::InvalidBody(QueryDecodeError::unhandled(err)))?;
+    find_parameter(body, "Action")
+        .map_err(Error::InvalidBody)?
+        .ok_or(Error::MissingAction)
+}
+
+/// The routes, shared by the router and the requests it dispatches.
+///
+/// `Route` isn't `Sync`, so each route is behind a [`Mutex`] that is only locked to clone it.
+type Routes<S> = Arc<TinyMap<&'static str, Mutex<S>, ROUTE_CUTOFF>>;
+
+/// Clones each route out of `routes`.
+fn cloned<S: Clone>(routes: &Routes<S>) -> impl Iterator<Item = (&'static str, S)> + '_ {
+    routes.iter().map(|(key, route)| (*key, route.lock().unwrap().clone()))
+}
+
+/// A [`Router`] supporting the [AWS Query] protocol.
+///
+/// The operation is named by the `Action` parameter in the form-urlencoded request body, so the
+/// body can't be inspected when matching the request. Instead, every request that is a `POST`
+/// to `/` is matched to an [`ActionDispatch`] service, which buffers the body, looks up the route
+/// for the action, and forwards the request (with the buffered body) to it.
+///
+/// [AWS Query]: https://smithy.io/2.0/aws/protocols/aws-query-protocol.html
+#[derive(Debug, Clone)]
+pub struct AwsQueryRouter<S> {
+    routes: Routes<S>,
+    max_body_size: usize,
+}
+
+impl<S> AwsQueryRouter<S> {
+    /// Sets the largest request body, in bytes, that is buffered to find the `Action` parameter.
+    ///
+    /// Larger requests are rejected with `413 Payload Too Large`. Defaults to
+    /// [`DEFAULT_MAX_BODY_SIZE`].
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Applies a [`Layer`] uniformly to all routes.
+    pub fn layer<L>(self, layer: L) -> AwsQueryRouter<L::Service>
+    where
+        L: Layer<S>,
+        S: Clone,
+    {
+        cloned(&self.routes)
+            .map(|(key, route)| (key, layer.layer(route)))
+            .collect::<AwsQueryRouter<_>>()
+            .max_body_size(self.max_body_size)
+    }
+
+    /// Applies type erasure to the inner route using [`Route::new`].
+    pub fn boxed<B>(self) -> AwsQueryRouter<Route<B>>
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        cloned(&self.routes)
+            .map(|(key, s)| (key, Route::new(s)))
+            .collect::<AwsQueryRouter<_>>()
+            .max_body_size(self.max_body_size)
+    }
+}
+
+impl<B, S: Clone> Router<B> for AwsQueryRouter<S> {
+    type Service = ActionDispatch<S>;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<Self::Service, Self::Error> {
+        // The URI must be root,
+        if request.uri() != "/" {
+            return Err(Error::NotRootUrl);
+        }
+
+        // Only `Method::POST` is allowed.
+        if request.method() != http::Method::POST {
+            return Err(Error::MethodNotAllowed);
+        }
+
+        let content_type = request
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok());
+        content_type_header_classifier(content_type, Some(CONTENT_TYPE)).map_err(Error::UnsupportedMediaType)?;
+
+        Ok(ActionDispatch {
+            routes: self.routes.clone(),
+            max_body_size: self.max_body_size,
+        })
+    }
+}
+
+impl<S> FromIterator<(&'static str, S)> for AwsQueryRouter<S> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (&'static str, S)>>(iter: T) -> Self {
+        Self {
+            routes: Arc::new(iter.into_iter().map(|(key, route)| (key, Mutex::new(route))).collect()),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+}
+
+/// The [`Service`] that [`AwsQueryRouter`] matches requests to. It buffers the request body to
+/// dispatch the request on its `Action` parameter.
+#[derive(Debug, Clone)]
+pub struct ActionDispatch<S> {
+    routes: Routes<S>,
+    max_body_size: usize,
+}
+
+impl<B> Service<http::Request<B>> for ActionDispatch<Route<B>>
+where
+    B: HttpBody<Data = Bytes> + From<Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let routes = self.routes.clone();
+        let max_body_size = self.max_body_size;
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let route = match hyper::body::to_bytes(Limited::new(body, max_body_size)).await {
+                Ok(body) => action(&body).and_then(|action| {
+                    let route = routes.get(action.as_str()).ok_or(Error::NotFound)?;
+                    let route = route.lock().unwrap().clone();
+                    Ok((route, body))
+                }),
+                Err(err) if err.is::<LengthLimitError>() => Err(Error::BodyTooLarge(max_body_size)),
+                Err(err) => Err(Error::BufferBody(crate::Error::new(err))),
+            };
+            match route {
+                Ok((route, body)) => route.oneshot(http::Request::from_parts(parts, B::from(body))).await,
+                Err(error) => {
+                    tracing::debug!(%error, "failed to route");
+                    Ok(error.into_response())
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{body::Body, protocol::test_helpers::get_body_as_string, routing::RoutingService};
+
+    use http::Method;
+    use pretty_assertions::assert_eq;
+    use tower::service_fn;
+
+    fn router() -> RoutingService<AwsQueryRouter<Route<Body>>, AwsQuery> {
+        let routes = ["GetCallerIdentity", "AssumeRole"].into_iter().map(|operation| {
+            let service = service_fn(move |request: http::Request<Body>| async move {
+                let body = match hyper::body::to_bytes(request.into_body()).await {
+                    Ok(body) => format!("{operation}: {}", String::from_utf8_lossy(&body)),
+                    Err(err) => format!("{operation} failed to read the body: {err}"),
+                };
+                Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed(body)))
+            });
+            (operation, Route::new(service))
+        });
+        RoutingService::new(routes.collect())
+    }
+
+    fn req(method: &Method, uri: &str, body: &'static str) -> http::Request<Body> {
+        http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/x-www-form-urlencoded; charset=utf-8")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        let router = router();
+
+        // Valid request, should be routed with its body intact.
+        let res = router
+            .clone()
+            .oneshot(req(&Method::POST, "/", "Action=AssumeRole&Version=2011-06-15"))
+            .await
+            .unwrap();
+        assert_eq!(
+            "AssumeRole: Action=AssumeRole&Version=2011-06-15",
+            get_body_as_string(res.into_body()).await
+        );
+
+        // Unknown or missing actions should return `NotFound`.
+        for body in ["Action=Unknown&Version=2011-06-15", "Version=2011-06-15"] {
+            let res = router.clone().oneshot(req(&Method::POST, "/", body)).await.unwrap();
+            assert_eq!(http::StatusCode::NOT_FOUND, res.status());
+            assert_eq!(
+                UNKNOWN_OPERATION_EXCEPTION,
+                res.extensions().get::<RuntimeErrorExtension>().unwrap().as_str()
+            );
+        }
+
+        // Wrong HTTP method, should return `MethodNotAllowed`.
+        let res = router
+            .clone()
+            .oneshot(req(&Method::GET, "/", "Action=AssumeRole"))
+            .await
+            .unwrap();
+        assert_eq!(http::StatusCode::METHOD_NOT_ALLOWED, res.status());
+
+        // Wrong URI, should return `NotRootUrl`.
+        let res = router
+            .oneshot(req(&Method::POST, "/something", "Action=AssumeRole"))
+            .await
+            .unwrap();
+        assert_eq!(http::StatusCode::NOT_FOUND, res.status());
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_content_type() {
+        for content_type in [None, Some("application/json")] {
+            let mut request = req(&Method::POST, "/", "Action=AssumeRole");
+            request.headers_mut().remove("Content-Type");
+            if let Some(content_type) = content_type {
+                request
+                    .headers_mut()
+                    .insert("Content-Type", http::HeaderValue::from_static(content_type));
+            }
+            let res = router().oneshot(request).await.unwrap();
+            assert_eq!(http::StatusCode::UNSUPPORTED_MEDIA_TYPE, res.status());
+            assert_eq!(
+                "<ErrorResponse><Error><Type>Sender</Type><Code>UnsupportedMediaTypeException</Code></Error></ErrorResponse>",
+                get_body_as_string(res.into_body()).await
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_large_bodies() {
+        let router = router().map(|router| router.max_body_size(20));
+        let res = router
+            .clone()
+            .oneshot(req(&Method::POST, "/", "Action=AssumeRole"))
+            .await
+            .unwrap();
+        assert_eq!(http::StatusCode::OK, res.status());
+
+        let res = router
+            .oneshot(req(&Method::POST, "/", "Action=AssumeRole&Version=2011-06-15"))
+            .await
+            .unwrap();
+        assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, res.status());
+    }
+
+    #[test]
+    fn routes_are_shared_with_dispatched_requests() {
+        let service = service_fn(|_request: http::Request<Body>| async {
+            Ok::<_, Infallible>(http::Response::new(crate::body::empty()))
+        });
+        let router: AwsQueryRouter<Route<Body>> = [("AssumeRole", Route::new(service))].into_iter().collect();
+        let dispatch = Router::<Body>::match_route(&router, &req(&Method::POST, "/", "Action=AssumeRole")).unwrap();
+        assert!(Arc::ptr_eq(&router.routes, &dispatch.routes));
+    }
+
+    #[test]
+    fn action_is_decoded() {
+        assert_eq!("Some Action", action(b"Version=1&Action=Some+Action").unwrap());
+        assert_eq!(
+            Error::MissingAction.to_string(),
+            action(b"Version=1").unwrap_err().to_string()
+        );
+    }
+}
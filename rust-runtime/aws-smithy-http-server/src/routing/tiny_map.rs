@@ -114,6 +114,14 @@ where
             TinyMapInner::HashMap(hash_map) => hash_map.get(key),
         }
     }
+
+    /// Returns an iterator over the entries of the map.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        match &self.inner {
+            TinyMapInner::Vec(vec) => OrIterator::Left(vec.iter().map(|(key, value)| (key, value))),
+            TinyMapInner::HashMap(hash_map) => OrIterator::Right(hash_map.iter()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -7,6 +7,7 @@
 
 use std::error::Error as StdError;
 
+mod channel;
 mod receiver;
 mod sender;
 
@@ -16,5 +17,8 @@ pub type BoxError = Box<dyn StdError + Send + Sync + 'static>;
 #[doc(inline)]
 pub use sender::{EventStreamSender, MessageStreamAdapter, MessageStreamError};
 
+#[doc(inline)]
+pub use channel::{ChannelSender, SendError, StreamFailureReporter};
+
 #[doc(inline)]
 pub use receiver::{Receiver, ReceiverError};
@@ -0,0 +1,263 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A bounded channel that events can be sent on as the input of an event stream.
+
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use aws_smithy_types::error::display::DisplayErrorContext;
+use futures_core::Stream;
+use std::error::Error as StdError;
+use std::fmt;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use tokio::sync::mpsc;
+
+/// State shared between the senders and the receiving end of a channel.
+#[derive(Debug, Default)]
+pub(super) struct ChannelState {
+    closed: AtomicBool,
+    // Woken when the channel is closed so that the receiving end can notice
+    receiver_waker: Mutex<Option<Waker>>,
+    // Set when the request body that the events are sent on fails, or when the response fails
+    failure: Mutex<Option<Arc<StreamFailure>>>,
+}
+
+impl ChannelState {
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Some(waker) = self.receiver_waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    /// Records why the event stream failed, so that it can be returned to the senders.
+    pub(super) fn fail(&self, message: String) {
+        self.failure
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| Arc::new(StreamFailure(message)));
+    }
+}
+
+/// Reports failures on the response side of an event stream to the [`ChannelSender`]s of its input.
+///
+/// Reporting a failure closes the channel, and sending then fails with a [`SendError`] whose
+/// [`source`](StdError::source) is the reported failure, rather than one that only says the
+/// channel was closed. Only the first failure that's reported is kept.
+#[derive(Clone, Debug)]
+pub struct StreamFailureReporter {
+    state: Arc<ChannelState>,
+}
+
+impl StreamFailureReporter {
+    pub(super) fn new(state: Arc<ChannelState>) -> Self {
+        Self { state }
+    }
+
+    /// Records that the event stream failed with `error`, and closes the channel.
+    pub fn fail(&self, error: &(dyn StdError + 'static)) {
+        self.state.fail(DisplayErrorContext(error).to_string());
+        self.state.close();
+    }
+}
+
+impl Storable for StreamFailureReporter {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Sends events on a bounded channel to an event stream.
+///
+/// Created with [`EventStreamSender::channel`](super::EventStreamSender::channel). Sending waits
+/// while the channel is full, so a producer that's faster than the network is slowed down to
+/// match it, and at most `capacity` events are buffered.
+///
+/// The event stream ends once [`close`](ChannelSender::close) is called, or once every
+/// `ChannelSender` is dropped. Either way, events that were already sent are sent before the
+/// end-of-stream message.
+pub struct ChannelSender<T> {
+    sender: mpsc::Sender<T>,
+    state: Arc<ChannelState>,
+}
+
+impl<T> fmt::Debug for ChannelSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelSender")
+            .field("capacity", &self.capacity())
+            .field("queued", &self.queued())
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl<T> Clone for ChannelSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> ChannelSender<T> {
+    /// Sends an event, waiting for room in the channel if it's full.
+    ///
+    /// Fails if the channel was closed, or if the event stream's request ended or failed, in
+    /// which case the event is returned in the error.
+    pub async fn send(&self, event: T) -> Result<(), SendError<T>> {
+        if self.state.closed.load(Ordering::SeqCst) {
+            return Err(self.send_error(event));
+        }
+        self.sender
+            .send(event)
+            .await
+            .map_err(|mpsc::error::SendError(event)| self.send_error(event))
+    }
+
+    /// Closes the channel, ending the event stream after the events that were already sent.
+    ///
+    /// This closes the channel for every clone of this sender.
+    pub fn close(&self) {
+        self.state.close();
+    }
+
+    /// Returns true if the channel was closed, or if the event stream's request ended or failed.
+    pub fn is_closed(&self) -> bool {
+        self.state.closed.load(Ordering::SeqCst) || self.sender.is_closed()
+    }
+
+    /// Waits until the event stream's request ends or fails.
+    ///
+    /// This is useful to stop producing events once they can't be sent anymore.
+    pub async fn closed(&self) {
+        self.sender.closed().await
+    }
+
+    /// Returns the maximum number of events that can be buffered.
+    pub fn capacity(&self) -> usize {
+        self.sender.max_capacity()
+    }
+
+    /// Returns the number of events that are buffered and haven't been sent yet.
+    pub fn queued(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    fn send_error(&self, event: T) -> SendError<T> {
+        SendError {
+            event,
+            failure: self.state.failure.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// The receiving end of a channel, which is the input stream of an event stream.
+pub(super) struct ChannelStream<T, E> {
+    receiver: mpsc::Receiver<T>,
+    state: Arc<ChannelState>,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<T, E> ChannelStream<T, E> {
+    pub(super) fn state(&self) -> &Arc<ChannelState> {
+        &self.state
+    }
+}
+
+impl<T, E> Stream for ChannelStream<T, E> {
+    type Item = Result<T, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.state.closed.load(Ordering::SeqCst) {
+            // Stop accepting events, but still return the ones that were already sent
+            self.receiver.close();
+        } else {
+            *self.state.receiver_waker.lock().unwrap() = Some(cx.waker().clone());
+            // The channel may have been closed before the waker was stored
+            if self.state.closed.load(Ordering::SeqCst) {
+                self.receiver.close();
+            }
+        }
+        self.receiver.poll_recv(cx).map(|event| event.map(Ok))
+    }
+}
+
+/// Creates a channel with room for `capacity` events.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub(super) fn channel<T, E>(capacity: usize) -> (ChannelSender<T>, ChannelStream<T, E>) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    let state = Arc::new(ChannelState::default());
+    (
+        ChannelSender {
+            sender,
+            state: state.clone(),
+        },
+        ChannelStream {
+            receiver,
+            state,
+            _phantom: PhantomData,
+        },
+    )
+}
+
+/// Why an event stream failed.
+#[derive(Debug)]
+struct StreamFailure(String);
+
+impl fmt::Display for StreamFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl StdError for StreamFailure {}
+
+/// An error returned when an event can't be sent on a [`ChannelSender`].
+pub struct SendError<T> {
+    event: T,
+    failure: Option<Arc<StreamFailure>>,
+}
+
+impl<T> SendError<T> {
+    /// Returns the event that couldn't be sent.
+    pub fn into_event(self) -> T {
+        self.event
+    }
+
+    /// Returns true if the event stream failed, rather than being closed or ending normally.
+    ///
+    /// The reason it failed is the [`source`](StdError::source) of this error.
+    pub fn is_stream_failure(&self) -> bool {
+        self.failure.is_some()
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError")
+            .field("failure", &self.failure)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.failure {
+            Some(_) => write!(f, "failed to send event: the event stream failed"),
+            None => write!(f, "failed to send event: the event stream is closed"),
+        }
+    }
+}
+
+impl<T> StdError for SendError<T> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.failure.as_ref().map(|failure| failure.as_ref() as _)
+    }
+}
@@ -3,8 +3,10 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use super::channel::{channel, ChannelSender, ChannelState, StreamFailureReporter};
 use aws_smithy_eventstream::frame::{write_message_to, MarshallMessage, SignMessage};
 use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_types::error::display::DisplayErrorContext;
 use aws_smithy_types::error::ErrorMetadata;
 use bytes::Bytes;
 use futures_core::Stream;
@@ -13,12 +15,15 @@ use std::fmt;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tracing::trace;
 
 /// Input type for Event Streams.
 pub struct EventStreamSender<T, E> {
     input_stream: Pin<Box<dyn Stream<Item = Result<T, E>> + Send + Sync>>,
+    // Set if the input stream is a channel, so that failures can be reported to its senders
+    channel_state: Option<Arc<ChannelState>>,
 }
 
 impl<T, E> Debug for EventStreamSender<T, E> {
@@ -29,6 +34,45 @@ impl<T, E> Debug for EventStreamSender<T, E> {
     }
 }
 
+impl<T: Send + 'static, E: 'static> EventStreamSender<T, E> {
+    /// Creates an input stream that events are sent to on a bounded channel.
+    ///
+    /// Returns a [`ChannelSender`] to send events with, and the `EventStreamSender` to give to
+    /// the operation. At most `capacity` events are buffered: once the channel is full,
+    /// [`ChannelSender::send`] waits until the event stream has sent some of them. If the event
+    /// stream's request fails or ends, then sending returns an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use aws_smithy_http::event_stream::EventStreamSender;
+    /// # #[derive(Debug)] struct Event;
+    /// # #[derive(Debug)] struct EventError;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (sender, input) = EventStreamSender::<Event, EventError>::channel(16);
+    /// // Give `input` to the operation, and then send events from another task
+    /// sender.send(Event).await.map_err(|err| err.to_string())?;
+    /// sender.close();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn channel(capacity: usize) -> (ChannelSender<T>, Self) {
+        let (sender, stream) = channel(capacity);
+        let channel_state = Some(stream.state().clone());
+        (
+            sender,
+            EventStreamSender {
+                input_stream: Box::pin(stream),
+                channel_state,
+            },
+        )
+    }
+}
+
 impl<T, E: StdError + Send + Sync + 'static> EventStreamSender<T, E> {
     #[doc(hidden)]
     pub fn into_body_stream(
@@ -37,7 +81,10 @@ impl<T, E: StdError + Send + Sync + 'static> EventStreamSender<T, E> {
         error_marshaller: impl MarshallMessage<Input = E> + Send + Sync + 'static,
         signer: impl SignMessage + Send + Sync + 'static,
     ) -> MessageStreamAdapter<T, E> {
-        MessageStreamAdapter::new(marshaller, error_marshaller, signer, self.input_stream)
+        let mut adapter =
+            MessageStreamAdapter::new(marshaller, error_marshaller, signer, self.input_stream);
+        adapter.channel_state = self.channel_state;
+        adapter
     }
 }
 
@@ -48,6 +95,7 @@ where
     fn from(stream: S) -> Self {
         EventStreamSender {
             input_stream: Box::pin(stream),
+            channel_state: None,
         }
     }
 }
@@ -116,6 +164,7 @@ pub struct MessageStreamAdapter<T, E: StdError + Send + Sync + 'static> {
     signer: Box<dyn SignMessage + Send + Sync>,
     stream: Pin<Box<dyn Stream<Item = Result<T, E>> + Send>>,
     end_signal_sent: bool,
+    channel_state: Option<Arc<ChannelState>>,
     _phantom: PhantomData<E>,
 }
 
@@ -135,9 +184,16 @@ impl<T, E: StdError + Send + Sync + 'static> MessageStreamAdapter<T, E> {
             signer: Box::new(signer),
             stream,
             end_signal_sent: false,
+            channel_state: None,
             _phantom: Default::default(),
         }
     }
+
+    /// Returns a reporter for failures on the response side of the event stream, if the events
+    /// are sent with a [`ChannelSender`].
+    pub fn failure_reporter(&self) -> Option<StreamFailureReporter> {
+        self.channel_state.clone().map(StreamFailureReporter::new)
+    }
 }
 
 impl<T, E: StdError + Send + Sync + 'static> Stream for MessageStreamAdapter<T, E> {
@@ -145,6 +201,19 @@ impl<T, E: StdError + Send + Sync + 'static> Stream for MessageStreamAdapter<T,
         Result<Bytes, SdkError<E, aws_smithy_runtime_api::client::orchestrator::HttpResponse>>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.as_mut().poll_next_message(cx);
+        if let (Poll::Ready(Some(Err(err))), Some(channel_state)) = (&poll, &self.channel_state) {
+            channel_state.fail(DisplayErrorContext(err).to_string());
+        }
+        poll
+    }
+}
+
+impl<T, E: StdError + Send + Sync + 'static> MessageStreamAdapter<T, E> {
+    fn poll_next_message(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<<Self as Stream>::Item>> {
         match self.stream.as_mut().poll_next(cx) {
             Poll::Ready(message_option) => {
                 if let Some(message_result) = message_option {
@@ -195,7 +264,7 @@ impl<T, E: StdError + Send + Sync + 'static> Stream for MessageStreamAdapter<T,
 #[cfg(test)]
 mod tests {
     use super::MarshallMessage;
-    use crate::event_stream::{EventStreamSender, MessageStreamAdapter};
+    use crate::event_stream::{ChannelSender, EventStreamSender, MessageStreamAdapter};
     use async_stream::stream;
     use aws_smithy_eventstream::error::Error as EventStreamError;
     use aws_smithy_eventstream::frame::{
@@ -206,6 +275,7 @@ mod tests {
     use bytes::Bytes;
     use futures_core::Stream;
     use futures_util::stream::StreamExt;
+    use futures_util::FutureExt;
     use std::error::Error as StdError;
 
     #[derive(Debug, Eq, PartialEq)]
@@ -327,6 +397,145 @@ mod tests {
         ));
     }
 
+    fn channel_adapter(
+        capacity: usize,
+    ) -> (
+        ChannelSender<TestMessage>,
+        MessageStreamAdapter<TestMessage, TestServiceError>,
+    ) {
+        let (sender, input) = EventStreamSender::channel(capacity);
+        let adapter = input.into_body_stream(Marshaller, ErrorMarshaller, TestSigner);
+        (sender, adapter)
+    }
+
+    fn sent_payload(mut sent_bytes: Bytes) -> Bytes {
+        let sent = read_message_from(&mut sent_bytes).unwrap();
+        let inner = read_message_from(&mut (&sent.payload()[..])).unwrap();
+        inner.payload().clone()
+    }
+
+    #[test]
+    fn channel_event_stream_sender_send_sync() {
+        let (sender, input) = EventStreamSender::<TestMessage, TestServiceError>::channel(1);
+        check_send_sync(sender);
+        check_send_sync(input);
+    }
+
+    #[tokio::test]
+    async fn channel_sends_events_and_then_the_end_signal_when_closed() {
+        let (sender, mut adapter) = channel_adapter(2);
+        // Clones don't keep the channel open once it's closed
+        let _clone = sender.clone();
+        sender.send(TestMessage("one".into())).await.unwrap();
+        sender.send(TestMessage("two".into())).await.unwrap();
+        sender.close();
+        assert!(sender.send(TestMessage("three".into())).await.is_err());
+
+        let sent = adapter.next().await.unwrap().unwrap();
+        assert_eq!(&b"one"[..], &sent_payload(sent)[..]);
+        let sent = adapter.next().await.unwrap().unwrap();
+        assert_eq!(&b"two"[..], &sent_payload(sent)[..]);
+        let mut end_signal_bytes = adapter.next().await.unwrap().unwrap();
+        let end_signal = read_message_from(&mut end_signal_bytes).unwrap();
+        assert_eq!(0, end_signal.payload().len());
+        assert!(adapter.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn channel_applies_backpressure() {
+        let (sender, mut adapter) = channel_adapter(1);
+        assert_eq!(1, sender.capacity());
+        sender.send(TestMessage("one".into())).await.unwrap();
+        assert_eq!(1, sender.queued());
+
+        // The channel is full, so sending waits until the adapter has taken an event
+        let mut send = Box::pin(sender.send(TestMessage("two".into())));
+        assert!((&mut send).now_or_never().is_none());
+        let sent = adapter.next().await.unwrap().unwrap();
+        assert_eq!(&b"one"[..], &sent_payload(sent)[..]);
+        send.await.unwrap();
+        assert_eq!(1, sender.queued());
+
+        drop(sender);
+        let sent = adapter.next().await.unwrap().unwrap();
+        assert_eq!(&b"two"[..], &sent_payload(sent)[..]);
+        // Dropping every sender ends the stream
+        assert!(adapter.next().await.unwrap().is_ok());
+        assert!(adapter.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn channel_send_fails_when_the_request_ends() {
+        let (sender, adapter) = channel_adapter(1);
+        drop(adapter);
+        sender.closed().await;
+        assert!(sender.is_closed());
+        let err = sender.send(TestMessage("one".into())).await.unwrap_err();
+        assert!(!err.is_stream_failure());
+        assert_eq!(TestMessage("one".into()), err.into_event());
+    }
+
+    #[tokio::test]
+    async fn channel_send_returns_why_the_event_stream_failed() {
+        #[derive(Debug)]
+        struct FailingSigner;
+        impl SignMessage for FailingSigner {
+            fn sign(&mut self, _message: Message) -> Result<Message, SignMessageError> {
+                Err("signing failed".into())
+            }
+
+            fn sign_empty(&mut self) -> Option<Result<Message, SignMessageError>> {
+                None
+            }
+        }
+
+        let (sender, input) = EventStreamSender::channel(1);
+        let mut adapter = input.into_body_stream(Marshaller, ErrorMarshaller, FailingSigner);
+        sender.send(TestMessage("one".into())).await.unwrap();
+        assert!(adapter.next().await.unwrap().is_err());
+        // The HTTP client drops the body when it fails
+        drop(adapter);
+
+        let err = sender.send(TestMessage("two".into())).await.unwrap_err();
+        assert!(err.is_stream_failure());
+        let source = StdError::source(&err).unwrap().to_string();
+        assert!(source.contains("signing failed"), "{source}");
+    }
+
+    #[tokio::test]
+    async fn channel_send_returns_why_the_response_failed() {
+        let (sender, mut adapter) = channel_adapter(1);
+        let reporter = adapter.failure_reporter().unwrap();
+        sender.send(TestMessage("one".into())).await.unwrap();
+
+        reporter.fail(&TestServiceError);
+        // A later failure doesn't replace the first one
+        reporter.fail(&std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "connection reset",
+        ));
+        assert!(sender.is_closed());
+        let err = sender.send(TestMessage("two".into())).await.unwrap_err();
+        assert!(err.is_stream_failure());
+        assert_eq!(TestMessage("two".into()), err.into_event());
+        let err = sender.send(TestMessage("three".into())).await.unwrap_err();
+        let source = StdError::source(&err).unwrap().to_string();
+        assert!(source.contains("TestServiceError"), "{source}");
+        assert!(!source.contains("connection reset"), "{source}");
+
+        // Events that were already sent are still sent
+        let sent = adapter.next().await.unwrap().unwrap();
+        assert_eq!(&b"one"[..], &sent_payload(sent)[..]);
+    }
+
+    #[test]
+    fn failure_reporter_requires_a_channel() {
+        let input: EventStreamSender<TestMessage, TestServiceError> =
+            futures_util::stream::empty().into();
+        let adapter = input.into_body_stream(Marshaller, ErrorMarshaller, TestSigner);
+        assert!(adapter.failure_reporter().is_none());
+    }
+
     // Verify the developer experience for this compiles
     #[allow(unused)]
     fn event_stream_input_ergonomics() {
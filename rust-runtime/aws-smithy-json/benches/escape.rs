@@ -0,0 +1,86 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_json::deserialize::EscapedStr;
+use aws_smithy_json::serialize::JsonValueWriter;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::distributions::{Alphanumeric, DistString};
+
+/// Generates a random string of a given length
+fn random_string(len: usize) -> String {
+    Alphanumeric.sample_string(&mut rand::thread_rng(), len)
+}
+
+/// Generates a random string of a given length with a newline every 64 characters
+fn random_string_with_escapes(len: usize) -> String {
+    let mut string = random_string(len);
+    for index in (63..len).step_by(64) {
+        string.replace_range(index..index + 1, "\n");
+    }
+    string
+}
+
+const INPUT_SIZES: [usize; 4] = [10, 100, 1_000, 100_000];
+
+fn bench_escape(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Escape");
+
+    for length in INPUT_SIZES {
+        for (name, input) in [
+            ("no_escapes", random_string(length)),
+            ("some_escapes", random_string_with_escapes(length)),
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("aws_smithy_json/{name}"), length),
+                &input,
+                |b, i| {
+                    let mut output = String::with_capacity(length * 2);
+                    b.iter(|| {
+                        output.clear();
+                        JsonValueWriter::new(&mut output).string(i);
+                        black_box(&output);
+                    })
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("serde_json/{name}"), length),
+                &input,
+                |b, i| {
+                    let mut output = Vec::with_capacity(length * 2);
+                    b.iter(|| {
+                        output.clear();
+                        serde_json::to_writer(&mut output, i).unwrap();
+                        black_box(&output);
+                    })
+                },
+            );
+        }
+    }
+    group.finish()
+}
+
+fn bench_unescape(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Unescape");
+
+    for length in INPUT_SIZES {
+        for (name, input) in [
+            ("no_escapes", random_string(length)),
+            ("some_escapes", random_string_with_escapes(length)),
+        ] {
+            let escaped = serde_json::to_string(&input).unwrap();
+            let escaped = &escaped[1..escaped.len() - 1];
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("aws_smithy_json/{name}"), length),
+                escaped,
+                |b, i| b.iter(|| black_box(EscapedStr::new(i).to_unescaped().unwrap())),
+            );
+        }
+    }
+    group.finish()
+}
+
+criterion_group!(benches, bench_escape, bench_unescape);
+criterion_main!(benches);
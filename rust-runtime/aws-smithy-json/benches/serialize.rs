@@ -0,0 +1,66 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_json::serialize::{JsonObjectWriter, JsonOutput};
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::{DateTime, Number};
+use bytes::BytesMut;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Writes a DynamoDB `PutItem`-like request with `items` attributes.
+fn write_request<O: JsonOutput>(output: &mut O, items: usize) {
+    let mut object = JsonObjectWriter::new(output);
+    object.key("TableName").string("benchmark-table");
+    let mut item = object.key("Item").start_object();
+    for index in 0..items {
+        let mut attribute = item.key(&format!("attribute{index}")).start_object();
+        match index % 4 {
+            0 => attribute
+                .key("S")
+                .string("a string value with a \"quote\" in it"),
+            1 => attribute.key("N").number(Number::Float(index as f64 * 1.5)),
+            2 => attribute
+                .key("T")
+                .date_time(&DateTime::from_secs(index as i64), Format::EpochSeconds)
+                .expect("epoch seconds are always valid"),
+            _ => attribute.key("N").number(Number::PosInt(index as u64)),
+        }
+        attribute.finish();
+    }
+    item.finish();
+    object.finish();
+}
+
+fn bench_outputs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Serialize");
+
+    for items in [1, 10, 100, 1_000] {
+        group.bench_with_input(BenchmarkId::new("String", items), &items, |b, &i| {
+            b.iter(|| {
+                let mut output = String::new();
+                write_request(&mut output, i);
+                black_box(output)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Vec<u8>", items), &items, |b, &i| {
+            b.iter(|| {
+                let mut output = Vec::new();
+                write_request(&mut output, i);
+                black_box(output)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("BytesMut", items), &items, |b, &i| {
+            b.iter(|| {
+                let mut output = BytesMut::new();
+                write_request(&mut output, i);
+                black_box(output.freeze())
+            })
+        });
+    }
+    group.finish()
+}
+
+criterion_group!(benches, bench_outputs);
+criterion_main!(benches);
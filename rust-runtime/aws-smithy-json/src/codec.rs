@@ -0,0 +1,760 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Schema-driven JSON serializer and deserializer.
+//!
+//! These implement the [`ShapeSerializer`] and [`ShapeDeserializer`] traits on top of
+//! [`JsonValueWriter`] and the JSON token iterator so that generated code can describe shapes with
+//! a [`Schema`] instead of emitting a bespoke serializer per shape.
+
+use crate::deserialize::error::DeserializeError;
+use crate::deserialize::token::{
+    expect_blob_or_null, expect_bool_or_null, expect_document, expect_number_or_null,
+    expect_string_or_null, expect_timestamp_or_null, skip_value,
+};
+use crate::deserialize::{json_token_iter, JsonTokenIterator, Token};
+use crate::escape::escape_string_into;
+use crate::serialize::{JsonOutput, JsonValueWriter};
+use aws_smithy_types::date_time::{DateTimeFormatError, Format};
+use aws_smithy_types::schema::serde::{SerializableStruct, ShapeDeserializer, ShapeSerializer};
+use aws_smithy_types::schema::Schema;
+use aws_smithy_types::{base64, Blob, DateTime, Document, Number};
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt;
+use std::iter::Peekable;
+
+/// Failed to serialize a shape to JSON.
+#[derive(Debug)]
+pub struct JsonSerializeError {
+    source: DateTimeFormatError,
+}
+
+impl fmt::Display for JsonSerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to serialize JSON")
+    }
+}
+
+impl StdError for JsonSerializeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[derive(Debug)]
+enum Frame {
+    Struct { started: bool },
+    List { started: bool },
+    Map { started: bool },
+}
+
+/// A [`ShapeSerializer`] that writes JSON into a `String`, or any other [`JsonOutput`] such as
+/// a `Vec<u8>` or `BytesMut`.
+///
+/// By default, member names honor the `@jsonName` trait and timestamps without a
+/// `@timestampFormat` trait are written as epoch seconds, as required by `restJson1`.
+#[derive(Debug)]
+pub struct JsonShapeSerializer<'a, O: ?Sized = String> {
+    output: &'a mut O,
+    frames: Vec<Frame>,
+    use_json_name: bool,
+    default_timestamp_format: Format,
+}
+
+impl<'a, O: JsonOutput + ?Sized> JsonShapeSerializer<'a, O> {
+    /// Creates a serializer that appends to `output`.
+    pub fn new(output: &'a mut O) -> Self {
+        Self {
+            output,
+            frames: Vec::new(),
+            use_json_name: true,
+            default_timestamp_format: Format::EpochSeconds,
+        }
+    }
+
+    /// Sets whether the `@jsonName` trait is used for member names.
+    ///
+    /// The `awsJson1_0` and `awsJson1_1` protocols ignore `@jsonName`.
+    pub fn use_json_name(mut self, use_json_name: bool) -> Self {
+        self.use_json_name = use_json_name;
+        self
+    }
+
+    /// Sets the timestamp format used when a member has no `@timestampFormat` trait.
+    pub fn default_timestamp_format(mut self, format: Format) -> Self {
+        self.default_timestamp_format = format;
+        self
+    }
+
+    /// Writes the delimiter and member key that precede a value, and returns a writer for it.
+    fn value(&mut self, schema: &Schema) -> JsonValueWriter<'_, O> {
+        match self.frames.last_mut() {
+            Some(Frame::Struct { started }) => {
+                if *started {
+                    self.output.push_str(",");
+                }
+                *started = true;
+                let name = member_name(schema, self.use_json_name);
+                self.output.push_str("\"");
+                escape_string_into(name, self.output);
+                self.output.push_str("\":");
+            }
+            Some(Frame::List { started }) => {
+                if *started {
+                    self.output.push_str(",");
+                }
+                *started = true;
+            }
+            // The key (and any delimiter) was already written by `write_map_key`
+            Some(Frame::Map { .. }) | None => {}
+        }
+        JsonValueWriter::new(self.output)
+    }
+
+    fn nested<F>(
+        &mut self,
+        open: &str,
+        frame: Frame,
+        close: &str,
+        f: F,
+    ) -> Result<(), JsonSerializeError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), JsonSerializeError>,
+    {
+        self.frames.push(frame);
+        self.output.push_str(open);
+        let result = f(self);
+        self.output.push_str(close);
+        self.frames.pop();
+        result
+    }
+}
+
+fn member_name(schema: &Schema, use_json_name: bool) -> &'static str {
+    let json_name = match use_json_name {
+        true => schema.traits().json_name(),
+        false => None,
+    };
+    json_name
+        .or(schema.member_name())
+        .unwrap_or_else(|| schema.id().name())
+}
+
+impl<'a, O: JsonOutput + ?Sized> ShapeSerializer for JsonShapeSerializer<'a, O> {
+    type Error = JsonSerializeError;
+
+    fn write_struct<T>(&mut self, schema: &Schema, value: &T) -> Result<(), Self::Error>
+    where
+        T: SerializableStruct + ?Sized,
+    {
+        self.value(schema);
+        self.nested("{", Frame::Struct { started: false }, "}", |ser| {
+            value.serialize_members(ser)
+        })
+    }
+
+    fn write_list<F>(
+        &mut self,
+        schema: &Schema,
+        _len: usize,
+        write_elements: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        self.value(schema);
+        self.nested("[", Frame::List { started: false }, "]", write_elements)
+    }
+
+    fn write_map<F>(
+        &mut self,
+        schema: &Schema,
+        _len: usize,
+        write_entries: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        self.value(schema);
+        self.nested("{", Frame::Map { started: false }, "}", write_entries)
+    }
+
+    fn write_map_key(&mut self, _schema: &Schema, key: &str) -> Result<(), Self::Error> {
+        if let Some(Frame::Map { started }) = self.frames.last_mut() {
+            if *started {
+                self.output.push_str(",");
+            }
+            *started = true;
+        }
+        self.output.push_str("\"");
+        escape_string_into(key, self.output);
+        self.output.push_str("\":");
+        Ok(())
+    }
+
+    fn write_boolean(&mut self, schema: &Schema, value: bool) -> Result<(), Self::Error> {
+        self.value(schema).boolean(value);
+        Ok(())
+    }
+
+    fn write_byte(&mut self, schema: &Schema, value: i8) -> Result<(), Self::Error> {
+        self.write_long(schema, value.into())
+    }
+
+    fn write_short(&mut self, schema: &Schema, value: i16) -> Result<(), Self::Error> {
+        self.write_long(schema, value.into())
+    }
+
+    fn write_integer(&mut self, schema: &Schema, value: i32) -> Result<(), Self::Error> {
+        self.write_long(schema, value.into())
+    }
+
+    fn write_long(&mut self, schema: &Schema, value: i64) -> Result<(), Self::Error> {
+        let number = match value {
+            value if value >= 0 => Number::PosInt(value as u64),
+            value => Number::NegInt(value),
+        };
+        self.value(schema).number(number);
+        Ok(())
+    }
+
+    fn write_float(&mut self, schema: &Schema, value: f32) -> Result<(), Self::Error> {
+        self.write_double(schema, value.into())
+    }
+
+    fn write_double(&mut self, schema: &Schema, value: f64) -> Result<(), Self::Error> {
+        self.value(schema).number(Number::Float(value));
+        Ok(())
+    }
+
+    fn write_string(&mut self, schema: &Schema, value: &str) -> Result<(), Self::Error> {
+        self.value(schema).string(value);
+        Ok(())
+    }
+
+    fn write_blob(&mut self, schema: &Schema, value: &Blob) -> Result<(), Self::Error> {
+        self.value(schema).string_unchecked(&base64::encode(value));
+        Ok(())
+    }
+
+    fn write_timestamp(&mut self, schema: &Schema, value: &DateTime) -> Result<(), Self::Error> {
+        let format = schema
+            .traits()
+            .timestamp_format()
+            .unwrap_or(self.default_timestamp_format);
+        self.value(schema)
+            .date_time(value, format)
+            .map_err(|source| JsonSerializeError { source })
+    }
+
+    fn write_document(&mut self, schema: &Schema, value: &Document) -> Result<(), Self::Error> {
+        self.value(schema).document(value);
+        Ok(())
+    }
+
+    fn write_null(&mut self, schema: &Schema) -> Result<(), Self::Error> {
+        self.value(schema).null();
+        Ok(())
+    }
+}
+
+/// A [`ShapeDeserializer`] that reads JSON from a byte slice.
+///
+/// Null members of structures are treated as absent and are never passed to the consumer.
+pub struct JsonShapeDeserializer<'a> {
+    tokens: Peekable<JsonTokenIterator<'a>>,
+    use_json_name: bool,
+    default_timestamp_format: Format,
+}
+
+impl<'a> fmt::Debug for JsonShapeDeserializer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonShapeDeserializer")
+            .field("use_json_name", &self.use_json_name)
+            .field("default_timestamp_format", &self.default_timestamp_format)
+            .finish()
+    }
+}
+
+impl<'a> JsonShapeDeserializer<'a> {
+    /// Creates a deserializer reading from `input`.
+    pub fn new(input: &'a [u8]) -> Self {
+        Self {
+            tokens: json_token_iter(input).peekable(),
+            use_json_name: true,
+            default_timestamp_format: Format::EpochSeconds,
+        }
+    }
+
+    /// Sets whether the `@jsonName` trait is used for member names.
+    pub fn use_json_name(mut self, use_json_name: bool) -> Self {
+        self.use_json_name = use_json_name;
+        self
+    }
+
+    /// Sets the timestamp format used when a member has no `@timestampFormat` trait.
+    pub fn default_timestamp_format(mut self, format: Format) -> Self {
+        self.default_timestamp_format = format;
+        self
+    }
+
+    /// Verifies that the entire input has been consumed.
+    pub fn finish(mut self) -> Result<(), DeserializeError> {
+        match self.tokens.next() {
+            None => Ok(()),
+            Some(Err(err)) => Err(err),
+            Some(Ok(token)) => Err(token.error(Cow::Borrowed(
+                "found more JSON tokens after completing parsing",
+            ))),
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Result<Token<'a>, DeserializeError>> {
+        self.tokens.next()
+    }
+
+    fn expect_start(&mut self, start_object: bool) -> Result<(), DeserializeError> {
+        match (self.next_token().transpose()?, start_object) {
+            (Some(Token::StartObject { .. }), true) | (Some(Token::StartArray { .. }), false) => {
+                Ok(())
+            }
+            (Some(token), true) => Err(token.error(Cow::Borrowed("expected StartObject"))),
+            (Some(token), false) => Err(token.error(Cow::Borrowed("expected StartArray"))),
+            (None, _) => Err(DeserializeError::custom("unexpected end of stream")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Number, DeserializeError> {
+        expect_number_or_null(self.next_token())?.ok_or_else(unexpected_null)
+    }
+}
+
+fn unexpected_null() -> DeserializeError {
+    DeserializeError::custom("unexpected null value")
+}
+
+impl<'a> ShapeDeserializer for JsonShapeDeserializer<'a> {
+    type Error = DeserializeError;
+
+    fn read_struct<T, F>(
+        &mut self,
+        schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &Schema, &mut Self) -> Result<T, Self::Error>,
+    {
+        self.expect_start(true)?;
+        loop {
+            match self.next_token().transpose()? {
+                Some(Token::EndObject { .. }) => return Ok(state),
+                Some(Token::ObjectKey { key, .. }) => {
+                    let key = key.to_unescaped()?;
+                    let member = schema
+                        .members()
+                        .iter()
+                        .find(|member| member_name(member, self.use_json_name) == key);
+                    match member {
+                        Some(member) if !self.is_null() => {
+                            state = consumer(state, member, self)?;
+                        }
+                        _ => skip_value(&mut self.tokens)?,
+                    }
+                }
+                Some(token) => {
+                    return Err(token.error(Cow::Borrowed("expected object key or end object")))
+                }
+                None => return Err(DeserializeError::custom("unexpected end of stream")),
+            }
+        }
+    }
+
+    fn read_list<T, F>(
+        &mut self,
+        _schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &mut Self) -> Result<T, Self::Error>,
+    {
+        self.expect_start(false)?;
+        loop {
+            match self.tokens.peek() {
+                Some(Ok(Token::EndArray { .. })) => {
+                    self.next_token();
+                    return Ok(state);
+                }
+                _ => state = consumer(state, self)?,
+            }
+        }
+    }
+
+    fn read_map<T, F>(
+        &mut self,
+        _schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, String, &mut Self) -> Result<T, Self::Error>,
+    {
+        self.expect_start(true)?;
+        loop {
+            match self.next_token().transpose()? {
+                Some(Token::EndObject { .. }) => return Ok(state),
+                Some(Token::ObjectKey { key, .. }) => {
+                    let key = key.to_unescaped()?.into_owned();
+                    state = consumer(state, key, self)?;
+                }
+                Some(token) => {
+                    return Err(token.error(Cow::Borrowed("expected object key or end object")))
+                }
+                None => return Err(DeserializeError::custom("unexpected end of stream")),
+            }
+        }
+    }
+
+    fn read_boolean(&mut self, _schema: &Schema) -> Result<bool, Self::Error> {
+        expect_bool_or_null(self.next_token())?.ok_or_else(unexpected_null)
+    }
+
+    fn read_byte(&mut self, _schema: &Schema) -> Result<i8, Self::Error> {
+        Ok(i8::try_from(self.number()?)?)
+    }
+
+    fn read_short(&mut self, _schema: &Schema) -> Result<i16, Self::Error> {
+        Ok(i16::try_from(self.number()?)?)
+    }
+
+    fn read_integer(&mut self, _schema: &Schema) -> Result<i32, Self::Error> {
+        Ok(i32::try_from(self.number()?)?)
+    }
+
+    fn read_long(&mut self, _schema: &Schema) -> Result<i64, Self::Error> {
+        Ok(i64::try_from(self.number()?)?)
+    }
+
+    fn read_float(&mut self, _schema: &Schema) -> Result<f32, Self::Error> {
+        Ok(self.number()?.to_f32_lossy())
+    }
+
+    fn read_double(&mut self, _schema: &Schema) -> Result<f64, Self::Error> {
+        Ok(self.number()?.to_f64_lossy())
+    }
+
+    fn read_string(&mut self, _schema: &Schema) -> Result<String, Self::Error> {
+        let value = expect_string_or_null(self.next_token())?.ok_or_else(unexpected_null)?;
+        Ok(value.to_unescaped()?.into_owned())
+    }
+
+    fn read_blob(&mut self, _schema: &Schema) -> Result<Blob, Self::Error> {
+        expect_blob_or_null(self.next_token())?.ok_or_else(unexpected_null)
+    }
+
+    fn read_timestamp(&mut self, schema: &Schema) -> Result<DateTime, Self::Error> {
+        let format = schema
+            .traits()
+            .timestamp_format()
+            .unwrap_or(self.default_timestamp_format);
+        expect_timestamp_or_null(self.next_token(), format)?.ok_or_else(unexpected_null)
+    }
+
+    fn read_document(&mut self, _schema: &Schema) -> Result<Document, Self::Error> {
+        expect_document(&mut self.tokens)
+    }
+
+    fn is_null(&mut self) -> bool {
+        matches!(self.tokens.peek(), Some(Ok(Token::ValueNull { .. })))
+    }
+
+    fn read_null(&mut self) -> Result<(), Self::Error> {
+        match self.next_token().transpose()? {
+            Some(Token::ValueNull { .. }) => Ok(()),
+            Some(token) => Err(token.error(Cow::Borrowed("expected ValueNull"))),
+            None => Err(DeserializeError::custom("unexpected end of stream")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonShapeDeserializer, JsonShapeSerializer};
+    use crate::deserialize::error::DeserializeError;
+    use aws_smithy_types::schema::serde::{SerializableStruct, ShapeDeserializer, ShapeSerializer};
+    use aws_smithy_types::schema::{Schema, ShapeId, ShapeType, Traits};
+    use aws_smithy_types::{Blob, DateTime};
+    use std::collections::BTreeMap;
+
+    const ITEM_ID: ShapeId = ShapeId::new("com.example", "Item");
+    const CONTAINER_ID: ShapeId = ShapeId::new("com.example", "Container");
+    const IDS_ID: ShapeId = ShapeId::new("com.example", "Ids");
+    const SPARSE_ID: ShapeId = ShapeId::new("com.example", "SparseNames");
+    const TAGS_ID: ShapeId = ShapeId::new("com.example", "Tags");
+
+    static ITEM: Schema = Schema::new(ITEM_ID, ShapeType::Structure).with_members(&ITEM_MEMBERS);
+    static ITEM_MEMBERS: [Schema; 3] = [
+        Schema::new_member(ITEM_ID.with_member("name"), 0, ShapeType::String)
+            .with_traits(Traits::new().with_json_name("Name")),
+        Schema::new_member(ITEM_ID.with_member("payload"), 1, ShapeType::Blob),
+        Schema::new_member(ITEM_ID.with_member("at"), 2, ShapeType::Timestamp),
+    ];
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Item {
+        name: Option<String>,
+        payload: Option<Blob>,
+        at: Option<DateTime>,
+    }
+
+    impl SerializableStruct for Item {
+        fn serialize_members<S: ShapeSerializer>(&self, ser: &mut S) -> Result<(), S::Error> {
+            if let Some(name) = &self.name {
+                ser.write_string(&ITEM_MEMBERS[0], name)?;
+            }
+            if let Some(payload) = &self.payload {
+                ser.write_blob(&ITEM_MEMBERS[1], payload)?;
+            }
+            if let Some(at) = &self.at {
+                ser.write_timestamp(&ITEM_MEMBERS[2], at)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn deserialize_item<D: ShapeDeserializer>(de: &mut D) -> Result<Item, D::Error> {
+        de.read_struct(&ITEM, Item::default(), |mut item, member, de| {
+            match member.member_index() {
+                0 => item.name = Some(de.read_string(member)?),
+                1 => item.payload = Some(de.read_blob(member)?),
+                2 => item.at = Some(de.read_timestamp(member)?),
+                _ => unreachable!(),
+            }
+            Ok(item)
+        })
+    }
+
+    static CONTAINER: Schema =
+        Schema::new(CONTAINER_ID, ShapeType::Structure).with_members(&CONTAINER_MEMBERS);
+    static CONTAINER_MEMBERS: [Schema; 4] = [
+        Schema::new_member(CONTAINER_ID.with_member("ids"), 0, ShapeType::List)
+            .with_members(&IDS_MEMBERS),
+        Schema::new_member(CONTAINER_ID.with_member("names"), 1, ShapeType::List)
+            .with_traits(Traits::new().with_sparse())
+            .with_members(&SPARSE_MEMBERS),
+        Schema::new_member(CONTAINER_ID.with_member("tags"), 2, ShapeType::Map)
+            .with_members(&TAGS_MEMBERS),
+        Schema::new_member(CONTAINER_ID.with_member("item"), 3, ShapeType::Structure)
+            .with_members(&ITEM_MEMBERS),
+    ];
+    static IDS_MEMBERS: [Schema; 1] = [Schema::new_member(
+        IDS_ID.with_member("member"),
+        0,
+        ShapeType::Integer,
+    )];
+    static SPARSE_MEMBERS: [Schema; 1] = [Schema::new_member(
+        SPARSE_ID.with_member("member"),
+        0,
+        ShapeType::String,
+    )];
+    static TAGS_MEMBERS: [Schema; 2] = [
+        Schema::new_member(TAGS_ID.with_member("key"), 0, ShapeType::String),
+        Schema::new_member(TAGS_ID.with_member("value"), 1, ShapeType::String),
+    ];
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Container {
+        ids: Option<Vec<i32>>,
+        names: Option<Vec<Option<String>>>,
+        tags: Option<BTreeMap<String, String>>,
+        item: Option<Item>,
+    }
+
+    impl SerializableStruct for Container {
+        fn serialize_members<S: ShapeSerializer>(&self, ser: &mut S) -> Result<(), S::Error> {
+            if let Some(ids) = &self.ids {
+                ser.write_list(&CONTAINER_MEMBERS[0], ids.len(), |ser| {
+                    for id in ids {
+                        ser.write_integer(&IDS_MEMBERS[0], *id)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            if let Some(names) = &self.names {
+                ser.write_list(&CONTAINER_MEMBERS[1], names.len(), |ser| {
+                    for name in names {
+                        match name {
+                            Some(name) => ser.write_string(&SPARSE_MEMBERS[0], name)?,
+                            None => ser.write_null(&SPARSE_MEMBERS[0])?,
+                        }
+                    }
+                    Ok(())
+                })?;
+            }
+            if let Some(tags) = &self.tags {
+                ser.write_map(&CONTAINER_MEMBERS[2], tags.len(), |ser| {
+                    for (key, value) in tags {
+                        ser.write_map_key(&TAGS_MEMBERS[0], key)?;
+                        ser.write_string(&TAGS_MEMBERS[1], value)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            if let Some(item) = &self.item {
+                ser.write_struct(&CONTAINER_MEMBERS[3], item)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn deserialize_container<D: ShapeDeserializer>(de: &mut D) -> Result<Container, D::Error> {
+        de.read_struct(
+            &CONTAINER,
+            Container::default(),
+            |mut container, member, de| {
+                match member.member_index() {
+                    0 => {
+                        container.ids = Some(de.read_list(member, Vec::new(), |mut ids, de| {
+                            ids.push(de.read_integer(&IDS_MEMBERS[0])?);
+                            Ok(ids)
+                        })?)
+                    }
+                    1 => {
+                        container.names =
+                            Some(de.read_list(member, Vec::new(), |mut names, de| {
+                                if de.is_null() {
+                                    de.read_null()?;
+                                    names.push(None);
+                                } else {
+                                    names.push(Some(de.read_string(&SPARSE_MEMBERS[0])?));
+                                }
+                                Ok(names)
+                            })?)
+                    }
+                    2 => {
+                        container.tags =
+                            Some(de.read_map(member, BTreeMap::new(), |mut tags, key, de| {
+                                tags.insert(key, de.read_string(&TAGS_MEMBERS[1])?);
+                                Ok(tags)
+                            })?)
+                    }
+                    3 => container.item = Some(deserialize_item(de)?),
+                    _ => unreachable!(),
+                }
+                Ok(container)
+            },
+        )
+    }
+
+    fn deserialize_container_from(input: &str) -> Result<Container, DeserializeError> {
+        let mut de = JsonShapeDeserializer::new(input.as_bytes());
+        let container = deserialize_container(&mut de)?;
+        de.finish()?;
+        Ok(container)
+    }
+
+    #[test]
+    fn lists_maps_and_nested_structures() {
+        let container = Container {
+            ids: Some(vec![1, -2]),
+            names: Some(vec![Some("a".into()), None]),
+            tags: Some([("k\"".into(), "v".into()), ("q".into(), "".into())].into()),
+            item: Some(Item {
+                name: Some("nested".into()),
+                ..Default::default()
+            }),
+        };
+        let mut output = String::new();
+        JsonShapeSerializer::new(&mut output)
+            .write_struct(&CONTAINER, &container)
+            .unwrap();
+        assert_eq!(
+            r#"{"ids":[1,-2],"names":["a",null],"tags":{"k\"":"v","q":""},"item":{"Name":"nested"}}"#,
+            output
+        );
+        assert_eq!(container, deserialize_container_from(&output).unwrap());
+
+        let empty = Container {
+            ids: Some(vec![]),
+            tags: Some(BTreeMap::new()),
+            ..Default::default()
+        };
+        let mut output = String::new();
+        JsonShapeSerializer::new(&mut output)
+            .write_struct(&CONTAINER, &empty)
+            .unwrap();
+        assert_eq!(r#"{"ids":[],"tags":{}}"#, output);
+        assert_eq!(empty, deserialize_container_from(&output).unwrap());
+    }
+
+    #[test]
+    fn null_members_are_absent() {
+        assert_eq!(
+            Container::default(),
+            deserialize_container_from(r#"{"ids":null,"names":null,"tags":null,"item":null}"#)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        for input in [
+            // null elements of a list that isn't sparse
+            r#"{"ids":[1,null]}"#,
+            // null values of a map
+            r#"{"tags":{"k":null}}"#,
+            // values of the wrong type
+            r#"{"ids":{}}"#,
+            r#"{"ids":["1"]}"#,
+            r#"{"tags":["k","v"]}"#,
+            r#"{"tags":{"k":1}}"#,
+            r#"{"item":[]}"#,
+            r#"[]"#,
+            // integers that don't fit the member
+            r#"{"ids":[2147483648]}"#,
+            r#"{"ids":[1.5]}"#,
+            // truncated or trailing input
+            r#"{"ids":[1,"#,
+            r#"{"tags":{"k":"v""#,
+            r#"{"item":{}"#,
+            r#"{} {}"#,
+            "",
+        ] {
+            deserialize_container_from(input).expect_err(input);
+        }
+    }
+
+    #[test]
+    fn json_names_base64_blobs_and_epoch_seconds() {
+        let item = Item {
+            name: Some("a\n".into()),
+            payload: Some(Blob::new("hi")),
+            at: Some(DateTime::from_secs(1576540098)),
+        };
+        let mut output = String::new();
+        JsonShapeSerializer::new(&mut output)
+            .write_struct(&ITEM, &item)
+            .unwrap();
+        assert_eq!(r#"{"Name":"a\n","payload":"aGk=","at":1576540098}"#, output);
+
+        let input =
+            br#"{"unknown":{"nested":[1,{}]},"name":"member name","payload":null,"at":1576540098}"#;
+        let mut de = JsonShapeDeserializer::new(input).use_json_name(false);
+        assert_eq!(
+            Item {
+                name: Some("member name".into()),
+                payload: None,
+                at: item.at,
+            },
+            deserialize_item(&mut de).unwrap()
+        );
+        de.finish().unwrap();
+
+        let mut de = JsonShapeDeserializer::new(br#"{"at":"soon"} {}"#);
+        assert!(deserialize_item(&mut de).is_err());
+    }
+}
@@ -3,9 +3,12 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::serialize::JsonOutput;
 use std::borrow::Cow;
 use std::fmt;
 
+mod scan;
+
 #[derive(Debug, PartialEq, Eq)]
 enum EscapeErrorKind {
     ExpectedSurrogatePair(String),
@@ -54,54 +57,63 @@ impl From<EscapeErrorKind> for EscapeError {
 
 /// Escapes a string for embedding in a JSON string value.
 pub(crate) fn escape_string(value: &str) -> Cow<'_, str> {
-    let bytes = value.as_bytes();
-    for (index, byte) in bytes.iter().enumerate() {
-        match byte {
-            0..=0x1F | b'"' | b'\\' => {
-                return Cow::Owned(escape_string_inner(&bytes[0..index], &bytes[index..]))
-            }
-            _ => {}
+    match scan::find_escape(value.as_bytes()) {
+        None => Cow::Borrowed(value),
+        Some(index) => {
+            let mut escaped = String::with_capacity(value.len() + 1);
+            escaped.push_str(&value[..index]);
+            write_escape(value.as_bytes()[index], &mut escaped);
+            escape_string_into(&value[index + 1..], &mut escaped);
+            Cow::Owned(escaped)
         }
     }
-    Cow::Borrowed(value)
 }
 
-fn escape_string_inner(start: &[u8], rest: &[u8]) -> String {
-    let mut escaped = Vec::with_capacity(start.len() + rest.len() + 1);
-    escaped.extend(start);
-
-    for byte in rest {
-        match byte {
-            b'"' => escaped.extend(b"\\\""),
-            b'\\' => escaped.extend(b"\\\\"),
-            0x08 => escaped.extend(b"\\b"),
-            0x0C => escaped.extend(b"\\f"),
-            b'\n' => escaped.extend(b"\\n"),
-            b'\r' => escaped.extend(b"\\r"),
-            b'\t' => escaped.extend(b"\\t"),
-            0..=0x1F => escaped.extend(format!("\\u{:04x}", byte).bytes()),
-            _ => escaped.push(*byte),
-        }
+/// Escapes a string for embedding in a JSON string value, writing it straight into `output`.
+pub(crate) fn escape_string_into<O: JsonOutput + ?Sized>(value: &str, output: &mut O) {
+    let mut rest = value;
+    while let Some(index) = scan::find_escape(rest.as_bytes()) {
+        // Bytes that need escaping are ASCII, so `index` is always on a char boundary
+        output.push_str(&rest[..index]);
+        write_escape(rest.as_bytes()[index], output);
+        rest = &rest[index + 1..];
     }
+    output.push_str(rest);
+}
 
-    // This is safe because:
-    // - The original input was valid UTF-8 since it came in as a `&str`
-    // - Only single-byte code points were escaped
-    // - The escape sequences are valid UTF-8
-    debug_assert!(std::str::from_utf8(&escaped).is_ok());
-    unsafe { String::from_utf8_unchecked(escaped) }
+fn write_escape<O: JsonOutput + ?Sized>(byte: u8, output: &mut O) {
+    const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+    match byte {
+        b'"' => output.push_str("\\\""),
+        b'\\' => output.push_str("\\\\"),
+        0x08 => output.push_str("\\b"),
+        0x0C => output.push_str("\\f"),
+        b'\n' => output.push_str("\\n"),
+        b'\r' => output.push_str("\\r"),
+        b'\t' => output.push_str("\\t"),
+        _ => {
+            debug_assert!(byte <= 0x1F, "only control characters are left to escape");
+            let escape = [
+                b'\\',
+                b'u',
+                b'0',
+                b'0',
+                HEX_DIGITS[(byte >> 4) as usize],
+                HEX_DIGITS[(byte & 0xF) as usize],
+            ];
+            output.push_str(std::str::from_utf8(&escape).expect("escape is ASCII"));
+        }
+    }
 }
 
 /// Unescapes a JSON-escaped string.
 /// If there are no escape sequences, it directly returns the reference.
 pub(crate) fn unescape_string(value: &str) -> Result<Cow<'_, str>, EscapeError> {
     let bytes = value.as_bytes();
-    for (index, byte) in bytes.iter().enumerate() {
-        if *byte == b'\\' {
-            return unescape_string_inner(&bytes[0..index], &bytes[index..]).map(Cow::Owned);
-        }
+    match scan::find_backslash(bytes) {
+        Some(index) => unescape_string_inner(&bytes[0..index], &bytes[index..]).map(Cow::Owned),
+        None => Ok(Cow::Borrowed(value)),
     }
-    Ok(Cow::Borrowed(value))
 }
 
 fn unescape_string_inner(start: &[u8], rest: &[u8]) -> Result<String, EscapeError> {
@@ -141,9 +153,11 @@ fn unescape_string_inner(start: &[u8], rest: &[u8]) -> Result<String, EscapeErro
                     }
                 }
             }
-            byte => {
-                unescaped.push(byte);
-                index += 1
+            _ => {
+                // Copy everything up to the next escape sequence at once
+                let run = scan::find_backslash(&rest[index..]).unwrap_or(rest.len() - index);
+                unescaped.extend_from_slice(&rest[index..index + run]);
+                index += run;
             }
         }
     }
@@ -0,0 +1,188 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Vectorized searches for the bytes that start (or need) a JSON escape sequence.
+//!
+//! On `x86_64`, 32 bytes are checked at a time with AVX2 when the CPU supports it, and 16 bytes
+//! at a time with SSE2 (which every `x86_64` CPU supports) otherwise. On `aarch64`, 16 bytes are
+//! checked at a time with NEON. Everywhere else, and for the tail of the input that doesn't fill
+//! a vector, the bytes are checked one at a time.
+
+/// Returns true if `byte` must be escaped in a JSON string.
+#[inline]
+fn needs_escape(byte: u8) -> bool {
+    matches!(byte, 0..=0x1F | b'"' | b'\\')
+}
+
+/// Returns the index of the first byte in `bytes` that must be escaped in a JSON string.
+#[inline]
+pub(super) fn find_escape(bytes: &[u8]) -> Option<usize> {
+    find(bytes, Needle::Escape)
+}
+
+/// Returns the index of the first backslash in `bytes`.
+#[inline]
+pub(super) fn find_backslash(bytes: &[u8]) -> Option<usize> {
+    find(bytes, Needle::Backslash)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Needle {
+    /// A control character, quote, or backslash.
+    Escape,
+    /// A backslash.
+    Backslash,
+}
+
+fn scalar(bytes: &[u8], needle: Needle) -> Option<usize> {
+    match needle {
+        Needle::Escape => bytes.iter().position(|&byte| needs_escape(byte)),
+        Needle::Backslash => bytes.iter().position(|&byte| byte == b'\\'),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn find(bytes: &[u8], needle: Needle) -> Option<usize> {
+    if bytes.len() >= 32 && is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        unsafe { x86_64::find_avx2(bytes, needle) }
+    } else {
+        // SAFETY: SSE2 is part of the `x86_64` baseline
+        unsafe { x86_64::find_sse2(bytes, needle) }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn find(bytes: &[u8], needle: Needle) -> Option<usize> {
+    // SAFETY: NEON is part of the `aarch64` baseline
+    unsafe { aarch64::find_neon(bytes, needle) }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn find(bytes: &[u8], needle: Needle) -> Option<usize> {
+    scalar(bytes, needle)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    use super::{scalar, Needle};
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn find_sse2(bytes: &[u8], needle: Needle) -> Option<usize> {
+        let quote = _mm_set1_epi8(b'"' as i8);
+        let backslash = _mm_set1_epi8(b'\\' as i8);
+        let max_control = _mm_set1_epi8(0x1F);
+
+        let mut offset = 0;
+        while offset + 16 <= bytes.len() {
+            let chunk = _mm_loadu_si128(bytes.as_ptr().add(offset) as *const __m128i);
+            let mut matches = _mm_cmpeq_epi8(chunk, backslash);
+            if needle == Needle::Escape {
+                matches = _mm_or_si128(matches, _mm_cmpeq_epi8(chunk, quote));
+                // `max(byte, 0x1F) == 0x1F` (unsigned) is true only for control characters
+                let control = _mm_cmpeq_epi8(_mm_max_epu8(chunk, max_control), max_control);
+                matches = _mm_or_si128(matches, control);
+            }
+            let mask = _mm_movemask_epi8(matches);
+            if mask != 0 {
+                return Some(offset + mask.trailing_zeros() as usize);
+            }
+            offset += 16;
+        }
+        scalar(&bytes[offset..], needle).map(|index| offset + index)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn find_avx2(bytes: &[u8], needle: Needle) -> Option<usize> {
+        let quote = _mm256_set1_epi8(b'"' as i8);
+        let backslash = _mm256_set1_epi8(b'\\' as i8);
+        let max_control = _mm256_set1_epi8(0x1F);
+
+        let mut offset = 0;
+        while offset + 32 <= bytes.len() {
+            let chunk = _mm256_loadu_si256(bytes.as_ptr().add(offset) as *const __m256i);
+            let mut matches = _mm256_cmpeq_epi8(chunk, backslash);
+            if needle == Needle::Escape {
+                matches = _mm256_or_si256(matches, _mm256_cmpeq_epi8(chunk, quote));
+                let control = _mm256_cmpeq_epi8(_mm256_max_epu8(chunk, max_control), max_control);
+                matches = _mm256_or_si256(matches, control);
+            }
+            let mask = _mm256_movemask_epi8(matches) as u32;
+            if mask != 0 {
+                return Some(offset + mask.trailing_zeros() as usize);
+            }
+            offset += 32;
+        }
+        find_sse2(&bytes[offset..], needle).map(|index| offset + index)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use super::{scalar, Needle};
+    use std::arch::aarch64::*;
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn find_neon(bytes: &[u8], needle: Needle) -> Option<usize> {
+        let quote = vdupq_n_u8(b'"');
+        let backslash = vdupq_n_u8(b'\\');
+        let control_limit = vdupq_n_u8(0x20);
+
+        let mut offset = 0;
+        while offset + 16 <= bytes.len() {
+            let chunk = vld1q_u8(bytes.as_ptr().add(offset));
+            let mut matches = vceqq_u8(chunk, backslash);
+            if needle == Needle::Escape {
+                matches = vorrq_u8(matches, vceqq_u8(chunk, quote));
+                matches = vorrq_u8(matches, vcltq_u8(chunk, control_limit));
+            }
+            // NEON has no movemask, so find the exact position with a scalar search once
+            // the chunk is known to contain a match
+            if vmaxvq_u8(matches) != 0 {
+                return scalar(&bytes[offset..offset + 16], needle).map(|index| offset + index);
+            }
+            offset += 16;
+        }
+        scalar(&bytes[offset..], needle).map(|index| offset + index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_backslash, find_escape, scalar, Needle};
+    use proptest::proptest;
+
+    #[test]
+    fn finds_first_match() {
+        assert_eq!(None, find_escape(b""));
+        assert_eq!(
+            None,
+            find_escape("no escapes in this string, even with ünïcödé".as_bytes())
+        );
+        for index in 0..100 {
+            for byte in [b'"', b'\\', b'\n', 0x00, 0x1F] {
+                let mut input = vec![b'a'; 100];
+                input[index] = byte;
+                assert_eq!(Some(index), find_escape(&input), "{byte} at {index}");
+            }
+            let mut input = vec![b'"'; 100];
+            input[index] = b'\\';
+            assert_eq!(Some(index), find_backslash(&input), "backslash at {index}");
+        }
+        // Bytes that are negative as `i8` must not be mistaken for control characters
+        assert_eq!(None, find_escape(&[0x80; 64]));
+        assert_eq!(None, find_escape(&[0xFF; 64]));
+        assert_eq!(None, find_escape(&[0x20; 64]));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_scalar(bytes: Vec<u8>) {
+            assert_eq!(scalar(&bytes, Needle::Escape), find_escape(&bytes));
+            assert_eq!(scalar(&bytes, Needle::Backslash), find_backslash(&bytes));
+        }
+    }
+}
@@ -3,18 +3,52 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::escape::escape_string;
+use crate::escape::{escape_string, escape_string_into};
 use aws_smithy_types::date_time::{DateTimeFormatError, Format};
 use aws_smithy_types::primitive::Encoder;
 use aws_smithy_types::{DateTime, Document, Number};
+use bytes::BytesMut;
 use std::borrow::Cow;
 
-pub struct JsonValueWriter<'a> {
-    output: &'a mut String,
+/// A buffer that JSON can be written to.
+///
+/// The writers only ever append valid UTF-8, so byte buffers such as `Vec<u8>` and `BytesMut`
+/// can be written to directly, without validating the output as UTF-8 afterwards.
+pub trait JsonOutput {
+    /// Appends `value` to the output.
+    fn push_str(&mut self, value: &str);
 }
 
-impl<'a> JsonValueWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
+impl JsonOutput for String {
+    fn push_str(&mut self, value: &str) {
+        String::push_str(self, value)
+    }
+}
+
+impl JsonOutput for Vec<u8> {
+    fn push_str(&mut self, value: &str) {
+        self.extend_from_slice(value.as_bytes())
+    }
+}
+
+impl JsonOutput for BytesMut {
+    fn push_str(&mut self, value: &str) {
+        self.extend_from_slice(value.as_bytes())
+    }
+}
+
+impl<O: JsonOutput + ?Sized> JsonOutput for &mut O {
+    fn push_str(&mut self, value: &str) {
+        (**self).push_str(value)
+    }
+}
+
+pub struct JsonValueWriter<'a, O: ?Sized = String> {
+    output: &'a mut O,
+}
+
+impl<'a, O: JsonOutput + ?Sized> JsonValueWriter<'a, O> {
+    pub fn new(output: &'a mut O) -> Self {
         JsonValueWriter { output }
     }
 
@@ -57,9 +91,9 @@ impl<'a> JsonValueWriter<'a> {
 
     /// Writes a string `value`.
     pub fn string(self, value: &str) {
-        self.output.push('"');
-        self.output.push_str(&escape_string(value));
-        self.output.push('"');
+        self.output.push_str("\"");
+        escape_string_into(value, self.output);
+        self.output.push_str("\"");
     }
 
     /// Writes a string `value` without escaping it.
@@ -67,30 +101,22 @@ impl<'a> JsonValueWriter<'a> {
         // Verify in debug builds that we don't actually need to escape the string
         debug_assert!(matches!(escape_string(value), Cow::Borrowed(_)));
 
-        self.output.push('"');
+        self.output.push_str("\"");
         self.output.push_str(value);
-        self.output.push('"');
+        self.output.push_str("\"");
     }
 
     /// Writes a number `value`.
     pub fn number(self, value: Number) {
+        // itoa::Buffer and ryu::Buffer are fixed-size stack allocations, so this is cheap
         match value {
-            Number::PosInt(value) => {
-                // itoa::Buffer is a fixed-size stack allocation, so this is cheap
-                self.output.push_str(Encoder::from(value).encode());
-            }
-            Number::NegInt(value) => {
-                self.output.push_str(Encoder::from(value).encode());
-            }
-            Number::Float(value) => {
-                let mut encoder: Encoder = value.into();
-                // Nan / infinite values actually get written in quotes as a string value
-                if value.is_infinite() || value.is_nan() {
-                    self.string_unchecked(encoder.encode())
-                } else {
-                    self.output.push_str(encoder.encode())
-                }
-            }
+            Number::PosInt(value) => self.output.push_str(itoa::Buffer::new().format(value)),
+            Number::NegInt(value) => self.output.push_str(itoa::Buffer::new().format(value)),
+            Number::Float(value) if value.is_finite() => self
+                .output
+                .push_str(ryu::Buffer::new().format_finite(value)),
+            // Nan / infinite values actually get written in quotes as a string value
+            Number::Float(value) => self.string_unchecked(Encoder::from(value).encode()),
         }
     }
 
@@ -100,6 +126,12 @@ impl<'a> JsonValueWriter<'a> {
         date_time: &DateTime,
         format: Format,
     ) -> Result<(), DateTimeFormatError> {
+        // Whole epoch seconds are the common case, and don't need a `String` to be formatted
+        if format == Format::EpochSeconds && date_time.subsec_nanos() == 0 {
+            self.output
+                .push_str(itoa::Buffer::new().format(date_time.secs()));
+            return Ok(());
+        }
         let formatted = date_time.fmt(format)?;
         match format {
             Format::EpochSeconds => self.output.push_str(&formatted),
@@ -109,24 +141,24 @@ impl<'a> JsonValueWriter<'a> {
     }
 
     /// Starts an array.
-    pub fn start_array(self) -> JsonArrayWriter<'a> {
+    pub fn start_array(self) -> JsonArrayWriter<'a, O> {
         JsonArrayWriter::new(self.output)
     }
 
     /// Starts an object.
-    pub fn start_object(self) -> JsonObjectWriter<'a> {
+    pub fn start_object(self) -> JsonObjectWriter<'a, O> {
         JsonObjectWriter::new(self.output)
     }
 }
 
-pub struct JsonObjectWriter<'a> {
-    json: &'a mut String,
+pub struct JsonObjectWriter<'a, O: ?Sized = String> {
+    json: &'a mut O,
     started: bool,
 }
 
-impl<'a> JsonObjectWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
-        output.push('{');
+impl<'a, O: JsonOutput + ?Sized> JsonObjectWriter<'a, O> {
+    pub fn new(output: &'a mut O) -> Self {
+        output.push_str("{");
         Self {
             json: output,
             started: false,
@@ -134,14 +166,14 @@ impl<'a> JsonObjectWriter<'a> {
     }
 
     /// Starts a value with the given `key`.
-    pub fn key(&mut self, key: &str) -> JsonValueWriter<'_> {
+    pub fn key(&mut self, key: &str) -> JsonValueWriter<'_, O> {
         if self.started {
-            self.json.push(',');
+            self.json.push_str(",");
         }
         self.started = true;
 
-        self.json.push('"');
-        self.json.push_str(&escape_string(key));
+        self.json.push_str("\"");
+        escape_string_into(key, self.json);
         self.json.push_str("\":");
 
         JsonValueWriter::new(self.json)
@@ -149,18 +181,18 @@ impl<'a> JsonObjectWriter<'a> {
 
     /// Finishes the object.
     pub fn finish(self) {
-        self.json.push('}');
+        self.json.push_str("}");
     }
 }
 
-pub struct JsonArrayWriter<'a> {
-    json: &'a mut String,
+pub struct JsonArrayWriter<'a, O: ?Sized = String> {
+    json: &'a mut O,
     started: bool,
 }
 
-impl<'a> JsonArrayWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
-        output.push('[');
+impl<'a, O: JsonOutput + ?Sized> JsonArrayWriter<'a, O> {
+    pub fn new(output: &'a mut O) -> Self {
+        output.push_str("[");
         Self {
             json: output,
             started: false,
@@ -168,19 +200,19 @@ impl<'a> JsonArrayWriter<'a> {
     }
 
     /// Starts a new value in the array.
-    pub fn value(&mut self) -> JsonValueWriter<'_> {
+    pub fn value(&mut self) -> JsonValueWriter<'_, O> {
         self.comma_delimit();
         JsonValueWriter::new(self.json)
     }
 
     /// Finishes the array.
     pub fn finish(self) {
-        self.json.push(']');
+        self.json.push_str("]");
     }
 
     fn comma_delimit(&mut self) {
         if self.started {
-            self.json.push(',');
+            self.json.push_str(",");
         }
         self.started = true;
     }
@@ -426,6 +458,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn epoch_seconds_formatting() {
+        let format = |date_time: DateTime| {
+            let mut formatted = String::new();
+            JsonValueWriter::new(&mut formatted)
+                .date_time(&date_time, Format::EpochSeconds)
+                .unwrap();
+            formatted
+        };
+        assert_eq!("0", format(DateTime::from_secs(0)));
+        assert_eq!("1621870490", format(DateTime::from_secs(1621870490)));
+        assert_eq!("-1", format(DateTime::from_secs(-1)));
+        assert_eq!("5.2", format(DateTime::from_secs_f64(5.2)));
+    }
+
     proptest! {
         #[test]
         fn matches_serde_json_pos_int_format(value: u64) {
@@ -0,0 +1,640 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Schema-driven serializer and deserializer for the `awsQuery` and `ec2Query` protocols.
+//!
+//! Both protocols only use query strings for requests, so the deserializer is meant for
+//! server-side implementations (and the serializer for clients).
+
+use crate::decode::{QueryDecodeError, QueryNode};
+use aws_smithy_types::date_time::{DateTimeFormatError, Format};
+use aws_smithy_types::primitive::{Encoder, Parse};
+use aws_smithy_types::schema::serde::{SerializableStruct, ShapeDeserializer, ShapeSerializer};
+use aws_smithy_types::schema::Schema;
+use aws_smithy_types::{base64, Blob, DateTime, Document};
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Write};
+use urlencoding::encode;
+
+/// Failed to serialize a shape to a query string.
+#[derive(Debug)]
+pub struct QuerySerializeError {
+    message: Cow<'static, str>,
+    source: Option<DateTimeFormatError>,
+}
+
+impl QuerySerializeError {
+    fn unsupported(what: &'static str) -> Self {
+        Self {
+            message: format!("{} can't be represented in a query string", what).into(),
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for QuerySerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to serialize query string: {}", self.message)
+    }
+}
+
+impl StdError for QuerySerializeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_ref().map(|err| err as _)
+    }
+}
+
+#[derive(Debug)]
+enum Frame {
+    Struct {
+        prefix: String,
+    },
+    List {
+        prefix: String,
+        next_index: usize,
+    },
+    Map {
+        prefix: String,
+        key_name: &'static str,
+        value_name: &'static str,
+        next_index: usize,
+        // The parameter name of the value that follows the key that was just written
+        pending_value: Option<String>,
+    },
+}
+
+/// Returns the parameter name segment of the member described by `schema`.
+fn member_name(schema: &Schema, ec2: bool) -> Cow<'static, str> {
+    let traits = schema.traits();
+    let name = schema.member_name().unwrap_or_default();
+    if !ec2 {
+        return Cow::Borrowed(traits.xml_name().unwrap_or(name));
+    }
+    if let Some(name) = traits.ec2_query_name() {
+        return Cow::Borrowed(name);
+    }
+    let name = traits.xml_name().unwrap_or(name);
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if !first.is_uppercase() => {
+            Cow::Owned(first.to_uppercase().chain(chars).collect())
+        }
+        _ => Cow::Borrowed(name),
+    }
+}
+
+/// Returns the name of the segment preceding each list index, or `None` if there is none.
+fn list_member_name(schema: &Schema, ec2: bool) -> Option<&'static str> {
+    if ec2 || schema.traits().xml_flattened() {
+        None
+    } else {
+        Some(
+            schema
+                .list_member()
+                .and_then(|member| member.traits().xml_name())
+                .unwrap_or("member"),
+        )
+    }
+}
+
+/// Returns the names of the key and value segments of each map entry.
+fn map_entry_names(schema: &Schema) -> (&'static str, &'static str) {
+    (
+        schema
+            .map_key()
+            .and_then(|key| key.traits().xml_name())
+            .unwrap_or("key"),
+        schema
+            .map_value()
+            .and_then(|value| value.traits().xml_name())
+            .unwrap_or("value"),
+    )
+}
+
+/// A [`ShapeSerializer`] that writes `awsQuery` (or `ec2Query`) parameters into a `String`.
+#[derive(Debug)]
+pub struct QueryShapeSerializer<'a> {
+    output: &'a mut String,
+    frames: Vec<Frame>,
+    ec2: bool,
+}
+
+impl<'a> QueryShapeSerializer<'a> {
+    /// Creates a serializer that writes the `Action` and `Version` parameters to `output`.
+    ///
+    /// The operation input should then be written with
+    /// [`write_struct`](ShapeSerializer::write_struct).
+    pub fn new(output: &'a mut String, action: &str, version: &str) -> Self {
+        output.push_str("Action=");
+        output.push_str(&encode(action));
+        output.push_str("&Version=");
+        output.push_str(&encode(version));
+        Self {
+            output,
+            frames: Vec::new(),
+            ec2: false,
+        }
+    }
+
+    /// Use the `ec2Query` naming rules instead of the `awsQuery` ones.
+    ///
+    /// In `ec2Query`, member names come from `@ec2QueryName` (or the capitalized `@xmlName` or
+    /// member name), all lists are flattened, and empty lists are omitted.
+    pub fn ec2(mut self, ec2: bool) -> Self {
+        self.ec2 = ec2;
+        self
+    }
+
+    /// Returns the parameter name for the value described by `schema`.
+    fn param_name(&mut self, schema: &Schema) -> String {
+        let member_name = member_name(schema, self.ec2);
+        match self.frames.last_mut() {
+            None => member_name.into_owned(),
+            Some(Frame::Struct { prefix }) if prefix.is_empty() => member_name.into_owned(),
+            Some(Frame::Struct { prefix }) => format!("{}.{}", prefix, member_name),
+            Some(Frame::List { prefix, next_index }) => {
+                let name = format!("{}.{}", prefix, next_index);
+                *next_index += 1;
+                name
+            }
+            Some(Frame::Map { pending_value, .. }) => pending_value
+                .take()
+                .expect("write_map_key must be called before each map value"),
+        }
+    }
+
+    fn param(&mut self, schema: &Schema, value: &str) {
+        let name = self.param_name(schema);
+        write!(self.output, "&{}={}", name, encode(value)).unwrap();
+    }
+}
+
+impl<'a> ShapeSerializer for QueryShapeSerializer<'a> {
+    type Error = QuerySerializeError;
+
+    fn write_struct<T>(&mut self, schema: &Schema, value: &T) -> Result<(), Self::Error>
+    where
+        T: SerializableStruct + ?Sized,
+    {
+        let prefix = if self.frames.is_empty() {
+            // The operation input's members are written as top-level parameters
+            String::new()
+        } else {
+            self.param_name(schema)
+        };
+        self.frames.push(Frame::Struct { prefix });
+        let result = value.serialize_members(self);
+        self.frames.pop();
+        result
+    }
+
+    fn write_list<F>(
+        &mut self,
+        schema: &Schema,
+        _len: usize,
+        write_elements: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        let name = self.param_name(schema);
+        let prefix = match list_member_name(schema, self.ec2) {
+            Some(member) => format!("{}.{}", name, member),
+            None => name.clone(),
+        };
+        self.frames.push(Frame::List {
+            prefix,
+            next_index: 1,
+        });
+        let result = write_elements(self);
+        let empty = matches!(self.frames.pop(), Some(Frame::List { next_index: 1, .. }));
+        // https://github.com/awslabs/smithy/commit/715b1d94ab14764ad43496b016b0c2e85bcf1d1f
+        // If the list was empty, just serialize the parameter name
+        if empty && !self.ec2 {
+            write!(self.output, "&{}=", name).unwrap();
+        }
+        result
+    }
+
+    fn write_map<F>(
+        &mut self,
+        schema: &Schema,
+        _len: usize,
+        write_entries: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        let name = self.param_name(schema);
+        let prefix = if schema.traits().xml_flattened() {
+            name
+        } else {
+            format!("{}.entry", name)
+        };
+        let (key_name, value_name) = map_entry_names(schema);
+        self.frames.push(Frame::Map {
+            prefix,
+            key_name,
+            value_name,
+            next_index: 1,
+            pending_value: None,
+        });
+        let result = write_entries(self);
+        self.frames.pop();
+        result
+    }
+
+    fn write_map_key(&mut self, _schema: &Schema, key: &str) -> Result<(), Self::Error> {
+        if let Some(Frame::Map {
+            prefix,
+            key_name,
+            value_name,
+            next_index,
+            pending_value,
+        }) = self.frames.last_mut()
+        {
+            write!(
+                self.output,
+                "&{}.{}.{}={}",
+                prefix,
+                next_index,
+                key_name,
+                encode(key)
+            )
+            .unwrap();
+            *pending_value = Some(format!("{}.{}.{}", prefix, next_index, value_name));
+            *next_index += 1;
+        }
+        Ok(())
+    }
+
+    fn write_boolean(&mut self, schema: &Schema, value: bool) -> Result<(), Self::Error> {
+        self.param(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_byte(&mut self, schema: &Schema, value: i8) -> Result<(), Self::Error> {
+        self.param(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_short(&mut self, schema: &Schema, value: i16) -> Result<(), Self::Error> {
+        self.param(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_integer(&mut self, schema: &Schema, value: i32) -> Result<(), Self::Error> {
+        self.param(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_long(&mut self, schema: &Schema, value: i64) -> Result<(), Self::Error> {
+        self.param(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_float(&mut self, schema: &Schema, value: f32) -> Result<(), Self::Error> {
+        self.param(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_double(&mut self, schema: &Schema, value: f64) -> Result<(), Self::Error> {
+        self.param(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_string(&mut self, schema: &Schema, value: &str) -> Result<(), Self::Error> {
+        self.param(schema, value);
+        Ok(())
+    }
+
+    fn write_blob(&mut self, schema: &Schema, value: &Blob) -> Result<(), Self::Error> {
+        self.param(schema, &base64::encode(value));
+        Ok(())
+    }
+
+    fn write_timestamp(&mut self, schema: &Schema, value: &DateTime) -> Result<(), Self::Error> {
+        let format = schema
+            .traits()
+            .timestamp_format()
+            .unwrap_or(Format::DateTime);
+        let formatted = value.fmt(format).map_err(|source| QuerySerializeError {
+            message: "failed to format timestamp".into(),
+            source: Some(source),
+        })?;
+        self.param(schema, &formatted);
+        Ok(())
+    }
+
+    fn write_document(&mut self, _schema: &Schema, _value: &Document) -> Result<(), Self::Error> {
+        Err(QuerySerializeError::unsupported("documents"))
+    }
+
+    fn write_null(&mut self, _schema: &Schema) -> Result<(), Self::Error> {
+        Err(QuerySerializeError::unsupported("null values"))
+    }
+}
+
+/// A [`ShapeDeserializer`] that reads `awsQuery` (or `ec2Query`) parameters from a decoded
+/// [`QueryNode`] tree.
+#[derive(Debug)]
+pub struct QueryShapeDeserializer<'a> {
+    // The node that the next `read_*` call will consume.
+    current: &'a QueryNode,
+    ec2: bool,
+}
+
+impl<'a> QueryShapeDeserializer<'a> {
+    /// Creates a deserializer reading the operation input from the top-level parameters in `root`.
+    pub fn new(root: &'a QueryNode) -> Self {
+        Self {
+            current: root,
+            ec2: false,
+        }
+    }
+
+    /// Use the `ec2Query` naming rules instead of the `awsQuery` ones.
+    pub fn ec2(mut self, ec2: bool) -> Self {
+        self.ec2 = ec2;
+        self
+    }
+
+    /// Makes `node` the current node, invokes `read`, and restores the previous node.
+    fn read_node<T, F>(&mut self, node: &'a QueryNode, read: F) -> Result<T, QueryDecodeError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, QueryDecodeError>,
+    {
+        let parent = std::mem::replace(&mut self.current, node);
+        let result = read(self);
+        self.current = parent;
+        result
+    }
+
+    fn value(&self) -> Result<&'a str, QueryDecodeError> {
+        self.current
+            .value()
+            .ok_or_else(|| QueryDecodeError::custom("expected a value"))
+    }
+
+    fn parse<T: Parse>(&self) -> Result<T, QueryDecodeError> {
+        T::parse_smithy_primitive(self.value()?).map_err(QueryDecodeError::unhandled)
+    }
+}
+
+impl<'a> ShapeDeserializer for QueryShapeDeserializer<'a> {
+    type Error = QueryDecodeError;
+
+    fn read_struct<T, F>(
+        &mut self,
+        schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &Schema, &mut Self) -> Result<T, Self::Error>,
+    {
+        for member in schema.members() {
+            if let Some(node) = self.current.get(&member_name(member, self.ec2)) {
+                state = self.read_node(node, |de| consumer(state, member, de))?;
+            }
+        }
+        Ok(state)
+    }
+
+    fn read_list<T, F>(
+        &mut self,
+        schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &mut Self) -> Result<T, Self::Error>,
+    {
+        for item in self
+            .current
+            .list_items(list_member_name(schema, self.ec2))?
+        {
+            state = self.read_node(item, |de| consumer(state, de))?;
+        }
+        Ok(state)
+    }
+
+    fn read_map<T, F>(
+        &mut self,
+        schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, String, &mut Self) -> Result<T, Self::Error>,
+    {
+        let (key_name, value_name) = map_entry_names(schema);
+        let entries =
+            self.current
+                .map_entries(schema.traits().xml_flattened(), key_name, value_name)?;
+        for (key, value) in entries {
+            state = self.read_node(value, |de| consumer(state, key.to_string(), de))?;
+        }
+        Ok(state)
+    }
+
+    fn read_boolean(&mut self, _schema: &Schema) -> Result<bool, Self::Error> {
+        self.parse()
+    }
+
+    fn read_byte(&mut self, _schema: &Schema) -> Result<i8, Self::Error> {
+        self.parse()
+    }
+
+    fn read_short(&mut self, _schema: &Schema) -> Result<i16, Self::Error> {
+        self.parse()
+    }
+
+    fn read_integer(&mut self, _schema: &Schema) -> Result<i32, Self::Error> {
+        self.parse()
+    }
+
+    fn read_long(&mut self, _schema: &Schema) -> Result<i64, Self::Error> {
+        self.parse()
+    }
+
+    fn read_float(&mut self, _schema: &Schema) -> Result<f32, Self::Error> {
+        self.parse()
+    }
+
+    fn read_double(&mut self, _schema: &Schema) -> Result<f64, Self::Error> {
+        self.parse()
+    }
+
+    fn read_string(&mut self, _schema: &Schema) -> Result<String, Self::Error> {
+        self.value().map(str::to_string)
+    }
+
+    fn read_blob(&mut self, _schema: &Schema) -> Result<Blob, Self::Error> {
+        base64::decode(self.value()?)
+            .map(Blob::new)
+            .map_err(QueryDecodeError::unhandled)
+    }
+
+    fn read_timestamp(&mut self, schema: &Schema) -> Result<DateTime, Self::Error> {
+        let format = schema
+            .traits()
+            .timestamp_format()
+            .unwrap_or(Format::DateTime);
+        DateTime::from_str(self.value()?, format).map_err(QueryDecodeError::unhandled)
+    }
+
+    fn read_document(&mut self, _schema: &Schema) -> Result<Document, Self::Error> {
+        Err(QueryDecodeError::custom(
+            "documents can't be represented in a query string",
+        ))
+    }
+
+    fn is_null(&mut self) -> bool {
+        false
+    }
+
+    fn read_null(&mut self) -> Result<(), Self::Error> {
+        Err(QueryDecodeError::custom(
+            "null values can't be represented in a query string",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryShapeSerializer;
+    use crate::decode::QueryNode;
+    use aws_smithy_types::schema::serde::{SerializableStruct, ShapeSerializer};
+    use aws_smithy_types::schema::{Schema, ShapeId, ShapeType, Traits};
+    use aws_smithy_types::DateTime;
+    use std::collections::BTreeMap;
+
+    const INPUT_ID: ShapeId = ShapeId::new("com.example", "Input");
+    const NESTED_ID: ShapeId = ShapeId::new("com.example", "Nested");
+    const LIST_ID: ShapeId = ShapeId::new("com.example", "List");
+    const MAP_ID: ShapeId = ShapeId::new("com.example", "Map");
+
+    static INPUT: Schema = Schema::new(INPUT_ID, ShapeType::Structure).with_members(&INPUT_MEMBERS);
+    static INPUT_MEMBERS: [Schema; 6] = [
+        Schema::new_member(INPUT_ID.with_member("name"), 0, ShapeType::String),
+        Schema::new_member(INPUT_ID.with_member("nested"), 1, ShapeType::Structure)
+            .with_traits(Traits::new().with_ec2_query_name("NestedStruct"))
+            .with_members(&NESTED_MEMBERS),
+        Schema::new_member(INPUT_ID.with_member("items"), 2, ShapeType::List)
+            .with_members(&LIST_MEMBERS),
+        Schema::new_member(INPUT_ID.with_member("flat"), 3, ShapeType::List)
+            .with_traits(Traits::new().with_xml_flattened())
+            .with_members(&LIST_MEMBERS),
+        Schema::new_member(INPUT_ID.with_member("empty"), 4, ShapeType::List)
+            .with_members(&LIST_MEMBERS),
+        Schema::new_member(INPUT_ID.with_member("tags"), 5, ShapeType::Map)
+            .with_members(&MAP_MEMBERS),
+    ];
+    static NESTED_MEMBERS: [Schema; 1] = [Schema::new_member(
+        NESTED_ID.with_member("when"),
+        0,
+        ShapeType::Timestamp,
+    )];
+    static LIST_MEMBERS: [Schema; 1] =
+        [
+            Schema::new_member(LIST_ID.with_member("member"), 0, ShapeType::Integer)
+                .with_traits(Traits::new().with_xml_name("item")),
+        ];
+    static MAP_MEMBERS: [Schema; 2] = [
+        Schema::new_member(MAP_ID.with_member("key"), 0, ShapeType::String)
+            .with_traits(Traits::new().with_xml_name("K")),
+        Schema::new_member(MAP_ID.with_member("value"), 1, ShapeType::String),
+    ];
+
+    struct Nested;
+
+    impl SerializableStruct for Nested {
+        fn serialize_members<S: ShapeSerializer>(&self, ser: &mut S) -> Result<(), S::Error> {
+            ser.write_timestamp(&NESTED_MEMBERS[0], &DateTime::from_secs(1576540098))
+        }
+    }
+
+    struct Input;
+
+    fn write_list<S: ShapeSerializer>(
+        ser: &mut S,
+        schema: &Schema,
+        items: &[i32],
+    ) -> Result<(), S::Error> {
+        ser.write_list(schema, items.len(), |ser| {
+            for item in items {
+                ser.write_integer(&LIST_MEMBERS[0], *item)?;
+            }
+            Ok(())
+        })
+    }
+
+    impl SerializableStruct for Input {
+        fn serialize_members<S: ShapeSerializer>(&self, ser: &mut S) -> Result<(), S::Error> {
+            ser.write_string(&INPUT_MEMBERS[0], "a b")?;
+            ser.write_struct(&INPUT_MEMBERS[1], &Nested)?;
+            write_list(ser, &INPUT_MEMBERS[2], &[1, 2])?;
+            write_list(ser, &INPUT_MEMBERS[3], &[3])?;
+            write_list(ser, &INPUT_MEMBERS[4], &[])?;
+            let tags: BTreeMap<_, _> = [("k1", "v1"), ("k2", "v2")].into();
+            ser.write_map(&INPUT_MEMBERS[5], tags.len(), |ser| {
+                for (key, value) in &tags {
+                    ser.write_map_key(&MAP_MEMBERS[0], key)?;
+                    ser.write_string(&MAP_MEMBERS[1], value)?;
+                }
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn aws_query_and_ec2_query_names() {
+        let mut out = String::new();
+        QueryShapeSerializer::new(&mut out, "SomeAction", "1.0")
+            .write_struct(&INPUT, &Input)
+            .unwrap();
+        assert_eq!(
+            "Action=SomeAction\
+            &Version=1.0\
+            &name=a%20b\
+            &nested.when=2019-12-16T23%3A48%3A18Z\
+            &items.item.1=1\
+            &items.item.2=2\
+            &flat.1=3\
+            &empty=\
+            &tags.entry.1.K=k1\
+            &tags.entry.1.value=v1\
+            &tags.entry.2.K=k2\
+            &tags.entry.2.value=v2\
+            ",
+            out
+        );
+        assert_eq!(Some("SomeAction"), QueryNode::parse(&out).unwrap().action());
+
+        // `ec2Query` capitalizes names, prefers `ec2QueryName`, flattens lists and omits empty lists
+        let mut out = String::new();
+        QueryShapeSerializer::new(&mut out, "SomeAction", "1.0")
+            .ec2(true)
+            .write_struct(&INPUT, &Input)
+            .unwrap();
+        assert_eq!(
+            "Action=SomeAction\
+            &Version=1.0\
+            &Name=a%20b\
+            &NestedStruct.When=2019-12-16T23%3A48%3A18Z\
+            &Items.1=1\
+            &Items.2=2\
+            &Flat.1=3\
+            &Tags.entry.1.K=k1\
+            &Tags.entry.1.value=v1\
+            &Tags.entry.2.K=k2\
+            &Tags.entry.2.value=v2\
+            ",
+            out
+        );
+    }
+}
@@ -0,0 +1,314 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Decoding of `awsQuery` and `ec2Query` request bodies.
+//!
+//! A request body such as `Action=Op&Version=1&Tags.member.1.Key=a&Tags.member.1.Value=b` is
+//! decoded into a tree of [`QueryNode`]s, keyed by each dot-separated segment of the parameter
+//! names. Lists and maps are then read back out of the tree with
+//! [`QueryNode::list_items`] and [`QueryNode::map_entries`].
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::string::FromUtf8Error;
+
+#[derive(Debug)]
+enum QueryDecodeErrorKind {
+    InvalidEncoding(FromUtf8Error),
+    InvalidIndex { name: String },
+    Custom(Cow<'static, str>),
+    Unhandled(Box<dyn Error + Send + Sync + 'static>),
+}
+
+/// Failed to decode a query string.
+#[derive(Debug)]
+pub struct QueryDecodeError {
+    kind: QueryDecodeErrorKind,
+}
+
+impl Display for QueryDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            QueryDecodeErrorKind::InvalidEncoding(_) => {
+                write!(f, "query string contains invalid percent-encoded UTF-8")
+            }
+            QueryDecodeErrorKind::InvalidIndex { name } => {
+                write!(f, "invalid list or map index in query parameter `{}`", name)
+            }
+            QueryDecodeErrorKind::Custom(msg) => write!(f, "error parsing query string: {}", msg),
+            QueryDecodeErrorKind::Unhandled(_) => write!(f, "error parsing query string"),
+        }
+    }
+}
+
+impl Error for QueryDecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            QueryDecodeErrorKind::InvalidEncoding(source) => Some(source as _),
+            QueryDecodeErrorKind::Unhandled(source) => Some(source.as_ref() as _),
+            QueryDecodeErrorKind::InvalidIndex { .. } | QueryDecodeErrorKind::Custom(..) => None,
+        }
+    }
+}
+
+impl QueryDecodeError {
+    fn invalid_index(name: impl Into<String>) -> Self {
+        Self {
+            kind: QueryDecodeErrorKind::InvalidIndex { name: name.into() },
+        }
+    }
+
+    pub fn custom(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            kind: QueryDecodeErrorKind::Custom(msg.into()),
+        }
+    }
+
+    pub fn unhandled(error: impl Into<Box<dyn Error + Send + Sync + 'static>>) -> Self {
+        Self {
+            kind: QueryDecodeErrorKind::Unhandled(error.into()),
+        }
+    }
+}
+
+/// Decodes a single `application/x-www-form-urlencoded` component.
+fn decode_component(component: &str) -> Result<String, QueryDecodeError> {
+    urlencoding::decode(&component.replace('+', " "))
+        .map(Cow::into_owned)
+        .map_err(|err| QueryDecodeError {
+            kind: QueryDecodeErrorKind::InvalidEncoding(err),
+        })
+}
+
+/// Returns the decoded value of the parameter named `name` without decoding the rest of `input`.
+///
+/// As with [`QueryNode::parse`], if the parameter is repeated, the last value wins.
+pub fn find_parameter(input: &str, name: &str) -> Result<Option<String>, QueryDecodeError> {
+    let mut found = None;
+    for pair in input.split('&').filter(|pair| !pair.is_empty()) {
+        let (pair_name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        // Only names that are encoded need to be decoded to be compared
+        let matches = match pair_name.contains(['%', '+']) {
+            true => decode_component(pair_name)? == name,
+            false => pair_name == name,
+        };
+        if matches {
+            found = Some(value);
+        }
+    }
+    found.map(decode_component).transpose()
+}
+
+/// A node in a decoded query string.
+///
+/// Every node may have both a value and children: `Tags=` (an empty list) followed by
+/// `Tags.member.1=a` yields a `Tags` node with an empty value and a `member` child.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QueryNode {
+    value: Option<String>,
+    children: BTreeMap<String, QueryNode>,
+}
+
+impl QueryNode {
+    /// Decodes an `application/x-www-form-urlencoded` query string into a tree of nodes.
+    ///
+    /// If a parameter is repeated, the last value wins.
+    pub fn parse(input: &str) -> Result<Self, QueryDecodeError> {
+        let mut root = QueryNode::default();
+        for pair in input.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let name = decode_component(name)?;
+            let value = decode_component(value)?;
+            let node = name.split('.').fold(&mut root, |node, segment| {
+                node.children.entry(segment.to_string()).or_default()
+            });
+            node.value = Some(value);
+        }
+        Ok(root)
+    }
+
+    /// Returns the value of this node, if one was set.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Returns the child node named `name`.
+    pub fn get(&self, name: &str) -> Option<&QueryNode> {
+        self.children.get(name)
+    }
+
+    /// Returns the `Action` parameter.
+    pub fn action(&self) -> Option<&str> {
+        self.get("Action").and_then(QueryNode::value)
+    }
+
+    /// Returns the `Version` parameter.
+    pub fn version(&self) -> Option<&str> {
+        self.get("Version").and_then(QueryNode::value)
+    }
+
+    /// Returns this node's children with numeric names, in index order.
+    fn indexed(&self) -> Result<Vec<&QueryNode>, QueryDecodeError> {
+        let mut items = self
+            .children
+            .iter()
+            .map(|(name, node)| match name.parse::<usize>() {
+                Ok(index) if index > 0 => Ok((index, node)),
+                _ => Err(QueryDecodeError::invalid_index(name.as_str())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        items.sort_by_key(|(index, _)| *index);
+        Ok(items.into_iter().map(|(_, node)| node).collect())
+    }
+
+    /// Returns the items of the list rooted at this node, in index order.
+    ///
+    /// `member_name` is the name of the segment preceding the index (`member` unless renamed
+    /// with `@xmlName`), or `None` for flattened (and `ec2Query`) lists, which omit it.
+    pub fn list_items(
+        &self,
+        member_name: Option<&str>,
+    ) -> Result<Vec<&QueryNode>, QueryDecodeError> {
+        match member_name {
+            None => self.indexed(),
+            Some(member_name) => match self.get(member_name) {
+                Some(members) => members.indexed(),
+                None => Ok(Vec::new()),
+            },
+        }
+    }
+
+    /// Returns the entries of the map rooted at this node, in index order.
+    ///
+    /// Entries are read from `entry.N.<key_name>` and `entry.N.<value_name>`, or from
+    /// `N.<key_name>` and `N.<value_name>` when `flattened` is set.
+    pub fn map_entries(
+        &self,
+        flattened: bool,
+        key_name: &str,
+        value_name: &str,
+    ) -> Result<Vec<(&str, &QueryNode)>, QueryDecodeError> {
+        let entries = if flattened {
+            self.indexed()?
+        } else {
+            match self.get("entry") {
+                Some(entries) => entries.indexed()?,
+                None => return Ok(Vec::new()),
+            }
+        };
+        entries
+            .into_iter()
+            .map(|entry| {
+                let key = entry
+                    .get(key_name)
+                    .and_then(QueryNode::value)
+                    .ok_or_else(|| QueryDecodeError::custom("map entry is missing its key"))?;
+                let value = entry
+                    .get(value_name)
+                    .ok_or_else(|| QueryDecodeError::custom("map entry is missing its value"))?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_parameter, QueryNode};
+
+    fn values(items: Vec<&QueryNode>) -> Vec<&str> {
+        items
+            .into_iter()
+            .map(|item| item.value().unwrap())
+            .collect()
+    }
+
+    fn entries<'a>(entries: Vec<(&'a str, &'a QueryNode)>) -> Vec<(&'a str, &'a str)> {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key, value.value().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn nested_structures() {
+        let params =
+            QueryNode::parse("Action=Some+Action&Version=1.0&Outer.Inner.Name=a%26b&Outer.Count=3")
+                .unwrap();
+        assert_eq!(Some("Some Action"), params.action());
+        assert_eq!(Some("1.0"), params.version());
+        let outer = params.get("Outer").unwrap();
+        assert_eq!(None, outer.value());
+        assert_eq!(
+            Some("a&b"),
+            outer.get("Inner").unwrap().get("Name").unwrap().value()
+        );
+        assert_eq!(Some("3"), outer.get("Count").unwrap().value());
+    }
+
+    #[test]
+    fn lists() {
+        let params = QueryNode::parse(
+            "List.member.2=b&List.member.10=c&List.member.1=a&Flat.1=x&Flat.2=y&Empty=",
+        )
+        .unwrap();
+        let list = params.get("List").unwrap();
+        assert_eq!(
+            vec!["a", "b", "c"],
+            values(list.list_items(Some("member")).unwrap())
+        );
+        let flat = params.get("Flat").unwrap();
+        assert_eq!(vec!["x", "y"], values(flat.list_items(None).unwrap()));
+        let empty = params.get("Empty").unwrap();
+        assert!(empty.list_items(Some("member")).unwrap().is_empty());
+
+        let bad = QueryNode::parse("List.member.x=a").unwrap();
+        let err = bad
+            .get("List")
+            .unwrap()
+            .list_items(Some("member"))
+            .unwrap_err();
+        assert_eq!(
+            "invalid list or map index in query parameter `x`",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn maps() {
+        let params = QueryNode::parse(
+            "Map.entry.1.key=a&Map.entry.1.value=1&Map.entry.2.key=b&Map.entry.2.value=2\
+            &Flat.1.K=c&Flat.1.V=3",
+        )
+        .unwrap();
+        let map = params.get("Map").unwrap();
+        assert_eq!(
+            vec![("a", "1"), ("b", "2")],
+            entries(map.map_entries(false, "key", "value").unwrap())
+        );
+        let flat = params.get("Flat").unwrap();
+        assert_eq!(
+            vec![("c", "3")],
+            entries(flat.map_entries(true, "K", "V").unwrap())
+        );
+    }
+
+    #[test]
+    fn find_parameter_decodes_only_the_named_parameter() {
+        let input = "Version=1&Action=First&Tags.member.1=%FF&Act%69on=Some+Action%21";
+        assert_eq!(
+            Some("Some Action!".to_string()),
+            find_parameter(input, "Action").unwrap()
+        );
+        assert_eq!(
+            Some("".to_string()),
+            find_parameter("Version&Action=", "Version").unwrap()
+        );
+        assert_eq!(None, find_parameter(input, "Missing").unwrap());
+        find_parameter("Action=%FF", "Action").expect_err("invalid UTF-8");
+    }
+}
@@ -13,6 +13,8 @@ use aws_smithy_types::endpoint::Endpoint;
 use aws_smithy_types::type_erasure::TypeErasedBox;
 use error::InvalidEndpointError;
 use http_02x::uri::Authority;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -27,18 +29,54 @@ new_type_future! {
 /// The actual endpoint parameters are code generated from the Smithy model, and thus,
 /// are not known to the runtime crates. Hence, this struct is really a new-type around
 /// a [`TypeErasedBox`] that holds the actual concrete parameters in it.
+///
+/// Information that only becomes available during request execution, such as the resolved
+/// identity, is attached to the params as typed properties so that
+/// [`ResolveEndpoint::finalize_params`] can fold it into the concrete parameters.
 #[derive(Debug)]
-pub struct EndpointResolverParams(TypeErasedBox);
+pub struct EndpointResolverParams {
+    inner: TypeErasedBox,
+    properties: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
 
 impl EndpointResolverParams {
     /// Creates a new [`EndpointResolverParams`] from a concrete parameters instance.
     pub fn new<T: fmt::Debug + Send + Sync + 'static>(params: T) -> Self {
-        Self(TypeErasedBox::new(params))
+        Self {
+            inner: TypeErasedBox::new(params),
+            properties: HashMap::new(),
+        }
     }
 
     /// Attempts to downcast the underlying concrete parameters to `T` and return it as a reference.
     pub fn get<T: fmt::Debug + Send + Sync + 'static>(&self) -> Option<&T> {
-        self.0.downcast_ref()
+        self.inner.downcast_ref()
+    }
+
+    /// Attempts to downcast the underlying concrete parameters to `T` and return it as a mutable reference.
+    pub fn get_mut<T: fmt::Debug + Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.inner.downcast_mut()
+    }
+
+    /// Sets a property of type `T`, replacing any existing property of the same type.
+    pub fn set_property<T: Any + Send + Sync + 'static>(&mut self, property: T) {
+        self.properties
+            .insert(TypeId::of::<T>(), Box::new(property));
+    }
+
+    /// Returns the property of type `T`, if one has been set.
+    pub fn get_property<T: Any + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.properties
+            .get(&TypeId::of::<T>())
+            .and_then(|property| property.downcast_ref())
+    }
+
+    /// Removes and returns the property of type `T`, if one has been set.
+    pub fn take_property<T: Any + Send + Sync + 'static>(&mut self) -> Option<T> {
+        self.properties
+            .remove(&TypeId::of::<T>())
+            .and_then(|property| property.downcast().ok())
+            .map(|property| *property)
     }
 }
 
@@ -50,6 +88,30 @@ impl Storable for EndpointResolverParams {
 pub trait ResolveEndpoint: Send + Sync + fmt::Debug {
     /// Asynchronously resolves an endpoint to use from the given endpoint parameters.
     fn resolve_endpoint<'a>(&'a self, params: &'a EndpointResolverParams) -> EndpointFuture<'a>;
+
+    /// Finalizes the endpoint parameters before they're used to resolve an endpoint.
+    ///
+    /// This is called once per request attempt after the identity for the request has been resolved.
+    /// The [`Identity`](crate::client::identity::Identity) is available with
+    /// [`EndpointResolverParams::get_property`], so implementations can use it to populate
+    /// parameters that depend on it, such as an AWS account ID.
+    ///
+    /// The default implementation leaves the parameters unchanged.
+    fn finalize_params<'a>(
+        &'a self,
+        _params: &'a mut EndpointResolverParams,
+    ) -> Result<(), BoxError> {
+        Ok(())
+    }
+
+    /// Returns true if [`finalize_params`](ResolveEndpoint::finalize_params) uses the identity.
+    ///
+    /// The identity is only resolved ahead of the endpoint for resolvers that use it, since the
+    /// endpoint can otherwise change which auth scheme, and so which identity, a request is
+    /// signed with. The default implementation returns false.
+    fn finalize_params_uses_identity(&self) -> bool {
+        false
+    }
 }
 
 /// Shared endpoint resolver.
@@ -69,6 +131,17 @@ impl ResolveEndpoint for SharedEndpointResolver {
     fn resolve_endpoint<'a>(&'a self, params: &'a EndpointResolverParams) -> EndpointFuture<'a> {
         self.0.resolve_endpoint(params)
     }
+
+    fn finalize_params<'a>(
+        &'a self,
+        params: &'a mut EndpointResolverParams,
+    ) -> Result<(), BoxError> {
+        self.0.finalize_params(params)
+    }
+
+    fn finalize_params_uses_identity(&self) -> bool {
+        self.0.finalize_params_uses_identity()
+    }
 }
 
 impl ValidateConfig for SharedEndpointResolver {}
@@ -8,7 +8,8 @@ use crate::client::runtime_components::sealed::ValidateConfig;
 use crate::client::runtime_components::{RuntimeComponents, RuntimeComponentsBuilder};
 use crate::impl_shared_conversions;
 use aws_smithy_types::config_bag::ConfigBag;
-use std::any::Any;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Debug;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -255,12 +256,29 @@ impl_shared_conversions!(convert SharedIdentityResolver from ResolveIdentity usi
 /// an expiration time on the identity data. This is because an `Arc<dyn Any>`
 /// can't be downcast to any arbitrary trait, and expiring identities are
 /// common enough to be built-in.
+///
+/// Identities can also carry typed properties alongside their data, such as the
+/// AWS account that credentials belong to. These are set with [`Identity::with_property`]
+/// and retrieved with [`Identity::property`].
 #[derive(Clone)]
 pub struct Identity {
     data: Arc<dyn Any + Send + Sync>,
     #[allow(clippy::type_complexity)]
     data_debug: Arc<dyn (Fn(&Arc<dyn Any + Send + Sync>) -> &dyn Debug) + Send + Sync>,
     expiration: Option<SystemTime>,
+    properties: HashMap<TypeId, IdentityProperty>,
+}
+
+#[derive(Clone)]
+struct IdentityProperty {
+    value: Arc<dyn Any + Send + Sync>,
+    debug: fn(&(dyn Any + Send + Sync)) -> &dyn Debug,
+}
+
+impl Debug for IdentityProperty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.debug)(self.value.as_ref()).fmt(f)
+    }
 }
 
 impl Identity {
@@ -273,9 +291,34 @@ impl Identity {
             data: Arc::new(data),
             data_debug: Arc::new(|d| d.downcast_ref::<T>().expect("type-checked") as _),
             expiration,
+            properties: HashMap::new(),
         }
     }
 
+    /// Returns this identity with the given property added to it.
+    ///
+    /// Only one property of a given type is stored, so setting a property again replaces it.
+    pub fn with_property<T>(mut self, property: T) -> Self
+    where
+        T: Any + Debug + Send + Sync,
+    {
+        self.properties.insert(
+            TypeId::of::<T>(),
+            IdentityProperty {
+                value: Arc::new(property),
+                debug: |value| value.downcast_ref::<T>().expect("type-checked") as _,
+            },
+        );
+        self
+    }
+
+    /// Returns the property of type `T` stored on this identity, if any.
+    pub fn property<T: Any + Debug + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.properties
+            .get(&TypeId::of::<T>())
+            .and_then(|property| property.value.downcast_ref())
+    }
+
     /// Returns the raw identity data.
     pub fn data<T: Any + Debug + Send + Sync + 'static>(&self) -> Option<&T> {
         self.data.downcast_ref()
@@ -289,10 +332,14 @@ impl Identity {
 
 impl Debug for Identity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Identity")
+        let mut identity = f.debug_struct("Identity");
+        identity
             .field("data", (self.data_debug)(&self.data))
-            .field("expiration", &self.expiration)
-            .finish()
+            .field("expiration", &self.expiration);
+        if !self.properties.is_empty() {
+            identity.field("properties", &self.properties.values().collect::<Vec<_>>());
+        }
+        identity.finish()
     }
 }
 
@@ -329,4 +376,30 @@ mod tests {
         assert_eq!("bar", identity.data::<MyIdentityData>().unwrap().last);
         assert_eq!(Some(expiration), identity.expiration());
     }
+
+    #[test]
+    fn identity_properties() {
+        #[derive(Debug, PartialEq)]
+        struct AccountId(&'static str);
+
+        let identity = Identity::new("foo", None);
+        assert_eq!(None, identity.property::<AccountId>());
+        assert_eq!(
+            "Identity { data: \"foo\", expiration: None }",
+            format!("{identity:?}")
+        );
+
+        let identity = identity
+            .with_property(AccountId("111122223333"))
+            .with_property(AccountId("123456789012"));
+        assert_eq!(
+            Some(&AccountId("123456789012")),
+            identity.property::<AccountId>()
+        );
+        assert_eq!(Some(&"foo"), identity.data::<&str>());
+        assert_eq!(
+            "Identity { data: \"foo\", expiration: None, properties: [AccountId(\"123456789012\")] }",
+            format!("{identity:?}")
+        );
+    }
 }
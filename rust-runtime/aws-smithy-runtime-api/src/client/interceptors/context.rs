@@ -361,6 +361,18 @@ impl<I, O, E> InterceptorContext<I, O, E> {
         }
     }
 
+    /// Returns true if the request can be rewound to make another attempt.
+    ///
+    /// This is false if no request checkpoint could be saved, or if the request body can no longer
+    /// be cloned, such as when it has outgrown the replay buffer added by `SdkBody::with_replay_buffer`.
+    pub fn can_rewind(&self) -> bool {
+        match (self.request_checkpoint.as_ref(), self.tainted) {
+            (None, true) => false,
+            (_, false) => true,
+            (Some(request), _) => request.body().is_rewindable(),
+        }
+    }
+
     /// Returns false if rewinding isn't possible
     ///
     /// Note: This method is intended for internal use only.
@@ -373,16 +385,17 @@ impl<I, O, E> InterceptorContext<I, O, E> {
                 self.tainted = true;
                 return RewindResult::Unnecessary;
             }
-            (Some(req), _) => req.try_clone(),
+            (Some(req), _) => match req.try_clone() {
+                Some(req) => req,
+                // The request body can no longer be cloned, for example because it outgrew its
+                // replay buffer.
+                None => return RewindResult::Impossible,
+            },
         };
 
         // Otherwise, rewind to the saved request checkpoint
         self.phase = Phase::BeforeTransmit;
-        self.request = request_checkpoint;
-        assert!(
-            self.request.is_some(),
-            "if the request wasn't cloneable, then we should have already returned from this method."
-        );
+        self.request = Some(request_checkpoint);
         self.response = None;
         self.output_or_error = None;
         RewindResult::Occurred
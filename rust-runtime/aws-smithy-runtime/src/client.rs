@@ -6,12 +6,20 @@
 /// Smithy auth scheme implementations.
 pub mod auth;
 
+/// Bandwidth limiting for request and response bodies.
+pub mod bandwidth_limit;
+
 pub mod defaults;
 
 pub mod dns;
 
 pub mod endpoint;
 
+/// Event stream receivers that reconnect and resume when their stream fails, and an interceptor
+/// that reports response failures to event stream senders.
+#[cfg(feature = "event-stream")]
+pub mod event_stream;
+
 /// Built-in Smithy HTTP clients and connectors.
 ///
 /// See the [module docs in `aws-smithy-runtime-api`](aws_smithy_runtime_api::client::http)
@@ -44,6 +52,9 @@ pub mod identity;
 /// Interceptors for Smithy clients.
 pub mod interceptors;
 
+/// Progress reporting for request and response bodies.
+pub mod progress;
+
 /// Stalled stream protection for clients
 pub mod stalled_stream_protection;
 
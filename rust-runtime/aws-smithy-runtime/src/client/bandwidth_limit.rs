@@ -0,0 +1,146 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+pub use crate::client::http::body::bandwidth_limit::BandwidthLimiter;
+pub use crate::client::http::body::minimum_throughput::Throughput;
+
+use crate::client::http::body::bandwidth_limit::BandwidthLimitedBody;
+use aws_smithy_async::rt::sleep::SharedAsyncSleep;
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    BeforeDeserializationInterceptorContextMut, BeforeTransmitInterceptorContextMut,
+};
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use std::mem;
+
+/// Configuration for limiting the bandwidth used by request and/or response bodies.
+///
+/// Uploads and downloads are limited separately. To cap the combined throughput of several
+/// clients or operations, give each of them a clone of the same [`BandwidthLimiter`].
+#[derive(Clone, Debug, Default)]
+pub struct BandwidthLimitConfig {
+    upload_limiter: Option<BandwidthLimiter>,
+    download_limiter: Option<BandwidthLimiter>,
+}
+
+impl BandwidthLimitConfig {
+    /// Create a new config that doesn't limit bandwidth.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the throughput of request bodies with the given limiter.
+    pub fn with_upload_limiter(mut self, limiter: BandwidthLimiter) -> Self {
+        self.upload_limiter = Some(limiter);
+        self
+    }
+
+    /// Limit the throughput of response bodies with the given limiter.
+    pub fn with_download_limiter(mut self, limiter: BandwidthLimiter) -> Self {
+        self.download_limiter = Some(limiter);
+        self
+    }
+
+    /// Return the limiter used for request bodies, if any.
+    pub fn upload_limiter(&self) -> Option<&BandwidthLimiter> {
+        self.upload_limiter.as_ref()
+    }
+
+    /// Return the limiter used for response bodies, if any.
+    pub fn download_limiter(&self) -> Option<&BandwidthLimiter> {
+        self.download_limiter.as_ref()
+    }
+}
+
+impl Storable for BandwidthLimitConfig {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Limits the bandwidth of request and response bodies according to the
+/// [`BandwidthLimitConfig`] in the config bag.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct BandwidthLimitInterceptor;
+
+impl Intercept for BandwidthLimitInterceptor {
+    fn name(&self) -> &'static str {
+        "BandwidthLimitInterceptor"
+    }
+
+    fn modify_before_transmit(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let limiter = match cfg
+            .load::<BandwidthLimitConfig>()
+            .and_then(|config| config.upload_limiter())
+        {
+            Some(limiter) => limiter.clone(),
+            None => return Ok(()),
+        };
+        if let Some(0) = context.request().body().content_length() {
+            return Ok(());
+        }
+        let (async_sleep, time_source) = get_runtime_component_deps(runtime_components)?;
+        tracing::trace!("adding bandwidth limit to request body");
+        let it = mem::replace(context.request_mut().body_mut(), SdkBody::taken());
+        let it = it.map_preserve_contents(move |body| {
+            SdkBody::from_body_0_4(BandwidthLimitedBody::new(
+                limiter.clone(),
+                time_source.clone(),
+                async_sleep.clone(),
+                body,
+            ))
+        });
+        let _ = mem::replace(context.request_mut().body_mut(), it);
+        Ok(())
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let limiter = match cfg
+            .load::<BandwidthLimitConfig>()
+            .and_then(|config| config.download_limiter())
+        {
+            Some(limiter) => limiter.clone(),
+            None => return Ok(()),
+        };
+        let (async_sleep, time_source) = get_runtime_component_deps(runtime_components)?;
+        tracing::trace!("adding bandwidth limit to response body");
+        let it = mem::replace(context.response_mut().body_mut(), SdkBody::taken());
+        let it = it.map_preserve_contents(move |body| {
+            SdkBody::from_body_0_4(BandwidthLimitedBody::new(
+                limiter.clone(),
+                time_source.clone(),
+                async_sleep.clone(),
+                body,
+            ))
+        });
+        let _ = mem::replace(context.response_mut().body_mut(), it);
+        Ok(())
+    }
+}
+
+fn get_runtime_component_deps(
+    runtime_components: &RuntimeComponents,
+) -> Result<(SharedAsyncSleep, SharedTimeSource), BoxError> {
+    let async_sleep = runtime_components
+        .sleep_impl()
+        .ok_or("An async sleep implementation is required when bandwidth limiting is enabled")?;
+    let time_source = runtime_components
+        .time_source()
+        .ok_or("A time source is required when bandwidth limiting is enabled")?;
+    Ok((async_sleep, time_source))
+}
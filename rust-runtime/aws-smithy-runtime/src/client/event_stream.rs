@@ -0,0 +1,825 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Event stream receivers that reconnect and resume when their stream fails.
+//!
+//! A [`Receiver`] surfaces a dropped connection as a terminal error. For long-lived subscriptions,
+//! [`ResumableReceiver`] re-issues the operation with the last "resume token" it saw (for example,
+//! a sequence number), backing off between attempts, so that consumers see a single continuous
+//! stream of events:
+//!
+//! ```no_run
+//! # use aws_smithy_http::event_stream::Receiver;
+//! # use aws_smithy_runtime::client::event_stream::ResumableReceiver;
+//! # use aws_smithy_types::retry::RetryConfig;
+//! # #[derive(Debug)] struct Record { sequence_number: u64 }
+//! # #[derive(Debug)] struct StreamError;
+//! # impl std::fmt::Display for StreamError {
+//! #     fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { unimplemented!() }
+//! # }
+//! # impl std::error::Error for StreamError {}
+//! # async fn subscribe(_starting_after: Option<u64>) -> Result<Receiver<Record, StreamError>, StreamError> { unimplemented!() }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut records = ResumableReceiver::new(
+//!     // Re-issue the operation, continuing after the last record that was received
+//!     |starting_after| subscribe(starting_after),
+//!     |record: &Record| Some(record.sequence_number),
+//! )
+//! .retry_config(RetryConfig::standard().with_max_attempts(5))
+//! // Skip records that the service sends again after a reconnect
+//! .deduplicate(|last_sequence_number, record| record.sequence_number <= *last_sequence_number);
+//!
+//! while let Some(record) = records.recv().await? {
+//!     println!("{record:?}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`EventStreamFailureInterceptor`] reports the failures of an event stream operation's response
+//! to the [`ChannelSender`](aws_smithy_http::event_stream::ChannelSender)s of its input.
+
+use crate::client::retries::classifiers::{run_classifiers_on_ctx, TransientErrorClassifier};
+use crate::client::retries::strategy::standard::calculate_exponential_backoff;
+use aws_smithy_async::future::BoxFuture;
+use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep, SharedAsyncSleep};
+use aws_smithy_http::event_stream::{Receiver, StreamFailureReporter};
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    BeforeDeserializationInterceptorContextMut, Error, FinalizerInterceptorContextRef, Input,
+    InterceptorContext,
+};
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
+use aws_smithy_runtime_api::client::result::{ConnectorError, SdkError};
+use aws_smithy_runtime_api::client::retries::classifiers::{
+    ClassifyRetry, RetryAction, RetryReason, SharedRetryClassifier,
+};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::event_stream::RawMessage;
+use aws_smithy_types::retry::RetryConfig;
+use pin_project_lite::pin_project;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+
+/// An event stream that events can be received from, such as a [`Receiver`].
+pub trait RecvEvent<T, E>: Send {
+    /// Receives the next event from the stream, or `None` if the stream has ended.
+    fn recv(&mut self) -> BoxFuture<'_, Option<T>, SdkError<E, RawMessage>>;
+}
+
+impl<T, E> RecvEvent<T, E> for Receiver<T, E>
+where
+    T: Send,
+    E: Send,
+{
+    fn recv(&mut self) -> BoxFuture<'_, Option<T>, SdkError<E, RawMessage>> {
+        Box::pin(Receiver::recv(self))
+    }
+}
+
+/// An error returned by a [`ResumableReceiver`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ResumeError<E> {
+    /// The event stream failed with an error that isn't retryable, or it kept failing until
+    /// the maximum number of attempts was reached.
+    Stream(SdkError<E, RawMessage>),
+    /// The operation that connects to the event stream failed.
+    Connect(BoxError),
+}
+
+impl<E> fmt::Display for ResumeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stream(_) => write!(f, "failed to receive from the event stream"),
+            Self::Connect(_) => write!(f, "failed to connect to the event stream"),
+        }
+    }
+}
+
+impl<E> StdError for ResumeError<E>
+where
+    E: StdError + 'static,
+{
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Stream(err) => Some(err),
+            Self::Connect(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+type ConnectFn<T, E, Token> = Box<
+    dyn FnMut(Option<Token>) -> BoxFuture<'static, Box<dyn RecvEvent<T, E>>, BoxError>
+        + Send
+        + Sync,
+>;
+type ResumeTokenFn<T, Token> = Box<dyn Fn(&T) -> Option<Token> + Send + Sync>;
+type IsDuplicateFn<T, Token> = Box<dyn Fn(&Token, &T) -> bool + Send + Sync>;
+
+/// Receives events from an event stream, reconnecting when the stream fails with a retryable
+/// error.
+///
+/// When the stream fails, the error is classified with the receiver's retry classifiers. If it's
+/// retryable, then the receiver waits with exponential backoff and reconnects by calling its
+/// `connect` function with the resume token of the last event it received. By default, errors are
+/// classified with [`TransientErrorClassifier`], so that dropped connections and timeouts are
+/// retried.
+///
+/// Reconnect attempts are limited by the [`RetryConfig`]'s maximum number of attempts, which
+/// counts consecutive failures and resets whenever an event is received. Errors from the `connect`
+/// function itself are returned immediately, since the operation it invokes has already been
+/// retried by its client.
+pub struct ResumableReceiver<T, E, Token> {
+    connect: ConnectFn<T, E, Token>,
+    resume_token: ResumeTokenFn<T, Token>,
+    is_duplicate: Option<IsDuplicateFn<T, Token>>,
+    retry_config: RetryConfig,
+    retry_classifiers: Vec<SharedRetryClassifier>,
+    sleep_impl: Option<SharedAsyncSleep>,
+    resume_on_end_of_stream: bool,
+
+    receiver: Option<Box<dyn RecvEvent<T, E>>>,
+    last_token: Option<Token>,
+    // The number of consecutive failed attempts to receive an event
+    failed_attempts: u32,
+    // True until the first event that isn't a duplicate is received after a reconnect
+    reconnected: bool,
+    done: bool,
+}
+
+impl<T, E, Token> fmt::Debug for ResumableReceiver<T, E, Token> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResumableReceiver")
+            .field("retry_config", &self.retry_config)
+            .field("retry_classifiers", &self.retry_classifiers)
+            .field("resume_on_end_of_stream", &self.resume_on_end_of_stream)
+            .field("connected", &self.receiver.is_some())
+            .field("failed_attempts", &self.failed_attempts)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<T, E, Token> ResumableReceiver<T, E, Token>
+where
+    T: 'static,
+    E: StdError + Send + Sync + 'static,
+    Token: Clone + 'static,
+{
+    /// Creates a new `ResumableReceiver`.
+    ///
+    /// `connect` issues the operation that returns the event stream. It's called with `None` to
+    /// connect the first time, and with the resume token of the last event that was received (if
+    /// any) to reconnect. `resume_token` returns the resume token of an event, or `None` if the
+    /// event doesn't have one.
+    pub fn new<C, Fut, R, CE>(
+        mut connect: C,
+        resume_token: impl Fn(&T) -> Option<Token> + Send + Sync + 'static,
+    ) -> Self
+    where
+        C: FnMut(Option<Token>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, CE>> + Send + 'static,
+        R: RecvEvent<T, E> + 'static,
+        CE: Into<BoxError>,
+    {
+        Self {
+            connect: Box::new(move |token| {
+                let connecting = connect(token);
+                Box::pin(async move {
+                    match connecting.await {
+                        Ok(receiver) => Ok(Box::new(receiver) as Box<dyn RecvEvent<T, E>>),
+                        Err(err) => Err(err.into()),
+                    }
+                })
+            }),
+            resume_token: Box::new(resume_token),
+            is_duplicate: None,
+            retry_config: RetryConfig::standard(),
+            retry_classifiers: vec![SharedRetryClassifier::new(
+                TransientErrorClassifier::<E>::new(),
+            )],
+            sleep_impl: default_async_sleep(),
+            resume_on_end_of_stream: false,
+            receiver: None,
+            last_token: None,
+            failed_attempts: 0,
+            reconnected: false,
+            done: false,
+        }
+    }
+
+    /// Sets the retry config that determines how many times and how quickly the receiver
+    /// reconnects. Defaults to [`RetryConfig::standard`].
+    pub fn retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Adds a retry classifier that's used to decide whether to reconnect after the stream fails.
+    pub fn retry_classifier(mut self, retry_classifier: impl ClassifyRetry + 'static) -> Self {
+        self.retry_classifiers
+            .push(SharedRetryClassifier::new(retry_classifier));
+        self.retry_classifiers
+            .sort_by_key(|classifier| classifier.priority());
+        self
+    }
+
+    /// Sets the sleep implementation that's used to back off between reconnect attempts.
+    /// Defaults to the Tokio sleep implementation when the `rt-tokio` feature is enabled.
+    pub fn sleep_impl(mut self, sleep_impl: impl AsyncSleep + 'static) -> Self {
+        self.sleep_impl = Some(SharedAsyncSleep::new(sleep_impl));
+        self
+    }
+
+    /// Sets a function that returns true if an event received after reconnecting was already
+    /// received before, given the last resume token.
+    ///
+    /// Duplicates are skipped until the first event that isn't one is received.
+    pub fn deduplicate(
+        mut self,
+        is_duplicate: impl Fn(&Token, &T) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.is_duplicate = Some(Box::new(is_duplicate));
+        self
+    }
+
+    /// Sets whether to reconnect when the stream ends without an error.
+    ///
+    /// This is useful for subscriptions that the service ends periodically, and which have to be
+    /// renewed to keep receiving events. Defaults to `false`.
+    pub fn resume_on_end_of_stream(mut self, resume_on_end_of_stream: bool) -> Self {
+        self.resume_on_end_of_stream = resume_on_end_of_stream;
+        self
+    }
+
+    /// Asynchronously receives the next event, reconnecting as needed.
+    ///
+    /// If the stream has ended, it returns `Ok(None)`. Once an error is returned, the stream has
+    /// ended, and subsequent calls will return `Ok(None)`.
+    pub async fn recv(&mut self) -> Result<Option<T>, ResumeError<E>> {
+        loop {
+            if self.done {
+                return Ok(None);
+            }
+            if self.receiver.is_none() {
+                tracing::debug!(
+                    resuming = self.last_token.is_some(),
+                    "connecting to event stream"
+                );
+                match (self.connect)(self.last_token.clone()).await {
+                    Ok(receiver) => self.receiver = Some(receiver),
+                    Err(err) => {
+                        self.done = true;
+                        return Err(ResumeError::Connect(err));
+                    }
+                }
+            }
+            let receiver = self.receiver.as_mut().expect("connected above");
+
+            match receiver.recv().await {
+                Ok(Some(event)) => {
+                    if self.reconnected {
+                        if let (Some(is_duplicate), Some(token)) =
+                            (&self.is_duplicate, &self.last_token)
+                        {
+                            if is_duplicate(token, &event) {
+                                tracing::trace!("skipping duplicate event after reconnecting");
+                                continue;
+                            }
+                        }
+                        self.reconnected = false;
+                    }
+                    self.failed_attempts = 0;
+                    if let Some(token) = (self.resume_token)(&event) {
+                        self.last_token = Some(token);
+                    }
+                    return Ok(Some(event));
+                }
+                Ok(None) => {
+                    self.receiver = None;
+                    if !self.resume_on_end_of_stream {
+                        self.done = true;
+                        return Ok(None);
+                    }
+                    // A stream that keeps ending without sending any events counts as failing
+                    if self.reconnected || self.failed_attempts > 0 {
+                        if let Err(()) = self.back_off(None).await {
+                            self.done = true;
+                            return Ok(None);
+                        }
+                    }
+                    self.reconnected = true;
+                }
+                Err(err) => {
+                    self.receiver = None;
+                    let (retry_action, err) = classify(&self.retry_classifiers, err);
+                    let retry_after = match retry_action {
+                        RetryAction::RetryIndicated(RetryReason::RetryableError {
+                            retry_after,
+                            ..
+                        }) => retry_after,
+                        _ => {
+                            tracing::debug!(error = %DisplayErrorContext(&err), "event stream failed with an error that isn't retryable");
+                            self.done = true;
+                            return Err(ResumeError::Stream(err));
+                        }
+                    };
+                    if let Err(()) = self.back_off(retry_after).await {
+                        tracing::debug!(error = %DisplayErrorContext(&err), "event stream failed and no more reconnect attempts are allowed");
+                        self.done = true;
+                        return Err(ResumeError::Stream(err));
+                    }
+                    self.reconnected = true;
+                }
+            }
+        }
+    }
+
+    // Records a failed attempt and waits before the next one, or returns an error if no more
+    // attempts are allowed
+    async fn back_off(&mut self, retry_after: Option<Duration>) -> Result<(), ()> {
+        self.failed_attempts += 1;
+        if self.failed_attempts >= self.retry_config.max_attempts() {
+            return Err(());
+        }
+        let sleep_impl = match &self.sleep_impl {
+            Some(sleep_impl) => sleep_impl,
+            None => {
+                tracing::warn!("the event stream can't be reconnected because no sleep implementation is available to back off with");
+                return Err(());
+            }
+        };
+        let delay = retry_after.unwrap_or_else(|| {
+            let base = if self.retry_config.use_static_exponential_base() {
+                1.0
+            } else {
+                fastrand::f64()
+            };
+            calculate_exponential_backoff(
+                base,
+                self.retry_config.initial_backoff().as_secs_f64(),
+                self.failed_attempts - 1,
+                self.retry_config.max_backoff(),
+            )
+        });
+        tracing::debug!(
+            attempt = self.failed_attempts,
+            "reconnecting to event stream in {delay:?}"
+        );
+        sleep_impl.sleep(delay).await;
+        Ok(())
+    }
+}
+
+/// Runs the retry classifiers on an event stream error.
+///
+/// The classifiers see the service error itself, and an error of the same kind as any other error.
+fn classify<E>(
+    classifiers: &[SharedRetryClassifier],
+    err: SdkError<E, RawMessage>,
+) -> (RetryAction, SdkError<E, RawMessage>)
+where
+    E: StdError + Send + Sync + 'static,
+{
+    let context = DisplayErrorContext(&err).to_string();
+    // Move the service error (if any) into the context, and put it back once it's been classified
+    let mut service_error = None;
+    let err = err.map_service_error(|service_err| service_error = Some(service_err));
+    let orchestrator_error = match (service_error, &err) {
+        (Some(service_err), _) => OrchestratorError::operation(Error::erase(service_err)),
+        (None, SdkError::TimeoutError(_)) => OrchestratorError::timeout(context.into()),
+        (None, SdkError::ResponseError(_)) => OrchestratorError::response(context.into()),
+        (None, SdkError::DispatchFailure(failure)) => {
+            let source = context.into();
+            OrchestratorError::connector(if failure.is_io() {
+                ConnectorError::io(source)
+            } else if failure.is_timeout() {
+                ConnectorError::timeout(source)
+            } else if failure.is_user() {
+                ConnectorError::user(source)
+            } else {
+                ConnectorError::other(source, failure.as_other())
+            })
+        }
+        // Event streams don't fail to be constructed, so this can't be retried
+        (None, _) => {
+            return (
+                RetryAction::NoActionIndicated,
+                err.map_service_error(|_| unreachable!("not a service error")),
+            )
+        }
+    };
+
+    let mut ctx = InterceptorContext::new(Input::erase(()));
+    ctx.set_output_or_error(Err(orchestrator_error));
+    let retry_action = run_classifiers_on_ctx(classifiers.iter().cloned(), &ctx);
+
+    let mut service_error = None;
+    if let Some(Err(orchestrator_error)) = ctx.take_output_or_error() {
+        orchestrator_error
+            .map_operation_error(|service_err| service_error = service_err.downcast::<E>().ok());
+    }
+    let err = err.map_service_error(|_| service_error.expect("the service error was put back"));
+    (retry_action, err)
+}
+
+/// Reports the failures of an event stream operation's response to the senders of its input.
+///
+/// When an operation's input events are sent with a
+/// [`ChannelSender`](aws_smithy_http::event_stream::ChannelSender), its request serializer puts a
+/// [`StreamFailureReporter`] in the config bag. This interceptor reports the operation's error to
+/// it, as well as errors reading the response body, which happen after the output was returned
+/// when the response is an event stream. Sending then fails with the reason the stream failed,
+/// rather than only reporting that the channel was closed.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct EventStreamFailureInterceptor;
+
+impl Intercept for EventStreamFailureInterceptor {
+    fn name(&self) -> &'static str {
+        "EventStreamFailureInterceptor"
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let Some(reporter) = cfg.load::<StreamFailureReporter>().cloned() {
+            let body = mem::replace(context.response_mut().body_mut(), SdkBody::taken());
+            let body = body.map_preserve_contents(move |body| {
+                SdkBody::from_body_1_x(FailureReportingBody {
+                    body,
+                    reporter: reporter.clone(),
+                })
+            });
+            *context.response_mut().body_mut() = body;
+        }
+        Ok(())
+    }
+
+    fn read_after_execution(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let (Some(reporter), Some(Err(err))) = (
+            cfg.load::<StreamFailureReporter>(),
+            context.output_or_error(),
+        ) {
+            reporter.fail(err);
+        }
+        Ok(())
+    }
+}
+
+pin_project! {
+    /// A response body that reports errors reading it to a [`StreamFailureReporter`].
+    struct FailureReportingBody {
+        #[pin]
+        body: SdkBody,
+        reporter: StreamFailureReporter,
+    }
+}
+
+impl http_body_1x::Body for FailureReportingBody {
+    type Data = bytes::Bytes;
+    type Error = aws_smithy_types::body::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body_1x::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let frame = ready!(this.body.poll_frame(cx));
+        if let Some(Err(err)) = &frame {
+            this.reporter.fail(err.as_ref());
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body_1x::SizeHint {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventStreamFailureInterceptor, RecvEvent, ResumableReceiver, ResumeError};
+    use crate::client::retries::classifiers::ModeledAsRetryableClassifier;
+    use aws_smithy_async::future::BoxFuture;
+    use aws_smithy_async::test_util::instant_time_and_sleep;
+    use aws_smithy_eventstream::error::Error as EventStreamError;
+    use aws_smithy_eventstream::frame::{MarshallMessage, NoOpSigner};
+    use aws_smithy_http::event_stream::{ChannelSender, EventStreamSender, MessageStreamAdapter};
+    use aws_smithy_runtime_api::client::interceptors::context::{
+        Input, InterceptorContext, Output,
+    };
+    use aws_smithy_runtime_api::client::interceptors::Intercept;
+    use aws_smithy_runtime_api::client::orchestrator::{HttpResponse, OrchestratorError};
+    use aws_smithy_runtime_api::client::result::{ConnectorError, SdkError};
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::byte_stream::ByteStream;
+    use aws_smithy_types::config_bag::ConfigBag;
+    use aws_smithy_types::event_stream::{Message, RawMessage};
+    use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind, RetryConfig};
+    use bytes::Bytes;
+    use std::collections::VecDeque;
+    use std::error::Error as StdError;
+    use std::fmt;
+    use std::io;
+    use std::marker::PhantomData;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[derive(Debug)]
+    struct TestError {
+        retryable: bool,
+    }
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "TestError")
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    impl ProvideErrorKind for TestError {
+        fn retryable_error_kind(&self) -> Option<ErrorKind> {
+            self.retryable.then_some(ErrorKind::ThrottlingError)
+        }
+
+        fn code(&self) -> Option<&str> {
+            None
+        }
+    }
+
+    type Recv = Result<Option<u32>, SdkError<TestError, RawMessage>>;
+
+    struct TestStream(VecDeque<Recv>);
+
+    impl RecvEvent<u32, TestError> for TestStream {
+        fn recv(&mut self) -> BoxFuture<'_, Option<u32>, SdkError<TestError, RawMessage>> {
+            let next = self.0.pop_front().unwrap_or(Ok(None));
+            Box::pin(async move { next })
+        }
+    }
+
+    fn dropped_connection() -> Recv {
+        Err(SdkError::dispatch_failure(ConnectorError::io(
+            "connection reset".into(),
+        )))
+    }
+
+    // Returns a receiver that connects to the given streams in order, and the resume tokens
+    // that it connected with
+    #[allow(clippy::type_complexity)]
+    fn receiver(
+        streams: Vec<Vec<Recv>>,
+    ) -> (
+        ResumableReceiver<u32, TestError, u32>,
+        Arc<Mutex<Vec<Option<u32>>>>,
+    ) {
+        let (_time, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        let mut streams = VecDeque::from(streams);
+        let receiver = ResumableReceiver::new(
+            {
+                let connections = connections.clone();
+                move |token| {
+                    connections.lock().unwrap().push(token);
+                    let stream = streams.pop_front().map(|stream| TestStream(stream.into()));
+                    async move { stream.ok_or("no more streams") }
+                }
+            },
+            |event: &u32| Some(*event),
+        )
+        .sleep_impl(sleep);
+        (receiver, connections)
+    }
+
+    async fn recv_all(receiver: &mut ResumableReceiver<u32, TestError, u32>) -> Vec<u32> {
+        let mut events = Vec::new();
+        while let Some(event) = receiver.recv().await.unwrap() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn reconnects_with_the_last_resume_token() {
+        let (mut receiver, connections) = receiver(vec![
+            vec![Ok(Some(1)), Ok(Some(2)), dropped_connection()],
+            vec![dropped_connection()],
+            vec![Ok(Some(3))],
+        ]);
+        assert_eq!(vec![1, 2, 3], recv_all(&mut receiver).await);
+        assert_eq!(vec![None, Some(2), Some(2)], *connections.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn skips_duplicates_after_reconnecting() {
+        let (receiver, _) = receiver(vec![
+            vec![Ok(Some(1)), Ok(Some(2)), dropped_connection()],
+            vec![Ok(Some(1)), Ok(Some(2)), Ok(Some(3)), Ok(Some(1))],
+        ]);
+        let mut receiver = receiver.deduplicate(|last, event| event <= last);
+        // Only events right after reconnecting are deduplicated
+        assert_eq!(vec![1, 2, 3, 1], recv_all(&mut receiver).await);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (receiver, connections) = receiver(vec![
+            vec![Ok(Some(1)), dropped_connection()],
+            vec![dropped_connection()],
+            vec![dropped_connection()],
+            vec![Ok(Some(2))],
+        ]);
+        let mut receiver = receiver.retry_config(RetryConfig::standard().with_max_attempts(3));
+        assert_eq!(Some(1), receiver.recv().await.unwrap());
+        assert!(matches!(
+            receiver.recv().await,
+            Err(ResumeError::Stream(SdkError::DispatchFailure(_)))
+        ));
+        assert_eq!(3, connections.lock().unwrap().len());
+        // The stream has ended
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn service_errors_are_classified_with_the_configured_classifiers() {
+        let service_error = |retryable| {
+            Err(SdkError::service_error(
+                TestError { retryable },
+                RawMessage::invalid(None),
+            ))
+        };
+        let (receiver, _) = receiver(vec![
+            vec![Ok(Some(1)), service_error(true)],
+            vec![Ok(Some(2)), service_error(false)],
+        ]);
+        let mut receiver =
+            receiver.retry_classifier(ModeledAsRetryableClassifier::<TestError>::new());
+        assert_eq!(Some(1), receiver.recv().await.unwrap());
+        assert_eq!(Some(2), receiver.recv().await.unwrap());
+        match receiver.recv().await {
+            Err(ResumeError::Stream(err)) => {
+                assert!(!err.as_service_error().unwrap().retryable)
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_errors_are_not_retried() {
+        let (mut receiver, connections) = receiver(vec![]);
+        assert!(matches!(
+            receiver.recv().await,
+            Err(ResumeError::Connect(_))
+        ));
+        assert_eq!(1, connections.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn resumes_on_end_of_stream_when_enabled() {
+        let streams = || vec![vec![Ok(Some(1))], vec![Ok(Some(2))], vec![], vec![], vec![]];
+
+        let (mut ending, connections) = receiver(streams());
+        assert_eq!(vec![1], recv_all(&mut ending).await);
+        assert_eq!(1, connections.lock().unwrap().len());
+
+        let (resuming, connections) = receiver(streams());
+        let mut resuming = resuming.resume_on_end_of_stream(true);
+        assert_eq!(vec![1, 2], recv_all(&mut resuming).await);
+        // Streams that end without any events count as failed attempts
+        assert_eq!(
+            vec![None, Some(1), Some(2), Some(2), Some(2)],
+            *connections.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn backs_off_between_attempts() {
+        let (_time, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let (receiver, _) = receiver(vec![
+            vec![dropped_connection()],
+            vec![dropped_connection()],
+            vec![Ok(Some(1))],
+        ]);
+        let mut receiver = receiver.sleep_impl(sleep.clone()).retry_config(
+            RetryConfig::standard()
+                .with_initial_backoff(Duration::from_secs(1))
+                .with_use_static_exponential_base(true),
+        );
+        assert_eq!(vec![1], recv_all(&mut receiver).await);
+        assert_eq!(
+            vec![Duration::from_secs(1), Duration::from_secs(2)],
+            sleep.logs()
+        );
+    }
+
+    #[derive(Debug)]
+    struct TestMarshaller<T>(PhantomData<T>);
+
+    impl<T: fmt::Debug> MarshallMessage for TestMarshaller<T> {
+        type Input = T;
+
+        fn marshall(&self, input: T) -> Result<Message, EventStreamError> {
+            Ok(Message::new(format!("{input:?}").into_bytes()))
+        }
+    }
+
+    fn event_stream_channel() -> (ChannelSender<u32>, MessageStreamAdapter<u32, TestError>) {
+        let (sender, input) = EventStreamSender::channel(1);
+        let adapter = input.into_body_stream(
+            TestMarshaller(PhantomData),
+            TestMarshaller(PhantomData),
+            NoOpSigner {},
+        );
+        (sender, adapter)
+    }
+
+    #[tokio::test]
+    async fn failure_interceptor_reports_response_body_errors() {
+        let (sender, adapter) = event_stream_channel();
+        let mut cfg = ConfigBag::base();
+        cfg.interceptor_state()
+            .store_put(adapter.failure_reporter().unwrap());
+        let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+
+        let body = hyper_0_14::Body::wrap_stream(futures_util::stream::iter(vec![
+            Ok(Bytes::from_static(b"event")),
+            Err(io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                "connection reset",
+            )),
+        ]));
+        let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+        ctx.set_response(HttpResponse::new(
+            200.try_into().unwrap(),
+            SdkBody::from_body_0_4(body),
+        ));
+        EventStreamFailureInterceptor
+            .modify_before_deserialization(&mut (&mut ctx).into(), &rc, &mut cfg)
+            .unwrap();
+        sender.send(1).await.unwrap();
+
+        // The output is returned before the response body fails
+        let body = ctx.response_mut().unwrap().take_body();
+        ByteStream::new(body)
+            .collect()
+            .await
+            .expect_err("reading the body fails");
+        let err = sender.send(2).await.unwrap_err();
+        assert!(err.is_stream_failure());
+        let source = StdError::source(&err).unwrap().to_string();
+        assert!(source.contains("connection reset"), "{source}");
+    }
+
+    #[tokio::test]
+    async fn failure_interceptor_reports_operation_errors() {
+        let (sender, adapter) = event_stream_channel();
+        let mut cfg = ConfigBag::base();
+        cfg.interceptor_state()
+            .store_put(adapter.failure_reporter().unwrap());
+        let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+
+        let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+        ctx.set_output_or_error(Ok(Output::doesnt_matter()));
+        EventStreamFailureInterceptor
+            .read_after_execution(&(&ctx).into(), &rc, &mut cfg)
+            .unwrap();
+        assert!(!sender.is_closed());
+
+        ctx.set_output_or_error(Err(OrchestratorError::other("access denied")));
+        EventStreamFailureInterceptor
+            .read_after_execution(&(&ctx).into(), &rc, &mut cfg)
+            .unwrap();
+        assert!(sender.is_closed());
+        let err = sender.send(1).await.unwrap_err();
+        assert!(err.is_stream_failure());
+        let source = StdError::source(&err).unwrap().to_string();
+        assert!(source.contains("access denied"), "{source}");
+    }
+}
@@ -3,5 +3,6 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+pub mod bandwidth_limit;
 pub mod content_length_enforcement;
 pub mod minimum_throughput;
@@ -0,0 +1,283 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A body-wrapping type that limits the rate at which data is streamed.
+//!
+//! Bodies that share a [`BandwidthLimiter`] also share its bandwidth, so the limit applies to
+//! all of them combined.
+
+use crate::client::http::body::minimum_throughput::Throughput;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep, Sleep};
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::shared::IntoShared;
+use bytes::Bytes;
+use http_body_04x::{Body, SizeHint};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, SystemTime};
+
+/// A token bucket that limits the combined throughput of the bodies it's shared with.
+///
+/// Up to one second's worth of data may be sent in a burst. After that, data is released at the
+/// configured rate. Clones of a `BandwidthLimiter` share the same bucket, so a single limiter can
+/// be used to cap the throughput of an entire client or of a group of operations.
+#[derive(Clone)]
+pub struct BandwidthLimiter {
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl fmt::Debug for BandwidthLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bucket = self.bucket.lock().unwrap();
+        f.debug_struct("BandwidthLimiter")
+            .field("bytes_per_second", &bucket.bytes_per_second)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    bytes_per_second: f64,
+    tokens: f64,
+    last_refill: Option<SystemTime>,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, now: SystemTime) {
+        if let Some(last_refill) = self.last_refill {
+            // If the time source goes backwards, don't refill until it catches up.
+            let elapsed = now.duration_since(last_refill).unwrap_or_default();
+            self.tokens = (self.tokens + elapsed.as_secs_f64() * self.bytes_per_second)
+                .min(self.bytes_per_second);
+        }
+        if self
+            .last_refill
+            .map_or(true, |last_refill| now > last_refill)
+        {
+            self.last_refill = Some(now);
+        }
+    }
+}
+
+impl BandwidthLimiter {
+    /// Create a new bandwidth limiter that allows the given throughput.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    pub fn new(limit: Throughput) -> Self {
+        let bytes_per_second = limit.bytes_per_second();
+        assert!(
+            bytes_per_second > 0.0,
+            "a bandwidth limit must be greater than zero"
+        );
+        Self {
+            bucket: Arc::new(Mutex::new(TokenBucket {
+                bytes_per_second,
+                tokens: bytes_per_second,
+                last_refill: None,
+            })),
+        }
+    }
+
+    /// Try to take `len` bytes from the bucket.
+    ///
+    /// Returns how long to wait before trying again if there isn't enough bandwidth available.
+    /// Chunks larger than the bucket are let through once the bucket is full, which puts the
+    /// bucket into debt until it has been refilled.
+    fn try_acquire(&self, now: SystemTime, len: u64) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill(now);
+        let needed = (len as f64).min(bucket.bytes_per_second);
+        if bucket.tokens >= needed {
+            bucket.tokens -= len as f64;
+            Ok(())
+        } else {
+            let wait_secs = (needed - bucket.tokens) / bucket.bytes_per_second;
+            Err(Duration::from_nanos((wait_secs * 1e9).ceil() as u64))
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// A body-wrapping type that limits the rate at which data is streamed.
+    ///
+    /// Each chunk of data read from the inner body is held back until the [`BandwidthLimiter`]
+    /// has enough bandwidth available for it.
+    pub struct BandwidthLimitedBody<B> {
+        limiter: BandwidthLimiter,
+        time_source: SharedTimeSource,
+        async_sleep: SharedAsyncSleep,
+        buffered: Option<Bytes>,
+        #[pin]
+        sleep_fut: Option<Sleep>,
+        #[pin]
+        inner: B,
+    }
+}
+
+impl<B> BandwidthLimitedBody<B> {
+    /// Create a new bandwidth limited body.
+    pub fn new(
+        limiter: BandwidthLimiter,
+        time_source: impl TimeSource + 'static,
+        async_sleep: impl AsyncSleep + 'static,
+        body: B,
+    ) -> Self {
+        Self {
+            limiter,
+            time_source: time_source.into_shared(),
+            async_sleep: async_sleep.into_shared(),
+            buffered: None,
+            sleep_fut: None,
+            inner: body,
+        }
+    }
+}
+
+impl<B> Body for BandwidthLimitedBody<B>
+where
+    B: Body<Data = Bytes, Error = BoxError>,
+{
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let mut this = self.project();
+        loop {
+            if let Some(sleep_fut) = this.sleep_fut.as_mut().as_pin_mut() {
+                ready!(sleep_fut.poll(cx));
+                this.sleep_fut.set(None);
+            }
+            let len = match this.buffered {
+                Some(data) => data.len(),
+                None => match ready!(this.inner.as_mut().poll_data(cx)) {
+                    Some(Ok(data)) if !data.is_empty() => {
+                        let len = data.len();
+                        *this.buffered = Some(data);
+                        len
+                    }
+                    other => return Poll::Ready(other),
+                },
+            };
+            match this.limiter.try_acquire(this.time_source.now(), len as u64) {
+                Ok(()) => return Poll::Ready(this.buffered.take().map(Ok)),
+                Err(wait) => {
+                    tracing::trace!(
+                        "bandwidth limit reached; waiting {wait:?} to send {len} bytes"
+                    );
+                    this.sleep_fut.set(Some(this.async_sleep.sleep(wait)));
+                }
+            }
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http_02x::HeaderMap>, Self::Error>> {
+        self.project().inner.poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.buffered.is_none() && self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BandwidthLimitedBody, BandwidthLimiter};
+    use crate::client::http::body::minimum_throughput::Throughput;
+    use aws_smithy_async::test_util::instant_time_and_sleep;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::byte_stream::ByteStream;
+    use bytes::Bytes;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn chunked_body(chunks: usize, chunk_len: usize) -> SdkBody {
+        let chunks = (0..chunks)
+            .map(move |_| Ok::<_, std::convert::Infallible>(Bytes::from(vec![b'a'; chunk_len])));
+        SdkBody::from_body_0_4(hyper_0_14::Body::wrap_stream(futures_util::stream::iter(
+            chunks,
+        )))
+    }
+
+    fn assert_about(expected: Duration, actual: Duration) {
+        assert!(
+            actual >= expected && actual < expected + Duration::from_millis(1),
+            "expected about {expected:?} but was {actual:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn data_is_released_at_the_configured_rate() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let limiter = BandwidthLimiter::new(Throughput::new_bytes_per_second(100));
+        let body =
+            BandwidthLimitedBody::new(limiter, time_source, sleep.clone(), chunked_body(10, 100));
+
+        let data = ByteStream::new(SdkBody::from_body_0_4(body))
+            .collect()
+            .await
+            .unwrap()
+            .into_bytes();
+        assert_eq!(1000, data.len());
+        // The first second's worth of data is sent immediately.
+        assert_about(Duration::from_secs(9), sleep.total_duration());
+    }
+
+    #[tokio::test]
+    async fn chunks_larger_than_the_limit_are_let_through() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let limiter = BandwidthLimiter::new(Throughput::new_bytes_per_second(100));
+        let body =
+            BandwidthLimitedBody::new(limiter, time_source, sleep.clone(), chunked_body(2, 300));
+
+        let data = ByteStream::new(SdkBody::from_body_0_4(body))
+            .collect()
+            .await
+            .unwrap()
+            .into_bytes();
+        assert_eq!(600, data.len());
+        // The first chunk puts the bucket 200 bytes into debt, and the second chunk has to wait
+        // for the bucket to be full again.
+        assert_about(Duration::from_secs(3), sleep.total_duration());
+    }
+
+    #[tokio::test]
+    async fn bandwidth_is_shared_between_bodies() {
+        let (time_source, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let limiter = BandwidthLimiter::new(Throughput::new_bytes_per_second(100));
+        let first = BandwidthLimitedBody::new(
+            limiter.clone(),
+            time_source.clone(),
+            sleep.clone(),
+            chunked_body(5, 100),
+        );
+        let second =
+            BandwidthLimitedBody::new(limiter, time_source, sleep.clone(), chunked_body(5, 100));
+
+        let (first, second) = tokio::join!(
+            ByteStream::new(SdkBody::from_body_0_4(first)).collect(),
+            ByteStream::new(SdkBody::from_body_0_4(second)).collect(),
+        );
+        assert_eq!(500, first.unwrap().into_bytes().len());
+        assert_eq!(500, second.unwrap().into_bytes().len());
+        // 1000 bytes were sent in total at 100 bytes per second, so at least nine seconds
+        // must have been waited for between the two bodies.
+        assert!(sleep.total_duration() >= Duration::from_secs(9));
+    }
+}
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use self::auth::orchestrate_auth;
+use self::auth::{orchestrate_auth, resolve_identity_for_endpoint};
 use crate::client::interceptors::Interceptors;
 use crate::client::orchestrator::http::{log_response_body, read_body};
 use crate::client::timeout::{MaybeTimeout, MaybeTimeoutConfig, TimeoutKind};
@@ -350,14 +350,20 @@ async fn try_attempt(
 ) {
     run_interceptors!(halt_on_err: read_before_attempt(ctx, runtime_components, cfg));
 
-    halt_on_err!([ctx] => orchestrate_endpoint(ctx, runtime_components, cfg).await.map_err(OrchestratorError::other));
+    // The identity is resolved before the endpoint when endpoint parameters depend on it
+    let resolved_identity = resolve_identity_for_endpoint(runtime_components, cfg).await;
+    let identity = resolved_identity
+        .as_ref()
+        .map(|resolved| resolved.identity().clone());
+
+    halt_on_err!([ctx] => orchestrate_endpoint(identity, ctx, runtime_components, cfg).await.map_err(OrchestratorError::other));
 
     run_interceptors!(halt_on_err: {
         modify_before_signing(ctx, runtime_components, cfg);
         read_before_signing(ctx, runtime_components, cfg);
     });
 
-    halt_on_err!([ctx] => orchestrate_auth(ctx, runtime_components, cfg).await.map_err(OrchestratorError::other));
+    halt_on_err!([ctx] => orchestrate_auth(resolved_identity, ctx, runtime_components, cfg).await.map_err(OrchestratorError::other));
 
     run_interceptors!(halt_on_err: {
         read_after_signing(ctx, runtime_components, cfg);
@@ -1237,6 +1243,227 @@ mod tests {
         assert!(context.response().is_none());
     }
 
+    #[tokio::test]
+    async fn endpoint_params_are_finalized_with_the_resolved_identity() {
+        use crate::client::identity::no_auth::NoAuthIdentity;
+        use aws_smithy_runtime_api::client::endpoint::{EndpointFuture, ResolveEndpoint};
+        use aws_smithy_runtime_api::client::identity::{Identity, IdentityFuture, ResolveIdentity};
+        use aws_smithy_types::endpoint::Endpoint;
+
+        #[derive(Debug)]
+        struct AccountId(&'static str);
+
+        #[derive(Debug)]
+        struct TestIdentityResolver;
+        impl ResolveIdentity for TestIdentityResolver {
+            fn resolve_identity<'a>(
+                &'a self,
+                _: &'a RuntimeComponents,
+                _: &'a ConfigBag,
+            ) -> IdentityFuture<'a> {
+                IdentityFuture::ready(Ok(
+                    Identity::new(NoAuthIdentity::new(), None).with_property(AccountId("123"))
+                ))
+            }
+        }
+
+        #[derive(Debug)]
+        struct TestParams {
+            account_id: Option<&'static str>,
+        }
+
+        #[derive(Debug)]
+        struct TestEndpointResolver;
+        impl ResolveEndpoint for TestEndpointResolver {
+            fn resolve_endpoint<'a>(
+                &'a self,
+                params: &'a EndpointResolverParams,
+            ) -> EndpointFuture<'a> {
+                let account_id = params
+                    .get::<TestParams>()
+                    .and_then(|params| params.account_id)
+                    .unwrap_or("unknown");
+                EndpointFuture::ready(Ok(Endpoint::builder()
+                    .url(format!("https://{account_id}.example.com"))
+                    .build()))
+            }
+
+            fn finalize_params<'a>(
+                &'a self,
+                params: &'a mut EndpointResolverParams,
+            ) -> Result<(), BoxError> {
+                let account_id = params
+                    .get_property::<Identity>()
+                    .and_then(|identity| identity.property::<AccountId>())
+                    .map(|account_id| account_id.0);
+                params
+                    .get_mut::<TestParams>()
+                    .ok_or("params of expected type was not present")?
+                    .account_id = account_id;
+                Ok(())
+            }
+
+            fn finalize_params_uses_identity(&self) -> bool {
+                true
+            }
+        }
+
+        #[derive(Debug)]
+        struct EndpointParamsInterceptor;
+        impl Intercept for EndpointParamsInterceptor {
+            fn name(&self) -> &'static str {
+                "EndpointParamsInterceptor"
+            }
+
+            fn read_before_execution(
+                &self,
+                _context: &BeforeSerializationInterceptorContextRef<'_>,
+                cfg: &mut ConfigBag,
+            ) -> Result<(), BoxError> {
+                cfg.interceptor_state()
+                    .store_put(EndpointResolverParams::new(TestParams { account_id: None }));
+                Ok(())
+            }
+        }
+
+        #[derive(Debug)]
+        struct TestRuntimePlugin {
+            builder: RuntimeComponentsBuilder,
+        }
+
+        impl RuntimePlugin for TestRuntimePlugin {
+            fn runtime_components(
+                &self,
+                _: &RuntimeComponentsBuilder,
+            ) -> Cow<'_, RuntimeComponentsBuilder> {
+                Cow::Borrowed(&self.builder)
+            }
+        }
+
+        let runtime_plugins = RuntimePlugins::new()
+            .with_operation_plugin(TestOperationRuntimePlugin::new())
+            .with_operation_plugin(NoAuthRuntimePlugin::new())
+            .with_operation_plugin(TestRuntimePlugin {
+                builder: RuntimeComponentsBuilder::new("test")
+                    .with_identity_resolver(NO_AUTH_SCHEME_ID, TestIdentityResolver)
+                    .with_endpoint_resolver(Some(SharedEndpointResolver::new(TestEndpointResolver)))
+                    .with_interceptor(SharedInterceptor::new(EndpointParamsInterceptor)),
+            });
+
+        let context = invoke_with_stop_point(
+            "test",
+            "test",
+            Input::doesnt_matter(),
+            &runtime_plugins,
+            StopPoint::BeforeTransmit,
+        )
+        .await
+        .expect("success");
+        assert_eq!(
+            "https://123.example.com/",
+            context.request().expect("request is set").uri()
+        );
+    }
+
+    #[tokio::test]
+    async fn identity_is_only_resolved_ahead_of_the_endpoint_when_endpoint_params_use_it() {
+        use crate::client::identity::no_auth::NoAuthIdentity;
+        use aws_smithy_runtime_api::client::endpoint::{EndpointFuture, ResolveEndpoint};
+        use aws_smithy_runtime_api::client::identity::{Identity, IdentityFuture, ResolveIdentity};
+        use aws_smithy_runtime_api::client::interceptors::context::{Error, InterceptorContext};
+        use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+        use aws_smithy_runtime_api::client::result::SdkError;
+        use aws_smithy_types::endpoint::Endpoint;
+        use std::sync::atomic::AtomicUsize;
+
+        /// Fails the first time an identity is resolved
+        #[derive(Clone, Debug, Default)]
+        struct TestIdentityResolver {
+            calls: Arc<AtomicUsize>,
+        }
+        impl ResolveIdentity for TestIdentityResolver {
+            fn resolve_identity<'a>(
+                &'a self,
+                _: &'a RuntimeComponents,
+                _: &'a ConfigBag,
+            ) -> IdentityFuture<'a> {
+                if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    IdentityFuture::ready(Err("failed to resolve the identity".into()))
+                } else {
+                    IdentityFuture::ready(Ok(Identity::new(NoAuthIdentity::new(), None)))
+                }
+            }
+        }
+
+        #[derive(Debug)]
+        struct TestEndpointResolver {
+            uses_identity: bool,
+        }
+        impl ResolveEndpoint for TestEndpointResolver {
+            fn resolve_endpoint<'a>(&'a self, _: &'a EndpointResolverParams) -> EndpointFuture<'a> {
+                EndpointFuture::ready(Ok(Endpoint::builder().url("https://example.com").build()))
+            }
+
+            fn finalize_params_uses_identity(&self) -> bool {
+                self.uses_identity
+            }
+        }
+
+        #[derive(Debug)]
+        struct TestRuntimePlugin {
+            builder: RuntimeComponentsBuilder,
+        }
+
+        impl RuntimePlugin for TestRuntimePlugin {
+            fn runtime_components(
+                &self,
+                _: &RuntimeComponentsBuilder,
+            ) -> Cow<'_, RuntimeComponentsBuilder> {
+                Cow::Borrowed(&self.builder)
+            }
+        }
+
+        async fn invoke(
+            identity_resolver: TestIdentityResolver,
+            uses_identity: bool,
+        ) -> Result<InterceptorContext, SdkError<Error, HttpResponse>> {
+            let runtime_plugins = RuntimePlugins::new()
+                .with_operation_plugin(TestOperationRuntimePlugin::new())
+                .with_operation_plugin(NoAuthRuntimePlugin::new())
+                .with_operation_plugin(TestRuntimePlugin {
+                    builder: RuntimeComponentsBuilder::new("test")
+                        .with_identity_resolver(NO_AUTH_SCHEME_ID, identity_resolver)
+                        .with_endpoint_resolver(Some(SharedEndpointResolver::new(
+                            TestEndpointResolver { uses_identity },
+                        ))),
+                });
+            invoke_with_stop_point(
+                "test",
+                "test",
+                Input::doesnt_matter(),
+                &runtime_plugins,
+                StopPoint::BeforeTransmit,
+            )
+            .await
+        }
+
+        // Without endpoint params that use it, the identity is only resolved for auth, so its
+        // failure fails the request
+        let identity_resolver = TestIdentityResolver::default();
+        invoke(identity_resolver.clone(), false)
+            .await
+            .expect_err("the identity couldn't be resolved");
+        assert_eq!(1, identity_resolver.calls.load(Ordering::SeqCst));
+
+        // Failing to resolve the identity ahead of the endpoint doesn't fail the request, and it's
+        // resolved again for auth
+        let identity_resolver = TestIdentityResolver::default();
+        invoke(identity_resolver.clone(), true)
+            .await
+            .expect("success");
+        assert_eq!(2, identity_resolver.calls.load(Ordering::SeqCst));
+    }
+
     /// The "finally" interceptors should run upon error when the StopPoint is set to BeforeTransmit
     #[tokio::test]
     async fn test_stop_points_error_handling() {
@@ -10,17 +10,20 @@ use aws_smithy_runtime_api::client::auth::{
     AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, AuthSchemeOptionResolverParams,
     ResolveAuthSchemeOptions,
 };
-use aws_smithy_runtime_api::client::identity::ResolveIdentity;
-use aws_smithy_runtime_api::client::identity::{IdentityCacheLocation, ResolveCachedIdentity};
+use aws_smithy_runtime_api::client::endpoint::ResolveEndpoint;
+use aws_smithy_runtime_api::client::identity::{
+    Identity, IdentityCacheLocation, ResolveCachedIdentity, ResolveIdentity, SharedIdentityResolver,
+};
 use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 use aws_smithy_types::config_bag::ConfigBag;
 use aws_smithy_types::endpoint::Endpoint;
+use aws_smithy_types::error::display::DisplayErrorContext;
 use aws_smithy_types::Document;
 use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::fmt;
-use tracing::trace;
+use tracing::{debug, trace};
 
 #[derive(Debug)]
 struct NoMatchingAuthSchemeError(ExploredList);
@@ -109,7 +112,97 @@ impl fmt::Display for AuthOrchestrationError {
 
 impl StdError for AuthOrchestrationError {}
 
+/// An identity that was resolved ahead of endpoint resolution, along with the auth scheme it's for
+#[derive(Debug)]
+pub(super) struct ResolvedIdentity {
+    scheme_id: AuthSchemeId,
+    identity: Identity,
+}
+
+impl ResolvedIdentity {
+    pub(super) fn identity(&self) -> &Identity {
+        &self.identity
+    }
+}
+
+/// Resolves the identity for the first auth scheme option that can be used, if the endpoint
+/// resolver derives endpoint parameters from it
+///
+/// This happens before endpoint resolution. Since the endpoint may still rule out this auth scheme,
+/// [`orchestrate_auth`] falls back to resolving identities for the remaining options as it always
+/// has. Failing to resolve the identity here doesn't fail the request, since `orchestrate_auth`
+/// resolves it again, and reports the error if it still fails.
+pub(super) async fn resolve_identity_for_endpoint(
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+) -> Option<ResolvedIdentity> {
+    if !runtime_components
+        .endpoint_resolver()
+        .finalize_params_uses_identity()
+    {
+        return None;
+    }
+    match resolve_identity(runtime_components, cfg).await {
+        Ok(resolved_identity) => resolved_identity,
+        Err(err) => {
+            debug!(
+                error = %DisplayErrorContext(&*err),
+                "failed to resolve the identity ahead of endpoint resolution"
+            );
+            None
+        }
+    }
+}
+
+async fn resolve_identity(
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+) -> Result<Option<ResolvedIdentity>, BoxError> {
+    let params = cfg
+        .load::<AuthSchemeOptionResolverParams>()
+        .expect("auth scheme option resolver params must be set");
+    let option_resolver = runtime_components.auth_scheme_option_resolver();
+    let options = option_resolver.resolve_auth_scheme_options(params)?;
+
+    for &scheme_id in options.as_ref() {
+        if let Some(auth_scheme) = runtime_components.auth_scheme(scheme_id) {
+            if let Some(identity_resolver) = auth_scheme.identity_resolver(runtime_components) {
+                let identity =
+                    resolve_cached_identity(identity_resolver, runtime_components, cfg).await?;
+                trace!(identity = ?identity, "resolved identity ahead of endpoint resolution");
+                return Ok(Some(ResolvedIdentity {
+                    scheme_id,
+                    identity,
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+async fn resolve_cached_identity(
+    identity_resolver: SharedIdentityResolver,
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+) -> Result<Identity, BoxError> {
+    let identity_cache =
+        if identity_resolver.cache_location() == IdentityCacheLocation::RuntimeComponents {
+            runtime_components.identity_cache()
+        } else {
+            IdentityCache::no_cache()
+        };
+    trace!(
+        identity_cache = ?identity_cache,
+        identity_resolver = ?identity_resolver,
+        "resolving identity"
+    );
+    identity_cache
+        .resolve_cached_identity(identity_resolver, runtime_components, cfg)
+        .await
+}
+
 pub(super) async fn orchestrate_auth(
+    resolved_identity: Option<ResolvedIdentity>,
     ctx: &mut InterceptorContext,
     runtime_components: &RuntimeComponents,
     cfg: &ConfigBag,
@@ -137,29 +230,27 @@ pub(super) async fn orchestrate_auth(
         if let Some(auth_scheme) = runtime_components.auth_scheme(scheme_id) {
             // Use the resolved auth scheme to resolve an identity
             if let Some(identity_resolver) = auth_scheme.identity_resolver(runtime_components) {
-                let identity_cache = if identity_resolver.cache_location()
-                    == IdentityCacheLocation::RuntimeComponents
-                {
-                    runtime_components.identity_cache()
-                } else {
-                    IdentityCache::no_cache()
-                };
                 let signer = auth_scheme.signer();
                 trace!(
                     auth_scheme = ?auth_scheme,
-                    identity_cache = ?identity_cache,
                     identity_resolver = ?identity_resolver,
                     signer = ?signer,
-                    "resolved auth scheme, identity cache, identity resolver, and signing implementation"
+                    "resolved auth scheme, identity resolver, and signing implementation"
                 );
 
                 match extract_endpoint_auth_scheme_config(endpoint, scheme_id) {
                     Ok(auth_scheme_endpoint_config) => {
                         trace!(auth_scheme_endpoint_config = ?auth_scheme_endpoint_config, "extracted auth scheme endpoint config");
 
-                        let identity = identity_cache
-                            .resolve_cached_identity(identity_resolver, runtime_components, cfg)
-                            .await?;
+                        let identity = match &resolved_identity {
+                            Some(resolved) if resolved.scheme_id == scheme_id => {
+                                resolved.identity.clone()
+                            }
+                            _ => {
+                                resolve_cached_identity(identity_resolver, runtime_components, cfg)
+                                    .await?
+                            }
+                        };
                         trace!(identity = ?identity, "resolved identity");
 
                         trace!("signing request");
@@ -376,7 +467,7 @@ mod tests {
         layer.store_put(Endpoint::builder().url("dontcare").build());
         let cfg = ConfigBag::of_layers(vec![layer]);
 
-        orchestrate_auth(&mut ctx, &runtime_components, &cfg)
+        orchestrate_auth(None, &mut ctx, &runtime_components, &cfg)
             .await
             .expect("success");
 
@@ -432,7 +523,7 @@ mod tests {
         // First, test the presence of a basic auth login and absence of a bearer token
         let (runtime_components, cfg) =
             config_with_identity(HTTP_BASIC_AUTH_SCHEME_ID, Login::new("a", "b", None));
-        orchestrate_auth(&mut ctx, &runtime_components, &cfg)
+        orchestrate_auth(None, &mut ctx, &runtime_components, &cfg)
             .await
             .expect("success");
         assert_eq!(
@@ -453,7 +544,7 @@ mod tests {
         ctx.set_request(HttpRequest::empty());
         let _ = ctx.take_input();
         ctx.enter_before_transmit_phase();
-        orchestrate_auth(&mut ctx, &runtime_components, &cfg)
+        orchestrate_auth(None, &mut ctx, &runtime_components, &cfg)
             .await
             .expect("success");
         assert_eq!(
@@ -608,7 +699,7 @@ mod tests {
         layer.store_put(AuthSchemeOptionResolverParams::new("doesntmatter"));
         let config_bag = ConfigBag::of_layers(vec![layer]);
 
-        orchestrate_auth(&mut ctx, &runtime_components, &config_bag)
+        orchestrate_auth(None, &mut ctx, &runtime_components, &config_bag)
             .await
             .expect("success");
         assert_eq!(
@@ -6,6 +6,7 @@
 use aws_smithy_runtime_api::client::endpoint::{
     error::ResolveEndpointError, EndpointFuture, EndpointResolverParams, ResolveEndpoint,
 };
+use aws_smithy_runtime_api::client::identity::Identity;
 use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
 use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
@@ -68,12 +69,23 @@ impl From<StaticUriEndpointResolverParams> for EndpointResolverParams {
 }
 
 pub(super) async fn orchestrate_endpoint(
+    identity: Option<Identity>,
     ctx: &mut InterceptorContext,
     runtime_components: &RuntimeComponents,
     cfg: &mut ConfigBag,
 ) -> Result<(), BoxError> {
     trace!("orchestrating endpoint resolution");
 
+    let endpoint_resolver = runtime_components.endpoint_resolver();
+    // Params are normally set by an interceptor, but they may also come from a lower layer of the
+    // config bag, in which case they're used as-is since they can't be modified
+    if let Some(params) = cfg.get_mut_from_interceptor_state::<EndpointResolverParams>() {
+        if let Some(identity) = identity {
+            params.set_property(identity);
+        }
+        endpoint_resolver.finalize_params(params)?;
+    }
+
     let params = cfg
         .load::<EndpointResolverParams>()
         .expect("endpoint resolver params must be set");
@@ -81,10 +93,7 @@ pub(super) async fn orchestrate_endpoint(
     tracing::debug!(endpoint_params = ?params, endpoint_prefix = ?endpoint_prefix, "resolving endpoint");
     let request = ctx.request_mut().expect("set during serialization");
 
-    let endpoint = runtime_components
-        .endpoint_resolver()
-        .resolve_endpoint(params)
-        .await?;
+    let endpoint = endpoint_resolver.resolve_endpoint(params).await?;
     tracing::debug!("will use endpoint {:?}", endpoint);
     apply_endpoint(request, &endpoint, endpoint_prefix)?;
 
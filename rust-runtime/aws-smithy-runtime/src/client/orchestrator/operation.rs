@@ -443,9 +443,15 @@ mod tests {
     use crate::client::http::test_util::{capture_request, ReplayEvent, StaticReplayClient};
     use crate::client::retries::classifiers::HttpStatusCodeClassifier;
     use aws_smithy_async::rt::sleep::{SharedAsyncSleep, TokioSleep};
+    use aws_smithy_runtime_api::client::http::{
+        HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpConnector,
+    };
     use aws_smithy_runtime_api::client::result::ConnectorError;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
     use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::byte_stream::ByteStream;
     use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
 
     #[tokio::test]
     async fn operation() {
@@ -540,4 +546,99 @@ mod tests {
 
         connector.assert_requests_match(&[]);
     }
+
+    // Reads each request body, failing the first request with a 503
+    #[derive(Clone, Debug, Default)]
+    struct BodyReadingClient {
+        bodies: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpConnector for BodyReadingClient {
+        fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+            let bodies = self.bodies.clone();
+            HttpConnectorFuture::new(async move {
+                let body = ByteStream::new(request.into_body())
+                    .collect()
+                    .await
+                    .map_err(|err| ConnectorError::io(err.into()))?
+                    .into_bytes();
+                let mut bodies = bodies.lock().unwrap();
+                bodies.push(String::from_utf8(body.to_vec()).unwrap());
+                let status = if bodies.len() == 1 { 503 } else { 200 };
+                Ok(http_02x::Response::builder()
+                    .status(status)
+                    .body(SdkBody::empty())
+                    .unwrap()
+                    .try_into()
+                    .unwrap())
+            })
+        }
+    }
+
+    impl HttpClient for BodyReadingClient {
+        fn http_connector(
+            &self,
+            _: &HttpConnectorSettings,
+            _: &RuntimeComponents,
+        ) -> SharedHttpConnector {
+            self.clone().into_shared()
+        }
+    }
+
+    fn streaming_body(chunks: &[&'static str]) -> SdkBody {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok::<_, Infallible>(bytes::Bytes::from_static(chunk.as_bytes())))
+            .collect::<Vec<_>>();
+        SdkBody::from_body_0_4(hyper_0_14::Body::wrap_stream(futures_util::stream::iter(
+            chunks,
+        )))
+    }
+
+    async fn invoke_with_replay_buffer(capacity: usize) -> (bool, Vec<String>) {
+        let client = BodyReadingClient::default();
+        let operation = Operation::builder()
+            .service_name("test")
+            .operation_name("test")
+            .http_client(client.clone())
+            .endpoint_url("http://localhost:1234")
+            .no_auth()
+            .standard_retry(&RetryConfig::standard())
+            .retry_classifier(HttpStatusCodeClassifier::default())
+            .timeout_config(TimeoutConfig::disabled())
+            .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+            .serializer(move |_: ()| {
+                Ok(HttpRequest::new(
+                    streaming_body(&["what ", "are ", "you?"]).with_replay_buffer(capacity),
+                ))
+            })
+            .deserializer::<_, Infallible>(|response| {
+                if u16::from(response.status()) == 503 {
+                    Err(OrchestratorError::connector(ConnectorError::io(
+                        "test".into(),
+                    )))
+                } else {
+                    Ok(())
+                }
+            })
+            .build();
+
+        let succeeded = operation.invoke(()).await.is_ok();
+        let bodies = client.bodies.lock().unwrap().clone();
+        (succeeded, bodies)
+    }
+
+    #[tokio::test]
+    async fn replay_buffered_bodies_are_retried() {
+        let (succeeded, bodies) = invoke_with_replay_buffer(1024).await;
+        assert!(succeeded);
+        assert_eq!(vec!["what are you?", "what are you?"], bodies);
+    }
+
+    #[tokio::test]
+    async fn bodies_that_exceed_their_replay_buffer_are_not_retried() {
+        let (succeeded, bodies) = invoke_with_replay_buffer(8).await;
+        assert!(!succeeded);
+        assert_eq!(vec!["what are you?"], bodies);
+    }
 }
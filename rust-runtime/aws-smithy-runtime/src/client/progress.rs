@@ -0,0 +1,235 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    BeforeDeserializationInterceptorContextMut, BeforeTransmitInterceptorContextMut,
+};
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::retries::RequestAttempts;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::body::progress::ProgressListener;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+
+/// Reports the progress of sending request bodies and receiving response bodies.
+///
+/// This is usually added to a single operation invocation, with the `interceptor` method of the
+/// operation's `customize()` builder, to follow the progress of that upload or download.
+///
+/// The upload listener is attached to each request body as it's transmitted, so it reports the
+/// bytes actually sent (after any compression or checksum trailers are added). The download
+/// listener is attached to each response body before it's deserialized, and for streaming
+/// responses, reports progress as the response [`ByteStream`] is read.
+///
+/// When a request is retried, the listeners are told that the transfer was reset before progress
+/// is reported for the new attempt.
+///
+/// [`ByteStream`]: aws_smithy_types::byte_stream::ByteStream
+#[derive(Clone, Default)]
+pub struct ProgressInterceptor {
+    upload_listener: Option<Arc<dyn ProgressListener>>,
+    download_listener: Option<Arc<dyn ProgressListener>>,
+}
+
+impl fmt::Debug for ProgressInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressInterceptor")
+            .field("upload_listener", &self.upload_listener.is_some())
+            .field("download_listener", &self.download_listener.is_some())
+            .finish()
+    }
+}
+
+impl ProgressInterceptor {
+    /// Create a new `ProgressInterceptor` without any listeners.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report the progress of sending request bodies to the given listener.
+    pub fn with_upload_listener(mut self, listener: impl ProgressListener + 'static) -> Self {
+        self.upload_listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// Report the progress of receiving response bodies to the given listener.
+    pub fn with_download_listener(mut self, listener: impl ProgressListener + 'static) -> Self {
+        self.download_listener = Some(Arc::new(listener));
+        self
+    }
+}
+
+fn is_retry(cfg: &ConfigBag) -> bool {
+    cfg.load::<RequestAttempts>()
+        .map(|attempts| attempts.attempts() > 1)
+        .unwrap_or_default()
+}
+
+fn attach(body: &mut SdkBody, listener: &Arc<dyn ProgressListener>) {
+    let listener = listener.clone();
+    let it = mem::replace(body, SdkBody::taken());
+    let _ = mem::replace(body, it.with_progress_listener(listener));
+}
+
+impl Intercept for ProgressInterceptor {
+    fn name(&self) -> &'static str {
+        "ProgressInterceptor"
+    }
+
+    fn modify_before_transmit(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let Some(listener) = &self.upload_listener {
+            if is_retry(cfg) {
+                listener.on_reset();
+            }
+            attach(context.request_mut().body_mut(), listener);
+        }
+        Ok(())
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let Some(listener) = &self.download_listener {
+            if is_retry(cfg) {
+                listener.on_reset();
+            }
+            attach(context.response_mut().body_mut(), listener);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressInterceptor;
+    use aws_smithy_runtime_api::client::interceptors::context::{Input, InterceptorContext};
+    use aws_smithy_runtime_api::client::interceptors::Intercept;
+    use aws_smithy_runtime_api::client::retries::RequestAttempts;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::body::progress::{Progress, ProgressListener};
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
+    use http_body_04x::Body;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Progress(u64, Option<u64>),
+        Reset,
+    }
+
+    #[derive(Clone, Default)]
+    struct Events(Arc<Mutex<Vec<Event>>>);
+
+    impl ProgressListener for Events {
+        fn on_progress(&self, progress: Progress) {
+            self.0.lock().unwrap().push(Event::Progress(
+                progress.bytes_transferred(),
+                progress.total_bytes(),
+            ));
+        }
+
+        fn on_reset(&self) {
+            self.0.lock().unwrap().push(Event::Reset);
+        }
+    }
+
+    fn cfg(attempt: u32) -> ConfigBag {
+        let mut layer = Layer::new("test");
+        layer.store_put(RequestAttempts::new(attempt));
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    async fn read(body: &mut SdkBody) {
+        while let Some(data) = body.data().await {
+            data.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn upload_progress_is_reported_for_each_attempt() {
+        let events = Events::default();
+        let interceptor = ProgressInterceptor::new().with_upload_listener(events.clone());
+        let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+
+        for attempt in 1..=2 {
+            let mut context = InterceptorContext::new(Input::doesnt_matter());
+            context.enter_serialization_phase();
+            context.set_request(
+                http_02x::Request::builder()
+                    .body(SdkBody::from("hello!"))
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+            );
+            let _ = context.take_input();
+            context.enter_before_transmit_phase();
+            interceptor
+                .modify_before_transmit(&mut (&mut context).into(), &rc, &mut cfg(attempt))
+                .unwrap();
+            read(context.request_mut().unwrap().body_mut()).await;
+        }
+
+        assert_eq!(
+            vec![
+                Event::Progress(6, Some(6)),
+                Event::Reset,
+                Event::Progress(6, Some(6))
+            ],
+            *events.0.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn download_progress_is_reported() {
+        let events = Events::default();
+        let interceptor = ProgressInterceptor::new().with_download_listener(events.clone());
+        let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+
+        let mut context = InterceptorContext::new(Input::doesnt_matter());
+        context.enter_serialization_phase();
+        context.set_request(
+            http_02x::Request::builder()
+                .body(SdkBody::empty())
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        let _ = context.take_input();
+        context.enter_before_transmit_phase();
+        context.enter_transmit_phase();
+        let _ = context.take_request();
+        context.set_response(
+            http_02x::Response::builder()
+                .status(200)
+                .body(SdkBody::from("hello world!"))
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        context.enter_before_deserialization_phase();
+        interceptor
+            .modify_before_deserialization(&mut (&mut context).into(), &rc, &mut cfg(1))
+            .unwrap();
+        read(context.response_mut().unwrap().body_mut()).await;
+
+        assert_eq!(
+            vec![Event::Progress(12, Some(12))],
+            *events.0.lock().unwrap()
+        );
+    }
+}
@@ -220,6 +220,17 @@ impl RetryStrategy for StandardRetryStrategy {
         let classifier_result = run_classifiers_on_ctx(retry_classifiers, ctx);
 
         if classifier_result.should_retry() {
+            // Don't spend a retry permit on a retry that can't be attempted
+            if !ctx.can_rewind() {
+                update_rate_limiter_if_exists(runtime_components, cfg, false);
+
+                debug!(
+                    "attempt #{request_attempts} failed with {classifier_result:?}, but the request \
+                     body can't be replayed, so it won't be retried"
+                );
+                return Ok(ShouldAttempt::No);
+            }
+
             // Calculate the appropriate backoff time.
             let backoff = match self.calculate_backoff(
                 runtime_components,
@@ -289,7 +300,7 @@ fn check_rate_limiter_for_delay(
     None
 }
 
-fn calculate_exponential_backoff(
+pub(crate) fn calculate_exponential_backoff(
     base: f64,
     initial_backoff: f64,
     retry_attempts: u32,
@@ -445,6 +456,23 @@ mod tests {
         assert_eq!(ShouldAttempt::No, actual);
     }
 
+    #[test]
+    fn dont_retry_when_request_cant_be_rewound() {
+        let (mut ctx, rc, mut cfg) = set_up_cfg_and_context(
+            ErrorKind::TransientError,
+            1,
+            RetryConfig::standard().with_max_attempts(3),
+        );
+        // No checkpoint was saved because the request wasn't cloneable, and the first attempt
+        // has already been made.
+        ctx.rewind(&mut cfg);
+        let strategy = StandardRetryStrategy::new();
+        let actual = strategy
+            .should_attempt_retry(&ctx, &rc, &cfg)
+            .expect("method is infallible for this use");
+        assert_eq!(ShouldAttempt::No, actual);
+    }
+
     #[test]
     fn should_not_panic_when_exponential_backoff_duration_could_not_be_created() {
         let (ctx, rc, cfg) = set_up_cfg_and_context(
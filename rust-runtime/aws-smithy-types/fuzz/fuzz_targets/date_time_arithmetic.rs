@@ -0,0 +1,30 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![no_main]
+
+use aws_smithy_types::date_time::DateTime;
+use libfuzzer_sys::fuzz_target;
+use std::time::Duration;
+
+fuzz_target!(|data: [u8; 24]| {
+    let secs = i64::from_le_bytes(data[0..8].try_into().unwrap());
+    let nanos = u32::from_le_bytes(data[8..12].try_into().unwrap()) % 1_000_000_000;
+    let duration_secs = u64::from_le_bytes(data[12..20].try_into().unwrap());
+    let duration_nanos = u32::from_le_bytes(data[20..24].try_into().unwrap()) % 1_000_000_000;
+
+    let date_time = DateTime::from_secs_and_nanos(secs, nanos);
+    let duration = Duration::new(duration_secs, duration_nanos);
+    if let Some(later) = date_time.checked_add(duration) {
+        assert_eq!(Some(date_time), later.checked_sub(duration));
+        assert_eq!(Some(duration), later.checked_duration_since(date_time));
+    }
+    if let Some(earlier) = date_time.checked_sub(duration) {
+        assert_eq!(Some(date_time), earlier.checked_add(duration));
+    }
+    // Formatting should never panic, even when out of range.
+    let _ = date_time.fmt_iso8601_basic();
+    let _ = date_time.fmt_epoch_millis();
+});
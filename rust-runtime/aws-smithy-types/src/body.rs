@@ -21,6 +21,11 @@ use std::task::{Context, Poll};
 pub mod http_body_0_4_x;
 #[cfg(feature = "http-body-1-x")]
 pub mod http_body_1_x;
+pub mod progress;
+#[cfg(feature = "http-body-0-4-x")]
+mod replay;
+
+use progress::{ProgressListener, ProgressTracker};
 
 /// A generic, boxed error that's `Send` and `Sync`
 pub type Error = Box<dyn StdError + Send + Sync>;
@@ -37,10 +42,15 @@ pin_project! {
         inner: Inner,
         // An optional function to recreate the inner body
         //
-        // In the event of retry, this function will be called to generate a new body. See
-        // [`try_clone()`](SdkBody::try_clone)
-        rebuild: Option<Arc<dyn (Fn() -> Inner) + Send + Sync>>,
-        bytes_contents: Option<Bytes>
+        // In the event of retry, this function will be called to generate a new body. It returns
+        // `None` if the body can no longer be recreated. See [`try_clone()`](SdkBody::try_clone)
+        rebuild: Option<Arc<dyn (Fn() -> Option<Inner>) + Send + Sync>>,
+        // An optional function that checks whether `rebuild` can still recreate the body, without
+        // recreating it. If it's `None`, `rebuild` always can.
+        can_rebuild: Option<Arc<dyn (Fn() -> bool) + Send + Sync>>,
+        bytes_contents: Option<Bytes>,
+        // An optional listener that's told about the progress of reading the body
+        progress: Option<ProgressTracker>,
     }
 }
 
@@ -49,6 +59,7 @@ impl Debug for SdkBody {
         f.debug_struct("SdkBody")
             .field("inner", &self.inner)
             .field("retryable", &self.rebuild.is_some())
+            .field("progress", &self.progress)
             .finish()
     }
 }
@@ -107,10 +118,44 @@ impl SdkBody {
     /// is only necessary when you need to enable retries for your own streaming container.
     pub fn retryable(f: impl Fn() -> SdkBody + Send + Sync + 'static) -> Self {
         let initial = f();
+        Self::retryable_while(initial, move || Some(f()), None)
+    }
+
+    /// Construct a body from `initial` that can be recreated with `rebuild` until it returns `None`.
+    ///
+    /// `can_rebuild` must return `false` once `rebuild` would return `None`.
+    fn retryable_while(
+        initial: SdkBody,
+        rebuild: impl Fn() -> Option<SdkBody> + Send + Sync + 'static,
+        can_rebuild: Option<Arc<dyn (Fn() -> bool) + Send + Sync>>,
+    ) -> Self {
         SdkBody {
             inner: initial.inner,
-            rebuild: Some(Arc::new(move || f().inner)),
+            rebuild: Some(Arc::new(move || rebuild().map(|body| body.inner))),
+            can_rebuild,
             bytes_contents: initial.bytes_contents,
+            progress: initial.progress,
+        }
+    }
+
+    /// Make this body retryable by buffering up to `capacity` bytes of it in memory.
+    ///
+    /// Bodies created from single-use streams, like an arbitrary `AsyncRead` or a channel, can't
+    /// be recreated and so can't be retried. This buffers the data as it's read so that it can be
+    /// replayed if the request is retried. If more than `capacity` bytes are read, the buffer is
+    /// discarded and [`try_clone`](SdkBody::try_clone) will return `None` from then on, so the
+    /// request won't be retried.
+    ///
+    /// A [progress listener](SdkBody::with_progress_listener) is told about data replayed from the
+    /// buffer whether it was attached before or after the buffer.
+    ///
+    /// Bodies that are already retryable are returned unchanged.
+    #[cfg(feature = "http-body-0-4-x")]
+    pub fn with_replay_buffer(self, capacity: usize) -> Self {
+        if self.rebuild.is_some() {
+            self
+        } else {
+            replay::replay_buffered(self, capacity)
         }
     }
 
@@ -120,7 +165,9 @@ impl SdkBody {
         Self {
             inner: Inner::Taken,
             rebuild: None,
+            can_rebuild: None,
             bytes_contents: None,
+            progress: None,
         }
     }
 
@@ -128,8 +175,10 @@ impl SdkBody {
     pub fn empty() -> Self {
         Self {
             inner: Inner::Once { inner: None },
-            rebuild: Some(Arc::new(|| Inner::Once { inner: None })),
+            rebuild: Some(Arc::new(|| Some(Inner::Once { inner: None }))),
+            can_rebuild: None,
             bytes_contents: Some(Bytes::new()),
+            progress: None,
         }
     }
 
@@ -140,10 +189,21 @@ impl SdkBody {
 
     pub(crate) fn poll_next(
         self: Pin<&mut Self>,
-        #[allow(unused)] cx: &mut Context<'_>,
+        cx: &mut Context<'_>,
     ) -> Poll<Option<Result<Bytes, Error>>> {
         let this = self.project();
-        match this.inner.project() {
+        let result = Self::poll_inner(this.inner, cx);
+        if let (Some(progress), Poll::Ready(Some(Ok(bytes)))) = (this.progress, &result) {
+            progress.on_data(bytes.len());
+        }
+        result
+    }
+
+    fn poll_inner(
+        inner: Pin<&mut Inner>,
+        #[allow(unused)] cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Error>>> {
+        match inner.project() {
             InnerProj::Once { ref mut inner } => {
                 let data = inner.take();
                 match data {
@@ -186,7 +246,9 @@ impl SdkBody {
                 )),
             },
             rebuild: None,
+            can_rebuild: None,
             bytes_contents: None,
+            progress: None,
         }
     }
 
@@ -222,16 +284,54 @@ impl SdkBody {
     }
 
     /// Attempt to clone this SdkBody. This will fail if the inner data is not cloneable, such as when
-    /// it is a single-use stream that can't be recreated.
+    /// it is a single-use stream that can't be recreated, or a stream that has outgrown its
+    /// [replay buffer](SdkBody::with_replay_buffer).
     pub fn try_clone(&self) -> Option<Self> {
-        self.rebuild.as_ref().map(|rebuild| {
-            let next = rebuild();
-            Self {
-                inner: next,
-                rebuild: self.rebuild.clone(),
-                bytes_contents: self.bytes_contents.clone(),
-            }
-        })
+        self.rebuild
+            .as_ref()
+            .and_then(|rebuild| rebuild())
+            .map(|next| {
+                let mut body = Self {
+                    inner: next,
+                    rebuild: self.rebuild.clone(),
+                    can_rebuild: self.can_rebuild.clone(),
+                    bytes_contents: self.bytes_contents.clone(),
+                    progress: None,
+                };
+                body.progress = self
+                    .progress
+                    .as_ref()
+                    .map(|progress| progress.for_copy(body.content_length()));
+                body
+            })
+    }
+
+    /// Returns true if [`try_clone`](SdkBody::try_clone) would return a body.
+    ///
+    /// Unlike `try_clone`, this doesn't recreate the body, so it's cheap and has no side effects.
+    pub fn is_rewindable(&self) -> bool {
+        match (&self.rebuild, &self.can_rebuild) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(_), Some(can_rebuild)) => can_rebuild(),
+        }
+    }
+
+    /// Attach a [`ProgressListener`] that's told about the progress of reading this body.
+    ///
+    /// The listener is kept when the body is re-created with [`try_clone`](SdkBody::try_clone)
+    /// (e.g. when a request is retried), and is told that the transfer was reset when a
+    /// re-created body is read after an earlier copy of it was. The body's data, retryability,
+    /// and size hint aren't changed.
+    ///
+    /// A body can only have one listener, so this replaces any listener that was already
+    /// attached.
+    pub fn with_progress_listener(mut self, listener: impl ProgressListener + 'static) -> Self {
+        self.progress = Some(ProgressTracker::new(
+            Arc::new(listener),
+            self.content_length(),
+        ));
+        self
     }
 
     /// Return `true` if this SdkBody is streaming, `false` if it is in-memory.
@@ -294,10 +394,13 @@ impl SdkBody {
     /// Given a function to modify an `SdkBody`, run that function against this `SdkBody` before
     /// returning the result.
     pub fn map(self, f: impl Fn(SdkBody) -> SdkBody + Sync + Send + 'static) -> SdkBody {
-        if self.rebuild.is_some() {
-            SdkBody::retryable(move || f(self.try_clone().unwrap()))
-        } else {
-            f(self)
+        match self.try_clone() {
+            Some(initial) => {
+                let initial = f(initial);
+                let can_rebuild = self.can_rebuild.clone();
+                SdkBody::retryable_while(initial, move || self.try_clone().map(&f), can_rebuild)
+            }
+            None => f(self),
         }
     }
 
@@ -311,11 +414,7 @@ impl SdkBody {
         f: impl Fn(SdkBody) -> SdkBody + Sync + Send + 'static,
     ) -> SdkBody {
         let contents = self.bytes_contents.clone();
-        let mut out = if self.rebuild.is_some() {
-            SdkBody::retryable(move || f(self.try_clone().unwrap()))
-        } else {
-            f(self)
-        };
+        let mut out = self.map(f);
         out.bytes_contents = contents;
         out
     }
@@ -334,10 +433,14 @@ impl From<Bytes> for SdkBody {
             inner: Inner::Once {
                 inner: Some(bytes.clone()),
             },
-            rebuild: Some(Arc::new(move || Inner::Once {
-                inner: Some(bytes.clone()),
+            rebuild: Some(Arc::new(move || {
+                Some(Inner::Once {
+                    inner: Some(bytes.clone()),
+                })
             })),
+            can_rebuild: None,
             bytes_contents: Some(b),
+            progress: None,
         }
     }
 }
@@ -405,6 +508,95 @@ mod test {
         assert!(format!("{:?}", body).contains("Once"));
     }
 
+    #[tokio::test]
+    async fn progress_is_reported_and_reset_for_copies() {
+        use crate::body::progress::{Progress, ProgressListener};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Progress(u64, Option<u64>),
+            Reset,
+        }
+
+        #[derive(Clone, Default)]
+        struct Events(Arc<Mutex<Vec<Event>>>);
+
+        impl ProgressListener for Events {
+            fn on_progress(&self, progress: Progress) {
+                self.0.lock().unwrap().push(Event::Progress(
+                    progress.bytes_transferred(),
+                    progress.total_bytes(),
+                ));
+            }
+
+            fn on_reset(&self) {
+                self.0.lock().unwrap().push(Event::Reset);
+            }
+        }
+
+        let events = Events::default();
+        let body = SdkBody::from("hello!").with_progress_listener(events.clone());
+        assert_eq!(Some(6), body.content_length());
+        assert_eq!(Some(b"hello!".as_slice()), body.bytes());
+
+        let mut copy = body.try_clone().expect("retryable");
+        let mut body = body;
+        while body.next().await.is_some() {}
+        assert_eq!(vec![Event::Progress(6, Some(6))], *events.0.lock().unwrap());
+
+        while copy.next().await.is_some() {}
+        assert_eq!(
+            vec![
+                Event::Progress(6, Some(6)),
+                Event::Reset,
+                Event::Progress(6, Some(6))
+            ],
+            *events.0.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn progress_survives_map() {
+        use crate::body::progress::Progress;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        let transferred = Arc::new(AtomicU64::new(0));
+        let listener = {
+            let transferred = transferred.clone();
+            move |progress: Progress| {
+                transferred.store(progress.bytes_transferred(), Ordering::SeqCst)
+            }
+        };
+        let body = SdkBody::from("hello!")
+            .with_progress_listener(listener)
+            .map(|body| body);
+        let mut body = body.try_clone().expect("still retryable");
+        while body.next().await.is_some() {}
+        assert_eq!(6, transferred.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn is_rewindable_does_not_recreate_the_body() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let rebuilt = Arc::new(AtomicUsize::new(0));
+        let body = SdkBody::retryable({
+            let rebuilt = rebuilt.clone();
+            move || {
+                rebuilt.fetch_add(1, Ordering::SeqCst);
+                SdkBody::from("hello")
+            }
+        });
+        let rebuilt_initially = rebuilt.load(Ordering::SeqCst);
+        assert!(body.is_rewindable());
+        assert!(body.map(|body| body).is_rewindable());
+        assert_eq!(rebuilt_initially + 1, rebuilt.load(Ordering::SeqCst));
+        assert!(!SdkBody::taken().is_rewindable());
+    }
+
     #[test]
     fn sdk_body_is_send() {
         fn is_send<T: Send>() {}
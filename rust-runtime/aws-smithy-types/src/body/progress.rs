@@ -0,0 +1,140 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Progress reporting for [`SdkBody`](crate::body::SdkBody) and
+//! [`ByteStream`](crate::byte_stream::ByteStream).
+//!
+//! A [`ProgressListener`] can be attached to a body with
+//! [`SdkBody::with_progress_listener`](crate::body::SdkBody::with_progress_listener) or
+//! [`ByteStream::with_progress_listener`](crate::byte_stream::ByteStream::with_progress_listener).
+//! The listener is told how many bytes have been read from the body each time a chunk of data is
+//! read. When a retryable body is re-created to be sent again (e.g. when a request is retried),
+//! the listener is told that the transfer was reset before progress is reported for the new body.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// The progress of reading a body.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    bytes_transferred: u64,
+    chunk_len: u64,
+    total_bytes: Option<u64>,
+}
+
+impl Progress {
+    /// The number of bytes read from the body so far.
+    pub fn bytes_transferred(&self) -> u64 {
+        self.bytes_transferred
+    }
+
+    /// The number of bytes in the chunk of data that was just read.
+    pub fn chunk_len(&self) -> u64 {
+        self.chunk_len
+    }
+
+    /// The total size of the body in bytes, if it's known.
+    pub fn total_bytes(&self) -> Option<u64> {
+        self.total_bytes
+    }
+}
+
+/// Listens to the progress of reading a body.
+///
+/// Closures that take a [`Progress`] implement this trait, so
+/// `body.with_progress_listener(|progress: Progress| ...)` works for simple cases.
+pub trait ProgressListener: Send + Sync {
+    /// Called each time a chunk of data is read from the body.
+    fn on_progress(&self, progress: Progress);
+
+    /// Called when the body was re-created to be sent again, before any progress is reported for
+    /// it. Progress starts over from zero bytes transferred.
+    ///
+    /// This does nothing by default.
+    fn on_reset(&self) {}
+}
+
+impl<F> ProgressListener for F
+where
+    F: Fn(Progress) + Send + Sync,
+{
+    fn on_progress(&self, progress: Progress) {
+        self(progress)
+    }
+}
+
+impl<T> ProgressListener for Arc<T>
+where
+    T: ProgressListener + ?Sized,
+{
+    fn on_progress(&self, progress: Progress) {
+        self.as_ref().on_progress(progress)
+    }
+
+    fn on_reset(&self) {
+        self.as_ref().on_reset()
+    }
+}
+
+/// Tracks the progress of a single body, and whether an earlier copy of it was read.
+#[derive(Clone)]
+pub(crate) struct ProgressTracker {
+    listener: Arc<dyn ProgressListener>,
+    /// Shared by every copy of the body, and set once any of them has been read from.
+    any_copy_started: Arc<AtomicBool>,
+    started: bool,
+    bytes_transferred: u64,
+    total_bytes: Option<u64>,
+}
+
+impl fmt::Debug for ProgressTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressTracker")
+            .field("bytes_transferred", &self.bytes_transferred)
+            .field("total_bytes", &self.total_bytes)
+            .finish()
+    }
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(listener: Arc<dyn ProgressListener>, total_bytes: Option<u64>) -> Self {
+        Self {
+            listener,
+            any_copy_started: Arc::new(AtomicBool::new(false)),
+            started: false,
+            bytes_transferred: 0,
+            total_bytes,
+        }
+    }
+
+    /// Return a tracker for a re-created copy of the body, which hasn't been read from yet.
+    pub(crate) fn for_copy(&self, total_bytes: Option<u64>) -> Self {
+        Self {
+            listener: self.listener.clone(),
+            any_copy_started: self.any_copy_started.clone(),
+            started: false,
+            bytes_transferred: 0,
+            total_bytes,
+        }
+    }
+
+    pub(crate) fn on_data(&mut self, chunk_len: usize) {
+        if !self.started {
+            self.started = true;
+            if self.any_copy_started.swap(true, Ordering::AcqRel) {
+                self.listener.on_reset();
+            }
+        }
+        let chunk_len = chunk_len as u64;
+        self.bytes_transferred += chunk_len;
+        self.listener.on_progress(Progress {
+            bytes_transferred: self.bytes_transferred,
+            chunk_len,
+            total_bytes: self.total_bytes,
+        });
+    }
+}
@@ -0,0 +1,324 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A body that buffers the data of a single-use stream so that it can be replayed on retry.
+
+use crate::body::{Error, SdkBody};
+use bytes::Bytes;
+use http_body_0_4::{Body, SizeHint};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// State shared by every copy of a replay-buffered body.
+struct ReplayState {
+    // The single-use body that the data is read from. It's taken out while a copy reads from it, so
+    // that the lock isn't held while the source is polled.
+    source: Option<SdkBody>,
+    // The chunks read from `source` so far. Emptied once they exceed `capacity`.
+    buffer: Vec<Bytes>,
+    buffered_len: usize,
+    capacity: usize,
+    // The number of chunks read from `source` so far
+    chunks_read: usize,
+    // Set once more than `capacity` bytes have been read, at which point the body can't be
+    // replayed anymore.
+    overflowed: bool,
+    source_done: bool,
+    trailers: Option<Option<http::HeaderMap>>,
+}
+
+impl ReplayState {
+    fn take_source(&mut self) -> Result<SdkBody, Error> {
+        self.source.take().ok_or_else(|| {
+            "a replay-buffered body can't be read by two of its copies at once".into()
+        })
+    }
+
+    fn record(&mut self, chunk: &Bytes) {
+        self.chunks_read += 1;
+        if self.overflowed {
+            return;
+        }
+        if self.buffered_len + chunk.len() > self.capacity {
+            self.overflowed = true;
+            self.buffer = Vec::new();
+        } else {
+            self.buffered_len += chunk.len();
+            self.buffer.push(chunk.clone());
+        }
+    }
+}
+
+/// One copy of a replay-buffered body.
+///
+/// Each copy replays the chunks that were already read from the source before it continues
+/// reading from the source itself.
+struct ReplayBody {
+    state: Arc<Mutex<ReplayState>>,
+    // The index of the next chunk to return
+    position: usize,
+    size_hint: SizeHint,
+}
+
+impl ReplayBody {
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, Error>>> {
+        let mut source = {
+            let mut state = self.state.lock().unwrap();
+            if !state.overflowed && self.position < state.buffer.len() {
+                self.position += 1;
+                return Poll::Ready(Some(Ok(state.buffer[self.position - 1].clone())));
+            }
+            if self.position != state.chunks_read {
+                return Poll::Ready(Some(Err(
+                    "data needed to replay this body was discarded after its replay buffer was exceeded"
+                        .into(),
+                )));
+            }
+            if state.source_done {
+                return Poll::Ready(None);
+            }
+            match state.take_source() {
+                Ok(source) => source,
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        };
+        let result = Pin::new(&mut source).poll_next(cx);
+        let mut state = self.state.lock().unwrap();
+        state.source = Some(source);
+        match result {
+            Poll::Ready(Some(Ok(chunk))) => {
+                state.record(&chunk);
+                self.position += 1;
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(None) => {
+                state.source_done = true;
+                Poll::Ready(None)
+            }
+            other => other,
+        }
+    }
+}
+
+impl Body for ReplayBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        self.get_mut().poll_chunk(cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        let mut source = {
+            let mut state = self.state.lock().unwrap();
+            if let Some(trailers) = &state.trailers {
+                return Poll::Ready(Ok(trailers.clone()));
+            }
+            state.take_source()?
+        };
+        let result = Pin::new(&mut source).poll_next_trailers(cx);
+        let mut state = self.state.lock().unwrap();
+        state.source = Some(source);
+        let trailers = match result {
+            Poll::Ready(Ok(trailers)) => trailers,
+            other => return other,
+        };
+        state.trailers = Some(trailers.clone());
+        Poll::Ready(Ok(trailers))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.size_hint.exact() == Some(0)
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.size_hint.clone()
+    }
+}
+
+/// Wrap `source` so that up to `capacity` bytes of it are buffered for replay.
+pub(super) fn replay_buffered(mut source: SdkBody, capacity: usize) -> SdkBody {
+    // Progress is reported for the replay-buffered body rather than its source, so that a listener
+    // attached to the source is also told about data replayed from the buffer.
+    let progress = source.progress.take();
+    let mut size_hint = SizeHint::new();
+    let (lower, upper) = source.bounds_on_remaining_length();
+    size_hint.set_lower(lower);
+    if let Some(upper) = upper {
+        size_hint.set_upper(upper);
+    }
+    let state = Arc::new(Mutex::new(ReplayState {
+        source: Some(source),
+        buffer: Vec::new(),
+        buffered_len: 0,
+        capacity,
+        chunks_read: 0,
+        overflowed: false,
+        source_done: false,
+        trailers: None,
+    }));
+    let can_copy = {
+        let state = state.clone();
+        move || !state.lock().unwrap().overflowed
+    };
+    let copy = move || {
+        if state.lock().unwrap().overflowed {
+            return None;
+        }
+        Some(SdkBody::from_body_0_4_internal(ReplayBody {
+            state: state.clone(),
+            position: 0,
+            size_hint: size_hint.clone(),
+        }))
+    };
+    let initial = copy().expect("nothing has been read yet");
+    let mut body = SdkBody::retryable_while(initial, copy, Some(Arc::new(can_copy)));
+    body.progress = progress;
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::body::{Error, SdkBody};
+    use bytes::Bytes;
+    use http_body_0_4::Body;
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    // A single-use streaming body
+    struct Chunks(VecDeque<&'static str>);
+
+    impl Body for Chunks {
+        type Data = Bytes;
+        type Error = Error;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            Poll::Ready(self.0.pop_front().map(|chunk| Ok(Bytes::from(chunk))))
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+            Poll::Ready(Ok(None))
+        }
+    }
+
+    fn streaming_body(chunks: &[&'static str]) -> SdkBody {
+        SdkBody::from_body_0_4(Chunks(chunks.iter().copied().collect()))
+    }
+
+    async fn read_all(mut body: SdkBody) -> Result<String, Error> {
+        let mut out = String::new();
+        while let Some(chunk) = body.next().await {
+            out.push_str(std::str::from_utf8(&chunk?).unwrap());
+        }
+        Ok(out)
+    }
+
+    #[tokio::test]
+    async fn bodies_that_fit_can_be_replayed() {
+        let body = streaming_body(&["hello", " ", "world"]);
+        assert!(body.try_clone().is_none());
+
+        let body = body.with_replay_buffer(11);
+        let checkpoint = body.try_clone().expect("body is retryable");
+        assert_eq!("hello world", read_all(body).await.unwrap());
+        let retry = checkpoint.try_clone().expect("body fit in the buffer");
+        assert_eq!("hello world", read_all(retry).await.unwrap());
+        let retry = checkpoint.try_clone().expect("body fit in the buffer");
+        assert_eq!("hello world", read_all(retry).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn partially_read_bodies_continue_from_the_source() {
+        let body = streaming_body(&["hello", " ", "world"]).with_replay_buffer(11);
+        let checkpoint = body.try_clone().unwrap();
+
+        // Simulate a failed attempt that only sent the first chunk
+        let mut first_attempt = body;
+        assert_eq!(
+            Bytes::from("hello"),
+            first_attempt.next().await.unwrap().unwrap()
+        );
+        drop(first_attempt);
+
+        let retry = checkpoint.try_clone().unwrap();
+        assert_eq!("hello world", read_all(retry).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn bodies_that_exceed_the_buffer_are_no_longer_retryable() {
+        let body = streaming_body(&["hello", " ", "world"]).with_replay_buffer(8);
+        let checkpoint = body.try_clone().unwrap();
+        let stale_copy = body.try_clone().unwrap();
+
+        assert!(checkpoint.is_rewindable());
+        // The current body can still be read to the end
+        assert_eq!("hello world", read_all(body).await.unwrap());
+        assert!(!checkpoint.is_rewindable());
+        assert!(checkpoint.try_clone().is_none());
+        let mapped = checkpoint.map(|body| body);
+        assert!(!mapped.is_rewindable());
+        assert!(mapped.try_clone().is_none());
+        // Copies made before the buffer was exceeded can't be read anymore
+        assert!(read_all(stale_copy).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn mapped_bodies_stay_retryable() {
+        let body = streaming_body(&["hello", " ", "world"])
+            .with_replay_buffer(11)
+            .map_preserve_contents(|body| body);
+        let checkpoint = body.try_clone().unwrap();
+        assert_eq!("hello world", read_all(body).await.unwrap());
+        assert_eq!(
+            "hello world",
+            read_all(checkpoint.try_clone().unwrap()).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn progress_listeners_attached_to_the_source_see_replayed_data() {
+        use crate::body::progress::Progress;
+        use std::sync::{Arc, Mutex};
+
+        let transferred = Arc::new(Mutex::new(Vec::new()));
+        let listener = {
+            let transferred = transferred.clone();
+            move |progress: Progress| {
+                transferred
+                    .lock()
+                    .unwrap()
+                    .push(progress.bytes_transferred())
+            }
+        };
+        let body = streaming_body(&["hello", " ", "world"])
+            .with_progress_listener(listener)
+            .with_replay_buffer(11);
+        let checkpoint = body.try_clone().unwrap();
+        assert_eq!("hello world", read_all(body).await.unwrap());
+        assert_eq!("hello world", read_all(checkpoint).await.unwrap());
+        assert_eq!(vec![5, 6, 11, 5, 6, 11], *transferred.lock().unwrap());
+    }
+
+    #[test]
+    fn retryable_bodies_are_unchanged() {
+        let body = SdkBody::from("hello").with_replay_buffer(0);
+        assert_eq!(Some(b"hello".as_slice()), body.bytes());
+        assert!(body.try_clone().is_some());
+    }
+}
@@ -123,6 +123,7 @@
 //! # }
 //! ```
 
+use crate::body::progress::ProgressListener;
 use crate::body::SdkBody;
 use crate::byte_stream::error::Error;
 use bytes::Buf;
@@ -144,6 +145,11 @@ pub mod error;
 #[cfg(feature = "rt-tokio")]
 pub use self::bytestream_util::FsBuilder;
 
+#[cfg(feature = "rt-tokio")]
+mod fs_writer;
+#[cfg(feature = "rt-tokio")]
+pub use self::fs_writer::{FsWriter, WriteVerifier};
+
 /// This module is named after the `http-body` version number since we anticipate
 /// needing to provide equivalent functionality for 1.x of that crate in the future.
 /// The name has a suffix `_x` to avoid name collision with a third-party `http-body-0-4`.
@@ -450,11 +456,86 @@ impl ByteStream {
         tokio_util::io::StreamReader::new(FuturesStreamCompatByteStream(self))
     }
 
+    /// Write all the data of this `ByteStream` to the given [`AsyncWrite`](tokio::io::AsyncWrite),
+    /// returning the number of bytes written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use aws_smithy_types::byte_stream::ByteStream;
+    ///
+    /// # async fn dox(my_bytestream: ByteStream) -> Result<(), aws_smithy_types::byte_stream::error::Error> {
+    /// let mut out = Vec::new();
+    /// let written = my_bytestream.write_to(&mut out).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rt-tokio")]
+    pub async fn write_to<W>(self, writer: &mut W) -> Result<u64, Error>
+    where
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        fs_writer::copy(self, writer, None).await
+    }
+
+    /// Write all the data of this `ByteStream` to the file at `path`, returning the number of
+    /// bytes written.
+    ///
+    /// The file is created if it doesn't exist, and replaced if it does. For more control, such
+    /// as atomically writing the file or resuming a partial download, use an [`FsWriter`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_types::byte_stream::ByteStream;
+    ///
+    /// async fn save(stream: ByteStream) {
+    ///     stream.write_to_path("docs/rows.csv").await.expect("file should be writable");
+    /// }
+    /// ```
+    #[cfg(feature = "rt-tokio")]
+    pub async fn write_to_path(self, path: impl AsRef<std::path::Path>) -> Result<u64, Error> {
+        FsWriter::new(path).write(self).await
+    }
+
     /// Given a function to modify an [`SdkBody`], run it on the `SdkBody` inside this `Bytestream`.
     /// returning a new `Bytestream`.
     pub fn map(self, f: impl Fn(SdkBody) -> SdkBody + Send + Sync + 'static) -> ByteStream {
         ByteStream::new(self.into_inner().map(f))
     }
+
+    /// Make this `ByteStream` retryable by buffering up to `capacity` bytes of it in memory.
+    ///
+    /// See [`SdkBody::with_replay_buffer`] for details.
+    ///
+    /// ```no_run
+    /// use aws_smithy_types::byte_stream::ByteStream;
+    ///
+    /// # fn dox(stream: ByteStream) {
+    /// // Allow requests sending up to 8 MiB of data to be retried
+    /// let stream = stream.with_replay_buffer(8 * 1024 * 1024);
+    /// # }
+    /// ```
+    #[cfg(feature = "http-body-0-4-x")]
+    pub fn with_replay_buffer(self, capacity: usize) -> ByteStream {
+        ByteStream::new(self.into_inner().with_replay_buffer(capacity))
+    }
+
+    /// Attach a [`ProgressListener`] that's told about the progress of reading this `ByteStream`.
+    ///
+    /// This works for uploads, where the `ByteStream` is read as the request is sent, as well as
+    /// for downloads. See [`SdkBody::with_progress_listener`] for details.
+    ///
+    /// ```no_run
+    /// use aws_smithy_types::body::progress::Progress;
+    /// use aws_smithy_types::byte_stream::ByteStream;
+    ///
+    /// let stream = ByteStream::from_static(b"hello!").with_progress_listener(|progress: Progress| {
+    ///     println!("{} of {:?} bytes", progress.bytes_transferred(), progress.total_bytes());
+    /// });
+    /// ```
+    pub fn with_progress_listener(self, listener: impl ProgressListener + 'static) -> ByteStream {
+        ByteStream::new(self.into_inner().with_progress_listener(listener))
+    }
 }
 
 impl Default for ByteStream {
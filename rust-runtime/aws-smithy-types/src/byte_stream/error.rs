@@ -15,6 +15,8 @@ pub(super) enum ErrorKind {
     OffsetLargerThanFileSize,
     #[cfg(feature = "rt-tokio")]
     LengthLargerThanFileSizeMinusReadOffset,
+    #[cfg(feature = "rt-tokio")]
+    VerificationFailed(Box<dyn StdError + Send + Sync + 'static>),
     IoError(IoError),
     StreamingError(Box<dyn StdError + Send + Sync + 'static>),
 }
@@ -56,6 +58,8 @@ impl fmt::Display for Error {
                 f,
                 "`Length::Exact` was larger than file size minus read offset"
             ),
+            #[cfg(feature = "rt-tokio")]
+            ErrorKind::VerificationFailed(_) => write!(f, "written data failed verification"),
             ErrorKind::IoError(_) => write!(f, "IO error"),
             ErrorKind::StreamingError(_) => write!(f, "streaming error"),
         }
@@ -68,6 +72,8 @@ impl StdError for Error {
             ErrorKind::IoError(err) => Some(err as _),
             ErrorKind::StreamingError(err) => Some(err.as_ref() as _),
             #[cfg(feature = "rt-tokio")]
+            ErrorKind::VerificationFailed(err) => Some(err.as_ref() as _),
+            #[cfg(feature = "rt-tokio")]
             ErrorKind::OffsetLargerThanFileSize
             | ErrorKind::LengthLargerThanFileSizeMinusReadOffset => None,
         }
@@ -0,0 +1,434 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::byte_stream::error::{Error, ErrorKind};
+use crate::byte_stream::ByteStream;
+use std::error::Error as StdError;
+use std::fmt;
+use std::io::ErrorKind as IoErrorKind;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+// Appended to the file name of the destination to get the name of the file that's written to
+// when writes are atomic.
+const PART_FILE_SUFFIX: &str = ".part";
+// The size of the buffer used to read back a partially written file when resuming.
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Verifies the data written by an [`FsWriter`] as it's written, for example by calculating
+/// its checksum.
+///
+/// When resuming a partially written file, the verifier is first updated with the data that's
+/// already in the file, so it always sees all the data.
+pub trait WriteVerifier: Send {
+    /// Update the verifier with the next chunk of data.
+    fn update(&mut self, data: &[u8]);
+
+    /// Verify all the data that was written.
+    ///
+    /// This is called once all data has been written and before the file is renamed (if writes
+    /// are atomic). Returning an error fails the write and removes the file that was written.
+    fn verify(self: Box<Self>) -> Result<(), Box<dyn StdError + Send + Sync + 'static>>;
+}
+
+/// Writes the data of a [`ByteStream`] to a file, with control over advanced options.
+///
+/// ```no_run
+/// # #[cfg(feature = "rt-tokio")]
+/// # {
+/// use aws_smithy_types::byte_stream::{ByteStream, FsWriter};
+///
+/// async fn download(stream: ByteStream) -> Result<u64, aws_smithy_types::byte_stream::error::Error> {
+///     FsWriter::new("docs/some-large-file.csv")
+///         // Sync the file to disk before returning
+///         .fsync(true)
+///         // Write to `some-large-file.csv.part` and rename it once all the data is written
+///         .atomic(true)
+///         .write(stream)
+///         .await
+/// }
+/// # }
+/// ```
+///
+/// ## Resuming downloads
+///
+/// With [`resume`](FsWriter::resume) enabled, data is appended to anything that was already
+/// written to the file. [`resume_offset`](FsWriter::resume_offset) returns the offset of the
+/// next byte to write, which can be used to request the rest of the data with a ranged request.
+///
+/// ```no_run
+/// # #[cfg(feature = "rt-tokio")]
+/// # {
+/// use aws_smithy_types::byte_stream::{ByteStream, FsWriter};
+///
+/// # async fn get_object(range: String) -> ByteStream { todo!() }
+/// async fn resume_download() -> Result<u64, aws_smithy_types::byte_stream::error::Error> {
+///     let writer = FsWriter::new("docs/some-large-file.csv").atomic(true).resume(true);
+///     let offset = writer.resume_offset().await?;
+///     let stream = get_object(format!("bytes={offset}-")).await;
+///     writer.write(stream).await
+/// }
+/// # }
+/// ```
+pub struct FsWriter {
+    path: PathBuf,
+    fsync: bool,
+    atomic: bool,
+    resume: bool,
+    verifier: Option<Box<dyn WriteVerifier>>,
+}
+
+impl fmt::Debug for FsWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FsWriter")
+            .field("path", &self.path)
+            .field("fsync", &self.fsync)
+            .field("atomic", &self.atomic)
+            .field("resume", &self.resume)
+            .field("verifier", &self.verifier.is_some())
+            .finish()
+    }
+}
+
+impl FsWriter {
+    /// Create a new [`FsWriter`] that writes to the given path.
+    ///
+    /// By default, the file is created if it doesn't exist or truncated if it does, and the data
+    /// is written to it directly.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            fsync: false,
+            atomic: false,
+            resume: false,
+            verifier: None,
+        }
+    }
+
+    /// Set whether the file is synced to disk once all data has been written.
+    ///
+    /// When writes are [atomic](FsWriter::atomic), the directory the file is renamed into is
+    /// synced as well, so that the rename isn't lost if the system crashes.
+    pub fn fsync(mut self, fsync: bool) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Set whether the file is only created at its path once all data has been written.
+    ///
+    /// When enabled, data is written to a file with `.part` appended to its name, which is
+    /// renamed once all data has been written and verified.
+    pub fn atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Set whether data is appended to a partially written file instead of replacing it.
+    ///
+    /// Use [`resume_offset`](FsWriter::resume_offset) to find out where the data to write
+    /// should start.
+    pub fn resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Verify the data as it's written with the given [`WriteVerifier`].
+    pub fn verifier(mut self, verifier: impl WriteVerifier + 'static) -> Self {
+        self.verifier = Some(Box::new(verifier));
+        self
+    }
+
+    /// Returns the offset of the next byte to write.
+    ///
+    /// This is the number of bytes that were already written if [`resume`](FsWriter::resume)
+    /// is enabled, and zero otherwise.
+    pub async fn resume_offset(&self) -> Result<u64, Error> {
+        if !self.resume {
+            return Ok(0);
+        }
+        match fs::metadata(self.write_path()).await {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(err) if err.kind() == IoErrorKind::NotFound => Ok(0),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Write all the data of the given [`ByteStream`] to the file.
+    ///
+    /// Returns the number of bytes written by this call, which doesn't include any data that was
+    /// already in the file when resuming.
+    pub async fn write(mut self, stream: ByteStream) -> Result<u64, Error> {
+        let write_path = self.write_path();
+        let written = match self.write_inner(&write_path, stream).await {
+            Ok(written) => written,
+            Err(err) => {
+                // A partially written `.part` file is only worth keeping if the write can be resumed
+                if self.atomic && !self.resume {
+                    let _ = fs::remove_file(&write_path).await;
+                }
+                return Err(err);
+            }
+        };
+        if let Some(verifier) = self.verifier.take() {
+            if let Err(err) = verifier.verify() {
+                // Data that failed verification can't be trusted, even if it was written to the
+                // destination directly
+                let _ = fs::remove_file(&write_path).await;
+                return Err(ErrorKind::VerificationFailed(err).into());
+            }
+        }
+        if self.atomic {
+            fs::rename(&write_path, &self.path).await?;
+            if self.fsync {
+                sync_parent_dir(&self.path).await?;
+            }
+        }
+        Ok(written)
+    }
+
+    async fn write_inner(&mut self, write_path: &Path, stream: ByteStream) -> Result<u64, Error> {
+        let mut options = OpenOptions::new();
+        options.create(true);
+        if self.resume {
+            if let Some(verifier) = self.verifier.as_deref_mut() {
+                read_existing(write_path, verifier).await?;
+            }
+            options.append(true);
+        } else {
+            options.write(true).truncate(true);
+        }
+
+        let mut file = options.open(write_path).await?;
+        let written = copy(stream, &mut file, self.verifier.as_deref_mut()).await?;
+        if self.fsync {
+            file.sync_all().await?;
+        }
+        Ok(written)
+    }
+
+    fn write_path(&self) -> PathBuf {
+        if self.atomic {
+            let mut path = self.path.clone().into_os_string();
+            path.push(PART_FILE_SUFFIX);
+            path.into()
+        } else {
+            self.path.clone()
+        }
+    }
+}
+
+/// Sync the directory containing `path` to disk, so that a rename into it is durable.
+#[cfg(unix)]
+async fn sync_parent_dir(path: &Path) -> Result<(), Error> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent).await?.sync_all().await?;
+    Ok(())
+}
+
+// Directories can't be opened (and don't need to be synced) on other platforms
+#[cfg(not(unix))]
+async fn sync_parent_dir(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Update the verifier with the data already written to the file at `path`, if it exists.
+async fn read_existing(
+    path: &Path,
+    verifier: &mut (dyn WriteVerifier + 'static),
+) -> Result<(), Error> {
+    let mut file = match File::open(path).await {
+        Ok(file) => file,
+        Err(err) if err.kind() == IoErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut buf = vec![0; READ_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buf).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        verifier.update(&buf[..read]);
+    }
+}
+
+/// Write all the data of `stream` to `writer`, returning the number of bytes written.
+pub(super) async fn copy<W>(
+    mut stream: ByteStream,
+    writer: &mut W,
+    mut verifier: Option<&mut (dyn WriteVerifier + 'static)>,
+) -> Result<u64, Error>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut written = 0;
+    while let Some(data) = stream.try_next().await? {
+        if let Some(verifier) = verifier.as_deref_mut() {
+            verifier.update(&data);
+        }
+        writer.write_all(&data).await?;
+        written += data.len() as u64;
+    }
+    writer.flush().await?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FsWriter, WriteVerifier};
+    use crate::byte_stream::ByteStream;
+    use std::error::Error as StdError;
+    use std::sync::{Arc, Mutex};
+
+    // Records all the data it's updated with and fails verification if it doesn't match `expected`
+    #[derive(Clone)]
+    struct TestVerifier {
+        seen: Arc<Mutex<Vec<u8>>>,
+        expected: &'static [u8],
+    }
+
+    impl TestVerifier {
+        fn new(expected: &'static [u8]) -> Self {
+            Self {
+                seen: Default::default(),
+                expected,
+            }
+        }
+    }
+
+    impl WriteVerifier for TestVerifier {
+        fn update(&mut self, data: &[u8]) {
+            self.seen.lock().unwrap().extend_from_slice(data);
+        }
+
+        fn verify(self: Box<Self>) -> Result<(), Box<dyn StdError + Send + Sync + 'static>> {
+            if self.seen.lock().unwrap().as_slice() == self.expected {
+                Ok(())
+            } else {
+                Err("data didn't match".into())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn write_to_writer() {
+        let mut out = Vec::new();
+        let written = ByteStream::from_static(b"hello world!")
+            .write_to(&mut out)
+            .await
+            .unwrap();
+        assert_eq!(12, written);
+        assert_eq!(b"hello world!", out.as_slice());
+    }
+
+    #[tokio::test]
+    async fn write_to_path_replaces_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        std::fs::write(&path, b"some much older data").unwrap();
+
+        let written = ByteStream::from_static(b"hello world!")
+            .write_to_path(&path)
+            .await
+            .unwrap();
+        assert_eq!(12, written);
+        assert_eq!(b"hello world!", std::fs::read(&path).unwrap().as_slice());
+    }
+
+    #[tokio::test]
+    async fn atomic_writes_rename_the_part_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+
+        FsWriter::new(&path)
+            .atomic(true)
+            .fsync(true)
+            .write(ByteStream::from_static(b"hello world!"))
+            .await
+            .unwrap();
+        assert_eq!(b"hello world!", std::fs::read(&path).unwrap().as_slice());
+        assert!(!dir.path().join("file.part").exists());
+    }
+
+    #[tokio::test]
+    async fn resumed_writes_append_to_the_part_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        std::fs::write(dir.path().join("file.part"), b"hello ").unwrap();
+
+        let verifier = TestVerifier::new(b"hello world!");
+        let writer = FsWriter::new(&path)
+            .atomic(true)
+            .resume(true)
+            .verifier(verifier.clone());
+        assert_eq!(6, writer.resume_offset().await.unwrap());
+        let written = writer
+            .write(ByteStream::from_static(b"world!"))
+            .await
+            .unwrap();
+        assert_eq!(6, written);
+        assert_eq!(b"hello world!", std::fs::read(&path).unwrap().as_slice());
+        assert_eq!(b"hello world!", verifier.seen.lock().unwrap().as_slice());
+    }
+
+    #[tokio::test]
+    async fn resume_offset_is_zero_for_new_files_or_without_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        assert_eq!(
+            0,
+            FsWriter::new(&path)
+                .resume(true)
+                .resume_offset()
+                .await
+                .unwrap()
+        );
+
+        std::fs::write(&path, b"hello").unwrap();
+        assert_eq!(0, FsWriter::new(&path).resume_offset().await.unwrap());
+        assert_eq!(
+            5,
+            FsWriter::new(&path)
+                .resume(true)
+                .resume_offset()
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_verification_removes_the_part_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+
+        let err = FsWriter::new(&path)
+            .atomic(true)
+            .resume(true)
+            .verifier(TestVerifier::new(b"something else"))
+            .write(ByteStream::from_static(b"hello world!"))
+            .await
+            .unwrap_err();
+        assert!(err.source().is_some(), "{err:?}");
+        assert!(!path.exists());
+        assert!(!dir.path().join("file.part").exists());
+    }
+
+    #[tokio::test]
+    async fn failed_verification_removes_files_written_directly() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        std::fs::write(&path, b"some much older data").unwrap();
+
+        FsWriter::new(&path)
+            .verifier(TestVerifier::new(b"something else"))
+            .write(ByteStream::from_static(b"hello world!"))
+            .await
+            .expect_err("verification failed");
+        assert!(!path.exists());
+    }
+}
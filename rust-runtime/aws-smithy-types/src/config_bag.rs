@@ -613,6 +613,20 @@ impl ConfigBag {
         }
     }
 
+    /// Returns a mutable reference to `T` if it is stored in the interceptor state
+    ///
+    /// Unlike [`ConfigBag::get_mut`], this doesn't require `T` to be [`Clone`] since values in
+    /// deeper layers of the bag are never copied up into the interceptor state.
+    pub fn get_mut_from_interceptor_state<T>(&mut self) -> Option<&mut T>
+    where
+        T: Storable<Storer = StoreReplace<T>> + Send + Sync + Debug + 'static,
+    {
+        match self.interceptor_state.get_mut::<StoreReplace<T>>() {
+            Some(Value::Set(t)) => Some(t),
+            _ => None,
+        }
+    }
+
     /// Returns a mutable reference to `T` if it is stored in the top layer of the bag
     ///
     /// - If `T` is in a deeper layer of the bag, that value will be cloned and inserted into the top layer
@@ -981,6 +995,28 @@ mod test {
         assert_eq!(bag.get_mut_or_default::<Foo>(), &Foo(0));
     }
 
+    #[test]
+    fn get_mut_from_interceptor_state() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Foo(usize);
+        impl Storable for Foo {
+            type Storer = StoreReplace<Foo>;
+        }
+
+        let mut base = Layer::new("base");
+        base.store_put(Foo(1));
+        let mut bag = ConfigBag::of_layers(vec![base]);
+        // values in deeper layers aren't returned since they can't be copied up
+        assert_eq!(bag.get_mut_from_interceptor_state::<Foo>(), None);
+
+        bag.interceptor_state().store_put(Foo(2));
+        bag.get_mut_from_interceptor_state::<Foo>().unwrap().0 += 1;
+        assert_eq!(bag.load::<Foo>(), Some(&Foo(3)));
+
+        bag.interceptor_state().unset::<Foo>();
+        assert_eq!(bag.get_mut_from_interceptor_state::<Foo>(), None);
+    }
+
     #[test]
     fn cloning_layers() {
         #[derive(Clone, Debug)]
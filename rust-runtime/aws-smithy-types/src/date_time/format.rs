@@ -493,6 +493,243 @@ pub(crate) mod rfc3339 {
     }
 }
 
+pub(crate) mod epoch_millis {
+    use super::{
+        DateTimeFormatError, DateTimeFormatErrorKind, DateTimeParseError, DateTimeParseErrorKind,
+    };
+    use crate::DateTime;
+    use std::str::FromStr;
+
+    /// Formats a `DateTime` as an integer number of milliseconds since the Unix epoch.
+    ///
+    /// Sub-millisecond precision is truncated.
+    pub(crate) fn format(date_time: &DateTime) -> Result<String, DateTimeFormatError> {
+        date_time
+            .to_millis()
+            .map(|millis| millis.to_string())
+            .map_err(|err| DateTimeFormatErrorKind::OutOfRange(err.0.into()).into())
+    }
+
+    /// Parses an integer number of milliseconds since the Unix epoch into a `DateTime`.
+    pub(crate) fn parse(value: &str) -> Result<DateTime, DateTimeParseError> {
+        if value.starts_with('+') {
+            return Err(
+                DateTimeParseErrorKind::Invalid("invalid epoch-millis timestamp".into()).into(),
+            );
+        }
+        let millis = <i64>::from_str(value).map_err(|_| DateTimeParseErrorKind::IntParseError)?;
+        Ok(DateTime::from_millis(millis))
+    }
+}
+
+pub(crate) mod iso8601_basic {
+    use super::{
+        DateTimeFormatError, DateTimeFormatErrorKind, DateTimeParseError, DateTimeParseErrorKind,
+    };
+    use crate::DateTime;
+    use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    /// Formats a `DateTime` in the ISO-8601 basic format used by SigV4, e.g. `20190101T000000Z`.
+    ///
+    /// Sub-second precision is truncated.
+    pub(crate) fn format(date_time: &DateTime) -> Result<String, DateTimeFormatError> {
+        fn out_of_range<E: std::fmt::Display>(cause: E) -> DateTimeFormatError {
+            DateTimeFormatErrorKind::OutOfRange(
+                format!(
+                    "ISO-8601 basic timestamps support dates between 00010101T000000Z \
+                            and 99991231T235959Z. {}",
+                    cause
+                )
+                .into(),
+            )
+            .into()
+        }
+        let structured =
+            OffsetDateTime::from_unix_timestamp(date_time.secs()).map_err(out_of_range)?;
+        if !(1..=9_999).contains(&structured.year()) {
+            return Err(out_of_range(""));
+        }
+        Ok(format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+            structured.year(),
+            u8::from(structured.month()),
+            structured.day(),
+            structured.hour(),
+            structured.minute(),
+            structured.second()
+        ))
+    }
+
+    /// Parses an ISO-8601 basic format date-time, e.g. `20190101T000000Z`, into a `DateTime`.
+    pub(crate) fn parse(value: &str) -> Result<DateTime, DateTimeParseError> {
+        let s = value.as_bytes();
+        if s.len() != 16 || s[8] != b'T' || s[15] != b'Z' {
+            return Err(DateTimeParseErrorKind::Invalid("incorrectly shaped string".into()).into());
+        }
+        let digits = |range: std::ops::Range<usize>| -> Result<u32, DateTimeParseError> {
+            let slice = &s[range];
+            if !slice.iter().all(u8::is_ascii_digit) {
+                return Err(DateTimeParseErrorKind::IntParseError.into());
+            }
+            Ok(slice
+                .iter()
+                .fold(0, |acc, digit| acc * 10 + u32::from(digit - b'0')))
+        };
+        let month = Month::try_from(digits(4..6)? as u8).map_err(|err| {
+            DateTimeParseErrorKind::Invalid(format!("invalid month: {}", err).into())
+        })?;
+        let date = Date::from_calendar_date(digits(0..4)? as i32, month, digits(6..8)? as u8)
+            .map_err(|err| {
+                DateTimeParseErrorKind::Invalid(
+                    format!("date components are out of range: {}", err).into(),
+                )
+            })?;
+        let time = Time::from_hms(
+            digits(9..11)? as u8,
+            digits(11..13)? as u8,
+            digits(13..15)? as u8,
+        )
+        .map_err(|err| {
+            DateTimeParseErrorKind::Invalid(
+                format!("time components are out of range: {}", err).into(),
+            )
+        })?;
+        let date_time = PrimitiveDateTime::new(date, time).assume_offset(UtcOffset::UTC);
+        Ok(DateTime::from_secs(date_time.unix_timestamp()))
+    }
+}
+
+pub(crate) mod rfc2822 {
+    use super::{DateTimeParseError, DateTimeParseErrorKind};
+    use crate::DateTime;
+    use std::str::FromStr;
+    use time::{Date, Month, PrimitiveDateTime, Time, UtcOffset};
+
+    fn invalid(message: &'static str) -> DateTimeParseError {
+        DateTimeParseErrorKind::Invalid(message.into()).into()
+    }
+
+    fn number<T: FromStr>(value: &str, max_len: usize) -> Result<T, DateTimeParseError> {
+        if value.is_empty() || value.len() > max_len || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(DateTimeParseErrorKind::IntParseError.into());
+        }
+        value
+            .parse()
+            .map_err(|_| DateTimeParseErrorKind::IntParseError.into())
+    }
+
+    fn zone_offset_seconds(zone: &str) -> Result<i32, DateTimeParseError> {
+        if let Some(sign) = zone.strip_prefix(['+', '-']).map(|_| zone.as_bytes()[0]) {
+            if zone.len() != 5 {
+                return Err(invalid("invalid time zone offset"));
+            }
+            let hours: i32 = number(&zone[1..3], 2)?;
+            let minutes: i32 = number(&zone[3..5], 2)?;
+            if minutes > 59 {
+                return Err(invalid("invalid time zone offset"));
+            }
+            let offset = hours * 3600 + minutes * 60;
+            return Ok(if sign == b'-' { -offset } else { offset });
+        }
+        // Obsolete zone names, see https://datatracker.ietf.org/doc/html/rfc2822#section-4.3
+        let hours = match zone.to_ascii_uppercase().as_str() {
+            "UT" | "GMT" | "Z" => 0,
+            "EDT" => -4,
+            "EST" | "CDT" => -5,
+            "CST" | "MDT" => -6,
+            "MST" | "PDT" => -7,
+            "PST" => -8,
+            // Military zones are treated as `-0000` since their meaning was inconsistently defined
+            military if military.len() == 1 && military.as_bytes()[0].is_ascii_alphabetic() => 0,
+            _ => return Err(invalid("invalid time zone")),
+        };
+        Ok(hours * 3600)
+    }
+
+    /// Parses an RFC-2822 date-time, e.g. `Mon, 16 Dec 2019 23:48:18 +0100`, into a `DateTime`.
+    ///
+    /// The day of the week and seconds are optional, and the obsolete two and three digit years
+    /// and named time zones are accepted. Comments are not supported.
+    pub(crate) fn parse(value: &str) -> Result<DateTime, DateTimeParseError> {
+        if !value.is_ascii() {
+            return Err(invalid("date-time must be ASCII"));
+        }
+        let value = match value.split_once(',') {
+            Some((weekday, rest)) => {
+                if !matches!(
+                    weekday.trim(),
+                    "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun"
+                ) {
+                    return Err(invalid("invalid day of the week"));
+                }
+                rest
+            }
+            None => value,
+        };
+        let parts: Vec<&str> = value.split_ascii_whitespace().collect();
+        let [day, month, year, time, zone] = parts[..] else {
+            return Err(invalid("incorrectly shaped string"));
+        };
+
+        let day: u8 = number(day, 2)?;
+        let month = match month {
+            "Jan" => Month::January,
+            "Feb" => Month::February,
+            "Mar" => Month::March,
+            "Apr" => Month::April,
+            "May" => Month::May,
+            "Jun" => Month::June,
+            "Jul" => Month::July,
+            "Aug" => Month::August,
+            "Sep" => Month::September,
+            "Oct" => Month::October,
+            "Nov" => Month::November,
+            "Dec" => Month::December,
+            _ => return Err(invalid("invalid month")),
+        };
+        let year = match (year.len(), number::<i32>(year, 4)?) {
+            (2, year) if year < 50 => year + 2000,
+            (2 | 3, year) => year + 1900,
+            (4, year) => year,
+            _ => return Err(invalid("invalid year")),
+        };
+        let date = Date::from_calendar_date(year, month, day).map_err(|err| {
+            DateTimeParseErrorKind::Invalid(
+                format!("date components are out of range: {}", err).into(),
+            )
+        })?;
+
+        let mut time_parts = time.split(':');
+        let (hour, minute, second) = match (
+            time_parts.next(),
+            time_parts.next(),
+            time_parts.next(),
+            time_parts.next(),
+        ) {
+            (Some(hour), Some(minute), second, None) => (
+                number(hour, 2)?,
+                number(minute, 2)?,
+                second
+                    .map(|second| number(second, 2))
+                    .transpose()?
+                    .unwrap_or(0),
+            ),
+            _ => return Err(invalid("incorrectly shaped time")),
+        };
+        // Leap seconds are clamped to the end of the minute
+        let time = Time::from_hms(hour, minute, second.min(59)).map_err(|err| {
+            DateTimeParseErrorKind::Invalid(
+                format!("time components are out of range: {}", err).into(),
+            )
+        })?;
+
+        let offset = UtcOffset::from_whole_seconds(zone_offset_seconds(zone)?)
+            .map_err(|_| invalid("invalid time zone offset"))?;
+        let date_time = PrimitiveDateTime::new(date, time).assume_offset(offset);
+        Ok(DateTime::from_secs(date_time.unix_timestamp()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -816,6 +1053,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn epoch_millis() {
+        let date_time = DateTime::from_secs_and_nanos(1576540098, 520_999_999);
+        assert_eq!("1576540098520", epoch_millis::format(&date_time).unwrap());
+        assert_eq!(
+            DateTime::from_millis(1576540098520),
+            epoch_millis::parse("1576540098520").unwrap()
+        );
+        assert_eq!(
+            DateTime::from_millis(-1),
+            epoch_millis::parse("-1").unwrap()
+        );
+        epoch_millis::parse("+1").expect_err("explicit sign isn't allowed");
+        epoch_millis::parse("1.5").expect_err("fractions aren't allowed");
+        epoch_millis::format(&DateTime::from_secs(i64::MAX)).expect_err("out of range");
+    }
+
+    #[test]
+    fn iso8601_basic() {
+        let date_time = DateTime::from_secs_and_nanos(1576540098, 520_000_000);
+        assert_eq!(
+            "20191216T234818Z",
+            iso8601_basic::format(&date_time).unwrap()
+        );
+        assert_eq!(
+            DateTime::from_secs(1576540098),
+            iso8601_basic::parse("20191216T234818Z").unwrap()
+        );
+        for invalid in [
+            "20191216T234818",
+            "2019-12-16T23:48:18Z",
+            "20191316T234818Z",
+            "20191216T246018Z",
+            "2019121+T234818Z",
+        ] {
+            iso8601_basic::parse(invalid).expect_err(invalid);
+        }
+        iso8601_basic::format(&DateTime::from_secs(-62135596801)).expect_err("before year 1");
+    }
+
+    #[test]
+    fn parse_rfc2822() {
+        let expected = DateTime::from_secs(1576540098);
+        for valid in [
+            "Mon, 16 Dec 2019 23:48:18 +0000",
+            "Mon, 16 Dec 2019 23:48:18 GMT",
+            "16 Dec 2019 23:48:18 -0000",
+            "Tue, 17 Dec 2019 00:48:18 +0100",
+            "Mon, 16 Dec 2019 15:48:18 PST",
+            "Mon,  16   Dec 19 18:48:18 EST",
+        ] {
+            assert_eq!(expected, rfc2822::parse(valid).unwrap(), "{}", valid);
+        }
+        assert_eq!(
+            DateTime::from_secs(1576540080),
+            rfc2822::parse("Mon, 16 Dec 2019 23:48 Z").unwrap()
+        );
+        for invalid in [
+            "Foo, 16 Dec 2019 23:48:18 +0000",
+            "Mon, 16 Foo 2019 23:48:18 +0000",
+            "Mon, 16 Dec 2019 23:48:18",
+            "Mon, 16 Dec 2019 23:48:18 +00",
+            "Mon, 32 Dec 2019 23:48:18 +0000",
+            "Mon, 16 Dec 2019 23:48:18:01 +0000",
+            "Mon, 16 Dec 2019 23:48:18 +0000 extra",
+        ] {
+            rfc2822::parse(invalid).expect_err(invalid);
+        }
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(10000))]
 
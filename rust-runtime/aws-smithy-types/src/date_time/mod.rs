@@ -13,6 +13,7 @@ use std::cmp::Ordering;
 use std::error::Error as StdError;
 use std::fmt;
 use std::fmt::Display;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
 use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
@@ -228,6 +229,72 @@ impl DateTime {
         ))
     }
 
+    /// Returns the `DateTime` that is `duration` after this one, or `None` if the result
+    /// can't be represented by a `DateTime`.
+    pub fn checked_add(&self, duration: Duration) -> Option<DateTime> {
+        let nanos = i128::try_from(duration.as_nanos()).ok()?;
+        DateTime::from_nanos(self.as_nanos().checked_add(nanos)?).ok()
+    }
+
+    /// Returns the `DateTime` that is `duration` before this one, or `None` if the result
+    /// can't be represented by a `DateTime`.
+    pub fn checked_sub(&self, duration: Duration) -> Option<DateTime> {
+        let nanos = i128::try_from(duration.as_nanos()).ok()?;
+        DateTime::from_nanos(self.as_nanos().checked_sub(nanos)?).ok()
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to this `DateTime`, or `None` if
+    /// `earlier` is later than this `DateTime`.
+    pub fn checked_duration_since(&self, earlier: DateTime) -> Option<Duration> {
+        let nanos = self.as_nanos().checked_sub(earlier.as_nanos())?;
+        if nanos < 0 {
+            return None;
+        }
+        let (seconds, subsecond_nanos) = nanos.div_rem(&NANOS_PER_SECOND);
+        Some(Duration::new(
+            u64::try_from(seconds).ok()?,
+            subsecond_nanos as u32,
+        ))
+    }
+
+    /// Parses a `DateTime` from the ISO-8601 basic format, e.g. `20191216T234818Z`.
+    ///
+    /// This is the format used by SigV4 in the `X-Amz-Date` header.
+    pub fn from_iso8601_basic(s: &str) -> Result<Self, DateTimeParseError> {
+        format::iso8601_basic::parse(s)
+    }
+
+    /// Formats the `DateTime` in the ISO-8601 basic format, e.g. `20191216T234818Z`.
+    ///
+    /// Sub-second precision is truncated. Returns an error for dates outside of
+    /// the years 1 through 9999.
+    pub fn fmt_iso8601_basic(&self) -> Result<String, DateTimeFormatError> {
+        format::iso8601_basic::format(self)
+    }
+
+    /// Parses a `DateTime` from an integer number of milliseconds since the Unix epoch,
+    /// e.g. `1576540098520`.
+    pub fn from_epoch_millis_str(s: &str) -> Result<Self, DateTimeParseError> {
+        format::epoch_millis::parse(s)
+    }
+
+    /// Formats the `DateTime` as an integer number of milliseconds since the Unix epoch.
+    ///
+    /// Sub-millisecond precision is truncated. Returns an error if the `DateTime`
+    /// can't be represented as `i64` milliseconds (see [`DateTime::to_millis`]).
+    pub fn fmt_epoch_millis(&self) -> Result<String, DateTimeFormatError> {
+        format::epoch_millis::format(self)
+    }
+
+    /// Parses a `DateTime` from an [RFC-2822](https://datatracker.ietf.org/doc/html/rfc2822#section-3.3)
+    /// date-time, e.g. `Mon, 16 Dec 2019 23:48:18 +0100`.
+    ///
+    /// The obsolete forms from section 4.3 of the RFC (two and three digit years and named
+    /// time zones) are also accepted.
+    pub fn from_rfc2822(s: &str) -> Result<Self, DateTimeParseError> {
+        format::rfc2822::parse(s)
+    }
+
     /// Read 1 date of `format` from `s`, expecting either `delim` or EOF
     ///
     /// Enable parsing multiple dates from the same string
@@ -325,6 +392,44 @@ impl From<SystemTime> for DateTime {
     }
 }
 
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    /// # Panics
+    ///
+    /// Panics if the resulting `DateTime` can't be represented. See [`DateTime::checked_add`]
+    /// for a version without panic.
+    fn add(self, duration: Duration) -> DateTime {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to DateTime")
+    }
+}
+
+impl AddAssign<Duration> for DateTime {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    /// # Panics
+    ///
+    /// Panics if the resulting `DateTime` can't be represented. See [`DateTime::checked_sub`]
+    /// for a version without panic.
+    fn sub(self, duration: Duration) -> DateTime {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from DateTime")
+    }
+}
+
+impl SubAssign<Duration> for DateTime {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
 impl PartialOrd for DateTime {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -392,7 +497,7 @@ mod test {
     use crate::date_time::Format;
     use crate::DateTime;
     use proptest::proptest;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
     use time::format_description::well_known::Rfc3339;
     use time::OffsetDateTime;
 
@@ -723,4 +828,79 @@ mod test {
             let _date = DateTime::from_secs_f64(secs);
         }
     }
+
+    #[test]
+    fn duration_arithmetic() {
+        let date_time = DateTime::from_secs_and_nanos(1576540098, 500_000_000);
+        let later = date_time
+            .checked_add(Duration::new(1, 600_000_000))
+            .unwrap();
+        assert_eq!(
+            DateTime::from_secs_and_nanos(1576540100, 100_000_000),
+            later
+        );
+        assert_eq!(
+            date_time,
+            later.checked_sub(Duration::new(1, 600_000_000)).unwrap()
+        );
+        assert_eq!(later, date_time + Duration::new(1, 600_000_000));
+        assert_eq!(date_time, later - Duration::new(1, 600_000_000));
+
+        let before_epoch = DateTime::from_secs(0) - Duration::from_millis(1500);
+        assert_eq!(DateTime::from_millis(-1500), before_epoch);
+
+        assert_eq!(
+            Some(Duration::new(1, 600_000_000)),
+            later.checked_duration_since(date_time)
+        );
+        assert_eq!(None, date_time.checked_duration_since(later));
+        assert_eq!(
+            Some(Duration::from_millis(1500)),
+            DateTime::from_secs(0).checked_duration_since(before_epoch)
+        );
+
+        assert_eq!(
+            None,
+            DateTime::from_secs(i64::MAX).checked_add(Duration::from_secs(1))
+        );
+        assert_eq!(
+            None,
+            DateTime::from_secs(i64::MIN).checked_sub(Duration::from_secs(1))
+        );
+        assert_eq!(None, DateTime::from_secs(0).checked_add(Duration::MAX));
+        assert_eq!(
+            Some(Duration::new(u64::MAX, 0)),
+            DateTime::from_secs(i64::MAX).checked_duration_since(DateTime::from_secs(i64::MIN))
+        );
+    }
+
+    #[test]
+    fn additional_formats() {
+        let date_time = DateTime::from_millis(1576540098520);
+        assert_eq!("20191216T234818Z", date_time.fmt_iso8601_basic().unwrap());
+        assert_eq!(
+            DateTime::from_secs(1576540098),
+            DateTime::from_iso8601_basic("20191216T234818Z").unwrap()
+        );
+        assert_eq!("1576540098520", date_time.fmt_epoch_millis().unwrap());
+        assert_eq!(
+            date_time,
+            DateTime::from_epoch_millis_str("1576540098520").unwrap()
+        );
+        assert_eq!(
+            DateTime::from_secs(1576540098),
+            DateTime::from_rfc2822("Tue, 17 Dec 2019 00:48:18 +0100").unwrap()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn duration_round_trip(secs in -100_000_000_000i64..100_000_000_000, nanos in 0..1_000_000_000u32, duration_nanos: u64) {
+            let date_time = DateTime::from_secs_and_nanos(secs, nanos);
+            let duration = Duration::from_nanos(duration_nanos);
+            let later = date_time.checked_add(duration).unwrap();
+            assert_eq!(date_time, later.checked_sub(duration).unwrap());
+            assert_eq!(Some(duration), later.checked_duration_since(date_time));
+        }
+    }
 }
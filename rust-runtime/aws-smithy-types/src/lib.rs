@@ -28,6 +28,7 @@ pub mod error;
 pub mod event_stream;
 pub mod primitive;
 pub mod retry;
+pub mod schema;
 pub mod timeout;
 
 /// Utilities for type erasure.
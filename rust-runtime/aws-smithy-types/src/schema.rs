@@ -0,0 +1,439 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Runtime representation of Smithy shapes.
+//!
+//! A [`Schema`] describes a shape and the subset of its traits that influence serialization
+//! (`@jsonName`, `@xmlName`, `@xmlAttribute`, `@timestampFormat`, etc.). Schemas are designed to be
+//! declared as `static` items so that generated code can describe its shapes compactly and
+//! delegate the actual encoding to the protocol-agnostic [`ShapeSerializer`](serde::ShapeSerializer)
+//! and [`ShapeDeserializer`](serde::ShapeDeserializer) implementations found in the codec crates.
+//!
+//! # Examples
+//! ```rust
+//! use aws_smithy_types::schema::{Schema, ShapeId, ShapeType, Traits};
+//!
+//! static WIDGET_ID: ShapeId = ShapeId::new("com.example", "Widget");
+//! static WIDGET: Schema = Schema::new(WIDGET_ID, ShapeType::Structure).with_members(&[
+//!     Schema::new_member(WIDGET_ID.with_member("name"), 0, ShapeType::String)
+//!         .with_traits(Traits::new().with_json_name("Name")),
+//!     Schema::new_member(WIDGET_ID.with_member("size"), 1, ShapeType::Integer),
+//! ]);
+//!
+//! assert_eq!(Some("Name"), WIDGET.member("name").unwrap().traits().json_name());
+//! assert_eq!(1, WIDGET.member("size").unwrap().member_index());
+//! ```
+
+use crate::date_time::Format;
+use std::fmt;
+
+pub mod serde;
+
+/// Identifier of a Smithy shape, optionally pointing at one of its members.
+///
+/// Renders as `namespace#Name` or `namespace#Name$member`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ShapeId {
+    namespace: &'static str,
+    name: &'static str,
+    member: Option<&'static str>,
+}
+
+impl ShapeId {
+    /// Creates a shape ID for the shape `name` in `namespace`.
+    pub const fn new(namespace: &'static str, name: &'static str) -> Self {
+        Self {
+            namespace,
+            name,
+            member: None,
+        }
+    }
+
+    /// Returns a shape ID pointing at the `member` of this shape.
+    pub const fn with_member(self, member: &'static str) -> Self {
+        Self {
+            namespace: self.namespace,
+            name: self.name,
+            member: Some(member),
+        }
+    }
+
+    /// The namespace of this shape.
+    pub fn namespace(&self) -> &'static str {
+        self.namespace
+    }
+
+    /// The name of this shape, without its namespace.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The member name, if this shape ID points at a member.
+    pub fn member(&self) -> Option<&'static str> {
+        self.member
+    }
+}
+
+impl fmt::Display for ShapeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.namespace, self.name)?;
+        if let Some(member) = self.member {
+            write!(f, "${}", member)?;
+        }
+        Ok(())
+    }
+}
+
+/// The type of a shape.
+///
+/// Member schemas carry the type of the shape they target.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShapeType {
+    /// A `blob` shape.
+    Blob,
+    /// A `boolean` shape.
+    Boolean,
+    /// A `string` shape.
+    String,
+    /// A `timestamp` shape.
+    Timestamp,
+    /// A `byte` shape.
+    Byte,
+    /// A `short` shape.
+    Short,
+    /// An `integer` shape.
+    Integer,
+    /// A `long` shape.
+    Long,
+    /// A `float` shape.
+    Float,
+    /// A `double` shape.
+    Double,
+    /// A `bigInteger` shape.
+    BigInteger,
+    /// A `bigDecimal` shape.
+    BigDecimal,
+    /// A `document` shape.
+    Document,
+    /// An `enum` shape.
+    Enum,
+    /// An `intEnum` shape.
+    IntEnum,
+    /// A `list` shape.
+    List,
+    /// A `map` shape.
+    Map,
+    /// A `structure` shape.
+    Structure,
+    /// A `union` shape.
+    Union,
+}
+
+/// Namespace of an `@xmlNamespace` trait.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct XmlNamespace {
+    uri: &'static str,
+    prefix: Option<&'static str>,
+}
+
+impl XmlNamespace {
+    /// Creates a new XML namespace with the given `uri` and optional `prefix`.
+    pub const fn new(uri: &'static str, prefix: Option<&'static str>) -> Self {
+        Self { uri, prefix }
+    }
+
+    /// The namespace URI.
+    pub fn uri(&self) -> &'static str {
+        self.uri
+    }
+
+    /// The namespace prefix, if any.
+    pub fn prefix(&self) -> Option<&'static str> {
+        self.prefix
+    }
+}
+
+/// The serialization-relevant traits applied to a shape or member.
+///
+/// For member schemas, these are the traits of the member merged with the traits of its target.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Traits {
+    json_name: Option<&'static str>,
+    xml_name: Option<&'static str>,
+    xml_attribute: bool,
+    xml_flattened: bool,
+    xml_namespace: Option<XmlNamespace>,
+    ec2_query_name: Option<&'static str>,
+    timestamp_format: Option<Format>,
+    sparse: bool,
+}
+
+impl Traits {
+    /// Creates an empty set of traits.
+    pub const fn new() -> Self {
+        Self {
+            json_name: None,
+            xml_name: None,
+            xml_attribute: false,
+            xml_flattened: false,
+            xml_namespace: None,
+            ec2_query_name: None,
+            timestamp_format: None,
+            sparse: false,
+        }
+    }
+
+    /// Sets the `@jsonName` trait.
+    pub const fn with_json_name(mut self, name: &'static str) -> Self {
+        self.json_name = Some(name);
+        self
+    }
+
+    /// Sets the `@xmlName` trait.
+    pub const fn with_xml_name(mut self, name: &'static str) -> Self {
+        self.xml_name = Some(name);
+        self
+    }
+
+    /// Sets the `@xmlAttribute` trait.
+    pub const fn with_xml_attribute(mut self) -> Self {
+        self.xml_attribute = true;
+        self
+    }
+
+    /// Sets the `@xmlFlattened` trait.
+    pub const fn with_xml_flattened(mut self) -> Self {
+        self.xml_flattened = true;
+        self
+    }
+
+    /// Sets the `@xmlNamespace` trait.
+    pub const fn with_xml_namespace(mut self, namespace: XmlNamespace) -> Self {
+        self.xml_namespace = Some(namespace);
+        self
+    }
+
+    /// Sets the `@ec2QueryName` trait.
+    pub const fn with_ec2_query_name(mut self, name: &'static str) -> Self {
+        self.ec2_query_name = Some(name);
+        self
+    }
+
+    /// Sets the `@timestampFormat` trait.
+    pub const fn with_timestamp_format(mut self, format: Format) -> Self {
+        self.timestamp_format = Some(format);
+        self
+    }
+
+    /// Sets the `@sparse` trait.
+    pub const fn with_sparse(mut self) -> Self {
+        self.sparse = true;
+        self
+    }
+
+    /// The value of the `@jsonName` trait, if present.
+    pub fn json_name(&self) -> Option<&'static str> {
+        self.json_name
+    }
+
+    /// The value of the `@xmlName` trait, if present.
+    pub fn xml_name(&self) -> Option<&'static str> {
+        self.xml_name
+    }
+
+    /// Whether the `@xmlAttribute` trait is present.
+    pub fn xml_attribute(&self) -> bool {
+        self.xml_attribute
+    }
+
+    /// Whether the `@xmlFlattened` trait is present.
+    pub fn xml_flattened(&self) -> bool {
+        self.xml_flattened
+    }
+
+    /// The value of the `@xmlNamespace` trait, if present.
+    pub fn xml_namespace(&self) -> Option<&XmlNamespace> {
+        self.xml_namespace.as_ref()
+    }
+
+    /// The value of the `@ec2QueryName` trait, if present.
+    pub fn ec2_query_name(&self) -> Option<&'static str> {
+        self.ec2_query_name
+    }
+
+    /// The value of the `@timestampFormat` trait, if present.
+    pub fn timestamp_format(&self) -> Option<Format> {
+        self.timestamp_format
+    }
+
+    /// Whether the `@sparse` trait is present.
+    pub fn sparse(&self) -> bool {
+        self.sparse
+    }
+}
+
+/// Runtime description of a Smithy shape or member.
+///
+/// Aggregate shapes expose their members through [`members`](Schema::members):
+/// - structures and unions list their members in definition order
+/// - lists have a single member named `member`
+/// - maps have two members named `key` and `value`
+///
+/// A member schema exposes the members of its target, so schemas for recursive shapes can be
+/// declared by having `static` items refer to each other.
+#[derive(Debug)]
+pub struct Schema {
+    id: ShapeId,
+    shape_type: ShapeType,
+    member_index: usize,
+    traits: Traits,
+    members: &'static [Schema],
+}
+
+impl Schema {
+    /// Creates a schema for a shape.
+    pub const fn new(id: ShapeId, shape_type: ShapeType) -> Self {
+        Self {
+            id,
+            shape_type,
+            member_index: 0,
+            traits: Traits::new(),
+            members: &[],
+        }
+    }
+
+    /// Creates a schema for the member `id` found at position `index` in its container.
+    ///
+    /// `shape_type` is the type of the member's target.
+    pub const fn new_member(id: ShapeId, index: usize, shape_type: ShapeType) -> Self {
+        Self {
+            id,
+            shape_type,
+            member_index: index,
+            traits: Traits::new(),
+            members: &[],
+        }
+    }
+
+    /// Sets the members of this schema.
+    pub const fn with_members(mut self, members: &'static [Schema]) -> Self {
+        self.members = members;
+        self
+    }
+
+    /// Sets the traits of this schema.
+    pub const fn with_traits(mut self, traits: Traits) -> Self {
+        self.traits = traits;
+        self
+    }
+
+    /// The shape ID of this schema.
+    pub fn id(&self) -> &ShapeId {
+        &self.id
+    }
+
+    /// The type of this shape (or of the member's target).
+    pub fn shape_type(&self) -> ShapeType {
+        self.shape_type
+    }
+
+    /// The member name if this is a member schema.
+    pub fn member_name(&self) -> Option<&'static str> {
+        self.id.member
+    }
+
+    /// The position of this member within its container.
+    pub fn member_index(&self) -> usize {
+        self.member_index
+    }
+
+    /// The traits of this schema.
+    pub fn traits(&self) -> &Traits {
+        &self.traits
+    }
+
+    /// The members of this shape.
+    pub fn members(&self) -> &'static [Schema] {
+        self.members
+    }
+
+    /// Returns the member named `name`, if one exists.
+    pub fn member(&self, name: &str) -> Option<&'static Schema> {
+        self.members
+            .iter()
+            .find(|member| member.member_name() == Some(name))
+    }
+
+    /// Returns the `member` member of a list.
+    pub fn list_member(&self) -> Option<&'static Schema> {
+        self.member("member")
+    }
+
+    /// Returns the `key` member of a map.
+    pub fn map_key(&self) -> Option<&'static Schema> {
+        self.member("key")
+    }
+
+    /// Returns the `value` member of a map.
+    pub fn map_value(&self) -> Option<&'static Schema> {
+        self.member("value")
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.id, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Schema, ShapeId, ShapeType, Traits};
+    use crate::date_time::Format;
+
+    const NODE_ID: ShapeId = ShapeId::new("com.example", "Node");
+    const NODE_LIST_ID: ShapeId = ShapeId::new("com.example", "NodeList");
+
+    static NODE: Schema = Schema::new(NODE_ID, ShapeType::Structure).with_members(&NODE_MEMBERS);
+    static NODE_MEMBERS: [Schema; 2] = [
+        Schema::new_member(NODE_ID.with_member("createdAt"), 0, ShapeType::Timestamp)
+            .with_traits(Traits::new().with_timestamp_format(Format::HttpDate)),
+        Schema::new_member(NODE_ID.with_member("children"), 1, ShapeType::List)
+            .with_members(&NODE_LIST_MEMBERS),
+    ];
+    static NODE_LIST_MEMBERS: [Schema; 1] =
+        [
+            Schema::new_member(NODE_LIST_ID.with_member("member"), 0, ShapeType::Structure)
+                .with_members(&NODE_MEMBERS),
+        ];
+
+    #[test]
+    fn shape_id_display() {
+        assert_eq!("com.example#Node", NODE_ID.to_string());
+        assert_eq!(
+            "com.example#Node$children",
+            NODE_ID.with_member("children").to_string()
+        );
+    }
+
+    #[test]
+    fn recursive_schemas() {
+        let children = NODE.member("children").unwrap();
+        assert_eq!(ShapeType::List, children.shape_type());
+        assert_eq!(1, children.member_index());
+        let child = children.list_member().unwrap();
+        assert_eq!(Some("member"), child.member_name());
+        assert_eq!(
+            Some(Format::HttpDate),
+            child
+                .member("createdAt")
+                .unwrap()
+                .traits()
+                .timestamp_format()
+        );
+        assert!(NODE.member("missing").is_none());
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Protocol-agnostic, schema-driven serialization traits.
+//!
+//! Generated shapes implement [`SerializableStruct`] by writing each of their set members
+//! with the member's [`Schema`]. Codecs implement [`ShapeSerializer`] and [`ShapeDeserializer`]
+//! and use the schema to decide how each value is represented on the wire (member names,
+//! timestamp formats, XML attributes, flattening, etc.).
+//!
+//! Aggregates are written and read through closures so that the codec can emit (or consume)
+//! whatever framing its format requires around the elements:
+//!
+//! ```rust
+//! use aws_smithy_types::schema::serde::{SerializableStruct, ShapeSerializer};
+//! use aws_smithy_types::schema::{Schema, ShapeId, ShapeType};
+//!
+//! const ID: ShapeId = ShapeId::new("com.example", "Widget");
+//! static WIDGET: Schema = Schema::new(ID, ShapeType::Structure).with_members(&WIDGET_MEMBERS);
+//! static WIDGET_MEMBERS: [Schema; 2] = [
+//!     Schema::new_member(ID.with_member("name"), 0, ShapeType::String),
+//!     Schema::new_member(ID.with_member("tags"), 1, ShapeType::List).with_members(&TAGS_MEMBERS),
+//! ];
+//! static TAGS_MEMBERS: [Schema; 1] = [Schema::new_member(
+//!     ShapeId::new("com.example", "Tags").with_member("member"),
+//!     0,
+//!     ShapeType::String,
+//! )];
+//!
+//! struct Widget {
+//!     name: Option<String>,
+//!     tags: Vec<String>,
+//! }
+//!
+//! impl SerializableStruct for Widget {
+//!     fn serialize_members<S: ShapeSerializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+//!         if let Some(name) = &self.name {
+//!             serializer.write_string(&WIDGET_MEMBERS[0], name)?;
+//!         }
+//!         let tags = &WIDGET_MEMBERS[1];
+//!         serializer.write_list(tags, self.tags.len(), |serializer| {
+//!             for tag in &self.tags {
+//!                 serializer.write_string(&TAGS_MEMBERS[0], tag)?;
+//!             }
+//!             Ok(())
+//!         })
+//!     }
+//! }
+//! ```
+
+use crate::schema::Schema;
+use crate::{Blob, DateTime, Document};
+use std::error::Error as StdError;
+
+/// A structure or union that can be written member by member.
+pub trait SerializableStruct {
+    /// Writes every set member of this shape to `serializer`.
+    ///
+    /// Each member must be written using its member schema.
+    fn serialize_members<S: ShapeSerializer>(&self, serializer: &mut S) -> Result<(), S::Error>;
+}
+
+/// Writes shapes to a protocol-specific format.
+///
+/// The `schema` passed to every method is the member schema when the value is a member of an
+/// aggregate, or the shape's own schema when writing the top-level value.
+pub trait ShapeSerializer {
+    /// The error returned when a value can't be written.
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Writes a structure or union.
+    fn write_struct<T>(&mut self, schema: &Schema, value: &T) -> Result<(), Self::Error>
+    where
+        T: SerializableStruct + ?Sized;
+
+    /// Writes a list of `len` elements.
+    ///
+    /// `write_elements` must write each element with the list's member schema.
+    fn write_list<F>(
+        &mut self,
+        schema: &Schema,
+        len: usize,
+        write_elements: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>;
+
+    /// Writes a map of `len` entries.
+    ///
+    /// `write_entries` must call [`write_map_key`](ShapeSerializer::write_map_key) for each
+    /// entry, followed by a write of the value with the map's value member schema.
+    fn write_map<F>(
+        &mut self,
+        schema: &Schema,
+        len: usize,
+        write_entries: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>;
+
+    /// Writes the key of the next map entry. `schema` is the map's key member schema.
+    fn write_map_key(&mut self, schema: &Schema, key: &str) -> Result<(), Self::Error>;
+
+    /// Writes a boolean.
+    fn write_boolean(&mut self, schema: &Schema, value: bool) -> Result<(), Self::Error>;
+
+    /// Writes a byte.
+    fn write_byte(&mut self, schema: &Schema, value: i8) -> Result<(), Self::Error>;
+
+    /// Writes a short.
+    fn write_short(&mut self, schema: &Schema, value: i16) -> Result<(), Self::Error>;
+
+    /// Writes an integer.
+    fn write_integer(&mut self, schema: &Schema, value: i32) -> Result<(), Self::Error>;
+
+    /// Writes a long.
+    fn write_long(&mut self, schema: &Schema, value: i64) -> Result<(), Self::Error>;
+
+    /// Writes a float.
+    fn write_float(&mut self, schema: &Schema, value: f32) -> Result<(), Self::Error>;
+
+    /// Writes a double.
+    fn write_double(&mut self, schema: &Schema, value: f64) -> Result<(), Self::Error>;
+
+    /// Writes a string (or the value of a string enum).
+    fn write_string(&mut self, schema: &Schema, value: &str) -> Result<(), Self::Error>;
+
+    /// Writes a blob.
+    fn write_blob(&mut self, schema: &Schema, value: &Blob) -> Result<(), Self::Error>;
+
+    /// Writes a timestamp, honoring the `@timestampFormat` trait where the protocol supports it.
+    fn write_timestamp(&mut self, schema: &Schema, value: &DateTime) -> Result<(), Self::Error>;
+
+    /// Writes a document.
+    fn write_document(&mut self, schema: &Schema, value: &Document) -> Result<(), Self::Error>;
+
+    /// Writes a null value. Only meaningful for elements of sparse lists and maps.
+    fn write_null(&mut self, schema: &Schema) -> Result<(), Self::Error>;
+}
+
+/// Reads shapes from a protocol-specific format.
+///
+/// Aggregates are consumed by threading a `state` (typically a builder) through a consumer
+/// closure that is invoked once per member, element or entry.
+pub trait ShapeDeserializer {
+    /// The error returned when a value can't be read.
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Reads a structure or union.
+    ///
+    /// `consumer` is invoked with the member schema of every known member that is present and
+    /// not null. Unknown members are skipped.
+    fn read_struct<T, F>(
+        &mut self,
+        schema: &Schema,
+        state: T,
+        consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &Schema, &mut Self) -> Result<T, Self::Error>;
+
+    /// Reads a list, invoking `consumer` once per element.
+    ///
+    /// Some formats (e.g. flattened XML lists) spread a list across repeated members, in which
+    /// case `consumer` is invoked once per occurrence. Callers should pass the partially built
+    /// collection back in as `state`.
+    fn read_list<T, F>(&mut self, schema: &Schema, state: T, consumer: F) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &mut Self) -> Result<T, Self::Error>;
+
+    /// Reads a map, invoking `consumer` once per entry with the entry's key.
+    fn read_map<T, F>(&mut self, schema: &Schema, state: T, consumer: F) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, String, &mut Self) -> Result<T, Self::Error>;
+
+    /// Reads a boolean.
+    fn read_boolean(&mut self, schema: &Schema) -> Result<bool, Self::Error>;
+
+    /// Reads a byte.
+    fn read_byte(&mut self, schema: &Schema) -> Result<i8, Self::Error>;
+
+    /// Reads a short.
+    fn read_short(&mut self, schema: &Schema) -> Result<i16, Self::Error>;
+
+    /// Reads an integer.
+    fn read_integer(&mut self, schema: &Schema) -> Result<i32, Self::Error>;
+
+    /// Reads a long.
+    fn read_long(&mut self, schema: &Schema) -> Result<i64, Self::Error>;
+
+    /// Reads a float.
+    fn read_float(&mut self, schema: &Schema) -> Result<f32, Self::Error>;
+
+    /// Reads a double.
+    fn read_double(&mut self, schema: &Schema) -> Result<f64, Self::Error>;
+
+    /// Reads a string (or the value of a string enum).
+    fn read_string(&mut self, schema: &Schema) -> Result<String, Self::Error>;
+
+    /// Reads a blob.
+    fn read_blob(&mut self, schema: &Schema) -> Result<Blob, Self::Error>;
+
+    /// Reads a timestamp, honoring the `@timestampFormat` trait where the protocol supports it.
+    fn read_timestamp(&mut self, schema: &Schema) -> Result<DateTime, Self::Error>;
+
+    /// Reads a document.
+    fn read_document(&mut self, schema: &Schema) -> Result<Document, Self::Error>;
+
+    /// Returns true if the next value is null. This doesn't consume the value.
+    fn is_null(&mut self) -> bool;
+
+    /// Reads a null value.
+    fn read_null(&mut self) -> Result<(), Self::Error>;
+}
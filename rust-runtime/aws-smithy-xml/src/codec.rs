@@ -0,0 +1,713 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Schema-driven XML serializer and deserializer.
+//!
+//! Element names come from `@xmlName` (falling back to the member name), members marked
+//! `@xmlAttribute` are written as attributes of their parent element, and `@xmlFlattened`
+//! lists and maps are written as repeated elements instead of being wrapped.
+
+use crate::decode::{next_child, read_text, skip_element, Document, StartEl, XmlDecodeError};
+use crate::escape::escape;
+use aws_smithy_types::date_time::{DateTimeFormatError, Format};
+use aws_smithy_types::primitive::{Encoder, Parse};
+use aws_smithy_types::schema::serde::{SerializableStruct, ShapeDeserializer, ShapeSerializer};
+use aws_smithy_types::schema::Schema;
+use aws_smithy_types::{base64, Blob, DateTime, Document as SmithyDocument};
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Write};
+
+/// Failed to serialize a shape to XML.
+#[derive(Debug)]
+pub struct XmlSerializeError {
+    message: Cow<'static, str>,
+    source: Option<DateTimeFormatError>,
+}
+
+impl XmlSerializeError {
+    fn unsupported(what: &'static str) -> Self {
+        Self {
+            message: format!("{} can't be represented in XML", what).into(),
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for XmlSerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to serialize XML: {}", self.message)
+    }
+}
+
+impl StdError for XmlSerializeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_ref().map(|err| err as _)
+    }
+}
+
+#[derive(Debug)]
+enum Frame {
+    /// Members of a structure are written in two passes: one for the attributes while the
+    /// start tag is still open, and one for the nested elements.
+    Struct {
+        attributes: bool,
+    },
+    List {
+        item_name: &'static str,
+    },
+    Map {
+        entry_name: &'static str,
+        key_name: &'static str,
+        value_name: &'static str,
+    },
+}
+
+fn xml_name(schema: &Schema) -> &'static str {
+    schema
+        .traits()
+        .xml_name()
+        .or(schema.member_name())
+        .unwrap_or_else(|| schema.id().name())
+}
+
+/// A [`ShapeSerializer`] that writes XML into a `String`.
+#[derive(Debug)]
+pub struct XmlShapeSerializer<'a> {
+    output: &'a mut String,
+    frames: Vec<Frame>,
+}
+
+impl<'a> XmlShapeSerializer<'a> {
+    /// Creates a serializer that appends to `output`.
+    pub fn new(output: &'a mut String) -> Self {
+        Self {
+            output,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Returns true if the value described by `schema` doesn't belong to the current pass.
+    fn skip(&self, schema: &Schema) -> bool {
+        match self.frames.last() {
+            Some(Frame::Struct { attributes }) => *attributes != schema.traits().xml_attribute(),
+            _ => false,
+        }
+    }
+
+    fn element_name(&self, schema: &Schema) -> &'static str {
+        match self.frames.last() {
+            Some(Frame::List { item_name }) => item_name,
+            Some(Frame::Map { value_name, .. }) => value_name,
+            Some(Frame::Struct { .. }) | None => xml_name(schema),
+        }
+    }
+
+    fn start_el(&mut self, name: &str, schema: &Schema) {
+        write!(self.output, "<{}", name).unwrap();
+        if let Some(namespace) = schema.traits().xml_namespace() {
+            match namespace.prefix() {
+                Some(prefix) => write!(
+                    self.output,
+                    " xmlns:{}=\"{}\"",
+                    prefix,
+                    escape(namespace.uri())
+                ),
+                None => write!(self.output, " xmlns=\"{}\"", escape(namespace.uri())),
+            }
+            .unwrap();
+        }
+    }
+
+    fn end_el(&mut self, name: &str) {
+        write!(self.output, "</{}>", name).unwrap();
+    }
+
+    /// Closes the map entry if the value that was just written belongs to one.
+    fn after_value(&mut self) {
+        if let Some(Frame::Map { entry_name, .. }) = self.frames.last() {
+            let entry_name = *entry_name;
+            self.end_el(entry_name);
+        }
+    }
+
+    fn text(&mut self, schema: &Schema, text: &str) {
+        if self.skip(schema) {
+            return;
+        }
+        if let Some(Frame::Struct { attributes: true }) = self.frames.last() {
+            write!(self.output, " {}=\"{}\"", xml_name(schema), escape(text)).unwrap();
+            return;
+        }
+        let name = self.element_name(schema);
+        self.start_el(name, schema);
+        write!(self.output, ">{}", escape(text)).unwrap();
+        self.end_el(name);
+        self.after_value();
+    }
+}
+
+impl<'a> ShapeSerializer for XmlShapeSerializer<'a> {
+    type Error = XmlSerializeError;
+
+    fn write_struct<T>(&mut self, schema: &Schema, value: &T) -> Result<(), Self::Error>
+    where
+        T: SerializableStruct + ?Sized,
+    {
+        if self.skip(schema) {
+            return Ok(());
+        }
+        let name = self.element_name(schema);
+        self.start_el(name, schema);
+        if schema
+            .members()
+            .iter()
+            .any(|member| member.traits().xml_attribute())
+        {
+            self.frames.push(Frame::Struct { attributes: true });
+            let result = value.serialize_members(self);
+            self.frames.pop();
+            result?;
+        }
+        self.output.push('>');
+        self.frames.push(Frame::Struct { attributes: false });
+        let result = value.serialize_members(self);
+        self.frames.pop();
+        self.end_el(name);
+        self.after_value();
+        result
+    }
+
+    fn write_list<F>(
+        &mut self,
+        schema: &Schema,
+        _len: usize,
+        write_elements: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        if self.skip(schema) {
+            return Ok(());
+        }
+        let name = self.element_name(schema);
+        let result = if schema.traits().xml_flattened() {
+            self.frames.push(Frame::List { item_name: name });
+            write_elements(self)
+        } else {
+            self.start_el(name, schema);
+            self.output.push('>');
+            let item_name = schema
+                .list_member()
+                .and_then(|member| member.traits().xml_name())
+                .unwrap_or("member");
+            self.frames.push(Frame::List { item_name });
+            let result = write_elements(self);
+            self.end_el(name);
+            result
+        };
+        self.frames.pop();
+        self.after_value();
+        result
+    }
+
+    fn write_map<F>(
+        &mut self,
+        schema: &Schema,
+        _len: usize,
+        write_entries: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Self::Error>,
+    {
+        if self.skip(schema) {
+            return Ok(());
+        }
+        let name = self.element_name(schema);
+        let flattened = schema.traits().xml_flattened();
+        let frame = Frame::Map {
+            entry_name: if flattened { name } else { "entry" },
+            key_name: schema
+                .map_key()
+                .and_then(|key| key.traits().xml_name())
+                .unwrap_or("key"),
+            value_name: schema
+                .map_value()
+                .and_then(|value| value.traits().xml_name())
+                .unwrap_or("value"),
+        };
+        if !flattened {
+            self.start_el(name, schema);
+            self.output.push('>');
+        }
+        self.frames.push(frame);
+        let result = write_entries(self);
+        self.frames.pop();
+        if !flattened {
+            self.end_el(name);
+        }
+        self.after_value();
+        result
+    }
+
+    fn write_map_key(&mut self, _schema: &Schema, key: &str) -> Result<(), Self::Error> {
+        if let Some(Frame::Map {
+            entry_name,
+            key_name,
+            ..
+        }) = self.frames.last()
+        {
+            write!(
+                self.output,
+                "<{entry_name}><{key_name}>{}</{key_name}>",
+                escape(key)
+            )
+            .unwrap();
+        }
+        Ok(())
+    }
+
+    fn write_boolean(&mut self, schema: &Schema, value: bool) -> Result<(), Self::Error> {
+        self.text(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_byte(&mut self, schema: &Schema, value: i8) -> Result<(), Self::Error> {
+        self.text(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_short(&mut self, schema: &Schema, value: i16) -> Result<(), Self::Error> {
+        self.text(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_integer(&mut self, schema: &Schema, value: i32) -> Result<(), Self::Error> {
+        self.text(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_long(&mut self, schema: &Schema, value: i64) -> Result<(), Self::Error> {
+        self.text(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_float(&mut self, schema: &Schema, value: f32) -> Result<(), Self::Error> {
+        self.text(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_double(&mut self, schema: &Schema, value: f64) -> Result<(), Self::Error> {
+        self.text(schema, Encoder::from(value).encode());
+        Ok(())
+    }
+
+    fn write_string(&mut self, schema: &Schema, value: &str) -> Result<(), Self::Error> {
+        self.text(schema, value);
+        Ok(())
+    }
+
+    fn write_blob(&mut self, schema: &Schema, value: &Blob) -> Result<(), Self::Error> {
+        self.text(schema, &base64::encode(value));
+        Ok(())
+    }
+
+    fn write_timestamp(&mut self, schema: &Schema, value: &DateTime) -> Result<(), Self::Error> {
+        let format = schema
+            .traits()
+            .timestamp_format()
+            .unwrap_or(Format::DateTime);
+        let formatted = value.fmt(format).map_err(|source| XmlSerializeError {
+            message: "failed to format timestamp".into(),
+            source: Some(source),
+        })?;
+        self.text(schema, &formatted);
+        Ok(())
+    }
+
+    fn write_document(
+        &mut self,
+        _schema: &Schema,
+        _value: &SmithyDocument,
+    ) -> Result<(), Self::Error> {
+        Err(XmlSerializeError::unsupported("documents"))
+    }
+
+    fn write_null(&mut self, _schema: &Schema) -> Result<(), Self::Error> {
+        Err(XmlSerializeError::unsupported("null values"))
+    }
+}
+
+/// A [`ShapeDeserializer`] that reads XML from a [`Document`].
+///
+/// By default, the root element of the document is read by the first `read_*` call. Use
+/// [`starting_at`](XmlShapeDeserializer::starting_at) to read from a nested element instead,
+/// e.g. the `<OperationResult>` element of an `awsQuery` response.
+pub struct XmlShapeDeserializer<'inp> {
+    doc: Document<'inp>,
+    // The element that the next `read_*` call will consume.
+    current: Option<StartEl<'inp>>,
+    // The attribute value that the next `read_*` call will consume.
+    attribute: Option<String>,
+}
+
+impl<'inp> fmt::Debug for XmlShapeDeserializer<'inp> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XmlShapeDeserializer")
+            .field("current", &self.current)
+            .field("attribute", &self.attribute)
+            .finish()
+    }
+}
+
+impl<'inp> XmlShapeDeserializer<'inp> {
+    /// Creates a deserializer reading from `doc`.
+    pub fn new(doc: Document<'inp>) -> Self {
+        Self {
+            doc,
+            current: None,
+            attribute: None,
+        }
+    }
+
+    /// Starts reading from `start_el`, which must have been read from this deserializer's document.
+    pub fn starting_at(mut self, start_el: StartEl<'inp>) -> Self {
+        self.current = Some(start_el);
+        self
+    }
+
+    fn take_element(&mut self) -> Result<StartEl<'inp>, XmlDecodeError> {
+        match self.current.take() {
+            Some(el) => Ok(el),
+            None => self
+                .doc
+                .next_start_element()
+                .ok_or_else(|| XmlDecodeError::custom("no root element")),
+        }
+    }
+
+    fn text(&mut self) -> Result<Cow<'inp, str>, XmlDecodeError> {
+        if let Some(attribute) = self.attribute.take() {
+            return Ok(Cow::Owned(attribute));
+        }
+        let el = self.take_element()?;
+        read_text(&mut self.doc, &el)
+    }
+
+    fn parse<T: Parse>(&mut self) -> Result<T, XmlDecodeError> {
+        let text = self.text()?;
+        T::parse_smithy_primitive(text.as_ref()).map_err(XmlDecodeError::unhandled)
+    }
+
+    /// Makes `el` the current element, invokes `read`, and skips whatever `read` left unread.
+    fn read_element<T, F>(&mut self, el: StartEl<'inp>, read: F) -> Result<T, XmlDecodeError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, XmlDecodeError>,
+    {
+        self.current = Some(el);
+        let result = read(self)?;
+        if let Some(el) = self.current.take() {
+            skip_element(&mut self.doc, &el)?;
+        }
+        Ok(result)
+    }
+
+    fn read_entry<T, F>(
+        &mut self,
+        schema: &Schema,
+        entry: &StartEl<'inp>,
+        mut state: T,
+        consumer: &mut F,
+    ) -> Result<T, XmlDecodeError>
+    where
+        F: FnMut(T, String, &mut Self) -> Result<T, XmlDecodeError>,
+    {
+        let key_name = schema
+            .map_key()
+            .and_then(|key| key.traits().xml_name())
+            .unwrap_or("key");
+        let value_name = schema
+            .map_value()
+            .and_then(|value| value.traits().xml_name())
+            .unwrap_or("value");
+        let mut key = None;
+        while let Some(child) = next_child(&mut self.doc, entry)? {
+            if child.matches(key_name) {
+                key = Some(read_text(&mut self.doc, &child)?.into_owned());
+            } else if child.matches(value_name) {
+                let key = key
+                    .take()
+                    .ok_or_else(|| XmlDecodeError::custom("map entry value preceded its key"))?;
+                state = self.read_element(child, |de| consumer(state, key, de))?;
+            } else {
+                skip_element(&mut self.doc, &child)?;
+            }
+        }
+        Ok(state)
+    }
+}
+
+impl<'inp> ShapeDeserializer for XmlShapeDeserializer<'inp> {
+    type Error = XmlDecodeError;
+
+    fn read_struct<T, F>(
+        &mut self,
+        schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &Schema, &mut Self) -> Result<T, Self::Error>,
+    {
+        let el = self.take_element()?;
+        for member in schema.members() {
+            if !member.traits().xml_attribute() {
+                continue;
+            }
+            if let Some(value) = el.attr(xml_name(member)) {
+                self.attribute = Some(value.to_string());
+                state = consumer(state, member, self)?;
+                self.attribute = None;
+            }
+        }
+        while let Some(child) = next_child(&mut self.doc, &el)? {
+            let member = schema
+                .members()
+                .iter()
+                .find(|member| !member.traits().xml_attribute() && child.matches(xml_name(member)));
+            match member {
+                Some(member) => {
+                    state = self.read_element(child, |de| consumer(state, member, de))?;
+                }
+                None => skip_element(&mut self.doc, &child)?,
+            }
+        }
+        Ok(state)
+    }
+
+    fn read_list<T, F>(
+        &mut self,
+        schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, &mut Self) -> Result<T, Self::Error>,
+    {
+        let el = self.take_element()?;
+        if schema.traits().xml_flattened() {
+            return self.read_element(el, |de| consumer(state, de));
+        }
+        while let Some(child) = next_child(&mut self.doc, &el)? {
+            state = self.read_element(child, |de| consumer(state, de))?;
+        }
+        Ok(state)
+    }
+
+    fn read_map<T, F>(
+        &mut self,
+        schema: &Schema,
+        mut state: T,
+        mut consumer: F,
+    ) -> Result<T, Self::Error>
+    where
+        F: FnMut(T, String, &mut Self) -> Result<T, Self::Error>,
+    {
+        let el = self.take_element()?;
+        if schema.traits().xml_flattened() {
+            return self.read_entry(schema, &el, state, &mut consumer);
+        }
+        while let Some(entry) = next_child(&mut self.doc, &el)? {
+            state = self.read_entry(schema, &entry, state, &mut consumer)?;
+        }
+        Ok(state)
+    }
+
+    fn read_boolean(&mut self, _schema: &Schema) -> Result<bool, Self::Error> {
+        self.parse()
+    }
+
+    fn read_byte(&mut self, _schema: &Schema) -> Result<i8, Self::Error> {
+        self.parse()
+    }
+
+    fn read_short(&mut self, _schema: &Schema) -> Result<i16, Self::Error> {
+        self.parse()
+    }
+
+    fn read_integer(&mut self, _schema: &Schema) -> Result<i32, Self::Error> {
+        self.parse()
+    }
+
+    fn read_long(&mut self, _schema: &Schema) -> Result<i64, Self::Error> {
+        self.parse()
+    }
+
+    fn read_float(&mut self, _schema: &Schema) -> Result<f32, Self::Error> {
+        self.parse()
+    }
+
+    fn read_double(&mut self, _schema: &Schema) -> Result<f64, Self::Error> {
+        self.parse()
+    }
+
+    fn read_string(&mut self, _schema: &Schema) -> Result<String, Self::Error> {
+        Ok(self.text()?.into_owned())
+    }
+
+    fn read_blob(&mut self, _schema: &Schema) -> Result<Blob, Self::Error> {
+        let text = self.text()?;
+        base64::decode(text.as_ref())
+            .map(Blob::new)
+            .map_err(XmlDecodeError::unhandled)
+    }
+
+    fn read_timestamp(&mut self, schema: &Schema) -> Result<DateTime, Self::Error> {
+        let format = schema
+            .traits()
+            .timestamp_format()
+            .unwrap_or(Format::DateTime);
+        let text = self.text()?;
+        DateTime::from_str(text.trim(), format).map_err(XmlDecodeError::unhandled)
+    }
+
+    fn read_document(&mut self, _schema: &Schema) -> Result<SmithyDocument, Self::Error> {
+        Err(XmlDecodeError::custom(
+            "documents can't be represented in XML",
+        ))
+    }
+
+    fn is_null(&mut self) -> bool {
+        false
+    }
+
+    fn read_null(&mut self) -> Result<(), Self::Error> {
+        Err(XmlDecodeError::custom(
+            "null values can't be represented in XML",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{XmlShapeDeserializer, XmlShapeSerializer};
+    use crate::decode::Document;
+    use aws_smithy_protocol_test::{assert_ok, validate_body, MediaType};
+    use aws_smithy_types::schema::serde::{SerializableStruct, ShapeDeserializer, ShapeSerializer};
+    use aws_smithy_types::schema::{Schema, ShapeId, ShapeType, Traits, XmlNamespace};
+
+    const ITEM_ID: ShapeId = ShapeId::new("com.example", "Item");
+
+    static ITEM: Schema = Schema::new(ITEM_ID, ShapeType::Structure)
+        .with_traits(
+            Traits::new().with_xml_namespace(XmlNamespace::new("https://example.com", None)),
+        )
+        .with_members(&ITEM_MEMBERS);
+    static ITEM_MEMBERS: [Schema; 3] = [
+        Schema::new_member(ITEM_ID.with_member("id"), 0, ShapeType::String)
+            .with_traits(Traits::new().with_xml_attribute().with_xml_name("ID")),
+        Schema::new_member(ITEM_ID.with_member("name"), 1, ShapeType::String)
+            .with_traits(Traits::new().with_xml_name("Name")),
+        Schema::new_member(ITEM_ID.with_member("flat"), 2, ShapeType::List)
+            .with_traits(Traits::new().with_xml_flattened())
+            .with_members(&FLAT_MEMBERS),
+    ];
+    static FLAT_MEMBERS: [Schema; 1] = [Schema::new_member(
+        ShapeId::new("com.example", "Flat").with_member("member"),
+        0,
+        ShapeType::String,
+    )];
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Item {
+        id: Option<String>,
+        name: Option<String>,
+        flat: Option<Vec<String>>,
+    }
+
+    impl SerializableStruct for Item {
+        fn serialize_members<S: ShapeSerializer>(&self, ser: &mut S) -> Result<(), S::Error> {
+            // Attributes deliberately written after elements
+            if let Some(name) = &self.name {
+                ser.write_string(&ITEM_MEMBERS[1], name)?;
+            }
+            if let Some(id) = &self.id {
+                ser.write_string(&ITEM_MEMBERS[0], id)?;
+            }
+            if let Some(flat) = &self.flat {
+                ser.write_list(&ITEM_MEMBERS[2], flat.len(), |ser| {
+                    for value in flat {
+                        ser.write_string(&FLAT_MEMBERS[0], value)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        }
+    }
+
+    fn deserialize_item<D: ShapeDeserializer>(de: &mut D) -> Result<Item, D::Error> {
+        de.read_struct(&ITEM, Item::default(), |mut item, member, de| {
+            match member.member_index() {
+                0 => item.id = Some(de.read_string(member)?),
+                1 => item.name = Some(de.read_string(member)?),
+                2 => {
+                    let flat = item.flat.take().unwrap_or_default();
+                    item.flat = Some(de.read_list(member, flat, |mut flat, de| {
+                        flat.push(de.read_string(&FLAT_MEMBERS[0])?);
+                        Ok(flat)
+                    })?)
+                }
+                _ => unreachable!(),
+            }
+            Ok(item)
+        })
+    }
+
+    #[test]
+    fn attributes_namespaces_and_flattened_lists() {
+        let mut output = String::new();
+        XmlShapeSerializer::new(&mut output)
+            .write_struct(
+                &ITEM,
+                &Item {
+                    id: Some("a&b".into()),
+                    name: Some("<item>".into()),
+                    flat: Some(vec!["1".into(), "2".into()]),
+                },
+            )
+            .unwrap();
+        assert_ok(validate_body(
+            &output,
+            r#"<Item ID="a&amp;b" xmlns="https://example.com">
+                <Name>&lt;item&gt;</Name>
+                <flat>1</flat>
+                <flat>2</flat>
+            </Item>"#,
+            MediaType::Xml,
+        ));
+
+        let xml = r#"<Response>
+            <ItemResult ID="7">
+                <flat>1</flat>
+                <Unknown><Name>not me</Name></Unknown>
+                <Name>me</Name>
+                <flat>2</flat>
+            </ItemResult>
+        </Response>"#;
+        let mut doc = Document::new(xml);
+        let _response = doc.next_start_element().unwrap();
+        let result = doc.next_start_element().unwrap();
+        let mut de = XmlShapeDeserializer::new(doc).starting_at(result);
+        assert_eq!(
+            Item {
+                id: Some("7".into()),
+                name: Some("me".into()),
+                flat: Some(vec!["1".into(), "2".into()]),
+            },
+            deserialize_item(&mut de).unwrap()
+        );
+    }
+}
@@ -451,6 +451,120 @@ pub fn try_data<'a, 'inp>(
     }
 }
 
+/// Returns the next child element of `parent`, or `None` once `parent` has been closed
+///
+/// The previous child (if any) must have been fully consumed, e.g. with [`skip_element`].
+pub(crate) fn next_child<'inp>(
+    doc: &mut Document<'inp>,
+    parent: &StartEl<'inp>,
+) -> Result<Option<StartEl<'inp>>, XmlDecodeError> {
+    if parent.closed {
+        return Ok(None);
+    }
+    loop {
+        let (tok, depth) = match doc.next() {
+            Some(result) => result?,
+            None => return Ok(None),
+        };
+        match tok.0 {
+            Token::ElementEnd { end, .. } if parent.end_el(end, depth) => return Ok(None),
+            Token::ElementStart { prefix, local, .. } => {
+                let mut el = StartEl::new(local.as_str(), prefix.as_str(), depth);
+                loop {
+                    match doc.next() {
+                        Some(Ok((
+                            XmlToken(Token::Attribute {
+                                prefix,
+                                local,
+                                value,
+                                ..
+                            }),
+                            _,
+                        ))) => el.attributes.push(Attr {
+                            name: Name {
+                                local: local.as_str(),
+                                prefix: prefix.as_str(),
+                            },
+                            value: unescape(value.as_str())?,
+                        }),
+                        Some(Ok((
+                            XmlToken(Token::ElementEnd {
+                                end: ElementEnd::Empty,
+                                ..
+                            }),
+                            _,
+                        ))) => {
+                            el.closed = true;
+                            return Ok(Some(el));
+                        }
+                        Some(Ok((XmlToken(Token::ElementEnd { .. }), _))) => return Ok(Some(el)),
+                        Some(Ok(_)) => {}
+                        Some(Err(err)) => return Err(err),
+                        None => return Err(XmlDecodeError::custom("unexpected end of document")),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reads the text content of `el`, consuming the document until the end of the element
+pub(crate) fn read_text<'inp>(
+    doc: &mut Document<'inp>,
+    el: &StartEl<'inp>,
+) -> Result<Cow<'inp, str>, XmlDecodeError> {
+    if el.closed {
+        return Ok(Cow::Borrowed(""));
+    }
+    let mut text: Option<Cow<'inp, str>> = None;
+    loop {
+        let (tok, depth) = match doc.next() {
+            Some(result) => result?,
+            None => return Err(XmlDecodeError::custom("unexpected end of document")),
+        };
+        match tok.0 {
+            Token::ElementEnd { end, .. } if el.end_el(end, depth) => {
+                return Ok(text.unwrap_or(Cow::Borrowed("")))
+            }
+            Token::Text { text: data } => {
+                let data = unescape(data.as_str())?;
+                text = Some(match text {
+                    None => data,
+                    Some(previous) => Cow::Owned(previous.into_owned() + &data),
+                });
+            }
+            Token::ElementStart { .. } => {
+                return Err(XmlDecodeError::custom(format!(
+                    "looking for a data element in `{}`, found a start element",
+                    el.local()
+                )))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Consumes the document until the end of `el`
+pub(crate) fn skip_element<'inp>(
+    doc: &mut Document<'inp>,
+    el: &StartEl<'inp>,
+) -> Result<(), XmlDecodeError> {
+    if el.closed {
+        return Ok(());
+    }
+    loop {
+        match doc.next() {
+            Some(Ok((XmlToken(Token::ElementEnd { end, .. }), depth))) if el.end_el(end, depth) => {
+                return Ok(())
+            }
+            Some(Ok(_)) => {}
+            Some(Err(err)) => return Err(err),
+            None => return Err(XmlDecodeError::custom("unexpected end of document")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::decode::{try_data, Attr, Depth, Document, Name, StartEl};
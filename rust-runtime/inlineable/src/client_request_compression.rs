@@ -29,10 +29,14 @@ pub(crate) struct RequestCompressionRuntimePlugin {
 }
 
 impl RequestCompressionRuntimePlugin {
-    pub(crate) fn new() -> Self {
+    /// Creates a runtime plugin that compresses requests with `algorithm`, the first encoding
+    /// listed by the operation's `@requestCompression` trait that's supported.
+    pub(crate) fn new(algorithm: CompressionAlgorithm) -> Self {
         Self {
             runtime_components: RuntimeComponentsBuilder::new("RequestCompressionRuntimePlugin")
-                .with_interceptor(SharedInterceptor::new(RequestCompressionInterceptor::new())),
+                .with_interceptor(SharedInterceptor::new(RequestCompressionInterceptor::new(
+                    algorithm,
+                ))),
         }
     }
 }
@@ -58,17 +62,21 @@ impl Storable for RequestCompressionInterceptorState {
 /// Interceptor for Smithy [`@requestCompression`][spec].
 ///
 /// [spec]: https://smithy.io/2.0/spec/behavior-traits.html#requestcompression-trait
-pub(crate) struct RequestCompressionInterceptor {}
+pub(crate) struct RequestCompressionInterceptor {
+    algorithm: CompressionAlgorithm,
+}
 
 impl fmt::Debug for RequestCompressionInterceptor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("RequestCompressionInterceptor").finish()
+        f.debug_struct("RequestCompressionInterceptor")
+            .field("algorithm", &self.algorithm.as_str())
+            .finish()
     }
 }
 
 impl RequestCompressionInterceptor {
-    pub(crate) fn new() -> Self {
-        Self {}
+    pub(crate) fn new(algorithm: CompressionAlgorithm) -> Self {
+        Self { algorithm }
     }
 }
 
@@ -144,10 +152,12 @@ impl Intercept for RequestCompressionInterceptor {
 
         wrap_request_body_in_compressed_body(
             request,
-            CompressionAlgorithm::Gzip.into_impl_http_body_0_4_x(&options),
+            self.algorithm.into_impl_http_body_0_4_x(&options),
         )?;
-        cfg.interceptor_state()
-            .store_append::<SmithySdkFeature>(SmithySdkFeature::GzipRequestCompression);
+        if self.algorithm == CompressionAlgorithm::Gzip {
+            cfg.interceptor_state()
+                .store_append::<SmithySdkFeature>(SmithySdkFeature::GzipRequestCompression);
+        }
 
         Ok(())
     }
@@ -284,11 +294,15 @@ mod tests {
     }
 
     fn context() -> InterceptorContext {
+        context_with_body(UNCOMPRESSED_INPUT)
+    }
+
+    fn context_with_body(body: &'static [u8]) -> InterceptorContext {
         let mut context = InterceptorContext::new(Input::doesnt_matter());
         context.enter_serialization_phase();
         context.set_request(
             http::Request::builder()
-                .body(SdkBody::from(UNCOMPRESSED_INPUT))
+                .body(SdkBody::from(body))
                 .unwrap()
                 .try_into()
                 .unwrap(),
@@ -307,7 +321,7 @@ mod tests {
         let mut context = context();
         let ctx = Into::into(&context);
 
-        let sut = RequestCompressionInterceptor::new();
+        let sut = RequestCompressionInterceptor::new(CompressionAlgorithm::Gzip);
         sut.read_before_execution(&ctx, &mut cfg).unwrap();
 
         let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
@@ -320,4 +334,44 @@ mod tests {
             cfg.load::<SmithySdkFeature>().next().unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn test_request_is_compressed_with_the_configured_algorithm() {
+        for algorithm in [
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Zstd,
+            CompressionAlgorithm::Brotli,
+        ] {
+            let mut cfg = ConfigBag::base();
+            let mut layer = Layer::new("test");
+            layer.store_put(RequestMinCompressionSizeBytes::from(0));
+            cfg.push_layer(layer);
+            let mut context = context_with_body(UNCOMPRESSED_INPUT);
+
+            let sut = RequestCompressionInterceptor::new(algorithm);
+            sut.read_before_execution(&Into::into(&context), &mut cfg)
+                .unwrap();
+            let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+            sut.modify_before_retry_loop(&mut Into::into(&mut context), &rc, &mut cfg)
+                .unwrap();
+
+            let request = context.request_mut().unwrap();
+            assert_eq!(
+                Some(algorithm.as_str()),
+                request.headers().get("content-encoding")
+            );
+            let mut body = request.take_body();
+            let mut compressed = Vec::new();
+            while let Some(data) = body.data().await {
+                compressed.extend_from_slice(&data.unwrap());
+            }
+            let mut decompress = algorithm.into_decompress_impl_http_body_0_4_x();
+            let mut decompressed = Vec::new();
+            decompress
+                .decompress_bytes(&compressed, &mut decompressed)
+                .unwrap();
+            decompress.finish(&mut decompressed).unwrap();
+            assert_eq!(UNCOMPRESSED_INPUT, decompressed);
+        }
+    }
 }
@@ -0,0 +1,539 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_compression::body::decompress::DecompressedBody;
+use aws_smithy_compression::CompressionAlgorithm;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    BeforeDeserializationInterceptorContextMut, BeforeTransmitInterceptorContextMut,
+};
+use aws_smithy_runtime_api::client::interceptors::{Intercept, SharedInterceptor};
+use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::{Order, RuntimePlugin};
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use std::borrow::Cow;
+use std::{fmt, mem};
+
+/// The algorithms that responses can be decompressed with, in order of preference.
+const SUPPORTED_ALGORITHMS: &[CompressionAlgorithm] = &[
+    CompressionAlgorithm::Zstd,
+    CompressionAlgorithm::Brotli,
+    CompressionAlgorithm::Gzip,
+];
+
+#[derive(Debug)]
+pub(crate) struct ResponseDecompressionRuntimePlugin {
+    runtime_components: RuntimeComponentsBuilder,
+}
+
+impl ResponseDecompressionRuntimePlugin {
+    pub(crate) fn new() -> Self {
+        Self {
+            runtime_components: RuntimeComponentsBuilder::new("ResponseDecompressionRuntimePlugin")
+                .with_interceptor(SharedInterceptor::new(
+                    ResponseDecompressionInterceptor::new(),
+                )),
+        }
+    }
+}
+
+impl RuntimePlugin for ResponseDecompressionRuntimePlugin {
+    // Content-length enforcement and checksum validation describe the body as it was
+    // transmitted, so their interceptors must wrap the response body before it's decompressed.
+    fn order(&self) -> Order {
+        Order::NestedComponents
+    }
+
+    fn runtime_components(
+        &self,
+        _: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Borrowed(&self.runtime_components)
+    }
+}
+
+/// Interceptor that transparently decompresses responses once it's enabled with
+/// [`EnableResponseDecompression`].
+///
+/// Unless an `accept-encoding` header was already set, the request advertises the supported
+/// algorithms. If the response has a `content-encoding` of one of them, the response body is
+/// decompressed as it's read, and the `content-encoding` and `content-length` headers are
+/// removed since they no longer describe the body. Decompression fails once the body exceeds
+/// [`ResponseMaxDecompressedSizeBytes`].
+///
+/// The body is wrapped in `modify_before_deserialization`. The runtime plugin has
+/// [`Order::NestedComponents`], so content-length enforcement and checksum validation wrap the
+/// body first and continue to apply to the body as it was transmitted, which is what the
+/// `content-length` header and checksums describe.
+pub(crate) struct ResponseDecompressionInterceptor {}
+
+impl fmt::Debug for ResponseDecompressionInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseDecompressionInterceptor").finish()
+    }
+}
+
+impl ResponseDecompressionInterceptor {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+fn is_enabled(cfg: &ConfigBag) -> bool {
+    cfg.load::<EnableResponseDecompression>()
+        .cloned()
+        .unwrap_or_default()
+        .0
+}
+
+impl Intercept for ResponseDecompressionInterceptor {
+    fn name(&self) -> &'static str {
+        "ResponseDecompressionInterceptor"
+    }
+
+    // The header is added after signing so that proxies that rewrite `accept-encoding`
+    // can't invalidate the signature.
+    fn modify_before_transmit(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if !is_enabled(cfg) {
+            tracing::trace!("response decompression is not enabled and will not be requested");
+            return Ok(());
+        }
+        let headers = context.request_mut().headers_mut();
+        if headers.contains_key(http::header::ACCEPT_ENCODING) {
+            tracing::trace!("`accept-encoding` was already set and will not be modified");
+            return Ok(());
+        }
+        let accept_encoding = SUPPORTED_ALGORITHMS
+            .iter()
+            .map(CompressionAlgorithm::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        headers.insert(http::header::ACCEPT_ENCODING, accept_encoding);
+        Ok(())
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if !is_enabled(cfg) {
+            return Ok(());
+        }
+        let max_decompressed_size = cfg
+            .load::<ResponseMaxDecompressedSizeBytes>()
+            .cloned()
+            .unwrap_or_default();
+        let response = context.response_mut();
+        if let Some(algorithm) = response_compression_algorithm(response) {
+            tracing::trace!(
+                algorithm = algorithm.as_str(),
+                "decompressing response body..."
+            );
+            wrap_response_body_in_decompressed_body(response, algorithm, max_decompressed_size.0);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the algorithm the response body was compressed with, if it's one of the
+/// [`SUPPORTED_ALGORITHMS`] that are advertised in `accept-encoding`.
+///
+/// Responses with multiple encodings applied are left as-is.
+fn response_compression_algorithm(response: &HttpResponse) -> Option<CompressionAlgorithm> {
+    let content_encoding = response
+        .headers()
+        .get(http::header::CONTENT_ENCODING)?
+        .trim();
+    if content_encoding.eq_ignore_ascii_case("identity") {
+        return None;
+    }
+    let algorithm = content_encoding
+        .parse::<CompressionAlgorithm>()
+        .ok()
+        .filter(|algorithm| SUPPORTED_ALGORITHMS.contains(algorithm));
+    if algorithm.is_none() {
+        tracing::debug!(
+            content_encoding,
+            "response has an unsupported `content-encoding` and will not be decompressed"
+        );
+    }
+    algorithm
+}
+
+fn wrap_response_body_in_decompressed_body(
+    response: &mut HttpResponse,
+    algorithm: CompressionAlgorithm,
+    max_decompressed_size: u64,
+) {
+    let headers = response.headers_mut();
+    headers.remove(http::header::CONTENT_ENCODING);
+    headers.remove(http::header::CONTENT_LENGTH);
+
+    let body = mem::replace(response.body_mut(), SdkBody::taken());
+    let mut body = body.map(move |body| {
+        SdkBody::from_body_0_4(
+            DecompressedBody::new(body, algorithm.into_decompress_impl_http_body_0_4_x())
+                .with_max_decompressed_size(max_decompressed_size),
+        )
+    });
+    mem::swap(response.body_mut(), &mut body);
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct EnableResponseDecompression(pub(crate) bool);
+
+impl From<bool> for EnableResponseDecompression {
+    fn from(value: bool) -> Self {
+        EnableResponseDecompression(value)
+    }
+}
+
+impl Storable for EnableResponseDecompression {
+    type Storer = StoreReplace<Self>;
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ResponseMaxDecompressedSizeBytes(pub(crate) u64);
+
+impl Default for ResponseMaxDecompressedSizeBytes {
+    fn default() -> Self {
+        ResponseMaxDecompressedSizeBytes(256 * 1024 * 1024)
+    }
+}
+
+impl From<u64> for ResponseMaxDecompressedSizeBytes {
+    fn from(value: u64) -> Self {
+        ResponseMaxDecompressedSizeBytes(value)
+    }
+}
+
+impl Storable for ResponseMaxDecompressedSizeBytes {
+    type Storer = StoreReplace<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        EnableResponseDecompression, ResponseDecompressionRuntimePlugin,
+        ResponseMaxDecompressedSizeBytes,
+    };
+    use aws_smithy_checksums::body::validate;
+    use aws_smithy_checksums::ChecksumAlgorithm;
+    use aws_smithy_compression::{CompressionAlgorithm, CompressionOptions};
+    use aws_smithy_runtime::client::http::body::content_length_enforcement::EnforceContentLengthRuntimePlugin;
+    use aws_smithy_runtime_api::box_error::BoxError;
+    use aws_smithy_runtime_api::client::interceptors::context::{
+        BeforeDeserializationInterceptorContextMut, Input, InterceptorContext,
+    };
+    use aws_smithy_runtime_api::client::interceptors::Intercept;
+    use aws_smithy_runtime_api::client::runtime_components::{
+        RuntimeComponents, RuntimeComponentsBuilder,
+    };
+    use aws_smithy_runtime_api::client::runtime_plugin::{RuntimePlugins, StaticRuntimePlugin};
+    use aws_smithy_runtime_api::http::Headers;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
+    use bytes::Bytes;
+    use http_body::Body;
+    use std::mem;
+
+    const UNCOMPRESSED_OUTPUT: &[u8] = b"hello world";
+    const COMPRESSED_INPUT: &[u8] = &[
+        31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0,
+        133, 17, 74, 13, 11, 0, 0, 0,
+    ];
+
+    fn cfg(enable_response_decompression: bool) -> ConfigBag {
+        let mut cfg = ConfigBag::base();
+        let mut layer = Layer::new("test");
+        layer.store_put(EnableResponseDecompression(enable_response_decompression));
+        cfg.push_layer(layer);
+        cfg
+    }
+
+    fn compress(algorithm: CompressionAlgorithm, data: &[u8]) -> Vec<u8> {
+        let mut compress = algorithm.into_impl_http_body_0_4_x(&CompressionOptions::default());
+        let mut compressed = Vec::new();
+        compress.compress_bytes(data, &mut compressed).unwrap();
+        compress.finish(&mut compressed).unwrap();
+        compressed
+    }
+
+    /// Validates the response body against a CRC32 checksum, like the response checksum
+    /// interceptor does.
+    #[derive(Debug)]
+    struct ValidateChecksum(Bytes);
+
+    impl Intercept for ValidateChecksum {
+        fn name(&self) -> &'static str {
+            "ValidateChecksum"
+        }
+
+        fn modify_before_deserialization(
+            &self,
+            context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+            _runtime_components: &RuntimeComponents,
+            _cfg: &mut ConfigBag,
+        ) -> Result<(), BoxError> {
+            let checksum = self.0.clone();
+            let body = mem::replace(context.response_mut().body_mut(), SdkBody::taken());
+            *context.response_mut().body_mut() = body.map(move |body| {
+                SdkBody::from_body_0_4(validate::ChecksumBody::new(
+                    body,
+                    ChecksumAlgorithm::Crc32.into_impl(),
+                    checksum.clone(),
+                ))
+            });
+            Ok(())
+        }
+    }
+
+    fn crc32(data: &[u8]) -> Bytes {
+        let mut checksum = ChecksumAlgorithm::Crc32.into_impl();
+        checksum.update(data);
+        checksum.finalize()
+    }
+
+    struct Exchange {
+        request_headers: Headers,
+        response_headers: Headers,
+        response_body: Result<Vec<u8>, BoxError>,
+    }
+
+    /// Sends a GET request through the interceptors registered by `plugins`, in the order the
+    /// orchestrator would run them, and then reads the response body.
+    async fn exchange(
+        plugins: RuntimePlugins,
+        mut cfg: ConfigBag,
+        request: http::request::Builder,
+        response: http::response::Builder,
+        body: Vec<u8>,
+    ) -> Exchange {
+        let interceptors = plugins
+            .apply_operation_configuration(&mut cfg)
+            .unwrap()
+            .interceptors()
+            .collect::<Vec<_>>();
+        let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+
+        let mut context = InterceptorContext::new(Input::doesnt_matter());
+        context.enter_serialization_phase();
+        let request = request.method("GET").body(SdkBody::empty()).unwrap();
+        context.set_request(request.try_into().unwrap());
+        let _ = context.take_input();
+        context.enter_before_transmit_phase();
+        for interceptor in &interceptors {
+            interceptor
+                .modify_before_transmit(&mut (&mut context).into(), &rc, &mut cfg)
+                .unwrap();
+            interceptor
+                .read_before_transmit(&(&context).into(), &rc, &mut cfg)
+                .unwrap();
+        }
+
+        context.enter_transmit_phase();
+        let request_headers = context.take_request().unwrap().headers().clone();
+        context.set_response(
+            response
+                .status(200)
+                .body(SdkBody::from(body))
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        context.enter_before_deserialization_phase();
+        for interceptor in &interceptors {
+            interceptor
+                .modify_before_deserialization(&mut (&mut context).into(), &rc, &mut cfg)
+                .unwrap();
+        }
+
+        let response = context.response_mut().unwrap();
+        let response_headers = response.headers().clone();
+        let mut body = response.take_body();
+        let mut data = Vec::new();
+        let response_body = loop {
+            match body.data().await {
+                Some(Ok(chunk)) => data.extend_from_slice(&chunk),
+                Some(Err(err)) => break Err(err),
+                None => break Ok(data),
+            }
+        };
+        Exchange {
+            request_headers,
+            response_headers,
+            response_body,
+        }
+    }
+
+    async fn decompression_exchange(
+        cfg: ConfigBag,
+        request: http::request::Builder,
+        content_encoding: Option<&str>,
+        body: Vec<u8>,
+    ) -> Exchange {
+        let mut response = http::Response::builder().header("content-length", body.len());
+        if let Some(content_encoding) = content_encoding {
+            response = response.header("content-encoding", content_encoding);
+        }
+        let plugins =
+            RuntimePlugins::new().with_operation_plugin(ResponseDecompressionRuntimePlugin::new());
+        exchange(plugins, cfg, request, response, body).await
+    }
+
+    #[tokio::test]
+    async fn accept_encoding_is_set_when_enabled() {
+        for (request, enable_response_decompression, expected) in [
+            (http::Request::builder(), true, Some("zstd, br, gzip")),
+            (http::Request::builder(), false, None),
+            (
+                http::Request::builder().header("accept-encoding", "br"),
+                true,
+                Some("br"),
+            ),
+        ] {
+            let exchange = decompression_exchange(
+                cfg(enable_response_decompression),
+                request,
+                None,
+                Vec::new(),
+            )
+            .await;
+            assert_eq!(expected, exchange.request_headers.get("accept-encoding"));
+        }
+        let exchange = decompression_exchange(
+            ConfigBag::base(),
+            http::Request::builder(),
+            None,
+            Vec::new(),
+        )
+        .await;
+        assert_eq!(None, exchange.request_headers.get("accept-encoding"));
+    }
+
+    #[tokio::test]
+    async fn compressed_responses_are_decompressed() {
+        for (content_encoding, body) in [
+            ("gzip", COMPRESSED_INPUT.to_vec()),
+            ("GZIP", COMPRESSED_INPUT.to_vec()),
+            (" gzip ", COMPRESSED_INPUT.to_vec()),
+            (
+                "zstd",
+                compress(CompressionAlgorithm::Zstd, UNCOMPRESSED_OUTPUT),
+            ),
+            (
+                "br",
+                compress(CompressionAlgorithm::Brotli, UNCOMPRESSED_OUTPUT),
+            ),
+            (
+                "BR",
+                compress(CompressionAlgorithm::Brotli, UNCOMPRESSED_OUTPUT),
+            ),
+        ] {
+            let exchange = decompression_exchange(
+                cfg(true),
+                http::Request::builder(),
+                Some(content_encoding),
+                body,
+            )
+            .await;
+            assert_eq!(UNCOMPRESSED_OUTPUT, exchange.response_body.unwrap());
+            assert!(!exchange.response_headers.contains_key("content-encoding"));
+            assert!(!exchange.response_headers.contains_key("content-length"));
+        }
+    }
+
+    #[tokio::test]
+    async fn other_responses_are_untouched() {
+        for (content_encoding, enable_response_decompression) in [
+            (None, true),
+            (Some("identity"), true),
+            (Some("deflate"), true),
+            (Some("gzip, br"), true),
+            (Some("gzip"), false),
+        ] {
+            let exchange = decompression_exchange(
+                cfg(enable_response_decompression),
+                http::Request::builder(),
+                content_encoding,
+                COMPRESSED_INPUT.to_vec(),
+            )
+            .await;
+            assert_eq!(COMPRESSED_INPUT, exchange.response_body.unwrap());
+            assert_eq!(
+                content_encoding,
+                exchange.response_headers.get("content-encoding")
+            );
+            assert!(exchange.response_headers.contains_key("content-length"));
+        }
+    }
+
+    #[tokio::test]
+    async fn max_decompressed_size_is_enforced() {
+        for (max, expected_ok) in [(11, true), (10, false)] {
+            let mut cfg = cfg(true);
+            let mut layer = Layer::new("max");
+            layer.store_put(ResponseMaxDecompressedSizeBytes(max));
+            cfg.push_layer(layer);
+            let exchange = decompression_exchange(
+                cfg,
+                http::Request::builder(),
+                Some("gzip"),
+                COMPRESSED_INPUT.to_vec(),
+            )
+            .await;
+            assert_eq!(expected_ok, exchange.response_body.is_ok(), "max: {max}");
+        }
+    }
+
+    #[tokio::test]
+    async fn content_length_and_checksums_apply_to_the_transmitted_body() {
+        let truncated = COMPRESSED_INPUT[..COMPRESSED_INPUT.len() - 1].to_vec();
+        let mut corrupted = COMPRESSED_INPUT.to_vec();
+        corrupted[COMPRESSED_INPUT.len() - 1] = 1;
+        for (body, expected_error) in [
+            (COMPRESSED_INPUT.to_vec(), None),
+            (truncated, Some("Invalid Content-Length")),
+            (corrupted, Some("body checksum mismatch")),
+        ] {
+            // Decompression is registered first, but still has to wrap the body last.
+            let plugins = RuntimePlugins::new()
+                .with_operation_plugin(ResponseDecompressionRuntimePlugin::new())
+                .with_operation_plugin(EnforceContentLengthRuntimePlugin::new())
+                .with_operation_plugin(
+                    StaticRuntimePlugin::new().with_runtime_components(
+                        RuntimeComponentsBuilder::new("test")
+                            .with_interceptor(ValidateChecksum(crc32(COMPRESSED_INPUT))),
+                    ),
+                );
+            let response = http::Response::builder()
+                .header("content-length", COMPRESSED_INPUT.len())
+                .header("content-encoding", "gzip");
+            let exchange =
+                exchange(plugins, cfg(true), http::Request::builder(), response, body).await;
+            match expected_error {
+                None => assert_eq!(UNCOMPRESSED_OUTPUT, exchange.response_body.unwrap()),
+                Some(expected_error) => {
+                    let error = exchange.response_body.expect_err("the body is invalid");
+                    assert!(
+                        error.to_string().contains(expected_error),
+                        "expected `{expected_error}` but got `{error}`"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -3,7 +3,9 @@
  *  SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_smithy_async::future::BoxFuture;
 use aws_smithy_http::event_stream::Receiver;
+use aws_smithy_runtime::client::event_stream::RecvEvent;
 use aws_smithy_runtime_api::client::result::SdkError;
 use aws_smithy_types::event_stream::RawMessage;
 
@@ -26,3 +28,13 @@ impl<T, E> EventReceiver<T, E> {
         self.inner.recv().await
     }
 }
+
+impl<T, E> RecvEvent<T, E> for EventReceiver<T, E>
+where
+    T: Send,
+    E: Send,
+{
+    fn recv(&mut self) -> BoxFuture<'_, Option<T>, SdkError<E, RawMessage>> {
+        Box::pin(EventReceiver::recv(self))
+    }
+}
@@ -42,6 +42,9 @@ mod auth_plugin;
 #[allow(unused)]
 mod client_request_compression;
 
+#[allow(unused)]
+mod client_response_decompression;
+
 // This test is outside of uuid.rs to enable copying the entirety of uuid.rs into the SDK without
 // requiring a proptest dependency
 #[cfg(test)]